@@ -1,6 +1,11 @@
+#![feature(alloc)]
+#![feature(raw_vec_growth_hook)]
+#![feature(raw_vec_internals)]
 #![feature(repr_simd)]
+#![feature(slice_partition_dedup)]
 #![feature(test)]
 
+extern crate alloc;
 extern crate test;
 
 mod btree;
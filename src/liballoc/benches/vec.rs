@@ -1,4 +1,6 @@
+use alloc::raw_vec;
 use test::Bencher;
+use std::cmp;
 use std::iter::{FromIterator, repeat};
 
 #[bench]
@@ -480,3 +482,147 @@ fn bench_clone_from_10_0100_0010(b: &mut Bencher) {
 fn bench_clone_from_10_1000_0100(b: &mut Bencher) {
     do_bench_clone_from(b, 10, 1000, 100)
 }
+
+fn grow_by_half(cap: usize, _required_cap: usize, _elem_size: usize) -> usize {
+    cap + cap / 2
+}
+
+fn round_to_size_class(cap: usize, required_cap: usize, elem_size: usize) -> usize {
+    let bytes = cmp::max(cap * 2, required_cap) * elem_size;
+    bytes.next_power_of_two() / elem_size
+}
+
+// The growth hook is global, so it is restored before the next benchmark runs.
+fn do_bench_push_growth(b: &mut Bencher, hook: fn(usize, usize, usize) -> usize, len: usize) {
+    raw_vec::set_growth_hook(hook);
+    b.bytes = len as u64 * 4;
+
+    b.iter(|| {
+        let mut v = Vec::new();
+        for i in 0..len as u32 {
+            v.push(i);
+        }
+        v
+    });
+    raw_vec::take_growth_hook();
+}
+
+#[bench]
+fn bench_push_growth_double_0100(b: &mut Bencher) {
+    do_bench_push_growth(b, raw_vec::default_growth_hook, 100)
+}
+
+#[bench]
+fn bench_push_growth_double_10000(b: &mut Bencher) {
+    do_bench_push_growth(b, raw_vec::default_growth_hook, 10000)
+}
+
+#[bench]
+fn bench_push_growth_half_0100(b: &mut Bencher) {
+    do_bench_push_growth(b, grow_by_half, 100)
+}
+
+#[bench]
+fn bench_push_growth_half_10000(b: &mut Bencher) {
+    do_bench_push_growth(b, grow_by_half, 10000)
+}
+
+#[bench]
+fn bench_push_growth_size_class_0100(b: &mut Bencher) {
+    do_bench_push_growth(b, round_to_size_class, 100)
+}
+
+#[bench]
+fn bench_push_growth_size_class_10000(b: &mut Bencher) {
+    do_bench_push_growth(b, round_to_size_class, 10000)
+}
+
+fn dedup_input(len: usize) -> Vec<u32> {
+    // Mostly unique: every sixteenth element repeats its predecessor.
+    (0..len as u32).map(|i| if i % 16 == 15 { i - 1 } else { i }).collect()
+}
+
+fn do_bench_dedup(b: &mut Bencher, len: usize) {
+    let src = dedup_input(len);
+    b.bytes = len as u64 * 4;
+    b.iter(|| {
+        let mut v = src.clone();
+        v.dedup();
+        v
+    });
+}
+
+// The implementation `dedup_by` used to have, for comparison.
+fn do_bench_partition_dedup(b: &mut Bencher, len: usize) {
+    let src = dedup_input(len);
+    b.bytes = len as u64 * 4;
+    b.iter(|| {
+        let mut v = src.clone();
+        let new_len = v.partition_dedup().0.len();
+        v.truncate(new_len);
+        v
+    });
+}
+
+#[bench]
+fn bench_dedup_0100(b: &mut Bencher) {
+    do_bench_dedup(b, 100)
+}
+
+#[bench]
+fn bench_dedup_10000(b: &mut Bencher) {
+    do_bench_dedup(b, 10000)
+}
+
+#[bench]
+fn bench_partition_dedup_0100(b: &mut Bencher) {
+    do_bench_partition_dedup(b, 100)
+}
+
+#[bench]
+fn bench_partition_dedup_10000(b: &mut Bencher) {
+    do_bench_partition_dedup(b, 10000)
+}
+
+fn flatten_input(outer: usize, inner: usize) -> Vec<Vec<u32>> {
+    (0..outer).map(|i| (0..inner as u32).map(|j| i as u32 ^ j).collect()).collect()
+}
+
+fn do_bench_collect_flattened(b: &mut Bencher, outer: usize, inner: usize) {
+    let src = flatten_input(outer, inner);
+    b.bytes = (outer * inner) as u64 * 4;
+    b.iter(|| {
+        let v: Vec<u32> = src.clone().into_iter().flatten().collect();
+        v
+    });
+}
+
+// The same, but flattening `vec::IntoIter`s, which takes the generic path.
+fn do_bench_collect_flattened_iters(b: &mut Bencher, outer: usize, inner: usize) {
+    let src = flatten_input(outer, inner);
+    b.bytes = (outer * inner) as u64 * 4;
+    b.iter(|| {
+        let v: Vec<u32> = src.clone().into_iter().flat_map(|v| v.into_iter()).collect();
+        v
+    });
+}
+
+#[bench]
+fn bench_collect_flattened_1000x10(b: &mut Bencher) {
+    do_bench_collect_flattened(b, 1000, 10)
+}
+
+#[bench]
+fn bench_collect_flattened_iters_1000x10(b: &mut Bencher) {
+    do_bench_collect_flattened_iters(b, 1000, 10)
+}
+
+#[bench]
+fn bench_collect_flattened_10x1000(b: &mut Bencher) {
+    do_bench_collect_flattened(b, 10, 1000)
+}
+
+#[bench]
+fn bench_collect_flattened_iters_10x1000(b: &mut Bencher) {
+    do_bench_collect_flattened_iters(b, 10, 1000)
+}
@@ -85,6 +85,7 @@
 #![feature(dropck_eyepatch)]
 #![feature(exact_size_is_empty)]
 #![feature(fmt_internals)]
+#![feature(iter_cloned_internals)]
 #![feature(fn_traits)]
 #![feature(fundamental)]
 #![feature(futures_api)]
@@ -112,6 +113,8 @@
 #![feature(rustc_const_unstable)]
 #![feature(const_vec_new)]
 #![feature(slice_partition_dedup)]
+#![feature(slice_chunk_by)]
+#![feature(fixed_size_array)]
 #![feature(maybe_uninit, maybe_uninit_slice, maybe_uninit_array)]
 #![feature(alloc_layout_extra)]
 #![feature(try_trait)]
@@ -7,6 +7,9 @@ use core::ops::Drop;
 use core::ptr::{self, NonNull, Unique};
 use core::slice;
 
+#[cfg(all(target_has_atomic = "ptr", target_has_atomic = "cas"))]
+use core::sync::atomic::{AtomicPtr, Ordering};
+
 use crate::alloc::{Alloc, Layout, Global, handle_alloc_error};
 use crate::collections::CollectionAllocErr::{self, *};
 use crate::boxed::Box;
@@ -423,10 +426,9 @@ impl<T, A: Alloc> RawVec<T, A> {
 
         // Nothing we can really do about these checks :(
         let required_cap = used_cap.checked_add(needed_extra_cap).ok_or(CapacityOverflow)?;
-        // Cannot overflow, because `cap <= isize::MAX`, and type of `cap` is `usize`.
-        let double_cap = self.cap * 2;
-        // `double_cap` guarantees exponential growth.
-        Ok(cmp::max(double_cap, required_cap))
+        let new_cap = growth_hook()(self.cap, required_cap, mem::size_of::<T>());
+        // Whatever the hook says, we need room for `required_cap` elements.
+        Ok(cmp::max(new_cap, required_cap))
     }
 
     /// The same as `reserve`, but returns on errors instead of panicking or aborting.
@@ -746,6 +748,67 @@ fn capacity_overflow() -> ! {
     panic!("capacity overflow")
 }
 
+#[cfg(all(target_has_atomic = "ptr", target_has_atomic = "cas"))]
+static GROWTH_HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Registers a custom growth strategy for the amortized `reserve` methods of every `RawVec`,
+/// and so of `Vec`, `String` and the other collections built on them, replacing any that was
+/// previously registered.
+///
+/// The hook is called with the current capacity, the capacity that is needed, and the size of
+/// an element, all in elements except the last, and returns the new capacity. It is only called
+/// when the buffer has to grow. Returning less than the needed capacity is allowed: the needed
+/// capacity is used instead. The default hook, [`default_growth_hook`], doubles the capacity.
+///
+/// This is meant for embedders that want to trade memory for reallocations, e.g. by growing by
+/// 1.5x or by rounding to the size classes of their allocator. The hook is a global resource and
+/// should be registered before any collection grows. The methods that reserve an exact capacity
+/// and `double` are not affected.
+///
+/// [`default_growth_hook`]: fn.default_growth_hook.html
+#[cfg(all(target_has_atomic = "ptr", target_has_atomic = "cas"))]
+#[unstable(feature = "raw_vec_growth_hook", reason = "implementation detail", issue = "0")]
+pub fn set_growth_hook(hook: fn(usize, usize, usize) -> usize) {
+    GROWTH_HOOK.store(hook as *mut (), Ordering::SeqCst);
+}
+
+/// Unregisters the current growth hook, returning it.
+///
+/// If no custom hook is registered, the default hook will be returned.
+#[cfg(all(target_has_atomic = "ptr", target_has_atomic = "cas"))]
+#[unstable(feature = "raw_vec_growth_hook", reason = "implementation detail", issue = "0")]
+pub fn take_growth_hook() -> fn(usize, usize, usize) -> usize {
+    let hook = GROWTH_HOOK.swap(ptr::null_mut(), Ordering::SeqCst);
+    if hook.is_null() {
+        default_growth_hook
+    } else {
+        unsafe { mem::transmute(hook) }
+    }
+}
+
+/// The default growth strategy: at least double the capacity.
+#[unstable(feature = "raw_vec_growth_hook", reason = "implementation detail", issue = "0")]
+pub fn default_growth_hook(cap: usize, _required_cap: usize, _elem_size: usize) -> usize {
+    // Cannot overflow, because `cap <= isize::MAX`, and type of `cap` is `usize`.
+    // Doubling guarantees exponential growth.
+    cap * 2
+}
+
+#[cfg(all(target_has_atomic = "ptr", target_has_atomic = "cas"))]
+fn growth_hook() -> fn(usize, usize, usize) -> usize {
+    let hook = GROWTH_HOOK.load(Ordering::SeqCst);
+    if hook.is_null() {
+        default_growth_hook
+    } else {
+        unsafe { mem::transmute(hook) }
+    }
+}
+
+#[cfg(not(all(target_has_atomic = "ptr", target_has_atomic = "cas")))]
+fn growth_hook() -> fn(usize, usize, usize) -> usize {
+    default_growth_hook
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,6 +117,8 @@ pub use core::slice::SliceIndex;
 pub use core::slice::{ChunksExact, ChunksExactMut};
 #[stable(feature = "rchunks", since = "1.31.0")]
 pub use core::slice::{RChunks, RChunksMut, RChunksExact, RChunksExactMut};
+#[unstable(feature = "slice_chunk_by", issue = "0")]
+pub use core::slice::{ChunkBy, ChunkByMut};
 
 ////////////////////////////////////////////////////////////////////////////////
 // Basic slice extension methods
@@ -21,6 +21,7 @@ use core::marker::{Unpin, Unsize, PhantomData};
 use core::hash::{Hash, Hasher};
 use core::{isize, usize};
 use core::convert::From;
+use core::iter::FromIterator;
 use core::slice::from_raw_parts_mut;
 
 use crate::alloc::{Global, Alloc, Layout, box_free, handle_alloc_error};
@@ -1646,6 +1647,228 @@ impl<T> From<Vec<T>> for Arc<[T]> {
     }
 }
 
+/// A vector that can be shared between owners and threads in `O(1)`, with
+/// copy-on-write mutation.
+///
+/// `SharedVec<T>` is a thin wrapper around `Arc<Vec<T>>`. Cloning it only
+/// bumps a reference count, so a vector that is built once and then handed
+/// to many tasks is cheap to fan out. Reading goes through [`Deref`] to
+/// `[T]`. Mutation goes through [`make_mut`], which clones the elements only
+/// if other owners currently share them, in the same way as
+/// [`Arc::make_mut`].
+///
+/// [`Deref`]: ../../std/ops/trait.Deref.html
+/// [`make_mut`]: #method.make_mut
+/// [`Arc::make_mut`]: struct.Arc.html#method.make_mut
+///
+/// # Examples
+///
+/// ```
+/// #![feature(shared_vec)]
+/// use std::sync::SharedVec;
+/// use std::thread;
+///
+/// let data = SharedVec::from(vec![1, 2, 3]);
+///
+/// let handles: Vec<_> = (0..4).map(|_| {
+///     let data = data.clone();
+///     thread::spawn(move || data.iter().sum::<i32>())
+/// }).collect();
+/// for handle in handles {
+///     assert_eq!(handle.join().unwrap(), 6);
+/// }
+///
+/// let mut mine = data.clone();
+/// mine.make_mut().push(4);
+/// assert_eq!(&*mine, &[1, 2, 3, 4]);
+/// assert_eq!(&*data, &[1, 2, 3]);
+/// ```
+#[unstable(feature = "shared_vec", issue = "0")]
+pub struct SharedVec<T> {
+    inner: Arc<Vec<T>>,
+}
+
+impl<T> SharedVec<T> {
+    /// Constructs a new, empty `SharedVec<T>`.
+    #[unstable(feature = "shared_vec", issue = "0")]
+    pub fn new() -> SharedVec<T> {
+        SharedVec { inner: Arc::new(Vec::new()) }
+    }
+
+    /// Returns a mutable reference to the underlying vector if this is the
+    /// only owner of it, and `None` otherwise.
+    ///
+    /// See also [`make_mut`], which clones the vector when it is shared.
+    ///
+    /// [`make_mut`]: #method.make_mut
+    #[unstable(feature = "shared_vec", issue = "0")]
+    pub fn get_mut(&mut self) -> Option<&mut Vec<T>> {
+        Arc::get_mut(&mut self.inner)
+    }
+
+    /// Returns `true` if this is the only owner of the underlying vector, so
+    /// that mutating it will not clone.
+    ///
+    /// If other threads can reach this `SharedVec`, one of them may clone it
+    /// right after this returns, so the result is only a snapshot.
+    #[unstable(feature = "shared_vec", issue = "0")]
+    pub fn is_unique(&self) -> bool {
+        // A `SharedVec` built from an `Arc` may still have `Weak` handles,
+        // which `get_mut` and `make_mut` treat as other owners.
+        Arc::strong_count(&self.inner) == 1 && Arc::weak_count(&self.inner) == 0
+    }
+
+    /// Returns `true` if the two `SharedVec`s share the same underlying
+    /// vector.
+    #[unstable(feature = "shared_vec", issue = "0")]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.inner, &other.inner)
+    }
+}
+
+impl<T: Clone> SharedVec<T> {
+    /// Returns a mutable reference to the underlying vector, cloning it first
+    /// if other owners share it.
+    ///
+    /// Other owners keep seeing the elements as they were before the call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(shared_vec)]
+    /// use std::sync::SharedVec;
+    ///
+    /// let mut a = SharedVec::from(vec![1, 2]);
+    /// a.make_mut().push(3); // not shared, no clone
+    ///
+    /// let b = a.clone();
+    /// a.make_mut()[0] = 10; // shared, clones the elements
+    /// assert_eq!(&*a, &[10, 2, 3]);
+    /// assert_eq!(&*b, &[1, 2, 3]);
+    /// ```
+    #[unstable(feature = "shared_vec", issue = "0")]
+    pub fn make_mut(&mut self) -> &mut Vec<T> {
+        Arc::make_mut(&mut self.inner)
+    }
+
+    /// Converts this `SharedVec` into a `Vec`, cloning the elements only if
+    /// other owners share them.
+    #[unstable(feature = "shared_vec", issue = "0")]
+    pub fn into_vec(self) -> Vec<T> {
+        match Arc::try_unwrap(self.inner) {
+            Ok(vec) => vec,
+            Err(shared) => (*shared).clone(),
+        }
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T> Clone for SharedVec<T> {
+    /// Makes another owner of the same vector. This does not clone the
+    /// elements.
+    #[inline]
+    fn clone(&self) -> SharedVec<T> {
+        SharedVec { inner: self.inner.clone() }
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T> Default for SharedVec<T> {
+    fn default() -> SharedVec<T> {
+        SharedVec::new()
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T> Deref for SharedVec<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        &self.inner
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T> AsRef<[T]> for SharedVec<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.inner
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T> borrow::Borrow<[T]> for SharedVec<T> {
+    fn borrow(&self) -> &[T] {
+        &self.inner
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T> From<Vec<T>> for SharedVec<T> {
+    fn from(vec: Vec<T>) -> SharedVec<T> {
+        SharedVec { inner: Arc::new(vec) }
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T> From<Arc<Vec<T>>> for SharedVec<T> {
+    fn from(inner: Arc<Vec<T>>) -> SharedVec<T> {
+        SharedVec { inner }
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T> From<SharedVec<T>> for Arc<Vec<T>> {
+    fn from(shared: SharedVec<T>) -> Arc<Vec<T>> {
+        shared.inner
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T> FromIterator<T> for SharedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> SharedVec<T> {
+        SharedVec::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T: PartialEq> PartialEq for SharedVec<T> {
+    fn eq(&self, other: &SharedVec<T>) -> bool {
+        **self == **other
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T: Eq> Eq for SharedVec<T> {}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T: PartialOrd> PartialOrd for SharedVec<T> {
+    fn partial_cmp(&self, other: &SharedVec<T>) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T: Ord> Ord for SharedVec<T> {
+    fn cmp(&self, other: &SharedVec<T>) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T: Hash> Hash for SharedVec<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+#[unstable(feature = "shared_vec", issue = "0")]
+impl<T: fmt::Debug> fmt::Debug for SharedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::boxed::Box;
@@ -1659,7 +1882,7 @@ mod tests {
     use std::sync::Mutex;
     use std::convert::From;
 
-    use super::{Arc, Weak};
+    use super::{Arc, SharedVec, Weak};
     use crate::vec::Vec;
 
     struct Canary(*mut atomic::AtomicUsize);
@@ -1735,6 +1958,51 @@ mod tests {
         drop(w2);
     }
 
+    #[test]
+    fn shared_vec_copy_on_write() {
+        let mut a = SharedVec::from(vec![1, 2, 3]);
+        assert!(a.is_unique());
+        a.make_mut().push(4);
+        assert_eq!(&*a, &[1, 2, 3, 4]);
+        let ptr = a.as_ptr();
+
+        let mut b = a.clone();
+        assert!(SharedVec::ptr_eq(&a, &b));
+        assert!(!b.is_unique());
+        assert!(b.get_mut().is_none());
+
+        b.make_mut()[0] = 10;
+        assert!(!SharedVec::ptr_eq(&a, &b));
+        assert_eq!(&*a, &[1, 2, 3, 4]);
+        assert_eq!(&*b, &[10, 2, 3, 4]);
+        assert!(a.is_unique());
+
+        // The unique owner gets the original allocation back.
+        let v = a.into_vec();
+        assert_eq!(v.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn shared_vec_from_arc_with_weak() {
+        let arc = Arc::new(vec![1, 2, 3]);
+        let weak = Arc::downgrade(&arc);
+        let mut v = SharedVec::from(arc);
+        assert!(!v.is_unique());
+        assert!(v.get_mut().is_none());
+
+        drop(weak);
+        assert!(v.is_unique());
+        assert!(v.get_mut().is_some());
+    }
+
+    #[test]
+    fn shared_vec_into_vec_shared() {
+        let a: SharedVec<_> = (0..3).collect();
+        let b = a.clone();
+        assert_eq!(a.into_vec(), vec![0, 1, 2]);
+        assert_eq!(&*b, &[0, 1, 2]);
+    }
+
     #[test]
     fn try_unwrap() {
         let x = Arc::new(3);
@@ -4,8 +4,19 @@
 #![feature(exact_size_is_empty)]
 #![feature(pattern)]
 #![feature(repeat_generic_slice)]
+#![feature(maybe_uninit)]
 #![feature(try_reserve)]
 #![feature(unboxed_closures)]
+#![feature(vec_cursor)]
+#![feature(vec_spare_capacity)]
+#![feature(vec_leak)]
+#![feature(vec_sorted)]
+#![feature(vec_try_collect_into)]
+#![feature(vec_shrink_if_wasteful)]
+#![feature(vec_from_small_buffer)]
+#![feature(vec_try_into_array)]
+#![feature(vec_into_remaining_vec)]
+#![feature(vec_gap_cursor)]
 #![feature(vecdeque_rotate)]
 
 use std::hash::{Hash, Hasher};
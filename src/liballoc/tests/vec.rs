@@ -1,7 +1,8 @@
 #![cfg(not(miri))]
 
 use std::borrow::Cow;
-use std::mem::size_of;
+use std::mem::{size_of, MaybeUninit};
+use std::rc::Rc;
 use std::{usize, isize};
 use std::vec::{Drain, IntoIter};
 use std::collections::CollectionAllocErr::*;
@@ -136,6 +137,25 @@ fn test_extend_ref() {
     assert_eq!(v, [1, 2, 3, 4, 5, 6, 7]);
 }
 
+#[test]
+fn test_from_iter_cloned_slice() {
+    let v: Vec<i32> = [1, 2, 3].iter().cloned().collect();
+    assert_eq!(v, [1, 2, 3]);
+    assert_eq!(v.capacity(), 3);
+
+    let mut it = [1, 2, 3, 4].iter();
+    it.next();
+    let mut v: Vec<i32> = it.cloned().collect();
+    assert_eq!(v, [2, 3, 4]);
+    assert_eq!(v.capacity(), 3);
+
+    v.extend([5, 6].iter().cloned());
+    assert_eq!(v, [2, 3, 4, 5, 6]);
+
+    let v: Vec<String> = ["a".to_string(), "b".to_string()].iter().cloned().collect();
+    assert_eq!(v, ["a", "b"]);
+}
+
 #[test]
 fn test_slice_from_mut() {
     let mut values = vec![1, 2, 3, 4, 5];
@@ -278,6 +298,42 @@ fn test_dedup_by() {
     assert_eq!(vec, [("foo", 3), ("bar", 12)]);
 }
 
+#[test]
+#[cfg(not(miri))] // Miri does not support panics
+fn test_dedup_by_panic_safety() {
+    use std::cell::Cell;
+    use std::panic::{self, AssertUnwindSafe};
+
+    struct Counted<'a>(u32, &'a Cell<u32>);
+
+    impl<'a> Drop for Counted<'a> {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+        }
+    }
+
+    let drops = Cell::new(0);
+    let mut vec = (0..8).map(|i| Counted(i / 2, &drops)).collect::<Vec<_>>();
+    let mut calls = 0;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        vec.dedup_by(|a, b| {
+            calls += 1;
+            if calls == 4 {
+                panic!("same_bucket panic");
+            }
+            a.0 == b.0
+        });
+    }));
+    assert!(result.is_err());
+
+    // The first three calls removed two duplicates. The element being compared
+    // when the panic happened is kept, along with everything after it.
+    assert_eq!(drops.get(), 2);
+    assert_eq!(vec.iter().map(|c| c.0).collect::<Vec<_>>(), [0, 1, 2, 2, 3, 3]);
+    drop(vec);
+    assert_eq!(drops.get(), 8);
+}
+
 #[test]
 fn test_dedup_unique() {
     let mut v0: Vec<Box<_>> = vec![box 1, box 1, box 2, box 3];
@@ -663,6 +719,26 @@ fn test_into_boxed_slice() {
     assert_eq!(&*ys, [1, 2, 3]);
 }
 
+#[test]
+fn test_into_boxed_slice_without_excess_capacity() {
+    let xs = vec![1, 2, 3];
+    assert_eq!(xs.len(), xs.capacity());
+    let ptr = xs.as_ptr();
+    let ys = xs.into_boxed_slice();
+    assert_eq!(ys.as_ptr(), ptr);
+}
+
+#[test]
+fn test_leak() {
+    let mut xs = Vec::with_capacity(10);
+    xs.extend_from_slice(&[1, 2, 3]);
+    let ptr = xs.as_ptr();
+    let leaked: &'static mut [i32] = xs.leak();
+    assert_eq!(leaked.as_ptr(), ptr);
+    leaked[0] = 4;
+    assert_eq!(leaked, [4, 2, 3]);
+}
+
 #[test]
 fn test_append() {
     let mut vec = vec![1, 2, 3];
@@ -958,6 +1034,77 @@ fn drain_filter_complex() {
     }
 }
 
+#[test]
+fn cursor_mut_remove_runs() {
+    let mut vec: Vec<i32> = (0..10).collect();
+    {
+        let mut cursor = vec.cursor_mut();
+        while let Some(&mut x) = cursor.current() {
+            if x % 4 == 1 || x % 4 == 2 {
+                assert_eq!(cursor.remove_current(), Some(x));
+            } else {
+                cursor.move_next();
+            }
+        }
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.remove_current(), None);
+    }
+    assert_eq!(vec, [0, 3, 4, 7, 8]);
+}
+
+#[test]
+fn cursor_mut_insert_and_peek() {
+    let mut vec = vec![1, 2, 3];
+    {
+        let mut cursor = vec.cursor_mut();
+        // Without a gap, inserting shifts the rest of the vector.
+        cursor.insert_before(0);
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.peek_next(), Some(&mut 2));
+
+        // After a removal, inserting fills the gap.
+        cursor.remove_current();
+        cursor.insert_before(10);
+        cursor.insert_before(11);
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.peek_next(), None);
+        cursor.insert_before(4);
+    }
+    assert_eq!(vec, [0, 10, 11, 2, 3, 4]);
+}
+
+#[test]
+fn cursor_mut_drop_closes_gap() {
+    let mut vec = vec![String::from("a"), String::from("b"), String::from("c")];
+    {
+        let mut cursor = vec.cursor_mut();
+        cursor.remove_current();
+        // Dropped in the middle, with the rest not yet visited.
+    }
+    assert_eq!(vec, ["b", "c"]);
+
+    let mut vec = vec![(); 5];
+    {
+        let mut cursor = vec.cursor_mut();
+        cursor.move_next();
+        cursor.remove_current();
+        cursor.insert_before(());
+        cursor.insert_before(());
+    }
+    assert_eq!(vec.len(), 6);
+}
+
+#[test]
+fn cursor_mut_debug() {
+    let mut vec = vec![1, 2, 3, 4];
+    let mut cursor = vec.cursor_mut();
+    cursor.move_next();
+    cursor.remove_current();
+    assert_eq!(format!("{:?}", cursor), "CursorMut([1], [3, 4])");
+}
+
 #[test]
 fn test_reserve_exact() {
     // This is all the same as test_reserve
@@ -1163,3 +1310,248 @@ fn test_try_reserve_exact() {
     }
 
 }
+
+#[test]
+fn test_try_with_capacity() {
+    let vec: Vec<u32> = Vec::try_with_capacity(10).unwrap();
+    assert_eq!(vec.len(), 0);
+    assert!(vec.capacity() >= 10);
+
+    let vec: Vec<()> = Vec::try_with_capacity(usize::MAX).unwrap();
+    assert_eq!(vec.capacity(), usize::MAX);
+
+    if let Err(CapacityOverflow) = Vec::<u32>::try_with_capacity(usize::MAX) {
+    } else { panic!("usize::MAX u32s should overflow") }
+}
+
+#[test]
+fn test_vec_from_elem_zeroed_option() {
+    use std::num::NonZeroU32;
+    use std::ptr::NonNull;
+
+    let x = 5;
+    let v: Vec<Option<&i32>> = vec![None; 100];
+    assert!(v.iter().all(|e| e.is_none()));
+    let v = vec![Some(&x); 3];
+    assert_eq!(v, [Some(&5); 3]);
+
+    let v: Vec<Option<Box<i32>>> = vec![None; 100];
+    assert!(v.iter().all(|e| e.is_none()));
+
+    let v: Vec<Option<NonNull<u8>>> = vec![None; 100];
+    assert!(v.iter().all(|e| e.is_none()));
+
+    let v: Vec<Option<NonZeroU32>> = vec![None; 100];
+    assert!(v.iter().all(|e| e.is_none()));
+    let v = vec![NonZeroU32::new(7); 3];
+    assert_eq!(v, [NonZeroU32::new(7); 3]);
+}
+
+#[test]
+fn test_collect_flattened_vecs() {
+    let vecs = vec![vec![1, 2], vec![], vec![3, 4, 5], vec![6]];
+    let v: Vec<i32> = vecs.into_iter().flatten().collect();
+    assert_eq!(v, [1, 2, 3, 4, 5, 6]);
+
+    // The largest buffer is reused when it can hold everything.
+    let mut big = Vec::with_capacity(16);
+    big.extend_from_slice(&[3, 4]);
+    let big_ptr = big.as_ptr();
+    let v: Vec<i32> = vec![vec![1, 2], big, vec![5]].into_iter().flatten().collect();
+    assert_eq!(v, [1, 2, 3, 4, 5]);
+    assert_eq!(v.as_ptr(), big_ptr);
+
+    // Partially consumed front and back vectors are included.
+    let mut it = vec![vec![1, 2, 3], vec![4], vec![5, 6, 7]].into_iter().flatten();
+    assert_eq!(it.next(), Some(1));
+    assert_eq!(it.next_back(), Some(7));
+    let v: Vec<i32> = it.collect();
+    assert_eq!(v, [2, 3, 4, 5, 6]);
+
+    let v: Vec<String> = (0..3).flat_map(|i| vec![i.to_string(); i]).collect();
+    assert_eq!(v, ["1", "2", "2"]);
+
+    let mut v = vec![0];
+    v.extend(vec![vec![1], vec![2, 3]].into_iter().flatten());
+    assert_eq!(v, [0, 1, 2, 3]);
+
+    let v: Vec<()> = vec![vec![(); 3], vec![(); 2]].into_iter().flatten().collect();
+    assert_eq!(v.len(), 5);
+}
+
+#[test]
+fn test_spare_capacity_mut() {
+    let mut v: Vec<String> = Vec::with_capacity(4);
+    v.push("a".to_string());
+    assert_eq!(v.spare_capacity_mut().len(), v.capacity() - 1);
+
+    let (init, spare) = v.split_at_spare_mut();
+    assert_eq!(init.len(), 1);
+    spare[0].set(init[0].clone() + "b");
+    unsafe {
+        v.set_len(2);
+    }
+    assert_eq!(v, ["a", "ab"]);
+
+    let mut v: Vec<()> = Vec::new();
+    assert_eq!(v.spare_capacity_mut().len(), usize::MAX);
+}
+
+#[test]
+fn test_insert_sorted() {
+    let mut v = Vec::new();
+    for &x in &[5, 1, 4, 1, 3, 5] {
+        v.insert_sorted(x);
+    }
+    assert_eq!(v, [1, 1, 3, 4, 5, 5]);
+    assert_eq!(v.insert_sorted(0), 0);
+    assert_eq!(v.insert_sorted(6), 7);
+    assert_eq!(v.insert_sorted(1), 3);
+    assert!(v.contains_sorted(&4));
+    assert!(!v.contains_sorted(&2));
+
+    // Equal elements keep their insertion order.
+    let mut v = Vec::new();
+    for &x in &[(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd')] {
+        v.insert_sorted_by(x, |a, b| a.0.cmp(&b.0));
+    }
+    assert_eq!(v, [(0, 'b'), (0, 'd'), (1, 'a'), (1, 'c')]);
+}
+
+#[test]
+fn test_try_collect_into() {
+    let mut v = vec![String::from("a")];
+    let res = v.try_collect_into(vec![Ok(String::from("b")), Err(1), Ok(String::from("c"))]);
+    assert_eq!(res, Err(1));
+    assert_eq!(v, ["a"]);
+    let capacity = v.capacity();
+    assert!(capacity >= 2);
+
+    let res: Result<(), i32> = v.try_collect_into(vec![Ok(String::from("b"))]);
+    assert_eq!(res, Ok(()));
+    assert_eq!(v, ["a", "b"]);
+    assert_eq!(v.capacity(), capacity);
+}
+
+#[test]
+fn test_try_collect_into_option() {
+    fn collect(v: &mut Vec<u32>, items: Vec<Option<u32>>) -> Option<()> {
+        v.try_collect_into(items)?;
+        Some(())
+    }
+
+    let mut v = vec![1];
+    assert_eq!(collect(&mut v, vec![Some(2), None, Some(3)]), None);
+    assert_eq!(v, [1]);
+    assert_eq!(collect(&mut v, vec![Some(2), Some(3)]), Some(()));
+    assert_eq!(v, [1, 2, 3]);
+}
+
+#[test]
+fn test_shrink_if_wasteful() {
+    let mut v: Vec<u32> = Vec::with_capacity(100);
+    v.extend(0..10);
+    assert!(!v.shrink_if_wasteful(10));
+    assert_eq!(v.capacity(), 100);
+    assert!(v.shrink_if_wasteful(4));
+    assert!(v.capacity() >= 10 && v.capacity() < 40);
+
+    v.clear();
+    assert!(v.shrink_if_wasteful(2));
+    assert_eq!(v.capacity(), 0);
+    assert!(!v.shrink_if_wasteful(2));
+
+    let mut v = vec![(); 10];
+    v.clear();
+    assert!(!v.shrink_if_wasteful(2));
+}
+
+#[test]
+fn test_from_small_buffer() {
+    let mut buf: [MaybeUninit<Box<u32>>; 3] = unsafe {
+        MaybeUninit::uninitialized().into_initialized()
+    };
+    buf[0].set(Box::new(1));
+    buf[1].set(Box::new(2));
+    let v = unsafe { Vec::from_small_buffer(&mut buf, 2) };
+    assert_eq!(v, [Box::new(1), Box::new(2)]);
+
+    let v: Vec<Box<u32>> = unsafe { Vec::from_small_buffer(&mut buf, 0) };
+    assert!(v.is_empty());
+}
+
+#[test]
+fn test_try_into_array() {
+    let rc = Rc::new(());
+    let v = vec![rc.clone(), rc.clone(), rc.clone()];
+    let v = v.try_into_array::<[Rc<()>; 2]>().unwrap_err();
+    assert_eq!(v.len(), 3);
+    let array: [Rc<()>; 3] = v.try_into_array().unwrap();
+    assert_eq!(Rc::strong_count(&rc), 4);
+    drop(array);
+    assert_eq!(Rc::strong_count(&rc), 1);
+
+    assert_eq!(Vec::<u8>::new().try_into_array(), Ok([]));
+}
+
+#[test]
+fn test_into_remaining_vec() {
+    let mut it = vec![String::from("a"), String::from("b"), String::from("c")].into_iter();
+    assert_eq!(it.next().as_ref().map(|s| &s[..]), Some("a"));
+    let remaining = it.as_slice().as_ptr();
+    let v = it.into_remaining_vec();
+    assert_eq!(v, ["b", "c"]);
+    assert_eq!(v.capacity(), 3);
+    assert_eq!(unsafe { v.as_ptr().add(1) }, remaining);
+
+    let mut it = vec![(); 5].into_iter();
+    it.next();
+    it.next_back();
+    assert_eq!(it.into_remaining_vec().len(), 3);
+
+    let mut it = vec![1, 2].into_iter();
+    it.by_ref().for_each(drop);
+    assert!(it.into_remaining_vec().is_empty());
+}
+
+#[test]
+fn test_gap_cursor() {
+    let mut v: Vec<String> = vec!["a".into(), "e".into()];
+    {
+        let mut cursor = v.gap_cursor(1);
+        for s in &["b", "c", "d"] {
+            cursor.insert(s.to_string());
+        }
+        assert_eq!(cursor.position(), 4);
+        assert_eq!(cursor.len(), 5);
+        let (front, back) = cursor.as_slices();
+        assert_eq!(front, ["a", "b", "c", "d"]);
+        assert_eq!(back, ["e"]);
+
+        cursor.move_to(1);
+        let (front, back) = cursor.as_slices();
+        assert_eq!(front, ["a"]);
+        assert_eq!(back, ["b", "c", "d", "e"]);
+        assert_eq!(cursor.remove_next().as_ref().map(|s| &s[..]), Some("b"));
+        assert_eq!(cursor.remove_prev().as_ref().map(|s| &s[..]), Some("a"));
+        assert!(cursor.remove_prev().is_none());
+        cursor.move_to(3);
+        assert!(cursor.remove_next().is_none());
+        cursor.insert("f".into());
+        cursor.move_to(2);
+    }
+    assert_eq!(v, ["c", "d", "e", "f"]);
+
+    // Leaking the cursor leaks the elements after it.
+    std::mem::forget(v.gap_cursor(1));
+    assert_eq!(v, ["c"]);
+
+    let mut v = vec![(); 3];
+    {
+        let mut cursor = v.gap_cursor(3);
+        cursor.move_to(0);
+        cursor.insert(());
+        assert_eq!(cursor.remove_next(), Some(()));
+    }
+    assert_eq!(v.len(), 3);
+}
@@ -79,14 +79,16 @@
 
 #![stable(feature = "rust1", since = "1.0.0")]
 
+use core::array::FixedSizeArray;
 use core::cmp::{self, Ordering};
+use core::convert::TryFrom;
 use core::fmt;
 use core::hash::{self, Hash};
 use core::intrinsics::{arith_offset, assume};
-use core::iter::{FromIterator, FusedIterator, TrustedLen};
+use core::iter::{Cloned, FromIterator, FusedIterator, TrustedLen, Flatten, FlatMap};
 use core::marker::PhantomData;
-use core::mem;
-use core::ops::{self, Index, IndexMut, RangeBounds};
+use core::mem::{self, MaybeUninit};
+use core::ops::{self, Index, IndexMut, RangeBounds, Try};
 use core::ops::Bound::{Excluded, Included, Unbounded};
 use core::ptr::{self, NonNull};
 use core::slice::{self, SliceIndex};
@@ -509,6 +511,46 @@ impl<T> Vec<T> {
         }
     }
 
+    /// 新しい空の`Vec<T>`を指定された容量で作成することを試みます。
+    ///
+    /// <!-- Tries to construct a new, empty `Vec<T>` with the specified capacity. -->
+    ///
+    /// [`with_capacity`]と同様ですが、メモリを確保できないときは処理を中断する代わりにエラーを返します。
+    ///
+    /// <!-- This is like [`with_capacity`], but returns an error instead of
+    /// aborting if the memory can't be allocated. -->
+    ///
+    /// [`with_capacity`]: #method.with_capacity
+    ///
+    /// # Errors
+    ///
+    /// 容量がオーバーフローする、またはアロケータが失敗を報告するときエラーを返します。
+    ///
+    /// <!-- If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned. -->
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(try_reserve)]
+    /// use std::collections::CollectionAllocErr;
+    ///
+    /// fn squares(n: usize) -> Result<Vec<usize>, CollectionAllocErr> {
+    ///     let mut output = Vec::try_with_capacity(n)?;
+    ///     output.extend((0..n).map(|i| i * i));
+    ///     Ok(output)
+    /// }
+    ///
+    /// assert_eq!(squares(4), Ok(vec![0, 1, 4, 9]));
+    /// assert_eq!(squares(usize::max_value()), Err(CollectionAllocErr::CapacityOverflow));
+    /// ```
+    #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
+    pub fn try_with_capacity(capacity: usize) -> Result<Vec<T>, CollectionAllocErr> {
+        let mut vec = Vec::new();
+        vec.try_reserve_exact(capacity)?;
+        Ok(vec)
+    }
+
     /// `Vec<T>`を他のベクターの生成分から直接作成します。
     ///
     /// <!-- Creates a `Vec<T>` directly from the raw components of another vector. -->
@@ -614,6 +656,98 @@ impl<T> Vec<T> {
         }
     }
 
+    /// `buf`の先頭`len`個の初期化済みの要素を新しいベクターにムーブします。
+    ///
+    /// <!-- Moves the first `len` elements of `buf`, which must be initialized,
+    /// into a new vector. -->
+    ///
+    /// スタック上の固定長バッファで要素を組み立ててから`Vec`に移すためのものです。
+    /// 要素は`transmute`なしにビット単位でコピーされ、アロケーションは一度だけです。
+    ///
+    /// <!-- This is meant for building elements in a fixed-size buffer, e.g. on
+    /// the stack, before moving them into a `Vec`. The elements are copied
+    /// bitwise without any transmute, with a single allocation. -->
+    ///
+    /// # パニック
+    ///
+    /// <!-- # Panics -->
+    ///
+    /// `len`が`buf`の長さより大きい場合パニックします。
+    ///
+    /// <!-- Panics if `len` is greater than the length of `buf`. -->
+    ///
+    /// # 安全性
+    ///
+    /// <!-- # Safety -->
+    ///
+    /// `buf`の先頭`len`個の要素は初期化されていなければなりません。
+    /// 呼び出しの後、それらの要素はムーブ済みであり、未初期化として扱わなければなりません。
+    ///
+    /// <!-- The first `len` elements of `buf` must be initialized. After the call
+    /// they have been moved out, and must be treated as uninitialized. -->
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(maybe_uninit, vec_from_small_buffer)]
+    /// use std::mem::MaybeUninit;
+    ///
+    /// let mut buf: [MaybeUninit<String>; 4] = unsafe {
+    ///     MaybeUninit::uninitialized().into_initialized()
+    /// };
+    /// buf[0].set("a".to_string());
+    /// buf[1].set("b".to_string());
+    ///
+    /// let vec = unsafe { Vec::from_small_buffer(&mut buf, 2) };
+    /// assert_eq!(vec, ["a", "b"]);
+    /// ```
+    #[unstable(feature = "vec_from_small_buffer", issue = "0")]
+    pub unsafe fn from_small_buffer(buf: &mut [MaybeUninit<T>], len: usize) -> Vec<T> {
+        assert!(len <= buf.len(), "`len` is out of bounds of the buffer");
+        let mut vec = Vec::with_capacity(len);
+        ptr::copy_nonoverlapping(buf.as_ptr() as *const T, vec.as_mut_ptr(), len);
+        vec.set_len(len);
+        vec
+    }
+
+    /// 長さがちょうど配列`A`の長さであるベクターを配列に変換します。
+    /// 長さが異なる場合はベクターをそのまま返します。
+    ///
+    /// <!-- Converts a vector whose length is exactly the length of the array `A`
+    /// into that array. If the length differs, the vector is given back
+    /// unchanged. -->
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_try_into_array)]
+    ///
+    /// let vec = vec![1, 2, 3];
+    /// let array: [i32; 3] = vec.try_into_array().unwrap();
+    /// assert_eq!(array, [1, 2, 3]);
+    ///
+    /// let vec = vec![1, 2, 3];
+    /// let vec = vec.try_into_array::<[i32; 2]>().unwrap_err();
+    /// assert_eq!(vec, [1, 2, 3]);
+    /// ```
+    #[unstable(feature = "vec_try_into_array", issue = "0")]
+    pub fn try_into_array<A>(mut self) -> Result<A, Vec<T>>
+        where A: FixedSizeArray<T>,
+              for<'a> &'a A: TryFrom<&'a [T]>
+    {
+        // `A` is an array of `T`, so this only checks the length.
+        let array = match <&A>::try_from(&self[..]) {
+            Ok(array) => array as *const A,
+            Err(_) => return Err(self),
+        };
+        unsafe {
+            // The elements are moved into the array, and the buffer is freed
+            // when `self` is dropped.
+            self.set_len(0);
+            Ok(ptr::read(array))
+        }
+    }
+
     /// ベクターがメモリの再確保なしに持つことのできる要素の数を返します。
     ///
     /// <!-- Returns the number of elements the vector can hold without
@@ -871,6 +1005,41 @@ impl<T> Vec<T> {
         self.buf.shrink_to_fit(cmp::max(self.len, min_capacity));
     }
 
+    /// 容量が長さの`factor`倍より大きいときに限り、容量を長さまで縮小します。縮小したときは`true`を返します。
+    ///
+    /// <!-- Shrinks the capacity of the vector to its length, but only if the
+    /// capacity is more than `factor` times the length. Returns `true` if
+    /// the vector was shrunk. -->
+    ///
+    /// 長く使われるバッファから定期的にメモリを回収するためのものです。長さが増減を繰り返していても、`factor`が2以上であれば、縮小と拡張の度に再アロケーションが起きることはありません。拡張は容量を倍にするため、縮小した直後にまた縮小されることはないからです。
+    ///
+    /// <!-- This is meant for periodically reclaiming memory from long-lived
+    /// buffers. With a `factor` of at least 2, a length going up and down
+    /// doesn't cause a reallocation on each change, as growing doubles the
+    /// capacity, which isn't wasteful again until the length shrinks by half. -->
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_shrink_if_wasteful)]
+    /// let mut vec = Vec::with_capacity(10);
+    /// vec.extend([1, 2, 3].iter().cloned());
+    ///
+    /// assert!(!vec.shrink_if_wasteful(4));
+    /// assert_eq!(vec.capacity(), 10);
+    /// assert!(vec.shrink_if_wasteful(2));
+    /// assert!(vec.capacity() >= 3);
+    /// ```
+    #[unstable(feature = "vec_shrink_if_wasteful", issue = "0")]
+    pub fn shrink_if_wasteful(&mut self, factor: usize) -> bool {
+        // Zero-sized types never allocate.
+        if mem::size_of::<T>() == 0 || self.capacity() <= self.len.saturating_mul(factor) {
+            return false;
+        }
+        self.shrink_to_fit();
+        true
+    }
+
     /// ベクターを[`Box<[T]>`][owned slice]に変換します。
     ///
     /// <!-- Converts the vector into [`Box<[T]>`][owned slice]. -->
@@ -901,9 +1070,24 @@ impl<T> Vec<T> {
     /// let slice = vec.into_boxed_slice();
     /// assert_eq!(slice.into_vec().capacity(), 3);
     /// ```
+    ///
+    /// 長さと容量が等しいときは、再確保もコピーも行わずにバッファをそのまま使います:
+    ///
+    /// <!-- If the length and the capacity are equal, the buffer is reused as is,
+    /// without reallocating or copying: -->
+    ///
+    /// ```
+    /// let vec = vec![1, 2, 3];
+    /// assert_eq!(vec.len(), vec.capacity());
+    ///
+    /// let ptr = vec.as_ptr();
+    /// let slice = vec.into_boxed_slice();
+    /// assert_eq!(slice.as_ptr(), ptr);
+    /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn into_boxed_slice(mut self) -> Box<[T]> {
         unsafe {
+            // This doesn't reallocate if there is no excess capacity.
             self.shrink_to_fit();
             let buf = ptr::read(&self.buf);
             mem::forget(self);
@@ -911,6 +1095,54 @@ impl<T> Vec<T> {
         }
     }
 
+    /// ベクターを消費してリークし、中身への可変参照`&'a mut [T]`を返します。
+    /// 型`T`は選ばれたライフタイム`'a`より長生きしなければなりません。
+    /// 型が`'static`な参照しか持たないか、参照を全く持たない場合は、`'static`を選ぶことができます。
+    ///
+    /// <!-- Consumes and leaks the vector, returning a mutable reference to the contents,
+    /// `&'a mut [T]`. Note that the type `T` must outlive the chosen lifetime
+    /// `'a`. If the type has only static references, or none at all, then this
+    /// may be chosen to be `'static`. -->
+    ///
+    /// この関数は主にプログラムの残りの期間にわたって生存するデータ、
+    /// 例えば起動時に作られる検索用の表などに役立ちます。
+    /// 返された参照を解放するとメモリリークになります。
+    ///
+    /// <!-- This function is mainly useful for data that lives for the remainder of
+    /// the program's life, such as lookup tables built at startup. Dropping the
+    /// returned reference will cause a memory leak. -->
+    ///
+    /// [`into_boxed_slice`]と違い、余剰の容量は落とされずに一緒にリークされるので、
+    /// 再確保やコピーは決して行われません。余剰の容量を解放したい場合は先に[`shrink_to_fit`]を呼び出してください。
+    ///
+    /// <!-- Unlike [`into_boxed_slice`], this doesn't drop any excess capacity, which is
+    /// leaked along with the contents, so it never reallocates or copies. Call
+    /// [`shrink_to_fit`] first if the excess capacity should be freed. -->
+    ///
+    /// [`into_boxed_slice`]: #method.into_boxed_slice
+    /// [`shrink_to_fit`]: #method.shrink_to_fit
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_leak)]
+    ///
+    /// let table: &'static mut [u32] = (0..256).map(|i| i * i).collect::<Vec<_>>().leak();
+    /// table[0] = 1;
+    /// assert_eq!(table[..3], [1, 1, 4]);
+    /// ```
+    #[unstable(feature = "vec_leak", issue = "0")]
+    #[inline]
+    pub fn leak<'a>(mut self) -> &'a mut [T]
+    where
+        T: 'a // Technically not needed, but kept to be explicit.
+    {
+        let ptr = self.as_mut_ptr();
+        let len = self.len;
+        mem::forget(self);
+        unsafe { slice::from_raw_parts_mut(ptr, len) }
+    }
+
     /// 最初の`len`個の要素を保ったまま、残りを破棄することでベクターを短くします。
     ///
     /// <!-- Shortens the vector, keeping the first `len` elements and dropping
@@ -1177,6 +1409,132 @@ impl<T> Vec<T> {
         self.len = new_len;
     }
 
+    /// ベクターの未使用の容量を`MaybeUninit<T>`のスライスとして返します。
+    ///
+    /// <!-- Returns the spare capacity of the vector as a slice of
+    /// `MaybeUninit<T>`. -->
+    ///
+    /// 返されたスライスはベクターに要素を書き込むのに使えます。例えばFFIの関数に渡して埋めてもらい、
+    /// その後[`set_len`]で初期化した要素をベクターの一部にします。
+    ///
+    /// <!-- The returned slice can be used to fill the vector with data, e.g.
+    /// by passing it to an FFI function, before marking the data as
+    /// initialized using the [`set_len`] method. -->
+    ///
+    /// [`set_len`]: #method.set_len
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_spare_capacity, maybe_uninit)]
+    ///
+    /// // 10要素分の十分な大きさのベクターを確保します。
+    /// let mut v = Vec::with_capacity(10);
+    ///
+    /// // 最初の3要素を埋めます。
+    /// let uninit = v.spare_capacity_mut();
+    /// uninit[0].set(0);
+    /// uninit[1].set(1);
+    /// uninit[2].set(2);
+    ///
+    /// // 最初の3要素を初期化済みとします。
+    /// unsafe {
+    ///     v.set_len(3);
+    /// }
+    ///
+    /// assert_eq!(&v, &[0, 1, 2]);
+    /// ```
+    ///
+    /// <!-- ``` -->
+    /// <!-- #![feature(vec_spare_capacity, maybe_uninit)]
+    ///
+    /// // Allocate vector big enough for 10 elements.
+    /// let mut v = Vec::with_capacity(10);
+    ///
+    /// // Fill in the first 3 elements.
+    /// let uninit = v.spare_capacity_mut();
+    /// uninit[0].set(0);
+    /// uninit[1].set(1);
+    /// uninit[2].set(2);
+    ///
+    /// // Mark the first 3 elements of the vector as being initialized.
+    /// unsafe {
+    ///     v.set_len(3);
+    /// }
+    ///
+    /// assert_eq!(&v, &[0, 1, 2]); -->
+    /// <!-- ``` -->
+    #[inline]
+    #[unstable(feature = "vec_spare_capacity", issue = "0")]
+    pub fn spare_capacity_mut(&mut self) -> &mut [mem::MaybeUninit<T>] {
+        self.split_at_spare_mut().1
+    }
+
+    /// ベクターの中身を初期化済みの要素のスライスと未使用の容量の`MaybeUninit<T>`のスライスとして返します。
+    ///
+    /// <!-- Returns the contents of the vector as a slice of its initialized
+    /// elements and a slice of `MaybeUninit<T>` for its spare capacity. -->
+    ///
+    /// [`spare_capacity_mut`]と違い、未使用の容量に書き込みながら既存の要素を参照できます。
+    ///
+    /// <!-- Unlike [`spare_capacity_mut`], this allows reading the existing
+    /// elements while writing into the spare capacity. -->
+    ///
+    /// [`spare_capacity_mut`]: #method.spare_capacity_mut
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_spare_capacity, maybe_uninit)]
+    ///
+    /// let mut v = Vec::with_capacity(6);
+    /// v.extend_from_slice(&[1, 2, 3]);
+    ///
+    /// // 既存の要素を2倍にしたものを後ろに書き込みます。
+    /// let (init, spare) = v.split_at_spare_mut();
+    /// for (x, slot) in init.iter().zip(spare) {
+    ///     slot.set(x * 2);
+    /// }
+    ///
+    /// unsafe {
+    ///     v.set_len(6);
+    /// }
+    ///
+    /// assert_eq!(&v, &[1, 2, 3, 2, 4, 6]);
+    /// ```
+    ///
+    /// <!-- ``` -->
+    /// <!-- #![feature(vec_spare_capacity, maybe_uninit)]
+    ///
+    /// let mut v = Vec::with_capacity(6);
+    /// v.extend_from_slice(&[1, 2, 3]);
+    ///
+    /// // Write the doubled elements after the existing ones.
+    /// let (init, spare) = v.split_at_spare_mut();
+    /// for (x, slot) in init.iter().zip(spare) {
+    ///     slot.set(x * 2);
+    /// }
+    ///
+    /// unsafe {
+    ///     v.set_len(6);
+    /// }
+    ///
+    /// assert_eq!(&v, &[1, 2, 3, 2, 4, 6]); -->
+    /// <!-- ``` -->
+    #[inline]
+    #[unstable(feature = "vec_spare_capacity", issue = "0")]
+    pub fn split_at_spare_mut(&mut self) -> (&mut [T], &mut [mem::MaybeUninit<T>]) {
+        let len = self.len;
+        let spare_len = self.capacity() - len;
+        let ptr = self.as_mut_ptr();
+        unsafe {
+            // `MaybeUninit<T>` has the same layout as `T`, and the spare
+            // capacity does not overlap the initialized elements.
+            let spare = ptr.add(len) as *mut mem::MaybeUninit<T>;
+            (slice::from_raw_parts_mut(ptr, len), slice::from_raw_parts_mut(spare, spare_len))
+        }
+    }
+
     /// ベクターから要素を取り除き、その要素を返します。
     ///
     /// <!-- Removes an element from the vector and returns it. -->
@@ -1390,12 +1748,64 @@ impl<T> Vec<T> {
     /// assert_eq!(vec, ["foo", "bar", "baz", "bar"]);
     /// ```
     #[stable(feature = "dedup_by", since = "1.16.0")]
-    pub fn dedup_by<F>(&mut self, same_bucket: F) where F: FnMut(&mut T, &mut T) -> bool {
-        let len = {
-            let (dedup, _) = self.as_mut_slice().partition_dedup_by(same_bucket);
-            dedup.len()
-        };
-        self.truncate(len);
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F) where F: FnMut(&mut T, &mut T) -> bool {
+        let len = self.len();
+        if len <= 1 {
+            return;
+        }
+
+        // Elements in `read..len` have not been looked at yet, elements in
+        // `write..read` have been dropped or moved out, and `0..write` is the
+        // deduplicated prefix. Invariant: `len >= read >= write >= 1`.
+        struct FillGapOnDrop<'a, T: 'a> {
+            read: usize,
+            write: usize,
+            vec: &'a mut Vec<T>,
+        }
+
+        impl<'a, T> Drop for FillGapOnDrop<'a, T> {
+            fn drop(&mut self) {
+                // Only reached if `same_bucket` or a destructor panics: shift
+                // the unchecked tail down over the gap so that no element is
+                // dropped twice or leaked.
+                unsafe {
+                    let ptr = self.vec.as_mut_ptr();
+                    let len = self.vec.len();
+                    let items_left = len.wrapping_sub(self.read);
+                    ptr::copy(ptr.add(self.read), ptr.add(self.write), items_left);
+                    let dropped = self.read.wrapping_sub(self.write);
+                    self.vec.set_len(len - dropped);
+                }
+            }
+        }
+
+        let mut gap = FillGapOnDrop { read: 1, write: 1, vec: self };
+        let ptr = gap.vec.as_mut_ptr();
+
+        unsafe {
+            while gap.read < len {
+                let read_ptr = ptr.add(gap.read);
+                let prev_ptr = ptr.add(gap.write - 1);
+
+                if same_bucket(&mut *read_ptr, &mut *prev_ptr) {
+                    // Advance before dropping, so a panicking destructor does
+                    // not make the guard drop the element again.
+                    gap.read += 1;
+                    ptr::drop_in_place(read_ptr);
+                } else {
+                    // Nothing has been removed yet as long as the cursors are
+                    // equal, and the element is already in place.
+                    if gap.read != gap.write {
+                        ptr::copy_nonoverlapping(read_ptr, ptr.add(gap.write), 1);
+                    }
+                    gap.write += 1;
+                    gap.read += 1;
+                }
+            }
+
+            gap.vec.set_len(gap.write);
+            mem::forget(gap);
+        }
     }
 
     /// 要素をコレクションの末尾に加えます。
@@ -1740,6 +2150,74 @@ impl<T> Vec<T> {
             self.truncate(new_len);
         }
     }
+
+    /// `Result`や`Option`のイテレータの成功値を`Vec`の末尾に追加します。最初の`Err`または`None`で追加を止め、そのエラーを返します。
+    ///
+    /// <!-- Appends the successful values of an iterator of `Result`s or
+    /// `Option`s to the `Vec`, stopping at the first `Err` or `None` and
+    /// returning the error. -->
+    ///
+    /// エラーが起きたときは、それまでに追加された要素は取り除かれ、`Vec`は呼び出し前の内容に戻りますが、確保された容量はそのまま残ります。そのため、同じ`Vec`を使って再試行するときに再アロケーションが起きません。`collect::<Result<Vec<_>, _>>()`ではエラーのときに`Vec`ごと捨てられます。
+    ///
+    /// <!-- If an error occurs, the elements appended so far are removed, so
+    /// that the `Vec` has the same contents as before the call, but the
+    /// allocated capacity is kept. Retrying with the same `Vec` therefore
+    /// doesn't allocate again, whereas `collect::<Result<Vec<_>, _>>()`
+    /// drops the whole `Vec` on error. -->
+    ///
+    /// `None`で止まったときのエラーは[`NoneError`]なので、`Option`を返す関数の中では`?`で`None`に変換できます。
+    ///
+    /// <!-- Stopping at a `None` returns a [`NoneError`], which `?` turns back
+    /// into `None` in a function returning `Option`. -->
+    ///
+    /// # パニック
+    ///
+    /// <!-- # Panics -->
+    ///
+    /// イテレータがパニックしたときは、それまでに追加された要素は`Vec`に残ります。
+    ///
+    /// <!-- If the iterator panics, the elements appended so far stay in the
+    /// `Vec`. -->
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_try_collect_into)]
+    /// let mut vec = vec![0];
+    ///
+    /// let res = Vec::try_collect_into(&mut vec, "1 2 x".split(' ').map(|s| s.parse::<i32>()));
+    /// assert!(res.is_err());
+    /// assert_eq!(vec, [0]);
+    ///
+    /// let res = Vec::try_collect_into(&mut vec, "1 2 3".split(' ').map(|s| s.parse::<i32>()));
+    /// assert!(res.is_ok());
+    /// assert_eq!(vec, [0, 1, 2, 3]);
+    ///
+    /// let res = vec.try_collect_into(vec![Some(4), None]);
+    /// assert!(res.is_err());
+    /// assert_eq!(vec, [0, 1, 2, 3]);
+    /// ```
+    ///
+    /// [`NoneError`]: ../../std/option/struct.NoneError.html
+    #[unstable(feature = "vec_try_collect_into", issue = "0")]
+    pub fn try_collect_into<I, R>(&mut self, iter: I) -> Result<(), R::Error>
+        where I: IntoIterator<Item = R>,
+              R: Try<Ok = T>
+    {
+        let len = self.len();
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for element in iter {
+            match element.into_result() {
+                Ok(element) => self.push(element),
+                Err(e) => {
+                    self.truncate(len);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<T: Clone> Vec<T> {
@@ -2005,6 +2483,96 @@ impl<T: PartialEq> Vec<T> {
     }
 }
 
+impl<T: Ord> Vec<T> {
+    /// ソートされたベクターに、順序を保つように`value`を挿入し、その位置を返します。
+    ///
+    /// <!-- Inserts `value` into a sorted vector, keeping it sorted, and returns
+    /// the position it was inserted at. -->
+    ///
+    /// `value`と等しい要素が既にあるときは、それらの後に挿入されます。ベクターがソートされていないとき、挿入位置は規定されません。
+    ///
+    /// <!-- If the vector already contains elements equal to `value`, it is
+    /// inserted after them. If the vector is not sorted, the position is
+    /// unspecified. -->
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_sorted)]
+    /// let mut vec = vec![1, 3, 3, 5];
+    ///
+    /// assert_eq!(vec.insert_sorted(4), 3);
+    /// assert_eq!(vec.insert_sorted(3), 3);
+    /// assert_eq!(vec, [1, 3, 3, 3, 4, 5]);
+    /// ```
+    #[unstable(feature = "vec_sorted", issue = "0")]
+    pub fn insert_sorted(&mut self, value: T) -> usize {
+        self.insert_sorted_by(value, |a, b| a.cmp(b))
+    }
+
+    /// ソートされたベクターが`value`と等しい要素を含むとき`true`を返します。
+    ///
+    /// <!-- Returns `true` if the sorted vector contains an element equal to
+    /// `value`. -->
+    ///
+    /// 二分探索を用いるため、ベクターがソートされていないときの結果は規定されません。
+    ///
+    /// <!-- This uses a binary search, so if the vector is not sorted, the result
+    /// is unspecified. -->
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_sorted)]
+    /// let vec = vec![1, 3, 5];
+    ///
+    /// assert!(vec.contains_sorted(&3));
+    /// assert!(!vec.contains_sorted(&4));
+    /// ```
+    #[unstable(feature = "vec_sorted", issue = "0")]
+    pub fn contains_sorted(&self, value: &T) -> bool {
+        self.binary_search(value).is_ok()
+    }
+}
+
+impl<T> Vec<T> {
+    /// 比較関数`compare`に従ってソートされたベクターに、順序を保つように`value`を挿入し、その位置を返します。
+    ///
+    /// <!-- Inserts `value` into a vector sorted according to the comparator
+    /// function `compare`, keeping it sorted, and returns the position it was
+    /// inserted at. -->
+    ///
+    /// `value`と等しい要素が既にあるときは、それらの後に挿入されます。
+    ///
+    /// <!-- If the vector already contains elements equal to `value`, it is
+    /// inserted after them. -->
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_sorted)]
+    /// let mut vec = vec![5, 3, 1];
+    ///
+    /// assert_eq!(vec.insert_sorted_by(4, |a, b| b.cmp(a)), 1);
+    /// assert_eq!(vec, [5, 4, 3, 1]);
+    /// ```
+    #[unstable(feature = "vec_sorted", issue = "0")]
+    pub fn insert_sorted_by<F>(&mut self, value: T, mut compare: F) -> usize
+        where F: FnMut(&T, &T) -> Ordering
+    {
+        // Never report a match, so that the search ends after all the
+        // elements which are not greater than `value`.
+        let index = match self.binary_search_by(|probe| match compare(probe, &value) {
+            Ordering::Greater => Ordering::Greater,
+            Ordering::Less | Ordering::Equal => Ordering::Less,
+        }) {
+            Ok(index) | Err(index) => index,
+        };
+        self.insert(index, value);
+        index
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Internal methods and functions
 ////////////////////////////////////////////////////////////////////////////////
@@ -2061,7 +2629,28 @@ impl<T: Clone + IsZero> SpecFromElem for T {
     }
 }
 
-unsafe trait IsZero {
+/// 全ビットがゼロの表現を持つ値を判定するトレイト。
+///
+/// <!-- A trait for values whose all-zero bit pattern is a valid value. -->
+///
+/// `vec![elem; n]`は`elem.is_zero()`が`true`を返すとき、要素を一つずつ書き込む代わりに
+/// ゼロ初期化されたメモリを確保します (多くのプラットフォームでは`calloc`に相当します)。
+///
+/// <!-- `vec![elem; n]` allocates zeroed memory instead of writing each element
+/// when `elem.is_zero()` returns `true` (the equivalent of `calloc` on most
+/// platforms). -->
+///
+/// # Safety
+///
+/// `is_zero`が`true`を返すのは、`self`の表現が全ビットゼロであり、
+/// 全ビットゼロのメモリを`Self`の値として読めるときに限らなければなりません。
+///
+/// <!-- `is_zero` must only return `true` when the representation of `self` is
+/// all zero bits, and all-zero memory can be read as a value of `Self`. -->
+#[unstable(feature = "vec_is_zero",
+           reason = "internal specialization hook for `vec![x; n]`",
+           issue = "0")]
+pub unsafe trait IsZero {
     /// この値がゼロかどうか
     ///
     /// <!-- Whether this value is zero -->
@@ -2112,6 +2701,46 @@ unsafe impl<T: ?Sized> IsZero for *mut T {
     }
 }
 
+// `Option<&T>`, `Option<Box<T>>` and `Option<NonNull<T>>` are guaranteed to
+// use the null pointer for `None`, so a zeroed allocation is a slice of
+// `None`s.
+unsafe impl<T: ?Sized> IsZero for Option<&T> {
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.is_none()
+    }
+}
+
+unsafe impl<T: ?Sized> IsZero for Option<Box<T>> {
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.is_none()
+    }
+}
+
+unsafe impl<T: ?Sized> IsZero for Option<NonNull<T>> {
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.is_none()
+    }
+}
+
+macro_rules! impl_is_zero_option_of_nonzero {
+    ($($t:ident),+) => {$(
+        unsafe impl IsZero for Option<core::num::$t> {
+            #[inline]
+            fn is_zero(&self) -> bool {
+                self.is_none()
+            }
+        }
+    )+};
+}
+
+impl_is_zero_option_of_nonzero!(
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize
+);
+
 
 ////////////////////////////////////////////////////////////////////////////////
 // Common trait implementations for Vec
@@ -2292,16 +2921,36 @@ trait SpecExtend<T, I> {
 impl<T, I> SpecExtend<T, I> for Vec<T>
     where I: Iterator<Item=T>,
 {
-    default fn from_iter(mut iterator: I) -> Self {
+    default fn from_iter(iterator: I) -> Self {
+        SpecFlatten::collect_vec(iterator)
+    }
+
+    default fn spec_extend(&mut self, iter: I) {
+        SpecFlatten::extend_vec(iter, self)
+    }
+}
+
+// Specialization trait used for Vec::from_iter and Vec::extend with iterators
+// that are not TrustedLen, to pick out flattened vectors. This can't be done
+// with SpecExtend directly, as Flatten might become TrustedLen upstream.
+trait SpecFlatten<T>: Iterator<Item = T> + Sized {
+    fn collect_vec(self) -> Vec<T>;
+    fn extend_vec(self, vec: &mut Vec<T>);
+}
+
+impl<T, I> SpecFlatten<T> for I
+    where I: Iterator<Item=T>,
+{
+    default fn collect_vec(mut self) -> Vec<T> {
         // Unroll the first iteration, as the vector is going to be
         // expanded on this iteration in every case when the iterable is not
         // empty, but the loop in extend_desugared() is not going to see the
         // vector being full in the few subsequent loop iterations.
         // So we get better branch prediction.
-        let mut vector = match iterator.next() {
+        let mut vector = match self.next() {
             None => return Vec::new(),
             Some(element) => {
-                let (lower, _) = iterator.size_hint();
+                let (lower, _) = self.size_hint();
                 let mut vector = Vec::with_capacity(lower.saturating_add(1));
                 unsafe {
                     ptr::write(vector.get_unchecked_mut(0), element);
@@ -2310,12 +2959,126 @@ impl<T, I> SpecExtend<T, I> for Vec<T>
                 vector
             }
         };
-        <Vec<T> as SpecExtend<T, I>>::spec_extend(&mut vector, iterator);
+        <Vec<T> as SpecExtend<T, I>>::spec_extend(&mut vector, self);
         vector
     }
 
-    default fn spec_extend(&mut self, iter: I) {
-        self.extend_desugared(iter)
+    default fn extend_vec(self, vec: &mut Vec<T>) {
+        vec.extend_desugared(self)
+    }
+}
+
+impl<T, I> SpecFlatten<T> for Flatten<I>
+    where I: Iterator<Item=Vec<T>>,
+{
+    fn collect_vec(self) -> Vec<T> {
+        let (front, iter, back) = self.into_parts();
+        Vec::from_flattened_parts(front, iter, back)
+    }
+
+    fn extend_vec(self, vec: &mut Vec<T>) {
+        let (front, iter, back) = self.into_parts();
+        vec.extend_flattened_parts(front, iter, back)
+    }
+}
+
+impl<T, I, F> SpecFlatten<T> for FlatMap<I, Vec<T>, F>
+    where I: Iterator,
+          F: FnMut(I::Item) -> Vec<T>,
+{
+    fn collect_vec(self) -> Vec<T> {
+        let (front, iter, back) = self.into_parts();
+        Vec::from_flattened_parts(front, iter, back)
+    }
+
+    fn extend_vec(self, vec: &mut Vec<T>) {
+        let (front, iter, back) = self.into_parts();
+        vec.extend_flattened_parts(front, iter, back)
+    }
+}
+
+impl<T> Vec<T> {
+    // Collects the elements of a flattened iterator of vectors, given its
+    // partially consumed front and back iterators and the remaining vectors.
+    // Elements are moved with one copy per vector into an allocation of
+    // exactly the total length, or into the largest of the vectors when it
+    // is big enough to hold them all.
+    fn from_flattened_parts<I>(front: Option<IntoIter<T>>,
+                               iter: I,
+                               back: Option<IntoIter<T>>) -> Vec<T>
+        where I: Iterator<Item=Vec<T>>,
+    {
+        let mut front = front;
+        let mut vecs: Vec<Vec<T>> = iter.collect();
+        let front_len = front.as_ref().map_or(0, |it| it.len());
+        let back_len = back.as_ref().map_or(0, |it| it.len());
+        let total = vecs.iter()
+            .try_fold(front_len, |total, v| total.checked_add(v.len()))
+            .and_then(|total| total.checked_add(back_len))
+            .expect("capacity overflow");
+
+        let largest = vecs.iter()
+            .enumerate()
+            .max_by_key(|&(_, v)| v.capacity())
+            .map(|(i, _)| i);
+        match largest {
+            Some(i) if vecs[i].capacity() >= total => {
+                let mut result = mem::replace(&mut vecs[i], Vec::new());
+                let before = vecs[..i].iter().fold(front_len, |n, v| n + v.len());
+                unsafe {
+                    // Make room for the elements that come before, then move
+                    // them in. Nothing here can panic, so the length is only
+                    // updated once all elements are in place.
+                    let len = result.len();
+                    let dst = result.as_mut_ptr();
+                    ptr::copy(dst, dst.add(before), len);
+                    let mut offset = 0;
+                    if let Some(ref mut it) = front {
+                        let slice = it.as_slice();
+                        ptr::copy_nonoverlapping(slice.as_ptr(), dst.add(offset), slice.len());
+                        offset += slice.len();
+                        it.ptr = it.end;
+                    }
+                    for v in &mut vecs[..i] {
+                        ptr::copy_nonoverlapping(v.as_ptr(), dst.add(offset), v.len());
+                        offset += v.len();
+                        v.set_len(0);
+                    }
+                    result.set_len(before + len);
+                }
+                result.extend_flattened_parts(None, vecs.into_iter().skip(i + 1), back);
+                result
+            }
+            _ => {
+                let mut result = Vec::with_capacity(total);
+                result.extend_flattened_parts(front, vecs.into_iter(), back);
+                result
+            }
+        }
+    }
+
+    // Extends the vector with the elements of a flattened iterator of
+    // vectors, reserving the total length up front.
+    fn extend_flattened_parts<I>(&mut self,
+                                 front: Option<IntoIter<T>>,
+                                 iter: I,
+                                 back: Option<IntoIter<T>>)
+        where I: Iterator<Item=Vec<T>>,
+    {
+        let mut vecs: Vec<Vec<T>> = iter.collect();
+        let total = vecs.iter()
+            .fold(front.as_ref().map_or(0, |it| it.len()), |n, v| n.saturating_add(v.len()))
+            .saturating_add(back.as_ref().map_or(0, |it| it.len()));
+        self.reserve(total);
+        if let Some(it) = front {
+            self.spec_extend(it);
+        }
+        for v in &mut vecs {
+            self.append(v);
+        }
+        if let Some(it) = back {
+            self.spec_extend(it);
+        }
     }
 }
 
@@ -2409,6 +3172,30 @@ impl<'a, T: 'a> SpecExtend<&'a T, slice::Iter<'a, T>> for Vec<T>
     }
 }
 
+impl<'a, T: 'a> SpecExtend<T, Cloned<slice::Iter<'a, T>>> for Vec<T>
+    where T: Copy,
+{
+    fn from_iter(iterator: Cloned<slice::Iter<'a, T>>) -> Self {
+        let slice = iterator.__inner().as_slice();
+        let mut vector = Vec::with_capacity(slice.len());
+        unsafe {
+            ptr::copy_nonoverlapping(slice.as_ptr(), vector.as_mut_ptr(), slice.len());
+            vector.set_len(slice.len());
+        }
+        vector
+    }
+
+    fn spec_extend(&mut self, iterator: Cloned<slice::Iter<'a, T>>) {
+        let slice = iterator.__inner().as_slice();
+        self.reserve(slice.len());
+        unsafe {
+            let len = self.len();
+            ptr::copy_nonoverlapping(slice.as_ptr(), self.as_mut_ptr().add(len), slice.len());
+            self.set_len(len + slice.len());
+        }
+    }
+}
+
 impl<T> Vec<T> {
     fn extend_desugared<I: Iterator<Item = T>>(&mut self, mut iterator: I) {
         // This is the case for a general iterator.
@@ -2587,6 +3374,110 @@ impl<T> Vec<T> {
             pred: filter,
         }
     }
+
+    /// ベクターの最初の要素を指す、前方にのみ移動するカーソルを作成します。
+    /// カーソルを使うと、ベクターを走査しながら現在の要素を取り除いたり、その直前に要素を挿入したりできます。
+    ///
+    /// <!-- Creates a forward-only cursor pointing at the first element of the vector.
+    /// The cursor lets you walk the vector while removing the current element or
+    /// inserting elements just before it. -->
+    ///
+    /// 取り除かれた要素の後ろにある要素は、カーソルが通過するときに一つずつ詰められるので、
+    /// 連続して要素を取り除いても各要素が移動されるのは高々一度です。
+    /// インデックスを手作業で調整しながら`remove`を呼び出す場合は、取り除くたびに後ろの要素がすべて移動されます。
+    ///
+    /// <!-- The elements after removed ones are moved into place one at a time as the
+    /// cursor passes them, so every element is moved at most once however many
+    /// elements are removed, whereas calling `remove` while adjusting indices by hand
+    /// moves all the following elements on every removal. -->
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_cursor)]
+    /// let mut vec = vec![1, 2, 3, 4, 5, 6];
+    ///
+    /// let mut cursor = vec.cursor_mut();
+    /// while let Some(&mut x) = cursor.current() {
+    ///     if x % 2 == 0 {
+    ///         cursor.remove_current();
+    ///     } else {
+    ///         cursor.insert_before(x * 10);
+    ///         cursor.move_next();
+    ///     }
+    /// }
+    /// drop(cursor);
+    ///
+    /// assert_eq!(vec, [10, 1, 30, 3, 50, 5]);
+    /// ```
+    #[unstable(feature = "vec_cursor", issue = "0")]
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        let old_len = self.len();
+
+        // Guard against us getting leaked (leak amplification)
+        unsafe { self.set_len(0); }
+
+        CursorMut {
+            vec: self,
+            write: 0,
+            read: 0,
+            old_len,
+        }
+    }
+
+    /// `index`の位置に隙間を開けたカーソルを作成します。カーソルの位置への挿入と削除は償却O(1)です。
+    ///
+    /// <!-- Creates a cursor with a gap opened at `index`. Insertions and removals at
+    /// the cursor take amortized O(1) time. -->
+    ///
+    /// `index`以降の要素はバッファの末尾に移動され、空いた容量が隙間になります。
+    /// カーソルを動かすと、その距離の分だけ要素が隙間の反対側に移動されます。
+    /// カーソルが破棄されると隙間が閉じられます。
+    /// 同じ位置の周りで何度も`insert`や`remove`を呼び出す場合は、そのたびに後ろの要素がすべて移動されます。
+    ///
+    /// <!-- The elements from `index` on are moved to the end of the buffer, and the
+    /// spare capacity becomes the gap. Moving the cursor moves as many elements as
+    /// its distance to the other side of the gap. The gap is closed when the cursor
+    /// is dropped. Calling `insert` or `remove` repeatedly around the same position
+    /// instead moves all the following elements every time. -->
+    ///
+    /// # パニック
+    ///
+    /// <!-- # Panics -->
+    ///
+    /// `index`が長さより大きい場合パニックします。
+    ///
+    /// <!-- Panics if `index` is greater than the length. -->
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_gap_cursor)]
+    /// let mut vec = vec![1, 2, 6];
+    ///
+    /// let mut cursor = vec.gap_cursor(2);
+    /// cursor.insert(3);
+    /// cursor.insert(4);
+    /// cursor.insert(5);
+    /// cursor.move_to(0);
+    /// cursor.insert(0);
+    /// assert_eq!(cursor.remove_next(), Some(1));
+    /// drop(cursor);
+    ///
+    /// assert_eq!(vec, [0, 2, 3, 4, 5, 6]);
+    /// ```
+    #[unstable(feature = "vec_gap_cursor", issue = "0")]
+    pub fn gap_cursor(&mut self, index: usize) -> GapCursor<'_, T> {
+        let len = self.len();
+        assert!(index <= len, "`index` is out of bounds");
+
+        // Guard against us getting leaked (leak amplification)
+        unsafe { self.set_len(index); }
+
+        let mut cursor = GapCursor { vec: self, tail_len: 0 };
+        cursor.open(len - index);
+        cursor
+    }
 }
 
 /// Vecに要素をプッシュする前に参照からコピーするExtendの実装です。
@@ -2893,6 +3784,41 @@ impl<T> IntoIter<T> {
             slice::from_raw_parts_mut(self.ptr as *mut T, self.len())
         }
     }
+
+    /// このイテレータの残りの要素を、元のベクターのアロケーションを再利用した`Vec`に変換します。
+    ///
+    /// <!-- Converts the remaining items of this iterator into a `Vec`, reusing
+    /// the allocation of the original vector. -->
+    ///
+    /// 残りの要素はバッファの先頭にムーブされます。新たなアロケーションは行われず、容量は元のベクターのままです。
+    ///
+    /// <!-- The remaining items are moved to the front of the buffer. No new
+    /// allocation is made, and the capacity is that of the original vector. -->
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_into_remaining_vec)]
+    /// let vec = vec!['a', 'b', 'c'];
+    /// let mut into_iter = vec.into_iter();
+    /// assert_eq!(into_iter.next(), Some('a'));
+    ///
+    /// let vec = into_iter.into_remaining_vec();
+    /// assert_eq!(vec, ['b', 'c']);
+    /// assert_eq!(vec.capacity(), 3);
+    /// ```
+    #[unstable(feature = "vec_into_remaining_vec", issue = "0")]
+    pub fn into_remaining_vec(self) -> Vec<T> {
+        let len = self.len();
+        let iter = mem::ManuallyDrop::new(self);
+        unsafe {
+            let buf = iter.buf.as_ptr();
+            if mem::size_of::<T>() != 0 {
+                ptr::copy(iter.ptr, buf, len);
+            }
+            Vec::from_raw_parts(buf, len, iter.cap)
+        }
+    }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -3272,3 +4198,327 @@ impl<T, F> Drop for DrainFilter<'_, T, F>
         }
     }
 }
+
+/// Vecで`cursor_mut`を呼び出すと得られるカーソル。
+///
+/// <!-- A cursor produced by calling `cursor_mut` on Vec. -->
+///
+/// カーソルが破棄されるまで、ベクターの長さは0に見えます。
+///
+/// <!-- The vector appears to be empty until the cursor is dropped. -->
+#[unstable(feature = "vec_cursor", issue = "0")]
+pub struct CursorMut<'a, T: 'a> {
+    vec: &'a mut Vec<T>,
+    // The elements the cursor has passed are at `..write`, and the current
+    // element and the ones after it at `read..old_len`. The gap in between is
+    // what has been removed and not yet filled by insertions.
+    write: usize,
+    read: usize,
+    old_len: usize,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    /// カーソルが破棄された後のベクターにおける、現在の要素のインデックスを返します。
+    /// カーソルが末尾に達している場合は`None`を返します。
+    ///
+    /// <!-- Returns the index the current element will have in the vector once the
+    /// cursor is dropped, or `None` if the cursor is at the end. -->
+    #[unstable(feature = "vec_cursor", issue = "0")]
+    pub fn index(&self) -> Option<usize> {
+        if self.read < self.old_len {
+            Some(self.write)
+        } else {
+            None
+        }
+    }
+
+    /// 現在の要素への参照を返します。カーソルが末尾に達している場合は`None`を返します。
+    ///
+    /// <!-- Returns a reference to the current element, or `None` if the cursor is
+    /// at the end. -->
+    #[unstable(feature = "vec_cursor", issue = "0")]
+    pub fn current(&mut self) -> Option<&mut T> {
+        if self.read < self.old_len {
+            unsafe { Some(&mut *self.vec.as_mut_ptr().add(self.read)) }
+        } else {
+            None
+        }
+    }
+
+    /// 現在の要素の次の要素への参照を、カーソルを動かさずに返します。
+    ///
+    /// <!-- Returns a reference to the element after the current one, without
+    /// moving the cursor. -->
+    #[unstable(feature = "vec_cursor", issue = "0")]
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        if self.read + 1 < self.old_len {
+            unsafe { Some(&mut *self.vec.as_mut_ptr().add(self.read + 1)) }
+        } else {
+            None
+        }
+    }
+
+    /// カーソルを次の要素に進めます。カーソルが末尾に達している場合は何もしません。
+    ///
+    /// <!-- Moves the cursor to the next element. Does nothing if the cursor is at
+    /// the end. -->
+    #[unstable(feature = "vec_cursor", issue = "0")]
+    pub fn move_next(&mut self) {
+        if self.read < self.old_len {
+            if self.write != self.read {
+                unsafe {
+                    let ptr = self.vec.as_mut_ptr();
+                    ptr::copy_nonoverlapping(ptr.add(self.read), ptr.add(self.write), 1);
+                }
+            }
+            self.write += 1;
+            self.read += 1;
+        }
+    }
+
+    /// 現在の要素を取り除いて返し、カーソルを次の要素に進めます。
+    /// カーソルが末尾に達している場合は`None`を返します。
+    ///
+    /// <!-- Removes and returns the current element, moving the cursor to the next
+    /// element. Returns `None` if the cursor is at the end. -->
+    #[unstable(feature = "vec_cursor", issue = "0")]
+    pub fn remove_current(&mut self) -> Option<T> {
+        if self.read < self.old_len {
+            let elem = unsafe { ptr::read(self.vec.as_ptr().add(self.read)) };
+            self.read += 1;
+            Some(elem)
+        } else {
+            None
+        }
+    }
+
+    /// 現在の要素の直前に要素を挿入します。カーソルは現在の要素を指したままです。
+    ///
+    /// <!-- Inserts an element just before the current one. The cursor keeps pointing
+    /// at the current element. -->
+    ///
+    /// 直前に取り除かれた要素の場所が空いていればそこに書き込みますが、
+    /// そうでなければ現在の要素以降をすべて一つずつ後ろに移動します。
+    ///
+    /// <!-- The element is written into the space left by removed elements if there
+    /// is any, but otherwise the current element and all the ones after it are
+    /// shifted back by one. -->
+    #[unstable(feature = "vec_cursor", issue = "0")]
+    pub fn insert_before(&mut self, value: T) {
+        if self.write == self.read {
+            self.vec.buf.reserve(self.old_len, 1);
+            unsafe {
+                let ptr = self.vec.as_mut_ptr().add(self.read);
+                ptr::copy(ptr, ptr.add(1), self.old_len - self.read);
+            }
+            self.read += 1;
+            self.old_len += 1;
+        }
+        unsafe {
+            ptr::write(self.vec.as_mut_ptr().add(self.write), value);
+        }
+        self.write += 1;
+    }
+}
+
+#[unstable(feature = "vec_cursor", issue = "0")]
+impl<T: fmt::Debug> fmt::Debug for CursorMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ptr = self.vec.as_ptr();
+        let (front, back) = unsafe {
+            (slice::from_raw_parts(ptr, self.write),
+             slice::from_raw_parts(ptr.add(self.read), self.old_len - self.read))
+        };
+        f.debug_tuple("CursorMut")
+         .field(&front)
+         .field(&back)
+         .finish()
+    }
+}
+
+#[unstable(feature = "vec_cursor", issue = "0")]
+impl<T> Drop for CursorMut<'_, T> {
+    fn drop(&mut self) {
+        let tail_len = self.old_len - self.read;
+        unsafe {
+            if self.write != self.read {
+                let ptr = self.vec.as_mut_ptr();
+                ptr::copy(ptr.add(self.read), ptr.add(self.write), tail_len);
+            }
+            self.vec.set_len(self.write + tail_len);
+        }
+    }
+}
+
+/// Vecで`gap_cursor`を呼び出すと得られる、隙間を開けたカーソル。
+///
+/// <!-- A cursor with a gap, produced by calling `gap_cursor` on Vec. -->
+///
+/// カーソルが破棄されるまで、ベクターはカーソルより前の要素だけを含むように見えます。
+///
+/// <!-- The vector appears to hold only the elements before the cursor until the
+/// cursor is dropped. -->
+#[unstable(feature = "vec_gap_cursor", issue = "0")]
+pub struct GapCursor<'a, T: 'a> {
+    // The elements before the cursor are the `len` first ones of `vec`, and
+    // the ones after it the `tail_len` last ones of its buffer.
+    vec: &'a mut Vec<T>,
+    tail_len: usize,
+}
+
+impl<'a, T> GapCursor<'a, T> {
+    /// カーソルの位置、つまりカーソルより前にある要素の数を返します。
+    ///
+    /// <!-- Returns the position of the cursor, i.e., the number of elements before
+    /// it. -->
+    #[unstable(feature = "vec_gap_cursor", issue = "0")]
+    pub fn position(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// カーソルの前後にある要素の総数を返します。
+    ///
+    /// <!-- Returns the total number of elements before and after the cursor. -->
+    #[unstable(feature = "vec_gap_cursor", issue = "0")]
+    pub fn len(&self) -> usize {
+        self.vec.len() + self.tail_len
+    }
+
+    /// カーソルより前の要素と後の要素をスライスとして返します。
+    ///
+    /// <!-- Returns the elements before and after the cursor as slices. -->
+    #[unstable(feature = "vec_gap_cursor", issue = "0")]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        unsafe {
+            let tail = self.vec.as_ptr().add(self.vec.capacity() - self.tail_len);
+            (&self.vec[..], slice::from_raw_parts(tail, self.tail_len))
+        }
+    }
+
+    /// カーソルの位置に要素を挿入し、カーソルをその後ろに進めます。
+    ///
+    /// <!-- Inserts an element at the cursor, moving the cursor after it. -->
+    ///
+    /// 隙間が埋まっている場合はバッファが拡張されます。
+    ///
+    /// <!-- The buffer grows if the gap is full. -->
+    #[unstable(feature = "vec_gap_cursor", issue = "0")]
+    pub fn insert(&mut self, value: T) {
+        let len = self.vec.len();
+        if len + self.tail_len == self.vec.capacity() {
+            let old_cap = self.vec.capacity();
+            // Reallocating keeps the whole buffer, so the elements after the
+            // cursor are then moved to the end of the new one.
+            self.vec.buf.reserve(old_cap, 1);
+            unsafe {
+                let ptr = self.vec.as_mut_ptr();
+                ptr::copy(ptr.add(old_cap - self.tail_len),
+                          ptr.add(self.vec.capacity() - self.tail_len),
+                          self.tail_len);
+            }
+        }
+        unsafe {
+            ptr::write(self.vec.as_mut_ptr().add(len), value);
+            self.vec.set_len(len + 1);
+        }
+    }
+
+    /// カーソルの直前の要素を取り除いて返します。カーソルが先頭にある場合は`None`を返します。
+    ///
+    /// <!-- Removes and returns the element just before the cursor, or `None` if the
+    /// cursor is at the start. -->
+    #[unstable(feature = "vec_gap_cursor", issue = "0")]
+    pub fn remove_prev(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+
+    /// カーソルの直後の要素を取り除いて返します。カーソルが末尾にある場合は`None`を返します。
+    ///
+    /// <!-- Removes and returns the element just after the cursor, or `None` if the
+    /// cursor is at the end. -->
+    #[unstable(feature = "vec_gap_cursor", issue = "0")]
+    pub fn remove_next(&mut self) -> Option<T> {
+        if self.tail_len == 0 {
+            return None;
+        }
+        unsafe {
+            let elem = ptr::read(self.vec.as_ptr().add(self.vec.capacity() - self.tail_len));
+            self.tail_len -= 1;
+            Some(elem)
+        }
+    }
+
+    /// カーソルを`index`の位置に動かします。カーソルが通過した要素が隙間の反対側に移動されます。
+    ///
+    /// <!-- Moves the cursor to position `index`. The elements the cursor passes
+    /// are moved to the other side of the gap. -->
+    ///
+    /// # パニック
+    ///
+    /// <!-- # Panics -->
+    ///
+    /// `index`が要素の総数より大きい場合パニックします。
+    ///
+    /// <!-- Panics if `index` is greater than the total number of elements. -->
+    #[unstable(feature = "vec_gap_cursor", issue = "0")]
+    pub fn move_to(&mut self, index: usize) {
+        assert!(index <= self.len(), "`index` is out of bounds");
+        let len = self.vec.len();
+        unsafe {
+            let ptr = self.vec.as_mut_ptr();
+            let tail = ptr.add(self.vec.capacity() - self.tail_len);
+            if index < len {
+                let count = len - index;
+                ptr::copy(ptr.add(index), tail.sub(count), count);
+                self.tail_len += count;
+            } else {
+                let count = index - len;
+                ptr::copy(tail, ptr.add(len), count);
+                self.tail_len -= count;
+            }
+            self.vec.set_len(index);
+        }
+    }
+
+    /// Moves the elements after the cursor right after the ones before it, and
+    /// returns their number.
+    fn close(&mut self) -> usize {
+        let tail_len = mem::replace(&mut self.tail_len, 0);
+        unsafe {
+            let len = self.vec.len();
+            let ptr = self.vec.as_mut_ptr();
+            ptr::copy(ptr.add(self.vec.capacity() - tail_len), ptr.add(len), tail_len);
+            self.vec.set_len(len + tail_len);
+        }
+        tail_len
+    }
+
+    /// Moves the `tail_len` elements right after the ones before the cursor to
+    /// the end of the buffer.
+    fn open(&mut self, tail_len: usize) {
+        unsafe {
+            let len = self.vec.len();
+            let ptr = self.vec.as_mut_ptr();
+            ptr::copy(ptr.add(len), ptr.add(self.vec.capacity() - tail_len), tail_len);
+        }
+        self.tail_len = tail_len;
+    }
+}
+
+#[unstable(feature = "vec_gap_cursor", issue = "0")]
+impl<T: fmt::Debug> fmt::Debug for GapCursor<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (front, back) = self.as_slices();
+        f.debug_tuple("GapCursor")
+         .field(&front)
+         .field(&back)
+         .finish()
+    }
+}
+
+#[unstable(feature = "vec_gap_cursor", issue = "0")]
+impl<T> Drop for GapCursor<'_, T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
@@ -6,7 +6,8 @@
 //! of an arena is very fast allocation; just a pointer bump.
 //!
 //! This crate implements `TypedArena`, a simple arena that can only hold
-//! objects of a single type.
+//! objects of a single type, and `ArenaVec`, a growable vector whose storage
+//! lives in a `DroplessArena`.
 
 #![doc(html_root_url = "https://doc.rust-lang.org/nightly/",
        test(no_crate_inject, attr(deny(warnings))))]
@@ -30,7 +31,8 @@ use std::cmp;
 use std::intrinsics;
 use std::marker::{PhantomData, Send};
 use std::mem;
-use std::ptr;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
 use std::slice;
 
 use alloc::raw_vec::RawVec;
@@ -122,6 +124,13 @@ impl<T> TypedArena<T> {
 
         self.chunks.borrow().iter().any(|chunk| chunk.start() <= ptr && ptr < chunk.end())
     }
+
+    /// Returns the number of bytes of memory reserved by the chunks of this
+    /// arena, whether or not they have been filled yet.
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.borrow().iter().map(|chunk| chunk.storage.cap() * mem::size_of::<T>()).sum()
+    }
+
     /// Allocates an object in the `TypedArena`, returning a reference to it.
     #[inline]
     pub fn alloc(&self, object: T) -> &mut T {
@@ -308,6 +317,12 @@ impl DroplessArena {
         self.chunks.borrow().iter().any(|chunk| chunk.start() <= ptr && ptr < chunk.end())
     }
 
+    /// Returns the number of bytes of memory reserved by the chunks of this
+    /// arena, whether or not they have been filled yet.
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.borrow().iter().map(|chunk| chunk.storage.cap()).sum()
+    }
+
     #[inline]
     fn align(&self, align: usize) {
         let final_address = ((self.ptr.get() as usize) + align - 1) & !(align - 1);
@@ -412,6 +427,143 @@ impl DroplessArena {
     }
 }
 
+/// A growable vector of `Copy` values whose buffer is allocated from a
+/// `DroplessArena`.
+///
+/// Growing the vector allocates a new, larger buffer from the arena and copies
+/// the elements over; old buffers are never freed individually but go away
+/// together with the arena. This suits scratch data that is built up during a
+/// single phase and then either discarded with the arena or converted into an
+/// owned `Vec` with `into_vec`.
+pub struct ArenaVec<'a, T: Copy> {
+    arena: &'a DroplessArena,
+    ptr: NonNull<T>,
+    len: usize,
+    cap: usize,
+}
+
+impl<'a, T: Copy> ArenaVec<'a, T> {
+    #[inline]
+    pub fn new(arena: &'a DroplessArena) -> ArenaVec<'a, T> {
+        ArenaVec {
+            arena,
+            ptr: NonNull::dangling(),
+            len: 0,
+            cap: if mem::size_of::<T>() == 0 { !0 } else { 0 },
+        }
+    }
+
+    pub fn with_capacity(arena: &'a DroplessArena, capacity: usize) -> ArenaVec<'a, T> {
+        let mut vec = ArenaVec::new(arena);
+        vec.reserve(capacity);
+        vec
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Ensures there is room for at least `additional` more elements,
+    /// moving the elements into a new arena allocation if needed.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional).expect("capacity overflow");
+        if required <= self.cap {
+            return;
+        }
+        let new_cap = cmp::max(cmp::max(self.cap * 2, required), 4);
+        let bytes = new_cap.checked_mul(mem::size_of::<T>()).expect("capacity overflow");
+        let mem = self.arena.alloc_raw(bytes, mem::align_of::<T>()) as *mut _ as *mut T;
+        unsafe {
+            ptr::copy_nonoverlapping(self.ptr.as_ptr(), mem, self.len);
+            self.ptr = NonNull::new_unchecked(mem);
+        }
+        self.cap = new_cap;
+    }
+
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        if self.len == self.cap {
+            self.reserve(1);
+        }
+        unsafe {
+            ptr::write(self.ptr.as_ptr().add(self.len), value);
+        }
+        self.len += 1;
+    }
+
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            unsafe { Some(ptr::read(self.ptr.as_ptr().add(self.len))) }
+        }
+    }
+
+    pub fn extend_from_slice(&mut self, other: &[T]) {
+        self.reserve(other.len());
+        unsafe {
+            ptr::copy_nonoverlapping(other.as_ptr(),
+                                     self.ptr.as_ptr().add(self.len),
+                                     other.len());
+        }
+        self.len += other.len();
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Returns the elements as a slice that lives as long as the arena.
+    pub fn into_arena_slice(self) -> &'a mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Copies the elements into an owned, heap-allocated `Vec`.
+    pub fn into_vec(self) -> Vec<T> {
+        self.to_vec()
+    }
+}
+
+impl<T: Copy> Deref for ArenaVec<'_, T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: Copy> DerefMut for ArenaVec<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: Copy> Extend<T> for ArenaVec<'_, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
 #[derive(Default)]
 // FIXME(@Zoxc): this type is entirely unused in rustc
 pub struct SyncTypedArena<T> {
@@ -451,6 +603,10 @@ impl SyncDroplessArena {
         self.lock.lock().in_arena(ptr)
     }
 
+    pub fn allocated_bytes(&self) -> usize {
+        self.lock.lock().allocated_bytes()
+    }
+
     #[inline(always)]
     pub fn alloc_raw(&self, bytes: usize, align: usize) -> &mut [u8] {
         // Extend the lifetime of the result since it's limited to the lock guard
@@ -477,7 +633,7 @@ impl SyncDroplessArena {
 mod tests {
     extern crate test;
     use test::Bencher;
-    use super::TypedArena;
+    use super::{ArenaVec, DroplessArena, TypedArena};
     use std::cell::Cell;
 
     #[allow(dead_code)]
@@ -492,6 +648,20 @@ mod tests {
     pub fn test_unused() {
         let arena: TypedArena<Point> = TypedArena::default();
         assert!(arena.chunks.borrow().is_empty());
+        assert_eq!(arena.allocated_bytes(), 0);
+    }
+
+    #[test]
+    pub fn test_allocated_bytes() {
+        let arena: TypedArena<Point> = TypedArena::default();
+        arena.alloc(Point { x: 1, y: 2, z: 3 });
+        let bytes = arena.allocated_bytes();
+        assert!(bytes >= std::mem::size_of::<Point>());
+        assert_eq!(bytes % std::mem::size_of::<Point>(), 0);
+
+        let dropless = DroplessArena::default();
+        dropless.alloc(0u64);
+        assert!(dropless.allocated_bytes() >= 8);
     }
 
     #[test]
@@ -537,6 +707,37 @@ mod tests {
         assert_eq!(result.inner.value, 10);
     }
 
+    #[test]
+    pub fn test_arena_vec() {
+        let arena = DroplessArena::default();
+        let mut vec = ArenaVec::new(&arena);
+        assert!(vec.is_empty());
+        for i in 0..100u32 {
+            vec.push(i);
+        }
+        vec.extend_from_slice(&[100, 101]);
+        vec.extend(102..110);
+        assert_eq!(vec.len(), 110);
+        assert_eq!(vec.pop(), Some(109));
+        assert!(vec.iter().cloned().eq(0..109));
+        assert!(arena.in_arena(vec.as_ptr()));
+
+        let owned = vec.into_vec();
+        assert!(!arena.in_arena(owned.as_ptr()));
+        assert!(owned.into_iter().eq(0..109));
+    }
+
+    #[test]
+    pub fn test_arena_vec_zero_sized() {
+        let arena = DroplessArena::default();
+        let mut vec = ArenaVec::new(&arena);
+        for _ in 0..1000 {
+            vec.push(());
+        }
+        assert_eq!(vec.len(), 1000);
+        assert!(arena.chunks.borrow().is_empty());
+    }
+
     #[test]
     pub fn test_copy() {
         let arena = TypedArena::default();
@@ -20,6 +20,15 @@ impl<I: Iterator, U: IntoIterator, F: FnMut(I::Item) -> U> FlatMap<I, U, F> {
     pub(in super::super) fn new(iter: I, f: F) -> FlatMap<I, U, F> {
         FlatMap { inner: FlattenCompat::new(iter.map(f)) }
     }
+
+    /// Splits the adapter into the partially consumed inner iterators at its
+    /// front and back and the iterator producing the remaining ones, so that
+    /// `collect` can move whole inner collections at once.
+    #[unstable(feature = "std_internals", issue = "0")]
+    #[doc(hidden)]
+    pub fn into_parts(self) -> (Option<U::IntoIter>, Map<I, F>, Option<U::IntoIter>) {
+        self.inner.into_parts()
+    }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -112,6 +121,17 @@ where I::Item: IntoIterator {
     pub(in super::super) fn new(iter: I) -> Flatten<I> {
         Flatten { inner: FlattenCompat::new(iter) }
     }
+
+    /// Splits the adapter into the partially consumed inner iterators at its
+    /// front and back and the iterator producing the remaining ones, so that
+    /// `collect` can move whole inner collections at once.
+    #[unstable(feature = "std_internals", issue = "0")]
+    #[doc(hidden)]
+    pub fn into_parts(self) -> (Option<<I::Item as IntoIterator>::IntoIter>,
+                                I,
+                                Option<<I::Item as IntoIterator>::IntoIter>) {
+        self.inner.into_parts()
+    }
 }
 
 #[stable(feature = "iterator_flatten", since = "1.29.0")]
@@ -201,6 +221,10 @@ impl<I, U> FlattenCompat<I, U> {
     fn new(iter: I) -> FlattenCompat<I, U> {
         FlattenCompat { iter, frontiter: None, backiter: None }
     }
+
+    fn into_parts(self) -> (Option<U>, I, Option<U>) {
+        (self.frontiter, self.iter, self.backiter)
+    }
 }
 
 impl<I, U> Iterator for FlattenCompat<I, U>
@@ -244,6 +244,14 @@ impl<I> Cloned<I> {
     pub(super) fn new(it: I) -> Cloned<I> {
         Cloned { it }
     }
+
+    #[unstable(feature = "iter_cloned_internals",
+               reason = "lets `Vec` copy from the underlying slice iterator",
+               issue = "0")]
+    #[doc(hidden)]
+    pub fn __inner(&self) -> &I {
+        &self.it
+    }
 }
 
 #[stable(feature = "iter_cloned", since = "1.1.0")]
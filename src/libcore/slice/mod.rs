@@ -1133,6 +1133,76 @@ impl<T> [T] {
         RSplitMut { inner: self.split_mut(pred) }
     }
 
+    /// Returns an iterator over the runs of consecutive elements of the slice,
+    /// where `pred` decides whether two neighbouring elements belong to the
+    /// same run.
+    ///
+    /// `pred` is called on each pair of neighbouring elements `a` and `b`, in
+    /// slice order, and the slice is split between them if it returns `false`.
+    /// None of the returned subslices is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(slice_chunk_by)]
+    ///
+    /// let slice = [1, 1, 1, 3, 3, 2, 2, 2];
+    /// let mut iter = slice.chunk_by(|a, b| a == b);
+    ///
+    /// assert_eq!(iter.next(), Some(&[1, 1, 1][..]));
+    /// assert_eq!(iter.next(), Some(&[3, 3][..]));
+    /// assert_eq!(iter.next(), Some(&[2, 2, 2][..]));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    ///
+    /// This can be used to find the sorted runs of a slice:
+    ///
+    /// ```
+    /// #![feature(slice_chunk_by)]
+    ///
+    /// let slice = [1, 2, 3, 2, 3, 1];
+    /// let runs: Vec<_> = slice.chunk_by(|a, b| a <= b).collect();
+    ///
+    /// assert_eq!(runs, [&[1, 2, 3][..], &[2, 3], &[1]]);
+    /// ```
+    #[unstable(feature = "slice_chunk_by", issue = "0")]
+    #[inline]
+    pub fn chunk_by<F>(&self, pred: F) -> ChunkBy<T, F>
+        where F: FnMut(&T, &T) -> bool
+    {
+        ChunkBy { v: self, pred }
+    }
+
+    /// Returns an iterator over mutable runs of consecutive elements of the
+    /// slice, where `pred` decides whether two neighbouring elements belong to
+    /// the same run.
+    ///
+    /// `pred` is called on each pair of neighbouring elements `a` and `b`, in
+    /// slice order, and the slice is split between them if it returns `false`.
+    /// None of the returned subslices is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(slice_chunk_by)]
+    ///
+    /// // Keep a running count in the last element of each run of equal keys.
+    /// let mut v = vec![('a', 1), ('a', 1), ('b', 1), ('a', 1), ('a', 1), ('a', 1)];
+    ///
+    /// for run in v.chunk_by_mut(|a, b| a.0 == b.0) {
+    ///     let len = run.len();
+    ///     run[len - 1].1 = len;
+    /// }
+    /// assert_eq!(v, [('a', 1), ('a', 2), ('b', 1), ('a', 1), ('a', 1), ('a', 3)]);
+    /// ```
+    #[unstable(feature = "slice_chunk_by", issue = "0")]
+    #[inline]
+    pub fn chunk_by_mut<F>(&mut self, pred: F) -> ChunkByMut<T, F>
+        where F: FnMut(&T, &T) -> bool
+    {
+        ChunkByMut { v: self, pred }
+    }
+
     /// Returns an iterator over subslices separated by elements that match
     /// `pred`, limited to returning at most `n` items. The matched element is
     /// not contained in the subslices.
@@ -3605,6 +3675,165 @@ impl<'a, T, P> DoubleEndedIterator for RSplitMut<'a, T, P> where
 #[stable(feature = "slice_rsplit", since = "1.27.0")]
 impl<T, P> FusedIterator for RSplitMut<'_, T, P> where P: FnMut(&T) -> bool {}
 
+/// An iterator over the runs of consecutive elements of a slice that are
+/// related by a predicate.
+///
+/// This struct is created by the [`chunk_by`] method on [slices].
+///
+/// [`chunk_by`]: ../../std/primitive.slice.html#method.chunk_by
+/// [slices]: ../../std/primitive.slice.html
+#[unstable(feature = "slice_chunk_by", issue = "0")]
+pub struct ChunkBy<'a, T:'a, P> {
+    v: &'a [T],
+    pred: P,
+}
+
+#[unstable(feature = "slice_chunk_by", issue = "0")]
+impl<T: fmt::Debug, P> fmt::Debug for ChunkBy<'_, T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ChunkBy")
+            .field("v", &self.v)
+            .finish()
+    }
+}
+
+#[unstable(feature = "slice_chunk_by", issue = "0")]
+impl<'a, T, P> Iterator for ChunkBy<'a, T, P> where P: FnMut(&T, &T) -> bool {
+    type Item = &'a [T];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [T]> {
+        if self.v.is_empty() {
+            return None;
+        }
+
+        let mut len = 1;
+        for pair in self.v.windows(2) {
+            if !(self.pred)(&pair[0], &pair[1]) {
+                break;
+            }
+            len += 1;
+        }
+        let (head, tail) = self.v.split_at(len);
+        self.v = tail;
+        Some(head)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.v.is_empty() {
+            (0, Some(0))
+        } else {
+            (1, Some(self.v.len()))
+        }
+    }
+}
+
+#[unstable(feature = "slice_chunk_by", issue = "0")]
+impl<'a, T, P> DoubleEndedIterator for ChunkBy<'a, T, P> where P: FnMut(&T, &T) -> bool {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a [T]> {
+        if self.v.is_empty() {
+            return None;
+        }
+
+        let mut len = 1;
+        for pair in self.v.windows(2).rev() {
+            if !(self.pred)(&pair[0], &pair[1]) {
+                break;
+            }
+            len += 1;
+        }
+        let (head, tail) = self.v.split_at(self.v.len() - len);
+        self.v = head;
+        Some(tail)
+    }
+}
+
+#[unstable(feature = "slice_chunk_by", issue = "0")]
+impl<T, P> FusedIterator for ChunkBy<'_, T, P> where P: FnMut(&T, &T) -> bool {}
+
+/// An iterator over the mutable runs of consecutive elements of a slice that
+/// are related by a predicate.
+///
+/// This struct is created by the [`chunk_by_mut`] method on [slices].
+///
+/// [`chunk_by_mut`]: ../../std/primitive.slice.html#method.chunk_by_mut
+/// [slices]: ../../std/primitive.slice.html
+#[unstable(feature = "slice_chunk_by", issue = "0")]
+pub struct ChunkByMut<'a, T:'a, P> {
+    v: &'a mut [T],
+    pred: P,
+}
+
+#[unstable(feature = "slice_chunk_by", issue = "0")]
+impl<T: fmt::Debug, P> fmt::Debug for ChunkByMut<'_, T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ChunkByMut")
+            .field("v", &self.v)
+            .finish()
+    }
+}
+
+#[unstable(feature = "slice_chunk_by", issue = "0")]
+impl<'a, T, P> Iterator for ChunkByMut<'a, T, P> where P: FnMut(&T, &T) -> bool {
+    type Item = &'a mut [T];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut [T]> {
+        if self.v.is_empty() {
+            return None;
+        }
+
+        let mut len = 1;
+        for pair in self.v.windows(2) {
+            if !(self.pred)(&pair[0], &pair[1]) {
+                break;
+            }
+            len += 1;
+        }
+        let tmp = mem::replace(&mut self.v, &mut []);
+        let (head, tail) = tmp.split_at_mut(len);
+        self.v = tail;
+        Some(head)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.v.is_empty() {
+            (0, Some(0))
+        } else {
+            (1, Some(self.v.len()))
+        }
+    }
+}
+
+#[unstable(feature = "slice_chunk_by", issue = "0")]
+impl<'a, T, P> DoubleEndedIterator for ChunkByMut<'a, T, P> where P: FnMut(&T, &T) -> bool {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut [T]> {
+        if self.v.is_empty() {
+            return None;
+        }
+
+        let mut len = 1;
+        for pair in self.v.windows(2).rev() {
+            if !(self.pred)(&pair[0], &pair[1]) {
+                break;
+            }
+            len += 1;
+        }
+        let tmp = mem::replace(&mut self.v, &mut []);
+        let split = tmp.len() - len;
+        let (head, tail) = tmp.split_at_mut(split);
+        self.v = head;
+        Some(tail)
+    }
+}
+
+#[unstable(feature = "slice_chunk_by", issue = "0")]
+impl<T, P> FusedIterator for ChunkByMut<'_, T, P> where P: FnMut(&T, &T) -> bool {}
+
 /// An private iterator over subslices separated by elements that
 /// match a predicate function, splitting at most a fixed number of
 /// times.
@@ -5174,3 +5403,30 @@ impl SliceContains for i8 {
         memchr::memchr(byte, bytes).is_some()
     }
 }
+
+macro_rules! slice_contains_chunked {
+    ($($t:ty)*) => ($(
+        impl SliceContains for $t {
+            fn slice_contains(&self, x: &[Self]) -> bool {
+                // Compare a whole chunk without branching, which lets the
+                // comparisons be vectorized, and only check for a match once
+                // per chunk.
+                const CHUNK_SIZE: usize = 8;
+                let needle = *self;
+                let mut chunks = x.chunks_exact(CHUNK_SIZE);
+                for chunk in &mut chunks {
+                    let mut found = false;
+                    for &y in chunk {
+                        found |= y == needle;
+                    }
+                    if found {
+                        return true;
+                    }
+                }
+                chunks.remainder().iter().any(|&y| y == needle)
+            }
+        }
+    )*)
+}
+
+slice_contains_chunked! { u16 u32 u64 u128 usize i16 i32 i64 i128 isize char }
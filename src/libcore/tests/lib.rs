@@ -32,6 +32,7 @@
 #![feature(inner_deref)]
 #![feature(slice_internals)]
 #![feature(slice_partition_dedup)]
+#![feature(slice_chunk_by)]
 #![feature(copy_within)]
 
 extern crate core;
@@ -18,6 +18,29 @@ fn test_rposition() {
     assert!(b.iter().rposition(|&v| v == 0) == None);
 }
 
+#[test]
+fn test_contains() {
+    // Lengths around the chunk size used for integers, with the needle at
+    // every position, including in the remainder.
+    for len in 0..20u32 {
+        let v: Vec<u32> = (0..len).collect();
+        for i in 0..len {
+            assert!(v.contains(&i));
+        }
+        assert!(!v.contains(&len));
+    }
+
+    let v = [1u64, 2, 3, 4, 5, 6, 7, 8, 9];
+    assert!(v.contains(&9));
+    assert!(!v.contains(&0));
+    let v = [-1i16, i16::min_value(), i16::max_value()];
+    assert!(v.contains(&i16::min_value()));
+    assert!(!v.contains(&0));
+    let v: Vec<char> = "hello, world".chars().collect();
+    assert!(v.contains(&'w'));
+    assert!(!v.contains(&'z'));
+}
+
 #[test]
 fn test_binary_search() {
     let b: [i32; 0] = [];
@@ -550,6 +573,46 @@ fn test_rchunks_exact_mut_zip() {
     assert_eq!(v1, [0, 16, 17, 22, 23]);
 }
 
+#[test]
+fn test_chunk_by() {
+    let v: &[i32] = &[1, 1, 1, 3, 3, 2, 2, 2, 1, 0];
+    let mut c = v.chunk_by(|a, b| a == b);
+    assert_eq!(c.next(), Some(&[1, 1, 1][..]));
+    assert_eq!(c.next(), Some(&[3, 3][..]));
+    assert_eq!(c.next_back(), Some(&[0][..]));
+    assert_eq!(c.next_back(), Some(&[1][..]));
+    assert_eq!(c.next(), Some(&[2, 2, 2][..]));
+    assert_eq!(c.next(), None);
+    assert_eq!(c.next_back(), None);
+
+    let v: &[i32] = &[];
+    assert_eq!(v.chunk_by(|a, b| a == b).next(), None);
+
+    let v: &[i32] = &[1, 2, 3, 2, 3, 1];
+    let c: Vec<_> = v.chunk_by(|a, b| a < b).rev().collect();
+    assert_eq!(c, [&[1][..], &[2, 3], &[1, 2, 3]]);
+}
+
+#[test]
+fn test_chunk_by_mut() {
+    let v: &mut [i32] = &mut [1, 1, 3, 2, 2, 2];
+    for (i, run) in v.chunk_by_mut(|a, b| a == b).enumerate() {
+        for x in run {
+            *x = i as i32;
+        }
+    }
+    assert_eq!(v, [0, 0, 1, 2, 2, 2]);
+
+    let v: &mut [i32] = &mut [1, 1, 3, 2, 2, 2];
+    let mut c = v.chunk_by_mut(|a, b| a == b);
+    assert_eq!(c.size_hint(), (1, Some(6)));
+    assert_eq!(c.next_back(), Some(&mut [2, 2, 2][..]));
+    assert_eq!(c.next(), Some(&mut [1, 1][..]));
+    assert_eq!(c.next_back(), Some(&mut [3][..]));
+    assert_eq!(c.next(), None);
+    assert_eq!(c.size_hint(), (0, Some(0)));
+}
+
 #[test]
 fn test_windows_count() {
     let v: &[i32] = &[0, 1, 2, 3, 4, 5];
@@ -452,6 +452,7 @@ define_dep_nodes!( <'tcx>
 
     // Represents different phases in the compiler.
     [] RegionScopeTree(DefId),
+    [] BodyOutlivesFacts(DefId),
     [eval_always] Coherence,
     [eval_always] CoherenceInherentImplOverlapCheck,
     [] CoherenceCheckTrait(DefId),
@@ -636,6 +637,8 @@ define_dep_nodes!( <'tcx>
     [input] MaybeUnusedTraitImport(DefId),
     [input] MaybeUnusedExternCrates,
     [input] NamesImportedByGlobUse(DefId),
+    [] UnusedTraitImports(CrateNum),
+    [] UnusedExternCrates(CrateNum),
     [eval_always] StabilityIndex,
     [eval_always] AllTraits,
     [input] AllCrateNums,
@@ -669,6 +672,7 @@ define_dep_nodes!( <'tcx>
     [] InstanceDefSizeEstimate { instance_def: InstanceDef<'tcx> },
 
     [input] Features,
+    [eval_always] BorrowckConfig,
 
     [] ProgramClausesFor(DefId),
     [] ProgramClausesForEnv(traits::Environment<'tcx>),
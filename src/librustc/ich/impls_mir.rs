@@ -24,7 +24,13 @@ impl_stable_hash_for!(struct mir::LocalDecl<'tcx> {
 });
 impl_stable_hash_for!(struct mir::UpvarDecl { debug_name, var_hir_id, by_ref, mutability });
 impl_stable_hash_for!(struct mir::BasicBlockData<'tcx> { statements, terminator, is_cleanup });
-impl_stable_hash_for!(struct mir::UnsafetyViolation { source_info, description, details, kind });
+impl_stable_hash_for!(struct mir::UnsafetyViolation {
+    source_info,
+    description,
+    details,
+    kind,
+    reason
+});
 impl_stable_hash_for!(struct mir::UnsafetyCheckResult { violations, unsafe_blocks });
 
 impl_stable_hash_for!(enum mir::BorrowKind {
@@ -39,6 +45,21 @@ impl_stable_hash_for!(enum mir::UnsafetyViolationKind {
     GeneralAndConstFn,
     ExternStatic(lint_node_id),
     BorrowPacked(lint_node_id),
+    ReasonNotAllowed(block_id),
+});
+
+impl_stable_hash_for!(enum mir::UnsafetyReason {
+    CallToUnsafeFn,
+    CallToForeignFn,
+    CallToIntrinsic,
+    InlineAsm,
+    DerefRawPointer,
+    UnionField,
+    MutableStatic,
+    ExternStatic,
+    BorrowPacked,
+    LayoutConstrainedField,
+    PointerOpInConst,
 });
 
 impl_stable_hash_for!(struct mir::Terminator<'tcx> {
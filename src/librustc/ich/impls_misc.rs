@@ -14,3 +14,17 @@ impl_stable_hash_for!(enum ::rustc_target::spec::PanicStrategy {
     Abort,
     Unwind
 });
+
+impl_stable_hash_for!(enum crate::session::config::BorrowckMode {
+    Ast,
+    Mir,
+    Compare,
+    Migrate
+});
+
+impl_stable_hash_for!(struct crate::session::config::BorrowckConfig {
+    mode,
+    two_phase_borrows,
+    emit_read_for_match,
+    bind_by_move_pattern_guards
+});
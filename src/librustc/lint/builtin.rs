@@ -161,6 +161,12 @@ declare_lint! {
     "safe borrows of fields of packed structs were was erroneously allowed"
 }
 
+declare_lint! {
+    pub UNALIGNED_REFERENCES,
+    Deny,
+    "detects unaligned references to fields of packed structs"
+}
+
 declare_lint! {
     pub PATTERNS_IN_FNS_WITHOUT_BODY,
     Warn,
@@ -430,6 +436,7 @@ impl LintPass for HardwiredLints {
             RENAMED_AND_REMOVED_LINTS,
             SAFE_EXTERN_STATICS,
             SAFE_PACKED_BORROWS,
+            UNALIGNED_REFERENCES,
             PATTERNS_IN_FNS_WITHOUT_BODY,
             LEGACY_DIRECTORY_OWNERSHIP,
             LEGACY_CONSTRUCTOR_VISIBILITY,
@@ -19,6 +19,9 @@ use syntax::symbol::Symbol;
 pub struct LintLevelSets {
     list: Vec<LintSet>,
     lint_cap: Level,
+    // Lints whose warnings are turned into errors by `-Z warnings-as-errors`,
+    // along with the group that was passed to it.
+    warnings_as_errors: FxHashMap<LintId, Symbol>,
 }
 
 enum LintSet {
@@ -39,6 +42,7 @@ impl LintLevelSets {
         let mut me = LintLevelSets {
             list: Vec::new(),
             lint_cap: Level::Forbid,
+            warnings_as_errors: FxHashMap::default(),
         };
         me.process_command_line(sess);
         return me
@@ -72,6 +76,20 @@ impl LintLevelSets {
             }
         }
 
+        for group in &sess.opts.debugging_opts.warnings_as_errors {
+            let group = group.replace("-", "_");
+            store.check_lint_name_cmdline(sess, &group, Level::Deny);
+
+            let ids = match store.find_lints(&group) {
+                Ok(ids) => ids,
+                Err(_) => continue, // errors handled in check_lint_name_cmdline above
+            };
+            let group = Symbol::intern(&group);
+            for id in ids {
+                self.warnings_as_errors.insert(id, group);
+            }
+        }
+
         self.list.push(LintSet::CommandLine {
             specs: specs,
         });
@@ -106,6 +124,15 @@ impl LintLevelSets {
             }
         }
 
+        // Warnings that survived the check above are turned into errors if
+        // their group was passed to `-Z warnings-as-errors`.
+        if level == Level::Warn {
+            if let Some(&group) = self.warnings_as_errors.get(&LintId::of(lint)) {
+                level = Level::Deny;
+                src = LintSource::WarningsAsErrors(group);
+            }
+        }
+
         // Ensure that we never exceed the `--cap-lints` argument.
         level = cmp::min(level, self.lint_cap);
 
@@ -535,9 +562,11 @@ impl<'a> HashStable<StableHashingContext<'a>> for LintLevelMap {
         let LintLevelSets {
             ref list,
             lint_cap,
+            ref warnings_as_errors,
         } = *sets;
 
         lint_cap.hash_stable(hcx, hasher);
+        warnings_as_errors.hash_stable(hcx, hasher);
 
         hcx.while_hashing_spans(true, |hcx| {
             list.len().hash_stable(hcx, hasher);
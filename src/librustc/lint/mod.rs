@@ -552,12 +552,17 @@ pub enum LintSource {
 
     /// Lint level was set by a command-line flag.
     CommandLine(Symbol),
+
+    /// Lint warning was turned into an error by `-Z warnings-as-errors` for
+    /// the given lint or group.
+    WarningsAsErrors(Symbol),
 }
 
 impl_stable_hash_for!(enum self::LintSource {
     Default,
     Node(name, span, reason),
-    CommandLine(text)
+    CommandLine(text),
+    WarningsAsErrors(group)
 });
 
 pub type LevelSource = (Level, LintSource);
@@ -653,6 +658,13 @@ pub fn struct_lint_level<'a>(sess: &'a Session,
                              hyphen_case_flag_val));
             }
         }
+        LintSource::WarningsAsErrors(group) => {
+            sess.diag_note_once(
+                &mut err,
+                DiagnosticMessageId::from(lint),
+                &format!("`{}` warnings are turned into errors by `-Z warnings-as-errors={}`",
+                         name.replace("_", "-"), group.as_str().replace("_", "-")));
+        }
         LintSource::Node(lint_attr_name, src, reason) => {
             if let Some(rationale) = reason {
                 err.note(&rationale.as_str());
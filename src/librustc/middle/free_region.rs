@@ -7,6 +7,7 @@ use crate::infer::outlives::free_region_map::{FreeRegionMap, FreeRegionRelations
 use crate::hir::def_id::DefId;
 use crate::middle::region;
 use crate::ty::{self, TyCtxt, Region};
+use syntax_pos::Span;
 
 /// Combines a `region::ScopeTree` (which governs relationships between
 /// scopes) and a `FreeRegionMap` (which governs relationships between
@@ -105,3 +106,36 @@ impl<'a, 'gcx, 'tcx> RegionRelations<'a, 'gcx, 'tcx> {
     }
 }
 
+
+/// Where an outlives relationship that holds in the body of a function comes
+/// from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub enum OutlivesSource {
+    /// A where-clause or bound declared on the function or on one of its
+    /// parents, such as the impl it belongs to.
+    WhereClause(Span),
+    /// A bound implied by the well-formedness of the argument or return type
+    /// at this span, such as `'b: 'a` from `&'a &'b u32`.
+    ImpliedBound(Span),
+}
+
+impl_stable_hash_for!(enum self::OutlivesSource {
+    WhereClause(span),
+    ImpliedBound(span)
+});
+
+/// An outlives relationship `longer: shorter` that holds in the body of a
+/// function, as returned by the `body_outlives_facts` query.
+///
+/// Regions and types are given in their printed form, so that the facts can
+/// be serialized for use outside the compiler.
+#[derive(Clone, Debug, PartialEq, Eq, RustcEncodable, RustcDecodable)]
+pub struct OutlivesFact {
+    /// The region or type that outlives `shorter`, like `'b` or `T`.
+    pub longer: String,
+    /// The region that is outlived.
+    pub shorter: String,
+    pub source: OutlivesSource,
+}
+
+impl_stable_hash_for!(struct self::OutlivesFact { longer, shorter, source });
@@ -16,7 +16,7 @@ use syntax_pos::{Span, MultiSpan};
 use syntax::ast;
 use syntax::ast::{NodeId, Attribute};
 use syntax::errors::Applicability;
-use syntax::feature_gate::{GateIssue, emit_feature_err};
+use syntax::feature_gate::{GateIssue, emit_feature_err, record_feature_use};
 use syntax::attr::{self, Stability, Deprecation};
 use crate::ty::{self, TyCtxt};
 use crate::util::nodemap::{FxHashSet, FxHashMap};
@@ -675,6 +675,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
                     return EvalResult::Allow;
                 }
                 if self.stability().active_features.contains(&feature) {
+                    record_feature_use(&self.sess.parse_sess, &feature.as_str(), span);
                     return EvalResult::Allow;
                 }
 
@@ -838,6 +839,25 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
 /// Given the list of enabled features that were not language features (i.e., that
 /// were expected to be library features), and the list of features used from
 /// libraries, identify activated features that don't exist and error about them.
+/// Looks for the crate that defines the library feature `feature`. Returns
+/// `Some(since)` if some crate defines it, where `since` is `None` while the
+/// feature is unstable.
+pub fn find_lib_feature<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                  feature: Symbol) -> Option<Option<Symbol>> {
+    let local_features = tcx.lib_features();
+    if let Some(&since) = local_features.stable.get(&feature) {
+        return Some(Some(since));
+    }
+    if local_features.unstable.contains(&feature) {
+        return Some(None);
+    }
+    tcx.crates().iter().filter_map(|&cnum| {
+        tcx.defined_lib_features(cnum).iter()
+            .find(|&&(name, _)| name == feature)
+            .map(|&(_, since)| since)
+    }).next()
+}
+
 pub fn check_unused_or_stable_features<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
     let access_levels = &tcx.privacy_access_levels(LOCAL_CRATE);
 
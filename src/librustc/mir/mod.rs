@@ -2856,6 +2856,62 @@ pub enum UnsafetyViolationKind {
     GeneralAndConstFn,
     ExternStatic(ast::NodeId),
     BorrowPacked(ast::NodeId),
+    /// An operation inside the `unsafe` block with the given id, whose
+    /// `#[unsafe_reasons]` attribute does not list the operation's reason.
+    ReasonNotAllowed(ast::NodeId),
+}
+
+/// What makes an operation unsafe, as named in `#[unsafe_reasons(...)]`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
+pub enum UnsafetyReason {
+    CallToUnsafeFn,
+    CallToForeignFn,
+    CallToIntrinsic,
+    InlineAsm,
+    DerefRawPointer,
+    UnionField,
+    MutableStatic,
+    ExternStatic,
+    BorrowPacked,
+    LayoutConstrainedField,
+    PointerOpInConst,
+}
+
+impl UnsafetyReason {
+    pub const ALL: &'static [UnsafetyReason] = &[
+        UnsafetyReason::CallToUnsafeFn,
+        UnsafetyReason::CallToForeignFn,
+        UnsafetyReason::CallToIntrinsic,
+        UnsafetyReason::InlineAsm,
+        UnsafetyReason::DerefRawPointer,
+        UnsafetyReason::UnionField,
+        UnsafetyReason::MutableStatic,
+        UnsafetyReason::ExternStatic,
+        UnsafetyReason::BorrowPacked,
+        UnsafetyReason::LayoutConstrainedField,
+        UnsafetyReason::PointerOpInConst,
+    ];
+
+    /// The name of this reason in `#[unsafe_reasons(...)]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            UnsafetyReason::CallToUnsafeFn => "unsafe_fn_call",
+            UnsafetyReason::CallToForeignFn => "ffi_call",
+            UnsafetyReason::CallToIntrinsic => "intrinsic_call",
+            UnsafetyReason::InlineAsm => "inline_asm",
+            UnsafetyReason::DerefRawPointer => "raw_pointer_deref",
+            UnsafetyReason::UnionField => "union_field",
+            UnsafetyReason::MutableStatic => "mutable_static",
+            UnsafetyReason::ExternStatic => "extern_static",
+            UnsafetyReason::BorrowPacked => "packed_borrow",
+            UnsafetyReason::LayoutConstrainedField => "layout_constrained_field",
+            UnsafetyReason::PointerOpInConst => "const_pointer_op",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<UnsafetyReason> {
+        UnsafetyReason::ALL.iter().cloned().find(|reason| reason.name() == name)
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
@@ -2864,6 +2920,7 @@ pub struct UnsafetyViolation {
     pub description: InternedString,
     pub details: InternedString,
     pub kind: UnsafetyViolationKind,
+    pub reason: UnsafetyReason,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
@@ -44,9 +44,44 @@ pub struct TypeSizeInfo {
     pub variants: Vec<VariantInfo>,
 }
 
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct GeneratorFieldInfo {
+    pub name: String,
+    /// Where the upvar or local is declared.
+    pub location: String,
+    /// `None` if the size depends on generic parameters.
+    pub size: Option<u64>,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct GeneratorSizeInfo {
+    pub generator_description: String,
+    /// The number of states, including the unresumed, returned and poisoned
+    /// states.
+    pub states: u32,
+    pub upvars: Vec<GeneratorFieldInfo>,
+    /// The locals which are live across a suspension point, and are thus
+    /// saved in the generator.
+    pub saved_locals: Vec<GeneratorFieldInfo>,
+}
+
+impl GeneratorSizeInfo {
+    /// The sum of the known sizes of the upvars and saved locals, and whether
+    /// all of their sizes are known.
+    fn fields_size(&self) -> (u64, bool) {
+        self.upvars.iter().chain(&self.saved_locals).fold((0, true), |(sum, exact), field| {
+            match field.size {
+                Some(size) => (sum + size, exact),
+                None => (sum, false),
+            }
+        })
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Default)]
 pub struct CodeStats {
     type_sizes: FxHashSet<TypeSizeInfo>,
+    generator_sizes: FxHashSet<GeneratorSizeInfo>,
 }
 
 impl CodeStats {
@@ -70,6 +105,47 @@ impl CodeStats {
         self.type_sizes.insert(info);
     }
 
+    pub fn record_generator_size(&mut self, info: GeneratorSizeInfo) {
+        self.generator_sizes.insert(info);
+    }
+
+    pub fn print_generator_sizes(&self) {
+        let mut sorted: Vec<_> = self.generator_sizes.iter().collect();
+
+        // Primary sort: large-to-small.
+        // Secondary sort: description (dictionary order)
+        sorted.sort_by(|info1, info2| {
+            match info2.fields_size().0.cmp(&info1.fields_size().0) {
+                Ordering::Equal => info1.generator_description.cmp(&info2.generator_description),
+                other => other,
+            }
+        });
+
+        for info in &sorted {
+            let (size, exact) = info.fields_size();
+            println!("print-generator-size generator: `{}`: {} states, {} saved locals, \
+                      {}{} bytes in upvars and saved locals",
+                     info.generator_description, info.states, info.saved_locals.len(),
+                     if exact { "" } else { "at least " }, size);
+            let indent = "    ";
+            let fields = info.upvars.iter().map(|field| ("upvar", field))
+                .chain(info.saved_locals.iter().map(|field| ("local", field)));
+            for (kind, field) in fields {
+                match field.size {
+                    Some(size) => {
+                        println!("print-generator-size {}{} `{}` at {}: {} bytes",
+                                 indent, kind, field.name, field.location, size);
+                    }
+                    None => {
+                        println!("print-generator-size {}{} `{}` at {}: size depends on \
+                                  generic parameters",
+                                 indent, kind, field.name, field.location);
+                    }
+                }
+            }
+        }
+    }
+
     pub fn print_type_sizes(&self) {
         let mut sorted: Vec<_> = self.type_sizes.iter().collect();
 
@@ -48,6 +48,29 @@ pub enum Sanitizer {
     Thread,
 }
 
+/// The level of `-Z trace-macros`.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub enum TraceMacros {
+    No,
+    /// Print every macro invocation and what it expanded to.
+    Yes,
+    /// Additionally show the matched `macro_rules!` rule and its bindings.
+    Detailed,
+}
+
+/// The format of `-Z export-mir`.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub enum MirExportFormat {
+    Json,
+}
+
+/// The format of `-Z ast-stats`.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub enum AstStatsFormat {
+    Text,
+    Json,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Hash)]
 pub enum OptLevel {
     No,         // -O0
@@ -474,6 +497,24 @@ impl BorrowckMode {
     }
 }
 
+/// The borrow checking settings of the local crate, as computed by the
+/// `borrowck_config` query from the `-Z` flags, the edition and the
+/// enabled features.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BorrowckConfig {
+    /// Which borrow checker(s) to run.
+    pub mode: BorrowckMode,
+    /// Whether two-phase borrows are enabled: by `-Z two-phase-borrows`,
+    /// `#![feature(nll)]` or an edition after 2015.
+    pub two_phase_borrows: bool,
+    /// Whether MIR construction for `match` emits `FakeRead`s of the
+    /// input and borrows of the match input.
+    pub emit_read_for_match: bool,
+    /// Whether bind-by-move pattern bindings are allowed on match arms
+    /// that have a guard.
+    pub bind_by_move_pattern_guards: bool,
+}
+
 pub enum Input {
     /// Loads source from file
     File(PathBuf),
@@ -817,11 +858,17 @@ macro_rules! options {
                   or the path to the linker plugin");
         pub const parse_merge_functions: Option<&str> =
             Some("one of: `disabled`, `trampolines`, or `aliases`");
+        pub const parse_trace_macros: Option<&str> =
+            Some("either a boolean (`yes`, `no`, `on`, `off`, etc), `detailed`, \
+                  or omitted");
+        pub const parse_mir_export_format: Option<&str> = Some("`json`");
+        pub const parse_ast_stats_format: Option<&str> = Some("`text`, `json`, or omitted");
     }
 
     #[allow(dead_code)]
     mod $mod_set {
-        use super::{$struct_name, Passes, Sanitizer, LtoCli, LinkerPluginLto};
+        use super::{$struct_name, Passes, Sanitizer, LtoCli, LinkerPluginLto, TraceMacros,
+                    MirExportFormat, AstStatsFormat};
         use rustc_target::spec::{LinkerFlavor, MergeFunctions, PanicStrategy, RelroLevel};
         use std::path::PathBuf;
         use std::str::FromStr;
@@ -1064,6 +1111,33 @@ macro_rules! options {
             }
             true
         }
+
+        fn parse_trace_macros(slot: &mut TraceMacros, v: Option<&str>) -> bool {
+            match v {
+                None | Some("y") | Some("yes") | Some("on") => *slot = TraceMacros::Yes,
+                Some("n") | Some("no") | Some("off") => *slot = TraceMacros::No,
+                Some("detailed") => *slot = TraceMacros::Detailed,
+                _ => return false,
+            }
+            true
+        }
+
+        fn parse_mir_export_format(slot: &mut Option<MirExportFormat>, v: Option<&str>) -> bool {
+            match v {
+                Some("json") => *slot = Some(MirExportFormat::Json),
+                _ => return false,
+            }
+            true
+        }
+
+        fn parse_ast_stats_format(slot: &mut Option<AstStatsFormat>, v: Option<&str>) -> bool {
+            match v {
+                None | Some("text") => *slot = Some(AstStatsFormat::Text),
+                Some("json") => *slot = Some(AstStatsFormat::Json),
+                _ => return false,
+            }
+            true
+        }
     }
 ) }
 
@@ -1181,6 +1255,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "measure time of each LLVM pass"),
     input_stats: bool = (false, parse_bool, [UNTRACKED],
         "gather statistics about the input"),
+    ast_stats: Option<AstStatsFormat> = (None, parse_ast_stats_format, [UNTRACKED],
+        "print per-kind node counts, nesting depth and source coverage of the AST \
+         before and after expansion"),
     codegen_stats: bool = (false, parse_bool, [UNTRACKED_WITH_WARNING(true,
         "The output of `-Z codegen-stats` might not be accurate when incremental \
          compilation is enabled")],
@@ -1269,10 +1346,15 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "adds unstable command line options to rustc interface"),
     force_overflow_checks: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "force overflow checks on or off"),
-    trace_macros: bool = (false, parse_bool, [UNTRACKED],
-        "for every macro invocation, print its name and arguments"),
+    trace_macros: TraceMacros = (TraceMacros::No, parse_trace_macros, [UNTRACKED],
+        "for every macro invocation, print its name and arguments \
+         (`detailed` also shows the matched rule and bindings)"),
     debug_macros: bool = (false, parse_bool, [TRACKED],
         "emit line numbers debug info inside macros"),
+    macro_call_site_spans: bool = (false, parse_bool, [TRACKED],
+        "replace the spans of tokens transcribed from `macro_rules!` bodies with the macro \
+         call site, discarding their position in the macro body (for coverage and profiling \
+         tools)"),
     keep_hygiene_data: bool = (false, parse_bool, [UNTRACKED],
         "don't clear the hygiene data after analysis"),
     keep_ast: bool = (false, parse_bool, [UNTRACKED],
@@ -1281,6 +1363,10 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "show spans for compiler debugging (expr|pat|ty)"),
     print_type_sizes: bool = (false, parse_bool, [UNTRACKED],
         "print layout information for each type encountered"),
+    print_generator_sizes: bool = (false, parse_bool, [UNTRACKED],
+        "print the states and saved locals of each generator"),
+    explain_const_promotion: bool = (false, parse_bool, [UNTRACKED],
+        "print why borrows of temporaries were not promoted to constants"),
     print_mono_items: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "print the result of the monomorphization collection pass"),
     mir_opt_level: usize = (1, parse_uint, [TRACKED],
@@ -1297,6 +1383,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
 
     dump_mir_dir: String = (String::from("mir_dump"), parse_string, [UNTRACKED],
         "the directory the MIR is dumped into"),
+    export_mir: Option<MirExportFormat> = (None, parse_mir_export_format, [UNTRACKED],
+        "write the optimized MIR of every body in the crate as structured data \
+         (`json`) to `<crate>.mir.json`"),
     dump_mir_graphviz: bool = (false, parse_bool, [UNTRACKED],
         "in addition to `.mir` files, create graphviz `.dot` files"),
     dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
@@ -1309,6 +1398,19 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "print some statistics about the query system"),
     hir_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about AST and HIR"),
+    warnings_as_errors: Vec<String> = (Vec::new(), parse_string_push, [TRACKED],
+        "turn warnings from the given lint or lint group into errors (can be used multiple times)"),
+    meminfo: bool = (false, parse_bool, [UNTRACKED],
+        "print the memory reserved by each of the compiler's global arenas"),
+    report_feature_gates: bool = (false, parse_bool, [UNTRACKED],
+        "print the features declared or used by the crate, with their status and the \
+         spans that needed them, as JSON"),
+    explain_cfg: bool = (false, parse_bool, [UNTRACKED],
+        "print how each `cfg` and `cfg_attr` attribute was evaluated as JSON"),
+    crate_stats: bool = (false, parse_bool, [UNTRACKED],
+        "print whole-crate statistics (items, impls, MIR size, interners) as JSON"),
+    emit_unused_report: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write a JSON report of unused extern crates and trait imports to the given path"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
         "encode MIR of all functions into the crate metadata"),
     unleash_the_miri_inside_of_you: bool = (false, parse_bool, [TRACKED],
@@ -2587,7 +2689,7 @@ mod tests {
     use syntax::symbol::Symbol;
     use syntax::edition::{Edition, DEFAULT_EDITION};
     use syntax;
-    use super::Options;
+    use super::{AstStatsFormat, MirExportFormat, Options, TraceMacros};
 
     fn optgroups() -> getopts::Options {
         let mut opts = getopts::Options::new();
@@ -3131,12 +3233,24 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.input_stats = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.ast_stats = Some(AstStatsFormat::Json);
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.codegen_stats = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.borrowck_stats = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.meta_stats = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.crate_stats = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.report_feature_gates = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.explain_cfg = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.meminfo = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.emit_unused_report = Some(PathBuf::from("unused.json"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.print_link_args = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.print_llvm_passes = true;
@@ -3171,7 +3285,7 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.unstable_options = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
-        opts.debugging_opts.trace_macros = true;
+        opts.debugging_opts.trace_macros = TraceMacros::Detailed;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.keep_hygiene_data = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
@@ -3185,6 +3299,8 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_graphviz = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.export_mir = Some(MirExportFormat::Json);
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
         // Make sure changing a [TRACKED] option changes the hash
         opts = reference.clone();
@@ -3242,6 +3358,14 @@ mod tests {
         opts = reference.clone();
         opts.debugging_opts.merge_functions = Some(MergeFunctions::Disabled);
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.macro_call_site_spans = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.warnings_as_errors = vec![String::from("unused")];
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
     }
 
     #[test]
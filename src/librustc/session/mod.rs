@@ -1,4 +1,5 @@
 pub use self::code_stats::{DataTypeKind, SizeKind, FieldInfo, VariantInfo};
+pub use self::code_stats::{GeneratorFieldInfo, GeneratorSizeInfo};
 use self::code_stats::CodeStats;
 
 use crate::dep_graph::cgu_reuse_tracker::CguReuseTracker;
@@ -177,6 +178,9 @@ pub struct PerfStats {
     pub normalize_ty_after_erasing_regions: AtomicUsize,
     /// Number of times this query is invoked.
     pub normalize_projection_ty: AtomicUsize,
+    /// Number of MIR statements at the end of each `MirPhase`, summed over
+    /// all bodies. Only collected with `-Z crate-stats`.
+    pub mir_statements_per_phase: [AtomicUsize; 4],
 }
 
 /// Enum to support dispatch of one-time diagnostics (in Session.diag_once)
@@ -1121,6 +1125,15 @@ pub fn build_session_(
     let target_cfg = config::build_target_config(&sopts, &span_diagnostic);
 
     let p_s = parse::ParseSess::with_span_handler(span_diagnostic, source_map);
+    if sopts.debugging_opts.report_feature_gates {
+        *p_s.feature_gate_uses.borrow_mut() = Some(vec![]);
+    }
+    if sopts.debugging_opts.explain_cfg {
+        *p_s.cfg_decisions.borrow_mut() = Some(vec![]);
+    }
+    if sopts.debugging_opts.trace_macros == config::TraceMacros::Detailed {
+        *p_s.expansion_traces.borrow_mut() = Some(vec![]);
+    }
     let sysroot = match &sopts.maybe_sysroot {
         Some(sysroot) => sysroot.clone(),
         None => filesearch::get_or_default_sysroot(),
@@ -1201,6 +1214,12 @@ pub fn build_session_(
             queries_canonicalized: AtomicUsize::new(0),
             normalize_ty_after_erasing_regions: AtomicUsize::new(0),
             normalize_projection_ty: AtomicUsize::new(0),
+            mir_statements_per_phase: [
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+            ],
         },
         code_stats: Default::default(),
         optimization_fuel_crate,
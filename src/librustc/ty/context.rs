@@ -3,7 +3,7 @@
 use crate::dep_graph::DepGraph;
 use crate::dep_graph::{self, DepNode, DepConstructor};
 use crate::session::Session;
-use crate::session::config::{BorrowckMode, OutputFilenames};
+use crate::session::config::{BorrowckConfig, BorrowckMode, OutputFilenames};
 use crate::session::config::CrateType;
 use crate::middle;
 use crate::hir::{TraitCandidate, HirId, ItemKind, ItemLocalId, Node};
@@ -40,10 +40,13 @@ use crate::ty::steal::Steal;
 use crate::ty::subst::{UserSubsts, UnpackedKind};
 use crate::ty::{BoundVar, BindingMode};
 use crate::ty::CanonicalPolyFnSig;
+use crate::util::common::to_readable_str;
 use crate::util::nodemap::{DefIdMap, DefIdSet, ItemLocalMap};
 use crate::util::nodemap::{FxHashMap, FxHashSet};
+use rustc_data_structures::fx::FxHasher;
 use errors::DiagnosticBuilder;
 use rustc_data_structures::interner::HashInterner;
+use rustc_data_structures::sharded::ShardedHashMap;
 use smallvec::SmallVec;
 use rustc_data_structures::stable_hasher::{HashStable, hash_stable_hashmap,
                                            StableHasher, StableHasherResult,
@@ -108,7 +111,71 @@ pub struct GlobalArenas<'tcx> {
     const_allocs: TypedArena<interpret::Allocation>,
 }
 
-type InternedSet<'tcx, T> = Lock<FxHashMap<Interned<'tcx, T>, ()>>;
+type InternedSet<'tcx, T> = ShardedHashMap<Interned<'tcx, T>, ()>;
+
+const SINGLETON_LIST_CACHE_BITS: u32 = 8;
+const SINGLETON_LIST_CACHE_SIZE: usize = 1 << SINGLETON_LIST_CACHE_BITS;
+
+/// A direct-mapped cache of interned single-element lists. Lists like the
+/// substs `[T]` of `Vec<T>` or `Option<T>` are interned over and over again,
+/// and looking them up here is cheaper than hashing the list and probing the
+/// interner's table.
+struct SingletonListCache<'tcx, T: 'tcx> {
+    data: Lock<SingletonListCacheData<'tcx, T>>,
+}
+
+struct SingletonListCacheData<'tcx, T: 'tcx> {
+    entries: [Option<&'tcx List<T>>; SINGLETON_LIST_CACHE_SIZE],
+    hits: u64,
+    misses: u64,
+}
+
+impl<'tcx, T> Default for SingletonListCache<'tcx, T> {
+    fn default() -> Self {
+        SingletonListCache {
+            data: Lock::new(SingletonListCacheData {
+                entries: [None; SINGLETON_LIST_CACHE_SIZE],
+                hits: 0,
+                misses: 0,
+            }),
+        }
+    }
+}
+
+impl<'tcx, T: Copy + Eq + Hash> SingletonListCache<'tcx, T> {
+    /// Returns the interned list `[elem]`, calling `intern` to intern it if it
+    /// isn't in the cache.
+    fn get_or_intern<F>(&self, elem: T, intern: F) -> &'tcx List<T>
+        where F: FnOnce() -> &'tcx List<T>
+    {
+        let mut hasher = FxHasher::default();
+        elem.hash(&mut hasher);
+        // The low bits of an `FxHasher` hash are poorly mixed, so use the
+        // high bits.
+        let index = (hasher.finish() >> (64 - SINGLETON_LIST_CACHE_BITS)) as usize;
+
+        {
+            let mut data = self.data.borrow_mut();
+            if let Some(list) = data.entries[index] {
+                if list[0] == elem {
+                    data.hits += 1;
+                    return list;
+                }
+            }
+            data.misses += 1;
+        }
+
+        let list = intern();
+        self.data.borrow_mut().entries[index] = Some(list);
+        list
+    }
+
+    /// Returns the number of hits and misses so far.
+    fn stats(&self) -> (u64, u64) {
+        let data = self.data.borrow();
+        (data.hits, data.misses)
+    }
+}
 
 pub struct CtxtInterners<'tcx> {
     /// The arena that types, regions, etc are allocated from
@@ -162,7 +229,7 @@ impl<'gcx: 'tcx, 'tcx> CtxtInterners<'tcx> {
         // determine that all contents are in the global tcx.
         // See comments on Lift for why we can't use that.
         if flags.flags.intersects(ty::TypeFlags::KEEP_IN_LOCAL_TCX) {
-            local.type_.borrow_mut().intern(st, |st| {
+            local.type_.intern(st, |st| {
                 let ty_struct = TyS {
                     sty: st,
                     flags: flags.flags,
@@ -180,7 +247,7 @@ impl<'gcx: 'tcx, 'tcx> CtxtInterners<'tcx> {
                 Interned(local.arena.alloc(ty_struct))
             }).0
         } else {
-            global.type_.borrow_mut().intern(st, |st| {
+            global.type_.intern(st, |st| {
                 let ty_struct = TyS {
                     sty: st,
                     flags: flags.flags,
@@ -280,6 +347,22 @@ impl<'a, V> LocalTableInContext<'a, V> {
     pub fn iter(&self) -> hash_map::Iter<'_, hir::ItemLocalId, V> {
         self.data.iter()
     }
+
+    /// Returns the entries of the table sorted by `ItemLocalId`, which unlike
+    /// `iter` does not depend on the order of the underlying hash map. Use this
+    /// whenever the order can be observed, e.g., in diagnostics.
+    pub fn iter_in_stable_order(&self) -> Vec<(hir::HirId, &'a V)> {
+        // "Null Object" tables have no root, but they are always empty.
+        let owner = match self.local_id_root {
+            Some(local_id_root) => local_id_root.index,
+            None => return Vec::new(),
+        };
+        let mut entries: Vec<_> = self.data.iter().map(|(&local_id, value)| {
+            (hir::HirId { owner, local_id }, value)
+        }).collect();
+        entries.sort_unstable_by_key(|&(id, _)| id.local_id);
+        entries
+    }
 }
 
 impl<'a, V> ::std::ops::Index<hir::HirId> for LocalTableInContext<'a, V> {
@@ -536,6 +619,12 @@ impl<'tcx> TypeckTables<'tcx> {
         }
     }
 
+    /// Returns the types recorded for all nodes of the body, sorted by
+    /// `ItemLocalId`. See `LocalTableInContext::iter_in_stable_order`.
+    pub fn node_types_in_stable_order(&self) -> Vec<(hir::HirId, Ty<'tcx>)> {
+        self.node_types().iter_in_stable_order().into_iter().map(|(id, &ty)| (id, ty)).collect()
+    }
+
     pub fn node_type(&self, id: hir::HirId) -> Ty<'tcx> {
         self.node_type_opt(id).unwrap_or_else(||
             bug!("node_type: no type for node `{}`",
@@ -600,6 +689,16 @@ impl<'tcx> TypeckTables<'tcx> {
         }
     }
 
+    /// Returns the adjustments recorded for all expressions of the body,
+    /// sorted by `ItemLocalId`. See `LocalTableInContext::iter_in_stable_order`.
+    pub fn adjustments_in_stable_order(&self)
+        -> Vec<(hir::HirId, &[ty::adjustment::Adjustment<'tcx>])>
+    {
+        self.adjustments().iter_in_stable_order().into_iter().map(|(id, adjustments)| {
+            (id, &adjustments[..])
+        }).collect()
+    }
+
     pub fn adjustments_mut(&mut self)
                            -> LocalTableInContextMut<'_, Vec<ty::adjustment::Adjustment<'tcx>>> {
         LocalTableInContextMut {
@@ -911,7 +1010,7 @@ impl<'tcx> CommonTypes<'tcx> {
     fn new(interners: &CtxtInterners<'tcx>) -> CommonTypes<'tcx> {
         let mk = |sty| CtxtInterners::intern_ty(interners, interners, sty);
         let mk_region = |r| {
-            interners.region.borrow_mut().intern(r, |r| {
+            interners.region.intern(r, |r| {
                 Interned(interners.arena.alloc(r))
             }).0
         };
@@ -1049,6 +1148,12 @@ pub struct GlobalCtxt<'tcx> {
 
     layout_interner: Lock<FxHashMap<&'tcx LayoutDetails, ()>>,
 
+    /// Caches of single-element lists, checked before the `substs` and
+    /// `predicates` interners. Only lists without inference types or regions
+    /// go through these, so they are only needed for the global interners.
+    substs_cache: SingletonListCache<'tcx, Kind<'tcx>>,
+    predicates_cache: SingletonListCache<'tcx, Predicate<'tcx>>,
+
     /// A general purpose channel to throw data out the back towards LLVM worker
     /// threads.
     ///
@@ -1276,6 +1381,8 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
             crate_name: Symbol::intern(crate_name),
             data_layout,
             layout_interner: Default::default(),
+            substs_cache: Default::default(),
+            predicates_cache: Default::default(),
             stability_interner: Default::default(),
             allocation_interner: Default::default(),
             alloc_map: Lock::new(interpret::AllocMap::new()),
@@ -1473,7 +1580,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
     /// because that method has a narrower effect that can be toggled
     /// off via a separate `-Z` flag, at least for the short term.
     pub fn allow_bind_by_move_patterns_with_guards(self) -> bool {
-        self.features().bind_by_move_pattern_guards && self.use_mir_borrowck()
+        self.borrowck_config(LOCAL_CRATE).bind_by_move_pattern_guards
     }
 
     /// If true, we should use a naive AST walk to determine if match
@@ -1483,11 +1590,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         // careful and ensure that MIR-borrowck is enabled (which can
         // happen via edition selection, via `feature(nll)`, or via an
         // appropriate `-Z` flag) before disabling the mutation check.
-        if self.allow_bind_by_move_patterns_with_guards() {
-            return false;
-        }
-
-        return true;
+        !self.allow_bind_by_move_patterns_with_guards()
     }
 
     /// If true, we should use the AST-based borrowck (we may *also* use
@@ -1518,7 +1621,7 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
     /// statements (which simulate the maximal effect of executing the
     /// patterns in a match arm).
     pub fn emit_read_for_match(&self) -> bool {
-        self.use_mir_borrowck() && !self.sess.opts.debugging_opts.nll_dont_emit_read_for_match
+        self.borrowck_config(LOCAL_CRATE).emit_read_for_match
     }
 
     /// If true, pattern variables for use in guards on match arms
@@ -1533,53 +1636,13 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
     /// done with either: `-Ztwo-phase-borrows`, `#![feature(nll)]`,
     /// or by opting into an edition after 2015.
     pub fn two_phase_borrows(self) -> bool {
-        self.sess.rust_2018() || self.features().nll ||
-        self.sess.opts.debugging_opts.two_phase_borrows
+        self.borrowck_config(LOCAL_CRATE).two_phase_borrows
     }
 
     /// What mode(s) of borrowck should we run? AST? MIR? both?
     /// (Also considers the `#![feature(nll)]` setting.)
     pub fn borrowck_mode(&self) -> BorrowckMode {
-        // Here are the main constraints we need to deal with:
-        //
-        // 1. An opts.borrowck_mode of `BorrowckMode::Ast` is
-        //    synonymous with no `-Z borrowck=...` flag at all.
-        //    (This is arguably a historical accident.)
-        //
-        // 2. `BorrowckMode::Migrate` is the limited migration to
-        //    NLL that we are deploying with the 2018 edition.
-        //
-        // 3. We want to allow developers on the Nightly channel
-        //    to opt back into the "hard error" mode for NLL,
-        //    (which they can do via specifying `#![feature(nll)]`
-        //    explicitly in their crate).
-        //
-        // So, this precedence list is how pnkfelix chose to work with
-        // the above constraints:
-        //
-        // * `#![feature(nll)]` *always* means use NLL with hard
-        //   errors. (To simplify the code here, it now even overrides
-        //   a user's attempt to specify `-Z borrowck=compare`, which
-        //   we arguably do not need anymore and should remove.)
-        //
-        // * Otherwise, if no `-Z borrowck=...` flag was given (or
-        //   if `borrowck=ast` was specified), then use the default
-        //   as required by the edition.
-        //
-        // * Otherwise, use the behavior requested via `-Z borrowck=...`
-
-        if self.features().nll { return BorrowckMode::Mir; }
-
-        match self.sess.opts.borrowck_mode {
-            mode @ BorrowckMode::Mir |
-            mode @ BorrowckMode::Compare |
-            mode @ BorrowckMode::Migrate => mode,
-
-            BorrowckMode::Ast => match self.sess.edition() {
-                Edition::Edition2015 => BorrowckMode::Ast,
-                Edition::Edition2018 => BorrowckMode::Migrate,
-            },
-        }
+        self.borrowck_config(LOCAL_CRATE).mode
     }
 
     #[inline]
@@ -2136,7 +2199,9 @@ macro_rules! sty_debug_print {
                 };
                 $(let mut $variant = total;)*
 
-                for &Interned(t) in tcx.interners.type_.borrow().keys() {
+                let shards = tcx.interners.type_.lock_shards();
+                let types = shards.iter().flat_map(|shard| shard.keys());
+                for &Interned(t) in types {
                     let variant = match t.sty {
                         ty::Bool | ty::Char | ty::Int(..) | ty::Uint(..) |
                             ty::Float(..) | ty::Str | ty::Never => continue,
@@ -2183,12 +2248,63 @@ impl<'a, 'tcx> TyCtxt<'a, 'tcx, 'tcx> {
             Generator, GeneratorWitness, Dynamic, Closure, Tuple, Bound,
             Param, Infer, UnnormalizedProjection, Projection, Opaque, Foreign);
 
-        println!("Substs interner: #{}", self.interners.substs.borrow().len());
-        println!("Region interner: #{}", self.interners.region.borrow().len());
+        println!("Substs interner: #{}", self.interners.substs.len());
+        for &(name, (hits, misses)) in &[
+            ("Substs", self.substs_cache.stats()),
+            ("Predicates", self.predicates_cache.stats()),
+        ] {
+            let lookups = hits + misses;
+            println!("{} singleton cache: {} hits / {} lookups ({:.1}%)",
+                     name, hits, lookups,
+                     if lookups == 0 { 0.0 } else { hits as f64 * 100.0 / lookups as f64 });
+        }
+        println!("Region interner: #{}", self.interners.region.len());
         println!("Stability interner: #{}", self.stability_interner.borrow().len());
         println!("Allocation interner: #{}", self.allocation_interner.borrow().len());
         println!("Layout interner: #{}", self.layout_interner.borrow().len());
     }
+
+    /// Returns the number of bytes reserved by each of the global arenas of
+    /// the current worker, plus the arena backing the interners.
+    pub fn arena_memory_usage(self) -> Vec<(&'static str, usize)> {
+        let arenas = &**self.global_arenas;
+        vec![
+            ("layout", arenas.layout.allocated_bytes()),
+            ("generics", arenas.generics.allocated_bytes()),
+            ("trait_def", arenas.trait_def.allocated_bytes()),
+            ("adt_def", arenas.adt_def.allocated_bytes()),
+            ("steal_mir", arenas.steal_mir.allocated_bytes()),
+            ("mir", arenas.mir.allocated_bytes()),
+            ("tables", arenas.tables.allocated_bytes()),
+            ("const_allocs", arenas.const_allocs.allocated_bytes()),
+            ("interners", self.interners.arena.allocated_bytes()),
+        ]
+    }
+
+    pub fn print_arena_memory_usage(self) {
+        let usage = self.arena_memory_usage();
+        let total: usize = usage.iter().map(|&(_, bytes)| bytes).sum();
+        println!("Arena memory usage:");
+        for (name, bytes) in usage {
+            println!("    {:<16}{:>16} bytes", name, to_readable_str(bytes));
+        }
+        println!("    {:<16}{:>16} bytes", "total", to_readable_str(total));
+    }
+
+    /// Returns the number of entries in each of the global interners, keyed by
+    /// a short human-readable name. Used by `-Z crate-stats`.
+    pub fn interner_counts(self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("types", self.interners.type_.len()),
+            ("type_lists", self.interners.type_list.len()),
+            ("substs", self.interners.substs.len()),
+            ("regions", self.interners.region.len()),
+            ("predicates", self.interners.predicates.len()),
+            ("stability", self.stability_interner.borrow().len()),
+            ("allocations", self.allocation_interner.borrow().len()),
+            ("layouts", self.layout_interner.borrow().len()),
+        ]
+    }
 }
 
 
@@ -2328,7 +2444,7 @@ macro_rules! intern_method {
                 // determine that all contents are in the global tcx.
                 // See comments on Lift for why we can't use that.
                 if ($keep_in_local_tcx)(&v) {
-                    self.interners.$name.borrow_mut().intern_ref(key, || {
+                    self.interners.$name.intern_ref(key, || {
                         // Make sure we don't end up with inference
                         // types/regions in the global tcx.
                         if self.is_global() {
@@ -2340,7 +2456,7 @@ macro_rules! intern_method {
                         Interned($alloc_method(&self.interners.arena, v))
                     }).0
                 } else {
-                    self.global_interners.$name.borrow_mut().intern_ref(key, || {
+                    self.global_interners.$name.intern_ref(key, || {
                         // This transmutes $alloc<'tcx> to $alloc<'gcx>
                         let v = unsafe {
                             mem::transmute(v)
@@ -2723,6 +2839,14 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         if preds.len() == 0 {
             // The macro-generated method below asserts we don't intern an empty slice.
             List::empty()
+        } else if preds.len() == 1 && !keep_local(&preds[0]) {
+            // This transmutes Predicate<'tcx> to Predicate<'gcx>; the list
+            // would be interned globally anyway.
+            let pred: Predicate<'gcx> = unsafe { mem::transmute(preds[0]) };
+            let global_tcx = self.global_tcx();
+            self.gcx.predicates_cache.get_or_intern(pred, || {
+                global_tcx._intern_predicates(&[pred])
+            })
         } else {
             self._intern_predicates(preds)
         }
@@ -2739,6 +2863,12 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
     pub fn intern_substs(self, ts: &[Kind<'tcx>]) -> &'tcx List<Kind<'tcx>> {
         if ts.len() == 0 {
             List::empty()
+        } else if ts.len() == 1 && !keep_local(&ts[0]) {
+            // This transmutes Kind<'tcx> to Kind<'gcx>; the list would be
+            // interned globally anyway.
+            let kind: Kind<'gcx> = unsafe { mem::transmute(ts[0]) };
+            let global_tcx = self.global_tcx();
+            self.gcx.substs_cache.get_or_intern(kind, || global_tcx._intern_substs(&[kind]))
         } else {
             self._intern_substs(ts)
         }
@@ -3055,6 +3185,62 @@ pub fn provide(providers: &mut ty::query::Providers<'_>) {
         assert_eq!(cnum, LOCAL_CRATE);
         Lrc::new(tcx.sess.features_untracked().clone())
     };
+    providers.borrowck_config = |tcx, cnum| {
+        assert_eq!(cnum, LOCAL_CRATE);
+        let features = tcx.features();
+
+        // Here are the main constraints we need to deal with:
+        //
+        // 1. An opts.borrowck_mode of `BorrowckMode::Ast` is
+        //    synonymous with no `-Z borrowck=...` flag at all.
+        //    (This is arguably a historical accident.)
+        //
+        // 2. `BorrowckMode::Migrate` is the limited migration to
+        //    NLL that we are deploying with the 2018 edition.
+        //
+        // 3. We want to allow developers on the Nightly channel
+        //    to opt back into the "hard error" mode for NLL,
+        //    (which they can do via specifying `#![feature(nll)]`
+        //    explicitly in their crate).
+        //
+        // So, this precedence list is how pnkfelix chose to work with
+        // the above constraints:
+        //
+        // * `#![feature(nll)]` *always* means use NLL with hard
+        //   errors. (To simplify the code here, it now even overrides
+        //   a user's attempt to specify `-Z borrowck=compare`, which
+        //   we arguably do not need anymore and should remove.)
+        //
+        // * Otherwise, if no `-Z borrowck=...` flag was given (or
+        //   if `borrowck=ast` was specified), then use the default
+        //   as required by the edition.
+        //
+        // * Otherwise, use the behavior requested via `-Z borrowck=...`
+
+        let mode = if features.nll {
+            BorrowckMode::Mir
+        } else {
+            match tcx.sess.opts.borrowck_mode {
+                mode @ BorrowckMode::Mir |
+                mode @ BorrowckMode::Compare |
+                mode @ BorrowckMode::Migrate => mode,
+
+                BorrowckMode::Ast => match tcx.sess.edition() {
+                    Edition::Edition2015 => BorrowckMode::Ast,
+                    Edition::Edition2018 => BorrowckMode::Migrate,
+                },
+            }
+        };
+
+        BorrowckConfig {
+            mode,
+            two_phase_borrows: tcx.sess.rust_2018() || features.nll ||
+                tcx.sess.opts.debugging_opts.two_phase_borrows,
+            emit_read_for_match: mode.use_mir() &&
+                !tcx.sess.opts.debugging_opts.nll_dont_emit_read_for_match,
+            bind_by_move_pattern_guards: features.bind_by_move_pattern_guards && mode.use_mir(),
+        }
+    };
     providers.is_panic_runtime = |tcx, cnum| {
         assert_eq!(cnum, LOCAL_CRATE);
         attr::contains_name(tcx.hir().krate_attrs(), "panic_runtime")
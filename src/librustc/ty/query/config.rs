@@ -827,6 +827,18 @@ impl<'tcx> QueryDescription<'tcx> for queries::maybe_unused_extern_crates<'tcx>
     }
 }
 
+impl<'tcx> QueryDescription<'tcx> for queries::unused_trait_imports<'tcx> {
+    fn describe(_tcx: TyCtxt<'_, '_, '_>, _: CrateNum) -> Cow<'static, str> {
+        "looking up unused trait imports".into()
+    }
+}
+
+impl<'tcx> QueryDescription<'tcx> for queries::unused_extern_crates<'tcx> {
+    fn describe(_tcx: TyCtxt<'_, '_, '_>, _: CrateNum) -> Cow<'static, str> {
+        "looking up unused extern crates".into()
+    }
+}
+
 impl<'tcx> QueryDescription<'tcx> for queries::stability_index<'tcx> {
     fn describe(_tcx: TyCtxt<'_, '_, '_>, _: CrateNum) -> Cow<'static, str> {
         "calculating the stability index for the local crate".into()
@@ -881,6 +893,12 @@ impl<'tcx> QueryDescription<'tcx> for queries::features_query<'tcx> {
     }
 }
 
+impl<'tcx> QueryDescription<'tcx> for queries::borrowck_config<'tcx> {
+    fn describe(_tcx: TyCtxt<'_, '_, '_>, _: CrateNum) -> Cow<'static, str> {
+        "computing the borrow checking configuration".into()
+    }
+}
+
 impl<'tcx> QueryDescription<'tcx> for queries::typeck_tables_of<'tcx> {
     #[inline]
     fn cache_on_disk(_: TyCtxt<'_, 'tcx, 'tcx>, def_id: Self::Key) -> bool {
@@ -9,6 +9,7 @@ use crate::middle::cstore::{ExternCrate, LinkagePreference, NativeLibrary, Forei
 use crate::middle::cstore::{NativeLibraryKind, DepKind, CrateSource};
 use crate::middle::privacy::AccessLevels;
 use crate::middle::reachable::ReachableSet;
+use crate::middle::free_region::OutlivesFact;
 use crate::middle::region;
 use crate::middle::resolve_lifetime::{ResolveLifetimes, Region, ObjectLifetimeDefault};
 use crate::middle::stability::{self, DeprecationEntry};
@@ -20,7 +21,7 @@ use crate::mir::mono::CodegenUnit;
 use crate::mir;
 use crate::mir::interpret::GlobalId;
 use crate::session::{CompileResult, CrateDisambiguator};
-use crate::session::config::{EntryFnType, OutputFilenames, OptLevel};
+use crate::session::config::{BorrowckConfig, EntryFnType, OutputFilenames, OptLevel};
 use crate::traits::{self, Vtable};
 use crate::traits::query::{
     CanonicalPredicateGoal, CanonicalProjectionGoal,
@@ -357,6 +358,11 @@ define_queries! { <'tcx>
         /// in the case of closures, this will be redirected to the enclosing function.
         [] fn region_scope_tree: RegionScopeTree(DefId) -> Lrc<region::ScopeTree>,
 
+        /// The outlives relationships known to hold in the body of a local function, from its
+        /// where-clauses and from the bounds implied by its signature, each with where it comes
+        /// from. Like for `region_scope_tree`, closures are redirected to the enclosing function.
+        [] fn body_outlives_facts: BodyOutlivesFacts(DefId) -> Lrc<Vec<OutlivesFact>>,
+
         [] fn mir_shims: mir_shim_dep_node(ty::InstanceDef<'tcx>) -> &'tcx mir::Mir<'tcx>,
 
         [] fn def_symbol_name: SymbolName(DefId) -> ty::SymbolName,
@@ -573,6 +579,12 @@ define_queries! { <'tcx>
             -> Lrc<Vec<(DefId, Span)>>,
         [] fn names_imported_by_glob_use: NamesImportedByGlobUse(DefId)
             -> Lrc<FxHashSet<ast::Name>>,
+        /// The trait imports of the local crate that were never used, with
+        /// the span of the import path, in item order.
+        [] fn unused_trait_imports: UnusedTraitImports(CrateNum) -> Lrc<Vec<(DefId, Span)>>,
+        /// The `extern crate` items of the local crate that are entirely
+        /// unused and could be removed, with their spans.
+        [] fn unused_extern_crates: UnusedExternCrates(CrateNum) -> Lrc<Vec<(DefId, Span)>>,
 
         [] fn stability_index: stability_index_node(CrateNum) -> Lrc<stability::Index<'tcx>>,
         [] fn all_crate_nums: all_crate_nums_node(CrateNum) -> Lrc<Vec<CrateNum>>,
@@ -730,6 +742,8 @@ define_queries! { <'tcx>
             -> usize,
 
         [] fn features_query: features_node(CrateNum) -> Lrc<feature_gate::Features>,
+
+        [] fn borrowck_config: borrowck_config_node(CrateNum) -> BorrowckConfig,
     },
 
     TypeChecking {
@@ -758,6 +772,10 @@ fn features_node<'tcx>(_: CrateNum) -> DepConstructor<'tcx> {
     DepConstructor::Features
 }
 
+fn borrowck_config_node<'tcx>(_: CrateNum) -> DepConstructor<'tcx> {
+    DepConstructor::BorrowckConfig
+}
+
 fn codegen_fn_attrs<'tcx>(id: DefId) -> DepConstructor<'tcx> {
     DepConstructor::CodegenFnAttrs { 0: id }
 }
@@ -10,7 +10,7 @@ use crate::ty::query::config::{QueryConfig, QueryDescription};
 use crate::ty::query::job::{QueryJob, QueryResult, QueryInfo};
 use crate::ty::item_path;
 
-use crate::util::common::{profq_msg, ProfileQueriesMsg, QueryMsg};
+use crate::util::common::{profq_msg, IceQuery, ProfileQueriesMsg, QueryMsg};
 
 use errors::DiagnosticBuilder;
 use errors::Level;
@@ -352,6 +352,60 @@ impl<'a, 'gcx, 'tcx> TyCtxt<'a, 'gcx, 'tcx> {
         eprintln!("end of query stack");
     }
 
+    /// Collects the names of the queries on the stack of the current thread,
+    /// innermost first, along with where each one was used. The source line
+    /// of each use is included if `include_sources` is true.
+    ///
+    /// This runs no query code, so the panic hook can call it.
+    pub fn try_collect_query_stack(include_sources: bool) -> Vec<IceQuery> {
+        let mut stack = Vec::new();
+
+        tls::with_context_opt(|icx| {
+            if let Some(icx) = icx {
+                let source_map = icx.tcx.sess.source_map();
+                let mut current_query = icx.query.clone();
+
+                while let Some(query) = current_query {
+                    let span = source_map.def_span(query.info.span);
+                    let source = if include_sources {
+                        source_map.span_to_snippet(span).ok()
+                    } else {
+                        None
+                    };
+                    stack.push(IceQuery {
+                        name: query.info.query.name(),
+                        location: source_map.span_to_string(span),
+                        source,
+                        description: None,
+                    });
+
+                    current_query = query.parent.clone();
+                }
+            }
+        });
+
+        stack
+    }
+
+    /// Describes the queries on the stack of the current thread, innermost
+    /// first. This runs query code, so it must not be called while panicking.
+    pub fn try_describe_query_stack() -> Vec<String> {
+        let mut stack = Vec::new();
+
+        tls::with_context_opt(|icx| {
+            if let Some(icx) = icx {
+                let mut current_query = icx.query.clone();
+
+                while let Some(query) = current_query {
+                    stack.push(query.info.query.describe(icx.tcx).into_owned());
+                    current_query = query.parent.clone();
+                }
+            }
+        });
+
+        stack
+    }
+
     #[inline(never)]
     pub(super) fn get_query<Q: QueryDescription<'gcx>>(
         self,
@@ -1245,6 +1299,7 @@ pub fn force_from_dep_node<'a, 'gcx, 'lcx>(tcx: TyCtxt<'a, 'gcx, 'lcx>,
         }
 
         DepKind::RegionScopeTree => { force!(region_scope_tree, def_id!()); }
+        DepKind::BodyOutlivesFacts => { force!(body_outlives_facts, def_id!()); }
 
         DepKind::Coherence => { force!(crate_inherent_impls, LOCAL_CRATE); }
         DepKind::CoherenceInherentImplOverlapCheck => {
@@ -1404,6 +1459,8 @@ pub fn force_from_dep_node<'a, 'gcx, 'lcx>(tcx: TyCtxt<'a, 'gcx, 'lcx>,
         }
         DepKind::NamesImportedByGlobUse => { force!(names_imported_by_glob_use, def_id!()); }
         DepKind::MaybeUnusedExternCrates => { force!(maybe_unused_extern_crates, LOCAL_CRATE); }
+        DepKind::UnusedTraitImports => { force!(unused_trait_imports, krate!()); }
+        DepKind::UnusedExternCrates => { force!(unused_extern_crates, krate!()); }
         DepKind::StabilityIndex => { force!(stability_index, LOCAL_CRATE); }
         DepKind::AllTraits => { force!(all_traits, LOCAL_CRATE); }
         DepKind::AllCrateNums => { force!(all_crate_nums, LOCAL_CRATE); }
@@ -1417,6 +1474,7 @@ pub fn force_from_dep_node<'a, 'gcx, 'lcx>(tcx: TyCtxt<'a, 'gcx, 'lcx>,
         DepKind::TargetFeaturesWhitelist => { force!(target_features_whitelist, LOCAL_CRATE); }
 
         DepKind::Features => { force!(features_query, LOCAL_CRATE); }
+        DepKind::BorrowckConfig => { force!(borrowck_config, LOCAL_CRATE); }
 
         DepKind::ProgramClausesFor => { force!(program_clauses_for, def_id!()); }
         DepKind::WasmImportModuleMap => { force!(wasm_import_module_map, krate!()); }
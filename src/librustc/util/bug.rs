@@ -1,7 +1,9 @@
 // These functions are used by macro expansion for bug! and span_bug!
 
-use crate::ty::tls;
+use crate::ty::{tls, TyCtxt};
+use crate::util::common::{ice_bundle_enabled, set_ice_query_descriptions};
 use std::fmt;
+use std::panic;
 use syntax_pos::{Span, MultiSpan};
 
 #[cold]
@@ -31,6 +33,13 @@ fn opt_span_bug_fmt<S: Into<MultiSpan>>(
 ) -> ! {
     tls::with_opt(move |tcx| {
         let msg = format!("{}:{}: {}", file, line, args);
+        if tcx.is_some() && ice_bundle_enabled() {
+            // This is the last point where a panic while describing the
+            // queries can still be caught, so do it for the report bundle.
+            if let Ok(descriptions) = panic::catch_unwind(TyCtxt::try_describe_query_stack) {
+                set_ice_query_descriptions(descriptions);
+            }
+        }
         match (tcx, span) {
             (Some(tcx), Some(span)) => tcx.sess.diagnostic().span_bug(span, &msg),
             (Some(tcx), None) => tcx.sess.diagnostic().bug(&msg),
@@ -8,6 +8,7 @@ use std::fmt::Debug;
 use std::hash::{Hash, BuildHasher};
 use std::panic;
 use std::env;
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
 use std::sync::mpsc::{Sender};
@@ -35,6 +36,61 @@ lazy_static! {
     };
 }
 
+/// What the panic hook saw of the last ICE, for the report bundle written
+/// by the driver after the compiler thread has unwound.
+#[derive(Clone, Debug)]
+pub struct IceInfo {
+    pub message: String,
+    pub location: Option<String>,
+    /// The queries that were running, innermost first.
+    pub query_stack: Vec<IceQuery>,
+}
+
+/// A query that was running when the compiler panicked.
+#[derive(Clone, Debug)]
+pub struct IceQuery {
+    pub name: &'static str,
+    /// Where the query was used.
+    pub location: String,
+    /// The source code at `location`, if it was asked for.
+    pub source: Option<String>,
+    /// What the query computes. Only known for ICEs raised with `bug!`.
+    pub description: Option<String>,
+}
+
+lazy_static! {
+    static ref LAST_ICE: Mutex<Option<IceInfo>> = Mutex::new(None);
+}
+
+thread_local!(static ICE_QUERY_DESCRIPTIONS: RefCell<Option<Vec<String>>> = RefCell::new(None));
+
+/// Records the descriptions of the queries on the stack, for the ICE that is
+/// about to be raised on this thread.
+///
+/// Describing a query runs query code. That can't be done from the panic hook,
+/// where another panic would abort and lose the whole report, so `bug!` does
+/// it before panicking.
+pub fn set_ice_query_descriptions(descriptions: Vec<String>) {
+    ICE_QUERY_DESCRIPTIONS.with(|d| *d.borrow_mut() = Some(descriptions));
+}
+
+/// Takes what the panic hook recorded about the last ICE, if anything.
+pub fn take_ice_info() -> Option<IceInfo> {
+    LAST_ICE.lock().ok().and_then(|mut info| info.take())
+}
+
+/// Whether a report bundle should be written on ICE, which the user can turn
+/// off with `RUSTC_ICE_BUNDLE=0`.
+pub fn ice_bundle_enabled() -> bool {
+    env::var_os("RUSTC_ICE_BUNDLE").map(|x| &x != "0").unwrap_or(true)
+}
+
+/// Whether the ICE report bundle should include source code, which the user
+/// has to opt into with `RUSTC_ICE_BUNDLE_SOURCES=1`.
+pub fn ice_bundle_includes_sources() -> bool {
+    env::var_os("RUSTC_ICE_BUNDLE_SOURCES").map(|x| &x == "1").unwrap_or(false)
+}
+
 fn panic_hook(info: &panic::PanicInfo<'_>) {
     (*DEFAULT_HOOK)(info);
 
@@ -44,6 +100,39 @@ fn panic_hook(info: &panic::PanicInfo<'_>) {
         TyCtxt::try_print_query_stack();
     }
 
+    if ice_bundle_enabled() {
+        let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = info.payload().downcast_ref::<String>() {
+            s.clone()
+        } else {
+            // `bug!` and friends have already printed their message.
+            "explicit bug (see the error above)".to_string()
+        };
+        // Only the names and spans of the queries are collected here, as
+        // anything running query code could panic again.
+        let mut query_stack = TyCtxt::try_collect_query_stack(ice_bundle_includes_sources());
+        let descriptions = ICE_QUERY_DESCRIPTIONS
+            .try_with(|d| d.borrow_mut().take())
+            .ok()
+            .and_then(|d| d);
+        if let Some(descriptions) = descriptions {
+            if descriptions.len() == query_stack.len() {
+                for (query, description) in query_stack.iter_mut().zip(descriptions) {
+                    query.description = Some(description);
+                }
+            }
+        }
+        let ice = IceInfo {
+            message,
+            location: info.location().map(|location| location.to_string()),
+            query_stack,
+        };
+        if let Ok(mut last_ice) = LAST_ICE.lock() {
+            *last_ice = Some(ice);
+        }
+    }
+
         #[cfg(windows)]
         unsafe {
             if env::var("RUSTC_BREAK_ON_ICE").is_ok() {
@@ -0,0 +1,120 @@
+//! Locked data structures split into shards by hash, so that threads working
+//! on different keys rarely contend for the same lock.
+//!
+//! Without `cfg(parallel_compiler)` there is a single shard, and a `Sharded<T>`
+//! behaves like a plain `Lock<T>`.
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RawEntryMut;
+use std::hash::{Hash, Hasher};
+
+use crate::fx::{FxHasher, FxHashMap};
+use crate::sync::{Lock, LockGuard};
+
+#[derive(Default)]
+#[cfg_attr(parallel_compiler, repr(align(64)))]
+struct CacheAligned<T>(T);
+
+#[cfg(parallel_compiler)]
+const SHARD_BITS: usize = 5;
+
+#[cfg(not(parallel_compiler))]
+const SHARD_BITS: usize = 0;
+
+const SHARDS: usize = 1 << SHARD_BITS;
+
+/// An array of cache-line aligned `Lock<T>`s, selected by the hash of a key.
+pub struct Sharded<T> {
+    shards: [CacheAligned<Lock<T>>; SHARDS],
+}
+
+impl<T: Default> Default for Sharded<T> {
+    #[inline]
+    fn default() -> Self {
+        Sharded { shards: Default::default() }
+    }
+}
+
+impl<T> Sharded<T> {
+    /// Returns the shard that `val` belongs to.
+    #[inline]
+    pub fn get_shard_by_value<K: Hash + ?Sized>(&self, val: &K) -> &Lock<T> {
+        if SHARDS == 1 {
+            &self.shards[0].0
+        } else {
+            self.get_shard_by_hash(make_hash(val))
+        }
+    }
+
+    /// Returns the shard for a value whose `FxHasher` hash is `hash`.
+    #[inline]
+    pub fn get_shard_by_hash(&self, hash: u64) -> &Lock<T> {
+        // Hash tables index by the low bits of the hash and may keep the top
+        // seven for themselves, so pick the shard with the bits just below
+        // those, keeping the keys of each shard spread over its whole table.
+        let bits = (hash >> (64 - 7 - SHARD_BITS)) as usize;
+        &self.shards[bits % SHARDS].0
+    }
+
+    /// Locks every shard, e.g. to iterate over all of their contents.
+    pub fn lock_shards(&self) -> Vec<LockGuard<'_, T>> {
+        self.shards.iter().map(|shard| shard.0.lock()).collect()
+    }
+}
+
+pub type ShardedHashMap<K, V> = Sharded<FxHashMap<K, V>>;
+
+impl<K: Eq + Hash, V> ShardedHashMap<K, V> {
+    pub fn len(&self) -> usize {
+        self.lock_shards().iter().map(|shard| shard.len()).sum()
+    }
+}
+
+impl<K: Eq + Hash + Copy> ShardedHashMap<K, ()> {
+    /// Like `HashInterner::intern_ref`, locking only the shard of `value`.
+    #[inline]
+    pub fn intern_ref<Q: ?Sized, F: FnOnce() -> K>(&self, value: &Q, make: F) -> K
+        where K: Borrow<Q>,
+              Q: Hash + Eq
+    {
+        let hash = make_hash(value);
+        let mut shard = self.get_shard_by_hash(hash).lock();
+        let entry = shard.raw_entry_mut().from_key_hashed_nocheck(hash, value);
+
+        match entry {
+            RawEntryMut::Occupied(e) => *e.key(),
+            RawEntryMut::Vacant(e) => {
+                let v = make();
+                e.insert_hashed_nocheck(hash, v, ());
+                v
+            }
+        }
+    }
+
+    /// Like `HashInterner::intern`, locking only the shard of `value`.
+    #[inline]
+    pub fn intern<Q, F: FnOnce(Q) -> K>(&self, value: Q, make: F) -> K
+        where K: Borrow<Q>,
+              Q: Hash + Eq
+    {
+        let hash = make_hash(&value);
+        let mut shard = self.get_shard_by_hash(hash).lock();
+        let entry = shard.raw_entry_mut().from_key_hashed_nocheck(hash, &value);
+
+        match entry {
+            RawEntryMut::Occupied(e) => *e.key(),
+            RawEntryMut::Vacant(e) => {
+                let v = make(value);
+                e.insert_hashed_nocheck(hash, v, ());
+                v
+            }
+        }
+    }
+}
+
+#[inline]
+fn make_hash<K: Hash + ?Sized>(val: &K) -> u64 {
+    let mut state = FxHasher::default();
+    val.hash(&mut state);
+    state.finish()
+}
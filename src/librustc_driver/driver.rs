@@ -10,7 +10,8 @@ use rustc::util::common::{install_panic_hook, time, ErrorReported};
 use rustc::util::profiling::ProfileCategory;
 use rustc::session::{CompileResult, CrateDisambiguator, Session};
 use rustc::session::CompileIncomplete;
-use rustc::session::config::{self, Input, OutputFilenames, OutputType};
+use rustc::session::config::{self, AstStatsFormat, Input, MirExportFormat, OutputFilenames,
+                              OutputType, TraceMacros};
 use rustc::session::search_paths::PathKind;
 use rustc_allocator as allocator;
 use rustc_borrowck as borrowck;
@@ -34,7 +35,7 @@ use syntax::early_buffered_lints::BufferedEarlyLint;
 use syntax::ext::base::ExtCtxt;
 use syntax::mut_visit::MutVisitor;
 use syntax::parse::{self, PResult};
-use syntax::util::node_count::NodeCounter;
+use syntax::util::node_count::{self, NodeCounter};
 use syntax::util::lev_distance::find_best_match_for_name;
 use syntax::symbol::Symbol;
 use syntax_pos::{FileName, hygiene};
@@ -327,10 +328,32 @@ pub fn compile_input(
                     }
                 }
 
+                if let Some(MirExportFormat::Json) = tcx.sess.opts.debugging_opts.export_mir {
+                    if let Err(e) = mir::util::export::emit_mir_json(tcx, &outputs) {
+                        sess.err(&format!("could not export MIR: {}", e));
+                        sess.abort_if_errors();
+                    }
+                }
+
                 if tcx.sess.opts.debugging_opts.query_stats {
                     tcx.queries.print_stats();
                 }
 
+                if tcx.sess.opts.debugging_opts.meminfo {
+                    tcx.print_arena_memory_usage();
+                }
+
+                if tcx.sess.opts.debugging_opts.crate_stats {
+                    rustc_passes::crate_stats::print_crate_stats(tcx);
+                }
+
+                if let Some(ref path) = tcx.sess.opts.debugging_opts.emit_unused_report {
+                    if let Err(e) = rustc_passes::unused_report::emit_unused_report(tcx, path) {
+                        sess.err(&format!("could not write unused report to `{}`: {}",
+                                          path.display(), e));
+                    }
+                }
+
                 Ok((outputs.clone(), ongoing_codegen, tcx.dep_graph.clone()))
             },
         )??
@@ -340,6 +363,10 @@ pub fn compile_input(
         sess.code_stats.borrow().print_type_sizes();
     }
 
+    if sess.opts.debugging_opts.print_generator_sizes {
+        sess.code_stats.borrow().print_generator_sizes();
+    }
+
     codegen_backend.join_codegen_and_link(ongoing_codegen, sess, &dep_graph, &outputs)?;
 
     if sess.opts.debugging_opts.perf_stats {
@@ -683,6 +710,10 @@ pub fn phase_1_parse_input<'a>(
         println!("Pre-expansion node count:  {}", count_nodes(&krate));
     }
 
+    if let Some(format) = sess.opts.debugging_opts.ast_stats {
+        print_ast_stats(&krate, "pre-expansion", format);
+    }
+
     if let Some(ref s) = sess.opts.debugging_opts.show_span {
         syntax::show_span::run(sess.diagnostic(), s, &krate);
     }
@@ -700,6 +731,14 @@ fn count_nodes(krate: &ast::Crate) -> usize {
     counter.count
 }
 
+fn print_ast_stats(krate: &ast::Crate, phase: &'static str, format: AstStatsFormat) {
+    let stats = node_count::ast_stats(krate, phase);
+    match format {
+        AstStatsFormat::Text => println!("{}", stats),
+        AstStatsFormat::Json => println!("{}", json::as_pretty_json(&stats)),
+    }
+}
+
 // For continuing compilation after a parsed crate has been
 // modified
 
@@ -967,8 +1006,14 @@ where
         let cfg = syntax::ext::expand::ExpansionConfig {
             features: Some(&features),
             recursion_limit: *sess.recursion_limit.get(),
-            trace_mac: sess.opts.debugging_opts.trace_macros,
+            trace_mac: sess.opts.debugging_opts.trace_macros != TraceMacros::No,
+            trace_mac_detailed: sess.opts.debugging_opts.trace_macros == TraceMacros::Detailed,
             should_test: sess.opts.test,
+            transcribed_spans: if sess.opts.debugging_opts.macro_call_site_spans {
+                syntax::ext::tt::transcribe::TranscribedSpans::CallSite
+            } else {
+                syntax::ext::tt::transcribe::TranscribedSpans::Definition
+            },
             ..syntax::ext::expand::ExpansionConfig::default(crate_name.to_string())
         };
 
@@ -1069,6 +1114,10 @@ where
         println!("Post-expansion node count: {}", count_nodes(&krate));
     }
 
+    if let Some(format) = sess.opts.debugging_opts.ast_stats {
+        print_ast_stats(&krate, "post-expansion", format);
+    }
+
     if sess.opts.debugging_opts.hir_stats {
         hir_stats::print_ast_stats(&krate, "POST EXPANSION AST STATS");
     }
@@ -1092,6 +1141,11 @@ where
         );
     });
 
+    if sess.opts.debugging_opts.explain_cfg {
+        let report = syntax::config::cfg_report(&sess.parse_sess);
+        println!("{}", json::as_pretty_json(&report));
+    }
+
     // Add all buffered lints from the `ParseSess` to the `Session`.
     sess.parse_sess.buffered_lints.with_lock(|buffered_lints| {
         info!("{} parse sess buffered_lints", buffered_lints.len());
@@ -1252,7 +1306,20 @@ where
             });
 
             // passes are timed inside typeck
-            match typeck::check_crate(tcx) {
+            let typeck_result = typeck::check_crate(tcx);
+
+            // Uses of library features are recorded by the stability checks,
+            // which run in the pass above and in typeck.
+            if sess.opts.debugging_opts.report_feature_gates {
+                let report = syntax::feature_gate::feature_gate_report(
+                    &tcx.features(),
+                    &sess.parse_sess,
+                    |feature| stability::find_lib_feature(tcx, feature),
+                );
+                println!("{}", json::as_pretty_json(&report));
+            }
+
+            match typeck_result {
                 Ok(x) => x,
                 Err(x) => {
                     f(tcx, rx, Err(x));
@@ -146,6 +146,15 @@ const ICE_REPORT_COMPILER_FLAGS_EXCLUDE: &[&str] = &["metadata", "extra-filename
 
 const ICE_REPORT_COMPILER_FLAGS_STRIP_VALUE: &[&str] = &["incremental"];
 
+// Environment variables whose values are included in ICE report bundles.
+const ICE_BUNDLE_ENV_VARS: &[&str] = &[
+    "RUST_BACKTRACE", "RUST_LOG", "RUSTC_BOOTSTRAP", "RUSTFLAGS",
+    "CARGO_PKG_NAME", "CARGO_PKG_VERSION", "CARGO_PRIMARY_PACKAGE",
+];
+
+// Crate roots larger than this are left out of ICE report bundles.
+const ICE_BUNDLE_MAX_SOURCE_LEN: u64 = 256 * 1024;
+
 pub fn abort_on_err<T>(result: Result<T, CompileIncomplete>, sess: &Session) -> T {
     match result {
         Err(CompileIncomplete::Errored(ErrorReported)) => {
@@ -461,7 +470,8 @@ fn run_compiler_with_pool<'a>(
         }
     }}
 
-    let descriptions = diagnostics_registry();
+    let mut descriptions = diagnostics_registry();
+    callbacks.register_diagnostics(&mut descriptions);
 
     do_or_return!(callbacks.early_callback(&matches,
                                            &sopts,
@@ -617,6 +627,12 @@ impl Compilation {
 /// A trait for customizing the compilation process. Offers a number of hooks for
 /// executing custom code or customizing input.
 pub trait CompilerCalls<'a> {
+    /// Hook for adding the explanations of error codes emitted by the driver
+    /// (e.g., by its own lint passes) to the diagnostic registry. This will
+    /// be called before `early_callback`, so the codes can be looked up with
+    /// `--explain` and are described in JSON output like the built-in ones.
+    fn register_diagnostics(&mut self, _: &mut errors::registry::Registry) {}
+
     /// Hook for a callback early in the process of handling arguments. This will
     /// be called straight after options have been parsed but before anything
     /// else (e.g., selecting input and output).
@@ -712,7 +728,9 @@ fn stdout_isatty() -> bool {
 fn handle_explain(code: &str,
                   descriptions: &errors::registry::Registry,
                   output: ErrorOutputType) {
-    let normalised = if code.starts_with("E") {
+    // Codes registered by drivers need not follow the `E0123` format, so only
+    // bare numbers are normalised.
+    let normalised = if code.starts_with("E") || !code.chars().all(|c| c.is_ascii_digit()) {
         code.to_string()
     } else {
         format!("E{0:0>4}", code)
@@ -722,8 +740,13 @@ fn handle_explain(code: &str,
             let mut is_in_code_block = false;
             let mut text = String::new();
 
-            // Slice off the leading newline and print.
-            for line in description[1..].lines() {
+            // Slice off the leading newline, if any, and print.
+            let description = if description.starts_with('\n') {
+                &description[1..]
+            } else {
+                description
+            };
+            for line in description.lines() {
                 let indent_level = line.find(|c: char| !c.is_whitespace())
                     .unwrap_or_else(|| line.len());
                 let dedented_line = &line[indent_level..];
@@ -1541,6 +1564,80 @@ fn extra_compiler_flags() -> Option<(Vec<String>, bool)> {
     }
 }
 
+/// Writes a file with what is known about an ICE, to be attached to the bug
+/// report, and returns its path.
+///
+/// The bundle is written to the directory named by `RUSTC_ICE_BUNDLE`, or the
+/// temporary directory if it is unset, and is not written at all if it is
+/// `0`. Source code is only included with `RUSTC_ICE_BUNDLE_SOURCES=1`.
+fn write_ice_bundle() -> Option<PathBuf> {
+    if !rustc::util::common::ice_bundle_enabled() {
+        return None;
+    }
+    let dir = match env::var_os("RUSTC_ICE_BUNDLE") {
+        Some(dir) => PathBuf::from(dir),
+        None => env::temp_dir(),
+    };
+    let ice = rustc::util::common::take_ice_info();
+    let include_sources = rustc::util::common::ice_bundle_includes_sources();
+
+    let mut report = String::new();
+    report.push_str(&format!("rustc {} running on {}\n",
+                             option_env!("CFG_VERSION").unwrap_or("unknown_version"),
+                             config::host_triple()));
+    report.push_str(&format!("os: {} {}\n", env::consts::OS, env::consts::ARCH));
+
+    let args = env::args_os().map(|arg| arg.to_string_lossy().to_string()).collect::<Vec<_>>();
+    report.push_str(&format!("\ncommand line:\n{}\n", args.join(" ")));
+    if let Ok(dir) = env::current_dir() {
+        report.push_str(&format!("working directory: {}\n", dir.display()));
+    }
+
+    report.push_str("\nenvironment:\n");
+    for var in ICE_BUNDLE_ENV_VARS {
+        if let Some(value) = env::var_os(var) {
+            report.push_str(&format!("{}={}\n", var, value.to_string_lossy()));
+        }
+    }
+
+    if let Some(ref ice) = ice {
+        report.push_str(&format!("\npanic: {}\n", ice.message));
+        if let Some(ref location) = ice.location {
+            report.push_str(&format!("at: {}\n", location));
+        }
+        report.push_str("\nquery stack:\n");
+        for (i, query) in ice.query_stack.iter().enumerate() {
+            report.push_str(&format!("#{} [{}]", i, query.name));
+            if let Some(ref description) = query.description {
+                report.push_str(&format!(" {}", description));
+            }
+            report.push_str(&format!(" at {}\n", query.location));
+            if let Some(ref source) = query.source {
+                report.push_str(&format!("    {}\n", source.trim()));
+            }
+        }
+    }
+
+    if include_sources {
+        let input = handle_options(&args).and_then(|matches| matches.free.get(0).cloned());
+        if let Some(input) = input {
+            let path = Path::new(&input);
+            let small = path.metadata().map(|m| m.len() <= ICE_BUNDLE_MAX_SOURCE_LEN);
+            if let (Ok(true), Ok(src)) = (small, std::fs::read_to_string(path)) {
+                report.push_str(&format!("\nsource of {}:\n{}\n", path.display(), src));
+            }
+        }
+    }
+
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = dir.join(format!("rustc-ice-{}-{}.txt", secs, process::id()));
+    std::fs::write(&path, report).ok()?;
+    Some(path)
+}
+
 #[derive(Debug)]
 pub struct CompilationFailure;
 
@@ -1598,6 +1695,12 @@ pub fn monitor<F: FnOnce() + Send + 'static>(f: F) -> Result<(), CompilationFail
                 }
             }
 
+            if let Some(path) = write_ice_bundle() {
+                xs.push(format!("a report bundle was written to `{}`; please attach it to the \
+                                 bug report (set `RUSTC_ICE_BUNDLE=0` to not write one)",
+                                path.display()).into());
+            }
+
             for note in &xs {
                 handler.emit(&MultiSpan::new(),
                              note,
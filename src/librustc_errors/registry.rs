@@ -1,5 +1,7 @@
 use rustc_data_structures::fx::FxHashMap;
 
+use std::collections::hash_map::Entry;
+
 #[derive(Clone)]
 pub struct Registry {
     descriptions: FxHashMap<&'static str, &'static str>,
@@ -13,4 +15,20 @@ impl Registry {
     pub fn find_description(&self, code: &str) -> Option<&'static str> {
         self.descriptions.get(code).cloned()
     }
+
+    /// Adds the long-form explanation of an error code that the compiler
+    /// itself does not know about, such as one emitted by a custom driver.
+    ///
+    /// The explanation is Markdown, in the same format as the built-in ones.
+    /// Returns `false` and leaves the registry unchanged if `code` already
+    /// has an explanation.
+    pub fn register(&mut self, code: &'static str, description: &'static str) -> bool {
+        match self.descriptions.entry(code) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(description);
+                true
+            }
+        }
+    }
 }
@@ -19,7 +19,8 @@ use rustc_data_structures::sync::Lrc;
 use rustc_errors::{Applicability, DiagnosticBuilder};
 use syntax_pos::Span;
 
-use super::borrow_set::BorrowData;
+use super::borrow_set::{BorrowData, TwoPhaseActivation};
+use super::path_utils::is_active;
 use super::{Context, MirBorrowckCtxt};
 use super::{InitializationRequiringAction, PrefixSet};
 use crate::dataflow::drop_flag_effects;
@@ -293,6 +294,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         self.explain_why_borrow_contains_point(context, borrow, None)
             .add_explanation_to_diagnostic(self.infcx.tcx, self.mir, &mut err, "");
+        self.explain_two_phase_reservation(context.loc, borrow, &mut err);
         err.buffer(&mut self.errors_buffer);
     }
 
@@ -330,6 +332,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         self.explain_why_borrow_contains_point(context, borrow, None)
             .add_explanation_to_diagnostic(self.infcx.tcx, self.mir, &mut err, "");
+        self.explain_two_phase_reservation(context.loc, borrow, &mut err);
         err.buffer(&mut self.errors_buffer);
     }
 
@@ -544,10 +547,39 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         explanation
             .add_explanation_to_diagnostic(self.infcx.tcx, self.mir, &mut err, first_borrow_desc);
+        self.explain_two_phase_reservation(context.loc, issued_borrow, &mut err);
 
         err.buffer(&mut self.errors_buffer);
     }
 
+    /// With `-Z two-phase-borrows`, explains that `borrow` conflicts with the
+    /// access at `location` while it is only reserved, i.e. between the point
+    /// where it is created and its first use.
+    fn explain_two_phase_reservation(
+        &self,
+        location: Location,
+        borrow: &BorrowData<'tcx>,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        if !self.infcx.tcx.sess.opts.debugging_opts.two_phase_borrows {
+            return;
+        }
+        match borrow.activation_location {
+            TwoPhaseActivation::ActivatedAt(_) => {}
+            TwoPhaseActivation::NotTwoPhase | TwoPhaseActivation::NotActivated => return,
+        }
+        if is_active(&self.dominators, borrow, location) {
+            return;
+        }
+
+        let place = self.describe_place(&borrow.borrowed_place).unwrap_or_else(|| "_".to_owned());
+        err.note(&format!(
+            "the mutable borrow of `{}` is two-phase: `{}` is only reserved until the borrow \
+             is used, and may be read before then but not mutated or mutably borrowed",
+            place, place,
+        ));
+    }
+
     /// Returns the description of the root place for a conflicting borrow and the full
     /// descriptions of the places that caused the conflict.
     ///
@@ -1368,6 +1400,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
         self.explain_why_borrow_contains_point(context, loan, None)
             .add_explanation_to_diagnostic(self.infcx.tcx, self.mir, &mut err, "");
+        self.explain_two_phase_reservation(context.loc, loan, &mut err);
 
         err.buffer(&mut self.errors_buffer);
     }
@@ -7,6 +7,7 @@
 use rustc::ty::{self, Ty, TyCtxt};
 use rustc::mir::*;
 use crate::transform::{MirPass, MirSource};
+use crate::dataflow::has_rustc_mir_with;
 
 pub struct AddRetag;
 
@@ -77,12 +78,17 @@ fn may_have_reference<'a, 'gcx, 'tcx>(ty: Ty<'tcx>, tcx: TyCtxt<'a, 'gcx, 'tcx>)
 impl MirPass for AddRetag {
     fn run_pass<'a, 'tcx>(&self,
                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
-                          _src: MirSource<'tcx>,
+                          src: MirSource<'tcx>,
                           mir: &mut Mir<'tcx>)
     {
         if !tcx.sess.opts.debugging_opts.mir_emit_retag {
             return;
         }
+        // `#[rustc_mir(no_retag)]` opts a single function out, which helps
+        // debugging a program with miri without losing retags everywhere else.
+        if has_rustc_mir_with(&tcx.get_attrs(src.def_id()), "no_retag").is_some() {
+            return;
+        }
         let (span, arg_count) = (mir.span, mir.arg_count);
         let (basic_blocks, local_decls) = mir.basic_blocks_and_local_decls_mut();
         let needs_retag = |place: &Place<'tcx>| {
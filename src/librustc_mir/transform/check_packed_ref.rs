@@ -0,0 +1,90 @@
+//! Lints references to fields of `#[repr(packed)]` structs that might be misaligned.
+//!
+//! Creating a misaligned reference is undefined behavior even if it is never dereferenced, so
+//! this applies inside `unsafe` blocks too, unlike the `safe_packed_borrows` unsafety check.
+
+use rustc::lint::builtin::UNALIGNED_REFERENCES;
+use rustc::mir::*;
+use rustc::mir::visit::{NonMutatingUseContext, PlaceContext, Visitor};
+use rustc::ty::{self, TyCtxt};
+use rustc_data_structures::indexed_vec::IndexVec;
+
+use crate::transform::{MirPass, MirSource};
+use crate::util;
+
+pub struct CheckPackedRef;
+
+impl MirPass for CheckPackedRef {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          src: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>)
+    {
+        let mir = &*mir;
+        let source_scope_local_data = match mir.source_scope_local_data {
+            ClearCrossCrate::Set(ref data) => data,
+            ClearCrossCrate::Clear => return,
+        };
+        let mut checker = PackedRefChecker {
+            mir,
+            source_scope_local_data,
+            tcx,
+            param_env: tcx.param_env(src.def_id()),
+            source_info: SourceInfo { span: mir.span, scope: OUTERMOST_SOURCE_SCOPE },
+        };
+        checker.visit_mir(mir);
+    }
+}
+
+struct PackedRefChecker<'a, 'tcx: 'a> {
+    mir: &'a Mir<'tcx>,
+    source_scope_local_data: &'a IndexVec<SourceScope, SourceScopeLocalData>,
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    source_info: SourceInfo,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for PackedRefChecker<'a, 'tcx> {
+    fn visit_terminator(&mut self,
+                        block: BasicBlock,
+                        terminator: &Terminator<'tcx>,
+                        location: Location)
+    {
+        self.source_info = terminator.source_info;
+        self.super_terminator(block, terminator, location);
+    }
+
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &Statement<'tcx>,
+                       location: Location)
+    {
+        self.source_info = statement.source_info;
+        self.super_statement(block, statement, location);
+    }
+
+    fn visit_place(&mut self,
+                   place: &Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location)
+    {
+        // Match guards get fake shallow borrows of the places being matched
+        // on, which are removed before codegen and never create a reference.
+        let is_real_borrow = match context {
+            PlaceContext::NonMutatingUse(NonMutatingUseContext::ShallowBorrow(..)) => false,
+            _ => context.is_borrow(),
+        };
+        if is_real_borrow && util::is_disaligned(self.tcx, self.mir, self.param_env, place) {
+            let source_info = self.source_info;
+            let lint_root = self.source_scope_local_data[source_info.scope].lint_root;
+            self.tcx.lint_node_note(UNALIGNED_REFERENCES,
+                                    lint_root,
+                                    source_info.span,
+                                    "reference to packed field is unaligned",
+                                    "fields of packed structs are not properly aligned, and \
+                                     creating a misaligned reference is undefined behavior \
+                                     (even if that reference is never dereferenced)");
+        }
+        self.super_place(place, context, location);
+    }
+}
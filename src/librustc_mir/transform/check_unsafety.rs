@@ -1,4 +1,4 @@
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::indexed_vec::IndexVec;
 use rustc_data_structures::sync::Lrc;
 
@@ -14,6 +14,7 @@ use rustc::mir::visit::{PlaceContext, Visitor, MutatingUseContext};
 
 use syntax::ast;
 use syntax::symbol::Symbol;
+use rustc_target::spec::abi::Abi;
 
 use std::ops::Bound;
 
@@ -31,6 +32,9 @@ pub struct UnsafetyChecker<'a, 'tcx: 'a> {
     /// Mark an `unsafe` block as used, so we don't lint it.
     used_unsafe: FxHashSet<ast::NodeId>,
     inherited_blocks: Vec<(ast::NodeId, bool)>,
+    /// The reasons allowed by the `#[unsafe_reasons]` attribute of each
+    /// `unsafe` block seen so far, if it has one.
+    allowed_reasons: FxHashMap<ast::NodeId, Option<Vec<UnsafetyReason>>>,
 }
 
 impl<'a, 'gcx, 'tcx> UnsafetyChecker<'a, 'tcx> {
@@ -60,6 +64,7 @@ impl<'a, 'gcx, 'tcx> UnsafetyChecker<'a, 'tcx> {
             param_env,
             used_unsafe: Default::default(),
             inherited_blocks: vec![],
+            allowed_reasons: Default::default(),
         }
     }
 }
@@ -92,9 +97,20 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                 let func_ty = func.ty(self.mir, self.tcx);
                 let sig = func_ty.fn_sig(self.tcx);
                 if let hir::Unsafety::Unsafe = sig.unsafety() {
+                    let reason = match func_ty.sty {
+                        // Intrinsics are foreign items too, but they are not FFI.
+                        _ if sig.abi() == Abi::RustIntrinsic ||
+                             sig.abi() == Abi::PlatformIntrinsic => {
+                            UnsafetyReason::CallToIntrinsic
+                        }
+                        ty::FnDef(def_id, _) if self.tcx.is_foreign_item(def_id) => {
+                            UnsafetyReason::CallToForeignFn
+                        }
+                        _ => UnsafetyReason::CallToUnsafeFn,
+                    };
                     self.require_unsafe("call to unsafe function",
                         "consult the function's documentation for information on how to avoid \
-                         undefined behavior", UnsafetyViolationKind::GeneralAndConstFn)
+                         undefined behavior", UnsafetyViolationKind::GeneralAndConstFn, reason)
                 }
             }
         }
@@ -122,7 +138,7 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
             StatementKind::InlineAsm { .. } => {
                 self.require_unsafe("use of inline assembly",
                     "inline assembly is entirely unchecked and can cause undefined behavior",
-                    UnsafetyViolationKind::General)
+                    UnsafetyViolationKind::General, UnsafetyReason::InlineAsm)
             },
         }
         self.super_statement(block, statement, location);
@@ -145,6 +161,7 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                                 "initializing a layout restricted type's field with a value \
                                 outside the valid range is undefined behavior",
                                 UnsafetyViolationKind::GeneralAndConstFn,
+                                UnsafetyReason::LayoutConstrainedField,
                             ),
                         }
                     }
@@ -174,6 +191,7 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                             details: Symbol::intern("casting pointers to integers in constants")
                                      .as_interned_str(),
                             kind: UnsafetyViolationKind::General,
+                            reason: UnsafetyReason::PointerOpInConst,
                         }], &[]);
                     },
                     _ => {},
@@ -192,6 +210,7 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                         details: Symbol::intern("operations on pointers in constants")
                                  .as_interned_str(),
                         kind: UnsafetyViolationKind::General,
+                        reason: UnsafetyReason::PointerOpInConst,
                     }], &[]);
                 }
             }
@@ -222,7 +241,8 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                                                 creating a misaligned reference is undefined \
                                                 behavior")
                                     .as_interned_str(),
-                            kind: UnsafetyViolationKind::BorrowPacked(lint_root)
+                            kind: UnsafetyViolationKind::BorrowPacked(lint_root),
+                            reason: UnsafetyReason::BorrowPacked,
                         }], &[]);
                     }
                 }
@@ -256,7 +276,8 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                         self.require_unsafe("dereference of raw pointer",
                             "raw pointers may be NULL, dangling or unaligned; they can violate \
                              aliasing rules and cause data races: all of these are undefined \
-                             behavior", UnsafetyViolationKind::General)
+                             behavior", UnsafetyViolationKind::General,
+                             UnsafetyReason::DerefRawPointer)
                     }
                     ty::Adt(adt, _) => {
                         if adt.is_union() {
@@ -282,7 +303,8 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                                         "assignment to non-`Copy` union field",
                                         "the previous content of the field will be dropped, which \
                                          causes undefined behavior if the field was not properly \
-                                         initialized", UnsafetyViolationKind::General)
+                                         initialized", UnsafetyViolationKind::General,
+                                         UnsafetyReason::UnionField)
                                 } else {
                                     // write to non-move union, safe
                                 }
@@ -290,7 +312,7 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                                 self.require_unsafe("access to union field",
                                     "the field may not be properly initialized: using \
                                      uninitialized data will cause undefined behavior",
-                                     UnsafetyViolationKind::General)
+                                     UnsafetyViolationKind::General, UnsafetyReason::UnionField)
                             }
                         }
                     }
@@ -309,7 +331,7 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                     self.require_unsafe("use of mutable static",
                         "mutable statics can be mutated by multiple threads: aliasing violations \
                          or data races will cause undefined behavior",
-                         UnsafetyViolationKind::General);
+                         UnsafetyViolationKind::General, UnsafetyReason::MutableStatic);
                 } else if self.tcx.is_foreign_item(def_id) {
                     let source_info = self.source_info;
                     let lint_root =
@@ -322,7 +344,8 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
                                             system: invalid data, aliasing violations or data \
                                             races will cause undefined behavior")
                                 .as_interned_str(),
-                        kind: UnsafetyViolationKind::ExternStatic(lint_root)
+                        kind: UnsafetyViolationKind::ExternStatic(lint_root),
+                        reason: UnsafetyReason::ExternStatic,
                     }], &[]);
                 }
             }
@@ -337,6 +360,7 @@ impl<'a, 'tcx> UnsafetyChecker<'a, 'tcx> {
         description: &'static str,
         details: &'static str,
         kind: UnsafetyViolationKind,
+        reason: UnsafetyReason,
     ) {
         let source_info = self.source_info;
         self.register_violations(&[UnsafetyViolation {
@@ -344,12 +368,61 @@ impl<'a, 'tcx> UnsafetyChecker<'a, 'tcx> {
             description: Symbol::intern(description).as_interned_str(),
             details: Symbol::intern(details).as_interned_str(),
             kind,
+            reason,
         }], &[]);
     }
 
+    /// Returns the reasons allowed in the `unsafe` block `block_id` by its
+    /// `#[unsafe_reasons(...)]` attribute, or `None` if it has none and so
+    /// allows every reason.
+    fn allowed_reasons(&mut self, block_id: ast::NodeId) -> Option<&[UnsafetyReason]> {
+        let tcx = self.tcx;
+        self.allowed_reasons.entry(block_id).or_insert_with(|| {
+            let attr = unsafe_reasons_attr(tcx, block_id)?;
+            let items = match attr.meta_item_list() {
+                Some(items) => items,
+                None => {
+                    tcx.sess.span_err(attr.span, "malformed `unsafe_reasons` attribute");
+                    return Some(vec![]);
+                }
+            };
+            let mut reasons = vec![];
+            for item in &items {
+                match item.word().and_then(|word| word.ident_str()) {
+                    Some(name) => match UnsafetyReason::from_name(name) {
+                        Some(reason) => reasons.push(reason),
+                        None => {
+                            let names: Vec<_> = UnsafetyReason::ALL.iter()
+                                .map(|reason| format!("`{}`", reason.name()))
+                                .collect();
+                            tcx.sess.struct_span_err(item.span,
+                                                     &format!("unknown unsafe reason `{}`", name))
+                                .help(&format!("the known reasons are {}", names.join(", ")))
+                                .emit();
+                        }
+                    },
+                    None => {
+                        tcx.sess.span_err(item.span, "expected the name of an unsafe reason");
+                    }
+                }
+            }
+            Some(reasons)
+        }).as_ref().map(|reasons| &reasons[..])
+    }
+
     fn register_violations(&mut self,
                            violations: &[UnsafetyViolation],
                            unsafe_blocks: &[(ast::NodeId, bool)]) {
+        // Operations disallowed by the `#[unsafe_reasons]` of some `unsafe`
+        // block are errors wherever they are propagated to.
+        for violation in violations {
+            if let UnsafetyViolationKind::ReasonNotAllowed(_) = violation.kind {
+                if !self.violations.contains(violation) {
+                    self.violations.push(*violation);
+                }
+            }
+        }
+
         let safety = self.source_scope_local_data[self.source_info.scope].safety;
         let within_unsafe = match safety {
             // `unsafe` blocks are required in safe code
@@ -358,7 +431,8 @@ impl<'a, 'tcx> UnsafetyChecker<'a, 'tcx> {
                     let mut violation = violation.clone();
                     match violation.kind {
                         UnsafetyViolationKind::GeneralAndConstFn |
-                        UnsafetyViolationKind::General => {},
+                        UnsafetyViolationKind::General |
+                        UnsafetyViolationKind::ReasonNotAllowed(_) => {},
                         UnsafetyViolationKind::BorrowPacked(_) |
                         UnsafetyViolationKind::ExternStatic(_) => if self.min_const_fn {
                             // const fns don't need to be backwards compatible and can
@@ -380,12 +454,30 @@ impl<'a, 'tcx> UnsafetyChecker<'a, 'tcx> {
                 if !violations.is_empty() {
                     self.used_unsafe.insert(node_id);
                 }
+                // only the reasons listed in `#[unsafe_reasons]` are allowed
+                let not_allowed: Vec<_> = match self.allowed_reasons(node_id) {
+                    Some(allowed) => violations.iter().filter(|violation| {
+                        match violation.kind {
+                            UnsafetyViolationKind::ReasonNotAllowed(_) => false,
+                            _ => !allowed.contains(&violation.reason),
+                        }
+                    }).cloned().collect(),
+                    None => vec![],
+                };
+                for mut violation in not_allowed {
+                    violation.kind = UnsafetyViolationKind::ReasonNotAllowed(node_id);
+                    if !self.violations.contains(&violation) {
+                        self.violations.push(violation)
+                    }
+                }
                 // only some unsafety is allowed in const fn
                 if self.min_const_fn {
                     for violation in violations {
                         match violation.kind {
                             // these unsafe things are stable in const fn
                             UnsafetyViolationKind::GeneralAndConstFn => {},
+                            // already registered above
+                            UnsafetyViolationKind::ReasonNotAllowed(_) => {},
                             // these things are forbidden in const fns
                             UnsafetyViolationKind::General |
                             UnsafetyViolationKind::BorrowPacked(_) |
@@ -445,6 +537,7 @@ impl<'a, 'tcx> UnsafetyChecker<'a, 'tcx> {
                                     description: Symbol::intern(description).as_interned_str(),
                                     details: Symbol::intern(details).as_interned_str(),
                                     kind: UnsafetyViolationKind::GeneralAndConstFn,
+                                    reason: UnsafetyReason::LayoutConstrainedField,
                                 }], &[]);
                             }
                         },
@@ -612,6 +705,18 @@ fn report_unused_unsafe(tcx: TyCtxt<'_, '_, '_>,
     db.emit();
 }
 
+/// Returns the `#[unsafe_reasons(...)]` attribute of the `unsafe` block
+/// `block_id`, if it has one.
+fn unsafe_reasons_attr(tcx: TyCtxt<'_, '_, '_>, block_id: ast::NodeId) -> Option<ast::Attribute> {
+    let expr_id = tcx.hir().get_parent_node(block_id);
+    match tcx.hir().find(expr_id) {
+        Some(Node::Expr(expr)) => {
+            expr.attrs.iter().find(|attr| attr.check_name("unsafe_reasons")).cloned()
+        }
+        _ => None,
+    }
+}
+
 fn builtin_derive_def_id<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> Option<DefId> {
     debug!("builtin_derive_def_id({:?})", def_id);
     if let Some(impl_def_id) = tcx.impl_of_method(def_id) {
@@ -642,18 +747,34 @@ pub fn check_unsafety<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) {
     } = tcx.unsafety_check_result(def_id);
 
     for &UnsafetyViolation {
-        source_info, description, details, kind
+        source_info, description, details, kind, reason
     } in violations.iter() {
         // Report an error.
         match kind {
             UnsafetyViolationKind::GeneralAndConstFn |
             UnsafetyViolationKind::General => {
-                struct_span_err!(
+                let mut err = struct_span_err!(
                     tcx.sess, source_info.span, E0133,
-                    "{} is unsafe and requires unsafe function or block", description)
-                    .span_label(source_info.span, &description.as_str()[..])
-                    .note(&details.as_str()[..])
-                    .emit();
+                    "{} is unsafe and requires unsafe function or block", description);
+                err.span_label(source_info.span, &description.as_str()[..])
+                    .note(&details.as_str()[..]);
+                if tcx.features().unsafe_reasons {
+                    err.note(&format!("the unsafe reason of this operation is `{}`",
+                                      reason.name()));
+                }
+                err.emit();
+            }
+            UnsafetyViolationKind::ReasonNotAllowed(block_id) => {
+                let mut err = tcx.sess.struct_span_err(
+                    source_info.span,
+                    &format!("{} is not allowed in this `unsafe` block", description));
+                err.span_label(source_info.span,
+                               format!("unsafe reason `{}`", reason.name()));
+                if let Some(attr) = unsafe_reasons_attr(tcx, block_id) {
+                    err.span_note(attr.span,
+                                  &format!("add `{}` here to allow it", reason.name()));
+                }
+                err.emit();
             }
             UnsafetyViolationKind::ExternStatic(lint_node_id) => {
                 tcx.lint_node_note(SAFE_EXTERN_STATICS,
@@ -18,9 +18,17 @@
 //! The assignment `DEST = SRC` must be (a) the only mutation of `DEST` and (b) the only
 //! (non-mutating) use of `SRC`. These restrictions are conservative and may be relaxed in the
 //! future.
+//!
+//! `SRC` may also be a field of a local, possibly nested, like `SRC.0.1`. Then the local must
+//! not be dropped either, as the drop could come before a use of `DEST`, and `DEST` must not
+//! be dropped or used as an index, as these need a local rather than a place.
+//!
+//! As `DEST` and the local of `SRC` are only mutated once, the uses of `DEST` do not need to be
+//! in the same basic block as the assignment: they see the same value wherever they are.
 
-use rustc::mir::{Constant, Local, LocalKind, Location, Place, Mir, Operand, Rvalue, StatementKind};
-use rustc::mir::visit::MutVisitor;
+use rustc::mir::{Constant, Local, LocalKind, Location, Place, Mir, Operand, ProjectionElem, Rvalue,
+                 StatementKind};
+use rustc::mir::visit::{MutVisitor, PlaceContext};
 use rustc::ty::TyCtxt;
 use crate::transform::{MirPass, MirSource};
 use crate::util::def_use::DefUseAnalysis;
@@ -97,9 +105,14 @@ impl MirPass for CopyPropagation {
                         StatementKind::Assign(Place::Local(local), box Rvalue::Use(ref operand)) if
                                 local == dest_local => {
                             let maybe_action = match *operand {
+                                Operand::Copy(Place::Local(src_local)) |
+                                Operand::Move(Place::Local(src_local)) => {
+                                    Action::local_copy(&mir, &def_use_analysis, src_local)
+                                }
                                 Operand::Copy(ref src_place) |
                                 Operand::Move(ref src_place) => {
-                                    Action::local_copy(&mir, &def_use_analysis, src_place)
+                                    Action::field_copy(&mir, &def_use_analysis, dest_local,
+                                                       src_place)
                                 }
                                 Operand::Constant(ref src_constant) => {
                                     Action::constant(src_constant)
@@ -169,31 +182,81 @@ fn eliminate_self_assignments<'tcx>(
 
 enum Action<'tcx> {
     PropagateLocalCopy(Local),
+    PropagateFieldCopy(Place<'tcx>),
     PropagateConstant(Constant<'tcx>),
 }
 
 impl<'tcx> Action<'tcx> {
-    fn local_copy(mir: &Mir<'tcx>, def_use_analysis: &DefUseAnalysis<'_>, src_place: &Place<'tcx>)
+    fn local_copy(mir: &Mir<'tcx>, def_use_analysis: &DefUseAnalysis<'_>, src_local: Local)
                   -> Option<Action<'tcx>> {
-        // The source must be a local.
-        let src_local = if let Place::Local(local) = *src_place {
+        if Action::is_unchanged_source(mir, def_use_analysis, src_local) {
+            Some(Action::PropagateLocalCopy(src_local))
+        } else {
+            None
+        }
+    }
+
+    fn field_copy(mir: &Mir<'tcx>,
+                  def_use_analysis: &DefUseAnalysis<'_>,
+                  dest_local: Local,
+                  src_place: &Place<'tcx>)
+                  -> Option<Action<'tcx>> {
+        // The source must be a field of a local. Other projections, like dereferences, can
+        // read memory which is changed without mutating the local.
+        let mut base = src_place;
+        while let Place::Projection(ref proj) = *base {
+            if let ProjectionElem::Field(..) = proj.elem {
+                base = &proj.base;
+            } else {
+                debug!("  Can't copy-propagate local: source is not a field of a local");
+                return None;
+            }
+        }
+        let src_local = if let Place::Local(local) = *base {
             local
         } else {
-            debug!("  Can't copy-propagate local: source is not a local");
+            debug!("  Can't copy-propagate local: source is not a field of a local");
             return None;
         };
+        if src_local == dest_local {
+            debug!("  Can't copy-propagate local: source is a field of dest");
+            return None;
+        }
+
+        if !Action::is_unchanged_source(mir, def_use_analysis, src_local) {
+            return None;
+        }
+        let src_use_info = def_use_analysis.local_info(src_local);
+        if src_use_info.defs_and_uses.iter().any(|place_use| place_use.context.is_drop()) {
+            debug!("  Can't copy-propagate local: source is dropped");
+            return None;
+        }
+        let dest_use_info = def_use_analysis.local_info(dest_local);
+        if dest_use_info.defs_and_uses.iter().any(|place_use| place_use.context.is_drop()) {
+            debug!("  Can't copy-propagate local: dest is dropped");
+            return None;
+        }
 
+        Some(Action::PropagateFieldCopy(src_place.clone()))
+    }
+
+    /// Checks that the copy is the only use of `src_local`, so that it keeps its value for
+    /// the uses of the destination.
+    fn is_unchanged_source(mir: &Mir<'tcx>,
+                           def_use_analysis: &DefUseAnalysis<'_>,
+                           src_local: Local)
+                           -> bool {
         // We're trying to copy propagate a local.
         // There must be exactly one use of the source used in a statement (not in a terminator).
         let src_use_info = def_use_analysis.local_info(src_local);
         let src_use_count = src_use_info.use_count();
         if src_use_count == 0 {
             debug!("  Can't copy-propagate local: no uses");
-            return None
+            return false
         }
         if src_use_count != 1 {
             debug!("  Can't copy-propagate local: {} uses", src_use_info.use_count());
-            return None
+            return false
         }
 
         // Verify that the source doesn't change in between. This is done conservatively for now,
@@ -217,10 +280,10 @@ impl<'tcx> Action<'tcx> {
                 src_def_count,
                 if is_arg { " (argument)" } else { "" },
             );
-            return None
+            return false
         }
 
-        Some(Action::PropagateLocalCopy(src_local))
+        true
     }
 
     fn constant(src_constant: &Constant<'tcx>) -> Option<Action<'tcx>> {
@@ -263,6 +326,53 @@ impl<'tcx> Action<'tcx> {
 
                 true
             }
+            Action::PropagateFieldCopy(src_place) => {
+                // First, remove all markers.
+                //
+                // FIXME(pcwalton): Don't do this. Merge live ranges instead.
+                debug!("  Replacing all uses of {:?} with {:?} (field)",
+                       dest_local,
+                       src_place);
+                let dest_local_info = def_use_analysis.local_info(dest_local);
+                for place_use in &dest_local_info.defs_and_uses {
+                    if place_use.context.is_storage_marker() {
+                        mir.make_statement_nop(place_use.location)
+                    }
+                }
+                let src_local = src_place.base_local().unwrap();
+                for place_use in &def_use_analysis.local_info(src_local).defs_and_uses {
+                    if place_use.context.is_storage_marker() {
+                        mir.make_statement_nop(place_use.location)
+                    }
+                }
+
+                // Replace all uses of the destination local with the source place.
+                let mut visitor = PlacePropagationVisitor {
+                    dest_local,
+                    place: src_place,
+                    uses_replaced: 0,
+                };
+                for dest_place_use in &dest_local_info.defs_and_uses {
+                    visitor.visit_location(mir, dest_place_use.location)
+                }
+
+                // Zap the assignment instruction if we eliminated all the uses. We won't have been
+                // able to do that if the destination was used as an index, which must be a local.
+                // The remaining uses still see the same value, as the source doesn't change.
+                let use_count = dest_local_info.use_count();
+                if visitor.uses_replaced == use_count {
+                    debug!("  {} of {} use(s) replaced; deleting assignment",
+                           visitor.uses_replaced,
+                           use_count);
+                    mir.make_statement_nop(location);
+                    true
+                } else {
+                    debug!("  {} of {} use(s) replaced; not deleting assignment",
+                           visitor.uses_replaced,
+                           use_count);
+                    visitor.uses_replaced > 0
+                }
+            }
             Action::PropagateConstant(src_constant) => {
                 // First, remove all markers.
                 //
@@ -339,3 +449,24 @@ impl<'tcx> MutVisitor<'tcx> for ConstantPropagationVisitor<'tcx> {
         self.uses_replaced += 1
     }
 }
+
+struct PlacePropagationVisitor<'tcx> {
+    dest_local: Local,
+    place: Place<'tcx>,
+    uses_replaced: usize,
+}
+
+impl<'tcx> MutVisitor<'tcx> for PlacePropagationVisitor<'tcx> {
+    fn visit_place(&mut self,
+                   place: &mut Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        match *place {
+            Place::Local(local) if local == self.dest_local && context.is_nonmutating_use() => {}
+            _ => return self.super_place(place, context, location),
+        }
+
+        *place = self.place.clone();
+        self.uses_replaced += 1
+    }
+}
@@ -7,6 +7,7 @@ use crate::dataflow::MoveDataParamEnv;
 use crate::dataflow::{self, do_dataflow, DebugFormatted};
 use crate::transform::{MirPass, MirSource};
 use crate::util::patch::MirPatch;
+use crate::util::{dump_enabled, dump_mir, PassWhere};
 use crate::util::elaborate_drops::{DropFlagState, Unwind, elaborate_drop};
 use crate::util::elaborate_drops::{DropElaborator, DropStyle, DropFlagMode};
 use rustc::ty::{self, TyCtxt};
@@ -15,6 +16,7 @@ use rustc::mir::*;
 use rustc::util::nodemap::FxHashMap;
 use rustc_data_structures::bit_set::BitSet;
 use std::fmt;
+use std::io;
 use syntax::ast;
 use syntax_pos::Span;
 
@@ -44,7 +46,7 @@ impl MirPass for ElaborateDrops {
                 move_data
             }
         };
-        let elaborate_patch = {
+        let (elaborate_patch, decisions) = {
             let mir = &*mir;
             let env = MoveDataParamEnv {
                 move_data,
@@ -68,12 +70,66 @@ impl MirPass for ElaborateDrops {
                 flow_uninits,
                 drop_flags: Default::default(),
                 patch: MirPatch::new(mir),
+                decisions: if dump_enabled(tcx, "ElaborateDrops", src) {
+                    Some(Default::default())
+                } else {
+                    None
+                },
             }.elaborate()
         };
+        if let Some(decisions) = decisions {
+            dump_decisions(tcx, src, mir, &decisions);
+        }
         elaborate_patch.apply(mir);
     }
 }
 
+/// What drop elaboration decided, for `-Z dump-mir=ElaborateDrops`.
+#[derive(Default)]
+struct ElaborationDecisions {
+    /// The drop flags which were created, with the places they track.
+    drop_flags: Vec<(Local, String)>,
+    /// How each `Drop` or `DropAndReplace` terminator was elaborated.
+    drop_styles: FxHashMap<BasicBlock, DropStyle>,
+}
+
+/// Dumps the MIR before drop elaboration, annotated with the drop flags and
+/// with how each drop was elaborated.
+fn dump_decisions<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                            src: MirSource<'tcx>,
+                            mir: &Mir<'tcx>,
+                            decisions: &ElaborationDecisions) {
+    dump_mir(tcx, None, "ElaborateDrops", &"decisions", src, mir,
+             |pass_where, w| -> io::Result<()> {
+        match pass_where {
+            PassWhere::BeforeCFG => {
+                if decisions.drop_flags.is_empty() {
+                    writeln!(w, "// no drop flags")?;
+                }
+                for (flag, place) in &decisions.drop_flags {
+                    writeln!(w, "// drop flag {:?} tracks {}", flag, place)?;
+                }
+            }
+            PassWhere::AfterTerminator(bb) => {
+                let decision = match decisions.drop_styles.get(&bb) {
+                    Some(DropStyle::Dead) => "dead: never initialized here, drop removed",
+                    Some(DropStyle::Static) => "static: always initialized here, drop kept",
+                    Some(DropStyle::Conditional) => {
+                        "conditional: maybe initialized here, drop guarded by its drop flag"
+                    }
+                    Some(DropStyle::Open) => {
+                        "open: partly initialized here, drop split into drops of its parts"
+                    }
+                    None => return Ok(()),
+                };
+                writeln!(w, "        // {}", decision)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    });
+}
+
 /// Returns the set of basic blocks whose unwind edges are known
 /// to not be reachable, because they are `drop` terminators
 /// that can't drop anything.
@@ -297,6 +353,7 @@ struct ElaborateDropsCtxt<'a, 'tcx: 'a> {
     flow_uninits:  DataflowResults<'tcx, MaybeUninitializedPlaces<'a, 'tcx, 'tcx>>,
     drop_flags: FxHashMap<MovePathIndex, Local>,
     patch: MirPatch<'tcx>,
+    decisions: Option<ElaborationDecisions>,
 }
 
 impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
@@ -333,9 +390,29 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
         self.drop_flags.get(&index).map(|t| Place::Local(*t))
     }
 
+    /// Records how the drop in `bb` is elaborated, if decisions are being
+    /// recorded.
+    fn record_drop_style(&mut self, bb: BasicBlock, style: DropStyle) {
+        if let Some(ref mut decisions) = self.decisions {
+            decisions.drop_styles.insert(bb, style);
+        }
+    }
+
+    /// Records how the drop of `path` in `bb` is elaborated, if decisions
+    /// are being recorded.
+    fn record_tracked_drop(&mut self,
+                           bb: BasicBlock,
+                           init_data: &InitializationData,
+                           path: MovePathIndex) {
+        if self.decisions.is_some() {
+            let style = Elaborator { init_data, ctxt: self }.drop_style(path, DropFlagMode::Deep);
+            self.record_drop_style(bb, style);
+        }
+    }
+
     /// create a patch that elaborates all drops in the input
-    /// MIR.
-    fn elaborate(mut self) -> MirPatch<'tcx>
+    /// MIR, and the decisions made, if they are being recorded.
+    fn elaborate(mut self) -> (MirPatch<'tcx>, Option<ElaborationDecisions>)
     {
         self.collect_drop_flags();
 
@@ -346,7 +423,15 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
         self.drop_flags_for_args();
         self.drop_flags_for_locs();
 
-        self.patch
+        if let Some(ref mut decisions) = self.decisions {
+            let move_paths = &self.env.move_data.move_paths;
+            decisions.drop_flags = self.drop_flags.iter().map(|(&path, &flag)| {
+                (flag, format!("{:?}", move_paths[path].place))
+            }).collect();
+            decisions.drop_flags.sort();
+        }
+
+        (self.patch, self.decisions)
     }
 
     fn collect_drop_flags(&mut self)
@@ -405,6 +490,7 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
                     let init_data = self.initialization_data_at(loc);
                     match self.move_data().rev_lookup.find(location) {
                         LookupResult::Exact(path) => {
+                            self.record_tracked_drop(bb, &init_data, path);
                             elaborate_drop(
                                 &mut Elaborator {
                                     init_data: &init_data,
@@ -496,6 +582,7 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
             LookupResult::Exact(path) => {
                 debug!("elaborate_drop_and_replace({:?}) - tracked {:?}", terminator, path);
                 let init_data = self.initialization_data_at(loc);
+                self.record_tracked_drop(bb, &init_data, path);
 
                 elaborate_drop(
                     &mut Elaborator {
@@ -519,6 +606,7 @@ impl<'b, 'tcx> ElaborateDropsCtxt<'b, 'tcx> {
                 // drop and replace behind a pointer/array/whatever. The location
                 // must be initialized.
                 debug!("elaborate_drop_and_replace({:?}) - untracked {:?}", terminator, parent);
+                self.record_drop_style(bb, DropStyle::Static);
                 self.patch.patch_terminator(bb, TerminatorKind::Drop {
                     location: location.clone(),
                     target,
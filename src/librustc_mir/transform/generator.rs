@@ -51,6 +51,7 @@
 
 use rustc::hir;
 use rustc::hir::def_id::DefId;
+use rustc::session::{GeneratorFieldInfo, GeneratorSizeInfo};
 use rustc::mir::*;
 use rustc::mir::visit::{PlaceContext, Visitor, MutVisitor};
 use rustc::ty::{self, TyCtxt, AdtDef, Ty};
@@ -62,6 +63,8 @@ use rustc_data_structures::bit_set::BitSet;
 use std::borrow::Cow;
 use std::iter::once;
 use std::mem;
+use syntax::ast;
+use syntax_pos::Span;
 use crate::transform::{MirPass, MirSource};
 use crate::transform::simplify;
 use crate::transform::no_landing_pads::no_landing_pads;
@@ -547,6 +550,40 @@ fn compute_layout<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     (remap, layout, storage_liveness)
 }
 
+fn record_generator_size<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                   def_id: DefId,
+                                   mir: &Mir<'tcx>,
+                                   upvar_tys: &[Ty<'tcx>],
+                                   layout: &GeneratorLayout<'tcx>,
+                                   suspension_points: usize) {
+    let param_env = tcx.param_env(def_id);
+    let source_map = tcx.sess.source_map();
+    let field_info = |name: Option<ast::Name>, span: Span, ty: Ty<'tcx>| GeneratorFieldInfo {
+        name: name.map_or_else(|| "_".to_string(), |name| name.to_string()),
+        location: source_map.span_to_string(span),
+        size: tcx.layout_of(param_env.and(ty)).ok().map(|layout| layout.size.bytes()),
+    };
+
+    let upvars = mir.upvar_decls.iter().zip(upvar_tys).map(|(decl, &ty)| {
+        let span = match decl.var_hir_id {
+            ClearCrossCrate::Set(hir_id) => tcx.hir().span_by_hir_id(hir_id),
+            ClearCrossCrate::Clear => mir.span,
+        };
+        field_info(Some(decl.debug_name), span, ty)
+    }).collect();
+    let saved_locals = layout.fields.iter().map(|decl| {
+        field_info(decl.name, decl.source_info.span, decl.ty)
+    }).collect();
+
+    tcx.sess.code_stats.borrow_mut().record_generator_size(GeneratorSizeInfo {
+        generator_description: tcx.item_path_str(def_id),
+        // The unresumed, returned and poisoned states come first.
+        states: 3 + suspension_points as u32,
+        upvars,
+        saved_locals,
+    });
+}
+
 fn insert_switch<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                            mir: &mut Mir<'tcx>,
                            cases: Vec<(u32, BasicBlock)>,
@@ -911,6 +948,7 @@ impl MirPass for StateTransform {
         // Extract locals which are live across suspension point into `layout`
         // `remap` gives a mapping from local indices onto generator struct indices
         // `storage_liveness` tells us which locals have live storage at suspension points
+        let upvar_tys = upvars.clone();
         let (remap, layout, storage_liveness) = compute_layout(
             tcx,
             source,
@@ -937,6 +975,11 @@ impl MirPass for StateTransform {
         };
         transform.visit_mir(mir);
 
+        if tcx.sess.opts.debugging_opts.print_generator_sizes {
+            record_generator_size(tcx, def_id, mir, &upvar_tys, &layout,
+                                  transform.suspension_points.len());
+        }
+
         // Update our MIR struct to reflect the changed we've made
         mir.yield_ty = None;
         mir.arg_count = 1;
@@ -10,6 +10,7 @@ use rustc::hir::intravisit::{self, Visitor, NestedVisitorMap};
 use rustc::util::nodemap::DefIdSet;
 use rustc_data_structures::sync::Lrc;
 use std::borrow::Cow;
+use std::sync::atomic::Ordering;
 use syntax::ast;
 use syntax_pos::Span;
 
@@ -17,6 +18,7 @@ pub mod add_retag;
 pub mod add_moves_for_packed_drops;
 pub mod cleanup_post_borrowck;
 pub mod check_unsafety;
+pub mod check_packed_ref;
 pub mod simplify_branches;
 pub mod simplify;
 pub mod erase_regions;
@@ -32,6 +34,7 @@ pub mod dump_mir;
 pub mod deaggregator;
 pub mod instcombine;
 pub mod copy_prop;
+pub mod nrvo;
 pub mod const_prop;
 pub mod generator;
 pub mod inline;
@@ -99,6 +102,7 @@ fn mir_keys<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, krate: CrateNum)
 
 fn mir_built<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Steal<Mir<'tcx>> {
     let mir = build::mir_build(tcx, def_id);
+    record_statement_count(tcx, &mir);
     tcx.alloc_steal_mir(mir)
 }
 
@@ -186,6 +190,7 @@ pub fn run_passes(
         }
 
         mir.phase = mir_phase;
+        record_statement_count(tcx, mir);
     };
 
     run_passes(mir, None);
@@ -198,6 +203,20 @@ pub fn run_passes(
     }
 }
 
+/// Adds the statements of `mir` to the per-phase totals reported by
+/// `-Z crate-stats`.
+fn record_statement_count<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, mir: &Mir<'tcx>) {
+    if !tcx.sess.opts.debugging_opts.crate_stats {
+        return;
+    }
+    let statements = mir.basic_blocks()
+                        .iter()
+                        .map(|data| data.statements.len())
+                        .sum::<usize>();
+    tcx.sess.perf_stats.mir_statements_per_phase[mir.phase.phase_index()]
+        .fetch_add(statements, Ordering::Relaxed);
+}
+
 fn mir_const<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Steal<Mir<'tcx>> {
     // Unsafety check uses the raw mir, so make sure it is run
     let _ = tcx.unsafety_check_result(def_id);
@@ -207,6 +226,7 @@ fn mir_const<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx Stea
         // What we need to do constant evaluation.
         &simplify::SimplifyCfg::new("initial"),
         &type_check::TypeckMir,
+        &check_packed_ref::CheckPackedRef,
         &rustc_peek::SanityCheck,
         &uniform_array_move_out::UniformArrayMoveOut,
     ]);
@@ -285,6 +305,7 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
         &simplify_branches::SimplifyBranches::new("after-const-prop"),
         &deaggregator::Deaggregator,
         &copy_prop::CopyPropagation,
+        &nrvo::RenameReturnPlace,
         &remove_noop_landing_pads::RemoveNoopLandingPads,
         &simplify::SimplifyCfg::new("final"),
         &simplify::SimplifyLocals,
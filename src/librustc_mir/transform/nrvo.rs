@@ -0,0 +1,210 @@
+//! A "named return value optimization" pass.
+//!
+//! Functions that build their result in a local and then move it out look like
+//! this in MIR:
+//!
+//!     _1 = ...;           // build the value
+//!     ...
+//!     _0 = move _1;
+//!     return;
+//!
+//! For large values, the final move is a `memcpy` into the return place. When
+//! every `return` is reached through such a move of the *same* local, and the
+//! return place isn't used for anything else, we can instead rename that local
+//! to the return place everywhere and remove the moves, so that the value is
+//! built directly in the return place:
+//!
+//!     _0 = ...;
+//!     ...
+//!     nop;
+//!     return;
+//!
+//! The restrictions are conservative:
+//!
+//! - The returned local must be a user variable or a temporary, not an
+//!   argument, and must have exactly the type of the return place.
+//! - The return place must not be read, borrowed or written anywhere except by
+//!   the final moves, so that nothing can observe or clobber the renamed
+//!   local's value through it.
+//! - The returned local must never be borrowed, not even in part, as a
+//!   reference to it would alias the return place after the renaming.
+//! - Each `return` must be reached from its final move (or copy, for `Copy`
+//!   types) along a path that is the only way to get there, and the returned
+//!   local may only have its storage killed along it, so that it can't be
+//!   modified after its value has been taken.
+
+use rustc::mir::{BasicBlock, Local, LocalKind, Location, Mir, Operand, Place, Rvalue};
+use rustc::mir::{StatementKind, TerminatorKind, RETURN_PLACE};
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::ty::TyCtxt;
+use rustc_data_structures::fx::FxHashSet;
+use crate::transform::{MirPass, MirSource};
+use crate::util::def_use::DefUseAnalysis;
+
+pub struct RenameReturnPlace;
+
+impl MirPass for RenameReturnPlace {
+    fn run_pass<'a, 'tcx>(&self,
+                          tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource<'tcx>,
+                          mir: &mut Mir<'tcx>) {
+        // Like copy propagation, this loses the debuginfo of the renamed
+        // variable, so only run it when optimizing MIR aggressively.
+        if tcx.sess.opts.debugging_opts.mir_opt_level <= 1 {
+            return;
+        }
+
+        // The return place of a generator is rewritten by the state transform.
+        if mir.yield_ty.is_some() {
+            return;
+        }
+
+        let mut def_use_analysis = DefUseAnalysis::new(mir);
+        def_use_analysis.analyze(mir);
+
+        let (returned_local, moves) = match find_returned_local(mir, &def_use_analysis) {
+            Some(candidate) => candidate,
+            None => return,
+        };
+        debug!("RenameReturnPlace: renaming {:?} to the return place", returned_local);
+
+        // Everything the return place is used for must be one of the moves.
+        let return_place_info = def_use_analysis.local_info(RETURN_PLACE);
+        if return_place_info.defs_and_uses.iter().any(|u| !moves.contains(&u.location)) {
+            debug!("RenameReturnPlace: return place has other uses");
+            return;
+        }
+
+        for &location in &moves {
+            mir.make_statement_nop(location);
+        }
+
+        // The return place is live for the whole body, so the storage markers
+        // of the renamed local have to go.
+        for data in mir.basic_blocks_mut() {
+            for statement in &mut data.statements {
+                match statement.kind {
+                    StatementKind::StorageLive(local) |
+                    StatementKind::StorageDead(local) if local == returned_local => {
+                        statement.make_nop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        def_use_analysis.analyze(mir);
+        def_use_analysis.replace_all_defs_and_uses_with(returned_local, mir, RETURN_PLACE);
+    }
+}
+
+/// Returns the local that is moved into the return place right before every
+/// `return`, along with the locations of those moves, if there is one.
+fn find_returned_local<'tcx>(mir: &Mir<'tcx>,
+                             def_use_analysis: &DefUseAnalysis<'tcx>)
+                             -> Option<(Local, FxHashSet<Location>)> {
+    let mut returned_local = None;
+    let mut moves = FxHashSet::default();
+
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        match data.terminator().kind {
+            TerminatorKind::Return => {}
+            _ => continue,
+        }
+
+        let (local, location) = find_move_to_return_place(mir, def_use_analysis, block)?;
+        if returned_local.map_or(false, |returned_local| returned_local != local) {
+            return None;
+        }
+        returned_local = Some(local);
+        moves.insert(location);
+    }
+
+    let returned_local = returned_local?;
+    match mir.local_kind(returned_local) {
+        LocalKind::Var | LocalKind::Temp => {}
+        LocalKind::Arg | LocalKind::ReturnPointer => return None,
+    }
+    if mir.local_decls[returned_local].ty != mir.local_decls[RETURN_PLACE].ty {
+        return None;
+    }
+    if is_borrowed(mir, returned_local) {
+        debug!("RenameReturnPlace: {:?} is borrowed", returned_local);
+        return None;
+    }
+
+    Some((returned_local, moves))
+}
+
+/// Walks back from the `return` at the end of `block` to the last assignment
+/// to the return place, which must be a move or copy of a local that isn't used
+/// again before the `return`.
+fn find_move_to_return_place<'tcx>(mir: &Mir<'tcx>,
+                                   def_use_analysis: &DefUseAnalysis<'tcx>,
+                                   mut block: BasicBlock)
+                                   -> Option<(Local, Location)> {
+    // The locations between the assignment and the `return`.
+    let mut later = FxHashSet::default();
+    let mut seen = FxHashSet::default();
+
+    while seen.insert(block) {
+        let data = &mir[block];
+        later.insert(mir.terminator_loc(block));
+
+        for (statement_index, statement) in data.statements.iter().enumerate().rev() {
+            let location = Location { block, statement_index };
+            if let StatementKind::Assign(Place::Local(RETURN_PLACE), ref rvalue) = statement.kind {
+                let local = match **rvalue {
+                    Rvalue::Use(Operand::Move(Place::Local(local))) |
+                    Rvalue::Use(Operand::Copy(Place::Local(local))) => local,
+                    _ => return None,
+                };
+
+                // After having been moved out, the local may only have its
+                // storage killed.
+                let uses = &def_use_analysis.local_info(local).defs_and_uses;
+                let used_later = uses.iter().any(|u| {
+                    later.contains(&u.location) && !u.context.is_storage_dead_marker()
+                });
+                if used_later {
+                    return None;
+                }
+                return Some((local, location));
+            }
+            later.insert(location);
+        }
+
+        // Only follow the path back as long as it is the only way to get here.
+        match mir.predecessors_for(block)[..] {
+            [predecessor] => block = predecessor,
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+/// Returns `true` if `local`, or any place based on it, is borrowed anywhere
+/// in the body. Raw pointers are created from borrows, so this covers them too.
+fn is_borrowed(mir: &Mir<'_>, local: Local) -> bool {
+    struct BorrowFinder {
+        local: Local,
+        borrowed: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for BorrowFinder {
+        fn visit_place(&mut self,
+                       place: &Place<'tcx>,
+                       context: PlaceContext<'tcx>,
+                       location: Location) {
+            if context.is_borrow() && place.base_local() == Some(self.local) {
+                self.borrowed = true;
+            }
+            self.super_place(place, context, location);
+        }
+    }
+
+    let mut finder = BorrowFinder { local, borrowed: false };
+    finder.visit_mir(mir);
+    finder.borrowed
+}
@@ -570,6 +570,29 @@ impl ConstCx<'_, 'tcx> {
     }
 }
 
+/// Why a borrow of a temporary was not promoted to a constant.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PromotionFailure {
+    MutableBorrow,
+    InteriorMutability,
+    NeedsDrop,
+    NotConst,
+    NotPromotable,
+}
+
+impl fmt::Display for PromotionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match *self {
+            PromotionFailure::MutableBorrow => "the borrow is mutable",
+            PromotionFailure::InteriorMutability => "the value may have interior mutability",
+            PromotionFailure::NeedsDrop => "the value needs to be dropped",
+            PromotionFailure::NotConst => "the value is not a constant expression",
+            PromotionFailure::NotPromotable =>
+                "the value comes from a `const fn` call, which is only promoted in constants",
+        })
+    }
+}
+
 struct Checker<'a, 'tcx> {
     cx: ConstCx<'a, 'tcx>,
 
@@ -579,6 +602,7 @@ struct Checker<'a, 'tcx> {
 
     temp_promotion_state: IndexVec<Local, TempState>,
     promotion_candidates: Vec<Candidate>,
+    promotion_failures: Vec<(Span, PromotionFailure)>,
 }
 
 macro_rules! unleash_miri {
@@ -649,7 +673,8 @@ impl<'a, 'tcx> Checker<'a, 'tcx> {
             def_id,
             rpo,
             temp_promotion_state: temps,
-            promotion_candidates: vec![]
+            promotion_candidates: vec![],
+            promotion_failures: vec![],
         }
     }
 
@@ -683,6 +708,22 @@ impl<'a, 'tcx> Checker<'a, 'tcx> {
         let mut qualifs = self.qualifs_in_value(source);
 
         if let ValueSource::Rvalue(&Rvalue::Ref(_, kind, ref place)) = source {
+            // We can only promote interior borrows of promotable temps.
+            let mut base = place;
+            while let Place::Projection(ref proj) = *base {
+                if proj.elem == ProjectionElem::Deref {
+                    break;
+                }
+                base = &proj.base;
+            }
+            debug!("qualify_consts: promotion candidate: place={:?}", base);
+            let temp = match *base {
+                Place::Local(local) if self.mir.local_kind(local) == LocalKind::Temp => {
+                    Some(local)
+                }
+                _ => None,
+            };
+
             // Getting `true` from `HasMutInterior::in_rvalue` means
             // the borrowed place is disallowed from being borrowed,
             // due to either a mutable borrow (with some exceptions),
@@ -693,6 +734,15 @@ impl<'a, 'tcx> Checker<'a, 'tcx> {
                 qualifs[HasMutInterior] = false;
                 qualifs[IsNotConst] = true;
 
+                if temp.is_some() {
+                    let failure = if let BorrowKind::Mut { .. } = kind {
+                        PromotionFailure::MutableBorrow
+                    } else {
+                        PromotionFailure::InteriorMutability
+                    };
+                    self.promotion_failures.push((self.span, failure));
+                }
+
                 if self.mode != Mode::Fn {
                     if let BorrowKind::Mut { .. } = kind {
                         let mut err = struct_span_err!(self.tcx.sess,  self.span, E0017,
@@ -720,27 +770,28 @@ impl<'a, 'tcx> Checker<'a, 'tcx> {
             } else {
                 // We might have a candidate for promotion.
                 let candidate = Candidate::Ref(location);
-                // We can only promote interior borrows of promotable temps.
-                let mut place = place;
-                while let Place::Projection(ref proj) = *place {
-                    if proj.elem == ProjectionElem::Deref {
-                        break;
-                    }
-                    place = &proj.base;
-                }
-                debug!("qualify_consts: promotion candidate: place={:?}", place);
-                if let Place::Local(local) = *place {
-                    if self.mir.local_kind(local) == LocalKind::Temp {
-                        debug!("qualify_consts: promotion candidate: local={:?}", local);
-                        // The borrowed place doesn't have `HasMutInterior`
-                        // (from `in_rvalue`), so we can safely ignore
-                        // `HasMutInterior` from the local's qualifications.
-                        // This allows borrowing fields which don't have
-                        // `HasMutInterior`, from a type that does, e.g.:
-                        // `let _: &'static _ = &(Cell::new(1), 2).1;`
-                        let mut local_qualifs = self.qualifs_in_local(local);
-                        local_qualifs[HasMutInterior] = false;
-                        if !local_qualifs.0.iter().any(|&qualif| qualif) {
+                if let Some(local) = temp {
+                    debug!("qualify_consts: promotion candidate: local={:?}", local);
+                    // The borrowed place doesn't have `HasMutInterior`
+                    // (from `in_rvalue`), so we can safely ignore
+                    // `HasMutInterior` from the local's qualifications.
+                    // This allows borrowing fields which don't have
+                    // `HasMutInterior`, from a type that does, e.g.:
+                    // `let _: &'static _ = &(Cell::new(1), 2).1;`
+                    let mut local_qualifs = self.qualifs_in_local(local);
+                    local_qualifs[HasMutInterior] = false;
+                    let failure = if local_qualifs[NeedsDrop] {
+                        Some(PromotionFailure::NeedsDrop)
+                    } else if local_qualifs[IsNotConst] {
+                        Some(PromotionFailure::NotConst)
+                    } else if local_qualifs[IsNotPromotable] {
+                        Some(PromotionFailure::NotPromotable)
+                    } else {
+                        None
+                    };
+                    match failure {
+                        Some(failure) => self.promotion_failures.push((self.span, failure)),
+                        None => {
                             debug!("qualify_consts: promotion candidate: {:?}", candidate);
                             self.promotion_candidates.push(candidate);
                         }
@@ -1429,6 +1480,20 @@ fn mir_const_qualif<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     Checker::new(tcx, def_id, mir, Mode::Const).check_const()
 }
 
+/// Returns the borrows of temporaries in the function `def_id` which would
+/// not be promoted to constants, and why. `mir` must be the MIR of the
+/// function before promotion, i.e., from the `mir_const` query.
+pub fn promotion_failures<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                    def_id: DefId,
+                                    mir: &Mir<'tcx>)
+                                    -> Vec<(Span, PromotionFailure)> {
+    let mut checker = Checker::new(tcx, def_id, mir, Mode::Fn);
+    while let Some((bb, data)) = checker.rpo.next() {
+        checker.visit_basic_block_data(bb, data);
+    }
+    checker.promotion_failures
+}
+
 pub struct QualifyAndPromoteConstants;
 
 impl MirPass for QualifyAndPromoteConstants {
@@ -1505,6 +1570,15 @@ impl MirPass for QualifyAndPromoteConstants {
                     }
                 }
 
+                if tcx.sess.opts.debugging_opts.explain_const_promotion {
+                    for &(span, failure) in &checker.promotion_failures {
+                        tcx.sess.span_note_without_error(
+                            span,
+                            &format!("this borrow was not promoted to a constant: {}", failure),
+                        );
+                    }
+                }
+
                 (checker.temp_promotion_state, checker.promotion_candidates)
             };
 
@@ -0,0 +1,189 @@
+//! Export of MIR bodies as structured data, for `-Z export-mir=json`.
+//!
+//! The exported form is deliberately shallow: the shape of the control flow
+//! graph (blocks, successors, cleanup edges) and the declarations of locals are
+//! given as structured fields, while statements, terminators and types are
+//! given in their pretty-printed form. That is enough for external analysis
+//! tools to walk the CFG without having to track the compiler's internal data
+//! structures, which change far more often than the printed syntax.
+
+use rustc::hir::def_id::DefId;
+use rustc::mir::*;
+use rustc::session::config::OutputFilenames;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::indexed_vec::Idx;
+use rustc_serialize::json;
+use std::fs::File;
+use std::io::{self, Write};
+
+use super::pretty::dump_mir_def_ids;
+
+#[derive(RustcEncodable)]
+pub struct ExportedLocal {
+    pub index: usize,
+    pub name: Option<String>,
+    pub ty: String,
+    pub mutable: bool,
+    pub user_variable: bool,
+    pub span: String,
+}
+
+#[derive(RustcEncodable)]
+pub struct ExportedStatement {
+    pub kind: &'static str,
+    pub text: String,
+    pub span: String,
+}
+
+#[derive(RustcEncodable)]
+pub struct ExportedTerminator {
+    pub kind: &'static str,
+    pub text: String,
+    pub successors: Vec<usize>,
+    pub unwind: Option<usize>,
+    pub span: String,
+}
+
+#[derive(RustcEncodable)]
+pub struct ExportedBlock {
+    pub index: usize,
+    pub is_cleanup: bool,
+    pub statements: Vec<ExportedStatement>,
+    pub terminator: ExportedTerminator,
+}
+
+#[derive(RustcEncodable)]
+pub struct ExportedBody {
+    /// `None` for the body of the item itself, `Some(i)` for its `i`th
+    /// promoted constant.
+    pub promoted: Option<usize>,
+    pub arg_count: usize,
+    pub locals: Vec<ExportedLocal>,
+    pub basic_blocks: Vec<ExportedBlock>,
+}
+
+#[derive(RustcEncodable)]
+pub struct ExportedMir {
+    pub def_path: String,
+    pub span: String,
+    pub bodies: Vec<ExportedBody>,
+}
+
+/// Exports the optimized MIR of `def_id` and of its promoted constants.
+pub fn export_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> ExportedMir {
+    let mir = tcx.optimized_mir(def_id);
+
+    let mut bodies = vec![export_body(tcx, None, mir)];
+    for (promoted, mir) in mir.promoted.iter_enumerated() {
+        bodies.push(export_body(tcx, Some(promoted.index()), mir));
+    }
+
+    ExportedMir {
+        def_path: tcx.item_path_str(def_id),
+        span: tcx.sess.source_map().span_to_string(mir.span),
+        bodies,
+    }
+}
+
+fn export_body<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                         promoted: Option<usize>,
+                         mir: &Mir<'tcx>)
+                         -> ExportedBody {
+    let source_map = tcx.sess.source_map();
+
+    let locals = mir.local_decls.iter_enumerated().map(|(local, decl)| {
+        ExportedLocal {
+            index: local.index(),
+            name: decl.name.map(|name| name.to_string()),
+            ty: decl.ty.to_string(),
+            mutable: decl.mutability == Mutability::Mut,
+            user_variable: decl.is_user_variable.is_some(),
+            span: source_map.span_to_string(decl.source_info.span),
+        }
+    }).collect();
+
+    let basic_blocks = mir.basic_blocks().iter_enumerated().map(|(bb, data)| {
+        let statements = data.statements.iter().map(|statement| {
+            ExportedStatement {
+                kind: statement_kind_name(&statement.kind),
+                text: format!("{:?}", statement),
+                span: source_map.span_to_string(statement.source_info.span),
+            }
+        }).collect();
+
+        let terminator = data.terminator();
+        ExportedBlock {
+            index: bb.index(),
+            is_cleanup: data.is_cleanup,
+            statements,
+            terminator: ExportedTerminator {
+                kind: terminator_kind_name(&terminator.kind),
+                text: format!("{:?}", terminator.kind),
+                successors: terminator.successors().map(|bb| bb.index()).collect(),
+                unwind: terminator.unwind().and_then(|unwind| *unwind).map(|bb| bb.index()),
+                span: source_map.span_to_string(terminator.source_info.span),
+            },
+        }
+    }).collect();
+
+    ExportedBody {
+        promoted,
+        arg_count: mir.arg_count,
+        locals,
+        basic_blocks,
+    }
+}
+
+fn statement_kind_name(kind: &StatementKind<'_>) -> &'static str {
+    match *kind {
+        StatementKind::Assign(..) => "Assign",
+        StatementKind::FakeRead(..) => "FakeRead",
+        StatementKind::SetDiscriminant { .. } => "SetDiscriminant",
+        StatementKind::StorageLive(..) => "StorageLive",
+        StatementKind::StorageDead(..) => "StorageDead",
+        StatementKind::InlineAsm { .. } => "InlineAsm",
+        StatementKind::Retag(..) => "Retag",
+        StatementKind::AscribeUserType(..) => "AscribeUserType",
+        StatementKind::Nop => "Nop",
+    }
+}
+
+fn terminator_kind_name(kind: &TerminatorKind<'_>) -> &'static str {
+    match *kind {
+        TerminatorKind::Goto { .. } => "Goto",
+        TerminatorKind::SwitchInt { .. } => "SwitchInt",
+        TerminatorKind::Resume => "Resume",
+        TerminatorKind::Abort => "Abort",
+        TerminatorKind::Return => "Return",
+        TerminatorKind::Unreachable => "Unreachable",
+        TerminatorKind::Drop { .. } => "Drop",
+        TerminatorKind::DropAndReplace { .. } => "DropAndReplace",
+        TerminatorKind::Call { .. } => "Call",
+        TerminatorKind::Assert { .. } => "Assert",
+        TerminatorKind::Yield { .. } => "Yield",
+        TerminatorKind::GeneratorDrop => "GeneratorDrop",
+        TerminatorKind::FalseEdges { .. } => "FalseEdges",
+        TerminatorKind::FalseUnwind { .. } => "FalseUnwind",
+    }
+}
+
+/// Writes the exported MIR of `single`, or of every body in the local crate
+/// if it is `None`, to `w` as a JSON array.
+pub fn write_mir_json<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                single: Option<DefId>,
+                                w: &mut dyn Write)
+                                -> io::Result<()> {
+    let exported: Vec<_> = dump_mir_def_ids(tcx, single).into_iter()
+        .map(|def_id| export_mir(tcx, def_id))
+        .collect();
+    writeln!(w, "{}", json::as_pretty_json(&exported))
+}
+
+/// Writes the exported MIR of the whole crate to `<output>.mir.json`.
+pub fn emit_mir_json<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                               outputs: &OutputFilenames)
+                               -> io::Result<()> {
+    let path = outputs.with_extension("mir.json");
+    let mut f = File::create(&path)?;
+    write_mir_json(tcx, None, &mut f)
+}
@@ -5,6 +5,7 @@ use syntax_pos::Span;
 pub mod borrowck_errors;
 pub mod elaborate_drops;
 pub mod def_use;
+pub mod export;
 pub mod patch;
 
 mod alignment;
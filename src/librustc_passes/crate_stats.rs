@@ -0,0 +1,120 @@
+// Whole-crate statistics, printed as JSON by `-Z crate-stats`.
+//
+// The intent is to give a stable, machine-readable summary of "how big" a
+// crate is as seen by the compiler, so that the effect of a change on a set of
+// representative crates can be measured by diffing two reports. The numbers
+// are collected after analysis, so the MIR counts describe optimized MIR.
+// The statement counts at the end of the earlier MIR phases can't be read back
+// at that point, since those MIR bodies have been stolen; they are recorded as
+// each phase finishes instead (see `Session::perf_stats`).
+
+use rustc::hir;
+use rustc::ty::TyCtxt;
+use rustc_serialize::json;
+use std::collections::BTreeMap;
+use std::sync::atomic::Ordering;
+use syntax_pos::symbol::Symbol;
+
+#[derive(Default, RustcEncodable)]
+pub struct HirStats {
+    pub items: usize,
+    pub fns: usize,
+    pub structs: usize,
+    pub enums: usize,
+    pub unions: usize,
+    pub traits: usize,
+    pub impls: usize,
+    pub trait_impls: usize,
+    pub consts: usize,
+    pub statics: usize,
+    pub mods: usize,
+    pub trait_items: usize,
+    pub impl_items: usize,
+    pub bodies: usize,
+}
+
+#[derive(Default, RustcEncodable)]
+pub struct MirStats {
+    pub bodies: usize,
+    pub basic_blocks: usize,
+    pub statements: usize,
+    pub locals: usize,
+    pub promoted: usize,
+    pub statements_per_phase: BTreeMap<String, usize>,
+}
+
+#[derive(RustcEncodable)]
+pub struct CrateStats {
+    pub crate_name: String,
+    pub hir: HirStats,
+    pub mir: MirStats,
+    pub interners: BTreeMap<String, usize>,
+    pub symbols: usize,
+}
+
+pub fn collect_crate_stats<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) -> CrateStats {
+    let krate = tcx.hir().krate();
+
+    let mut hir_stats = HirStats::default();
+    for item in krate.items.values() {
+        hir_stats.items += 1;
+        match item.node {
+            hir::ItemKind::Fn(..) => hir_stats.fns += 1,
+            hir::ItemKind::Struct(..) => hir_stats.structs += 1,
+            hir::ItemKind::Enum(..) => hir_stats.enums += 1,
+            hir::ItemKind::Union(..) => hir_stats.unions += 1,
+            hir::ItemKind::Trait(..) => hir_stats.traits += 1,
+            hir::ItemKind::Impl(.., ref trait_ref, _, _) => {
+                hir_stats.impls += 1;
+                if trait_ref.is_some() {
+                    hir_stats.trait_impls += 1;
+                }
+            }
+            hir::ItemKind::Const(..) => hir_stats.consts += 1,
+            hir::ItemKind::Static(..) => hir_stats.statics += 1,
+            hir::ItemKind::Mod(..) => hir_stats.mods += 1,
+            _ => {}
+        }
+    }
+    hir_stats.trait_items = krate.trait_items.len();
+    hir_stats.impl_items = krate.impl_items.len();
+    hir_stats.bodies = krate.body_ids.len();
+
+    let mut mir_stats = MirStats::default();
+    for def_id in tcx.body_owners() {
+        let mir = tcx.optimized_mir(def_id);
+        mir_stats.bodies += 1;
+        mir_stats.basic_blocks += mir.basic_blocks().len();
+        mir_stats.statements += mir.basic_blocks()
+                                   .iter()
+                                   .map(|data| data.statements.len())
+                                   .sum::<usize>();
+        mir_stats.locals += mir.local_decls.len();
+        mir_stats.promoted += mir.promoted.len();
+    }
+    // Computing `optimized_mir` above ran every earlier phase too.
+    let phase_names = ["built", "const", "validated", "optimized"];
+    let per_phase = &tcx.sess.perf_stats.mir_statements_per_phase;
+    mir_stats.statements_per_phase = phase_names.iter()
+        .zip(per_phase.iter())
+        .map(|(name, count)| (name.to_string(), count.load(Ordering::Relaxed)))
+        .collect();
+
+    let interners = tcx.interner_counts()
+                       .into_iter()
+                       .map(|(name, count)| (name.to_string(), count))
+                       .collect();
+
+    CrateStats {
+        crate_name: tcx.crate_name.to_string(),
+        hir: hir_stats,
+        mir: mir_stats,
+        interners,
+        symbols: Symbol::interned_count(),
+    }
+}
+
+pub fn print_crate_stats<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    let stats = collect_crate_stats(tcx);
+    println!("{}", json::as_pretty_json(&stats));
+}
@@ -15,16 +15,19 @@
 
 #[macro_use]
 extern crate rustc;
+extern crate serialize as rustc_serialize;
 
 use rustc::ty::query::Providers;
 
 mod diagnostics;
 
 pub mod ast_validation;
+pub mod crate_stats;
 pub mod rvalue_promotion;
 pub mod hir_stats;
 pub mod layout_test;
 pub mod loops;
+pub mod unused_report;
 
 __build_diagnostic_array! { librustc_passes, DIAGNOSTICS }
 
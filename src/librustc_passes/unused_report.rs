@@ -0,0 +1,99 @@
+// Machine-readable report of the unused `extern crate` items and trait
+// imports of a crate, written by `-Z emit-unused-report=PATH`.
+//
+// The same information feeds the `unused_extern_crates` and `unused_imports`
+// lints; this exposes it to workspace-cleanup tools without having to scrape
+// diagnostics.
+
+use rustc::hir;
+use rustc::hir::def_id::{DefId, LOCAL_CRATE};
+use rustc::ty::TyCtxt;
+use rustc_serialize::json;
+use syntax_pos::Span;
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+#[derive(RustcEncodable)]
+pub struct SpanData {
+    pub file_name: String,
+    pub line_start: usize,
+    pub column_start: usize,
+    pub line_end: usize,
+    pub column_end: usize,
+}
+
+#[derive(RustcEncodable)]
+pub struct UnusedItem {
+    /// The item path, e.g. `std::io::Write` or `serde`.
+    pub path: String,
+    /// The source text covered by `span`, if available.
+    pub snippet: Option<String>,
+    pub span: SpanData,
+}
+
+#[derive(RustcEncodable)]
+pub struct UnusedReport {
+    pub crate_name: String,
+    pub unused_extern_crates: Vec<UnusedItem>,
+    pub unused_trait_imports: Vec<UnusedItem>,
+}
+
+fn span_data<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, span: Span) -> SpanData {
+    let source_map = tcx.sess.source_map();
+    let lo = source_map.lookup_char_pos(span.lo());
+    let hi = source_map.lookup_char_pos(span.hi());
+    SpanData {
+        file_name: lo.file.name.to_string(),
+        line_start: lo.line,
+        column_start: lo.col.0 + 1,
+        line_end: hi.line,
+        column_end: hi.col.0 + 1,
+    }
+}
+
+/// The path shown for an unused item: the imported path of a `use` item, or
+/// the name of the crate loaded by an `extern crate` item.
+fn item_path<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> String {
+    if let Some(hir_id) = tcx.hir().as_local_hir_id(def_id) {
+        let item = tcx.hir().expect_item_by_hir_id(hir_id);
+        match item.node {
+            // Paths to the crate root start with an empty segment in the
+            // 2015 edition, which is printed as a leading `::`.
+            hir::ItemKind::Use(ref path, _) => {
+                return path.to_string().trim_start_matches("::").to_string();
+            }
+            hir::ItemKind::ExternCrate(orig_name) => {
+                return orig_name.unwrap_or(item.ident.name).to_string();
+            }
+            _ => {}
+        }
+    }
+    tcx.item_path_str(def_id)
+}
+
+fn unused_items<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          items: &[(DefId, Span)]) -> Vec<UnusedItem> {
+    items.iter().map(|&(def_id, span)| {
+        UnusedItem {
+            path: item_path(tcx, def_id),
+            snippet: tcx.sess.source_map().span_to_snippet(span).ok(),
+            span: span_data(tcx, span),
+        }
+    }).collect()
+}
+
+pub fn collect_unused_report<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) -> UnusedReport {
+    UnusedReport {
+        crate_name: tcx.crate_name.to_string(),
+        unused_extern_crates: unused_items(tcx, &tcx.unused_extern_crates(LOCAL_CRATE)),
+        unused_trait_imports: unused_items(tcx, &tcx.unused_trait_imports(LOCAL_CRATE)),
+    }
+}
+
+pub fn emit_unused_report<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, path: &Path) -> io::Result<()> {
+    let report = collect_unused_report(tcx);
+    let mut file = File::create(path)?;
+    writeln!(file, "{}", json::as_pretty_json(&report))
+}
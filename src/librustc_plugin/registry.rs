@@ -5,11 +5,13 @@ use rustc::session::Session;
 use rustc::util::nodemap::FxHashMap;
 
 use syntax::ext::base::{SyntaxExtension, NamedSyntaxExtension, NormalTT, IdentTT};
-use syntax::ext::base::MacroExpanderFn;
+use syntax::ext::base::{Annotatable, ExtCtxt, MacroExpanderFn, MultiItemModifier};
 use syntax::ext::hygiene;
 use syntax::symbol::Symbol;
+use syntax::tokenstream::TokenStream;
 use syntax::ast;
 use syntax::feature_gate::AttributeType;
+use syntax_ext::deriving::custom::expand_derive;
 use syntax_pos::Span;
 
 use std::borrow::ToOwned;
@@ -134,6 +136,53 @@ impl<'a> Registry<'a> {
         });
     }
 
+    /// Register a function-like macro, `name!(...)`, expanded by a function
+    /// from the tokens inside the delimiters to the tokens of the expansion.
+    ///
+    /// This is a convenience wrapper for `register_syntax_extension`. The
+    /// expansion gets its hygiene marks and backtrace from the expander, as
+    /// for the procedural macros of proc-macro crates.
+    pub fn register_bang_macro<F>(&mut self, name: &str, expander: F)
+        where F: Fn(TokenStream) -> TokenStream + Sync + Send + 'static
+    {
+        self.register_syntax_extension(Symbol::intern(name), SyntaxExtension::ProcMacro {
+            expander: Box::new(expander),
+            allow_internal_unstable: None,
+            edition: hygiene::default_edition(),
+        });
+    }
+
+    /// Register an attribute macro, `#[name(...)]`, expanded by a function
+    /// from the tokens of the attribute's arguments and of the annotated item
+    /// to the tokens of the items replacing it.
+    ///
+    /// This is a convenience wrapper for `register_syntax_extension`.
+    pub fn register_attr_macro<F>(&mut self, name: &str, expander: F)
+        where F: Fn(TokenStream, TokenStream) -> TokenStream + Sync + Send + 'static
+    {
+        self.register_syntax_extension(Symbol::intern(name), SyntaxExtension::AttrProcMacro(
+            Box::new(expander),
+            hygiene::default_edition(),
+        ));
+    }
+
+    /// Register a derive macro, `#[derive(name)]`, expanded by a function
+    /// from the tokens of the annotated struct, enum or union to the tokens
+    /// of the items to add after it. `attributes` are the names of the helper
+    /// attributes the derive accepts on the item and its fields.
+    ///
+    /// This is a convenience wrapper for `register_syntax_extension`.
+    pub fn register_derive_macro<F>(&mut self, name: &str, attributes: &[&str], expander: F)
+        where F: Fn(TokenStream) -> TokenStream + Sync + Send + 'static
+    {
+        let attrs: Vec<_> = attributes.iter().map(|&attr| Symbol::intern(attr)).collect();
+        self.register_syntax_extension(Symbol::intern(name), SyntaxExtension::ProcMacroDerive(
+            Box::new(DeriveMacro { expander, attrs: attrs.clone() }),
+            attrs,
+            hygiene::default_edition(),
+        ));
+    }
+
     /// Register a compiler lint pass.
     pub fn register_early_lint_pass(&mut self, lint_pass: EarlyLintPassObject) {
         self.early_lint_passes.push(lint_pass);
@@ -173,3 +222,22 @@ impl<'a> Registry<'a> {
         self.attributes.push((name, ty));
     }
 }
+
+/// The expander of a derive macro registered with `register_derive_macro`.
+struct DeriveMacro<F> {
+    expander: F,
+    attrs: Vec<ast::Name>,
+}
+
+impl<F> MultiItemModifier for DeriveMacro<F>
+    where F: Fn(TokenStream) -> TokenStream
+{
+    fn expand(&self,
+              ecx: &mut ExtCtxt<'_>,
+              span: Span,
+              _meta_item: &ast::MetaItem,
+              item: Annotatable)
+              -> Vec<Annotatable> {
+        expand_derive(ecx, span, &self.attrs, item, |_, input| (self.expander)(input))
+    }
+}
@@ -73,10 +73,6 @@ mod check_unused;
 mod build_reduced_graph;
 mod resolve_imports;
 
-fn is_known_tool(name: Name) -> bool {
-    ["clippy", "rustfmt"].contains(&&*name.as_str())
-}
-
 enum Weak {
     Yes,
     No,
@@ -1494,6 +1490,9 @@ pub struct Resolver<'a> {
 
     prelude: Option<Module<'a>>,
     pub extern_prelude: FxHashMap<Ident, ExternPreludeEntry<'a>>,
+    /// The tools whose attributes are accepted, i.e., `clippy`, `rustfmt` and
+    /// those listed in `#![register_tool(...)]`.
+    registered_tools: FxHashSet<Name>,
 
     /// N.B., this is used only for better diagnostics, not name resolution itself.
     has_self: FxHashSet<DefId>,
@@ -1840,6 +1839,24 @@ impl<'a> Resolver<'a> {
             }
         }
 
+        // The crate attributes have already been through cfg-stripping (see
+        // `syntax::config::features`), so this also sees the tools registered
+        // with `#![cfg_attr(..., register_tool(...))]`.
+        let mut registered_tools: FxHashSet<Name> =
+            ["clippy", "rustfmt"].iter().map(|tool| Symbol::intern(tool)).collect();
+        for attr in krate.attrs.iter().filter(|attr| attr.check_name("register_tool")) {
+            for nested in attr.meta_item_list().unwrap_or_default() {
+                match nested.word() {
+                    Some(word) if word.is_scoped().is_none() => {
+                        registered_tools.insert(word.name());
+                    }
+                    _ => {
+                        session.span_err(nested.span, "`register_tool` only accepts tool names");
+                    }
+                }
+            }
+        }
+
         let mut invocations = FxHashMap::default();
         invocations.insert(Mark::root(),
                            arenas.alloc_invocation_data(InvocationData::root(graph_root)));
@@ -1859,6 +1876,7 @@ impl<'a> Resolver<'a> {
             graph_root,
             prelude: None,
             extern_prelude,
+            registered_tools,
 
             has_self: FxHashSet::default(),
             field_names: FxHashMap::default(),
@@ -2137,7 +2155,7 @@ impl<'a> Resolver<'a> {
                     return Some(LexicalScopeBinding::Item(binding));
                 }
             }
-            if ns == TypeNS && is_known_tool(ident.name) {
+            if ns == TypeNS && self.is_known_tool(ident.name) {
                 let binding = (Def::ToolMod, ty::Visibility::Public,
                                DUMMY_SP, Mark::root()).to_name_binding(self.arenas);
                 return Some(LexicalScopeBinding::Item(binding));
@@ -5082,6 +5100,10 @@ impl<'a> Resolver<'a> {
         err.span_suggestion(span, message, String::new(), Applicability::MachineApplicable);
     }
 
+    fn is_known_tool(&self, name: Name) -> bool {
+        self.registered_tools.contains(&name)
+    }
+
     fn extern_prelude_get(&mut self, ident: Ident, speculative: bool)
                           -> Option<&'a NameBinding<'a>> {
         if ident.is_path_segment_keyword() {
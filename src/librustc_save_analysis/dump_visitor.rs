@@ -24,6 +24,7 @@ use std::path::Path;
 use std::env;
 
 use syntax::ast::{self, Attribute, NodeId, PatKind, CRATE_NODE_ID};
+use syntax::attr;
 use syntax::parse::token;
 use syntax::visit::{self, Visitor};
 use syntax::print::pprust::{
@@ -82,12 +83,9 @@ pub struct DumpVisitor<'l, 'tcx: 'l, 'll, O: DumpOutput> {
 
     cur_scope: NodeId,
 
-    // Set of macro definition (callee) spans, and the set
-    // of macro use (callsite) spans. We store these to ensure
-    // we only write one macro def per unique macro definition, and
-    // one macro use per unique callsite span.
-    // mac_defs: FxHashSet<Span>,
-    // macro_calls: FxHashSet<Span>,
+    // Set of macro use (callsite) spans. We store these to ensure we only
+    // write one macro use per unique callsite span.
+    macro_calls: FxHashSet<Span>,
 }
 
 impl<'l, 'tcx: 'l, 'll, O: DumpOutput + 'll> DumpVisitor<'l, 'tcx, 'll, O> {
@@ -102,8 +100,7 @@ impl<'l, 'tcx: 'l, 'll, O: DumpOutput + 'll> DumpVisitor<'l, 'tcx, 'll, O> {
             dumper,
             span: span_utils,
             cur_scope: CRATE_NODE_ID,
-            // mac_defs: FxHashSet::default(),
-            // macro_calls: FxHashSet::default(),
+            macro_calls: FxHashSet::default(),
         }
     }
 
@@ -769,10 +766,12 @@ impl<'l, 'tcx: 'l, 'll, O: DumpOutput + 'll> DumpVisitor<'l, 'tcx, 'll, O> {
     }
 
     fn process_path(&mut self, id: NodeId, path: &'l ast::Path) {
+        // Paths expanded from a macro are recorded at the macro's call
+        // site, if at all, see `SaveContext::ref_span`.
+        self.dump_path_ref(id, path);
         if self.span.filter_generated(path.span) {
             return;
         }
-        self.dump_path_ref(id, path);
 
         // Type arguments
         for seg in &path.segments {
@@ -1021,47 +1020,60 @@ impl<'l, 'tcx: 'l, 'll, O: DumpOutput + 'll> DumpVisitor<'l, 'tcx, 'll, O> {
         }
     }
 
-    /// Extracts macro use and definition information from the AST node defined
-    /// by the given NodeId, using the expansion information from the node's
-    /// span.
+    /// Extracts macro use information from the AST node defined by the given
+    /// NodeId, using the expansion information from the node's span.
     ///
     /// If the span is not macro-generated, do nothing, else use callee and
-    /// callsite spans to record macro definition and use data, using the
-    /// mac_uses and mac_defs sets to prevent multiples.
-    fn process_macro_use(&mut self, _span: Span) {
-        // FIXME if we're not dumping the defs (see below), there is no point
-        // dumping refs either.
-        // let source_span = span.source_callsite();
-        // if !self.macro_calls.insert(source_span) {
-        //     return;
-        // }
-
-        // let data = match self.save_ctxt.get_macro_use_data(span) {
-        //     None => return,
-        //     Some(data) => data,
-        // };
-
-        // self.dumper.macro_use(data);
-
-        // FIXME write the macro def
-        // let mut hasher = DefaultHasher::new();
-        // data.callee_span.hash(&mut hasher);
-        // let hash = hasher.finish();
-        // let qualname = format!("{}::{}", data.name, hash);
-        // Don't write macro definition for imported macros
-        // if !self.mac_defs.contains(&data.callee_span)
-        //     && !data.imported {
-        //     self.mac_defs.insert(data.callee_span);
-        //     if let Some(sub_span) = self.span.span_for_macro_def_name(data.callee_span) {
-        //         self.dumper.macro_data(MacroData {
-        //             span: sub_span,
-        //             name: data.name.clone(),
-        //             qualname: qualname.clone(),
-        //             // FIXME where do macro docs come from?
-        //             docs: String::new(),
-        //         }.lower(self.tcx));
-        //     }
-        // }
+    /// callsite spans to record a macro use, using the macro_calls set to
+    /// prevent multiples. The definitions of local macros are recorded when
+    /// their `macro_rules!` items are visited, see `process_macro_def`.
+    fn process_macro_use(&mut self, span: Span) {
+        if !generated_code(span) {
+            return;
+        }
+        let source_span = span.source_callsite();
+        if !self.macro_calls.insert(source_span) {
+            return;
+        }
+
+        let data = match self.save_ctxt.get_macro_use_data(span) {
+            None => return,
+            Some(data) => data,
+        };
+
+        self.dumper.macro_use(data);
+    }
+
+    fn process_macro_def(&mut self, item: &'l ast::Item, def: &'l ast::MacroDef) {
+        if self.span.filter_generated(item.ident.span) {
+            return;
+        }
+        let exported = attr::contains_name(&item.attrs, "macro_export");
+        let value = if def.legacy {
+            format!("macro_rules! {}", item.ident)
+        } else {
+            format!("macro {}", item.ident)
+        };
+        self.dumper.dump_def(
+            &Access {
+                public: exported || item.vis.node.is_pub(),
+                reachable: exported || self.save_ctxt.access_levels.is_reachable(item.id),
+            },
+            Def {
+                kind: DefKind::Macro,
+                id: id_from_node_id(item.id, &self.save_ctxt),
+                span: self.span_from_span(item.ident.span),
+                name: item.ident.to_string(),
+                qualname: format!("::{}", self.tcx.node_path_str(item.id)),
+                value,
+                parent: None,
+                children: vec![],
+                decl_id: None,
+                docs: self.save_ctxt.docs_for_attrs(&item.attrs),
+                sig: None,
+                attributes: lower_attributes(item.attrs.clone(), &self.save_ctxt),
+            },
+        );
     }
 
     fn process_trait_item(&mut self, trait_item: &'l ast::TraitItem, trait_id: DefId) {
@@ -1446,6 +1458,7 @@ impl<'l, 'tcx: 'l, 'll, O: DumpOutput + 'll> Visitor<'l> for DumpVisitor<'l, 'tc
 
                 self.process_generic_params(ty_params, &qualname, item.id);
             }
+            MacroDef(ref def) => self.process_macro_def(item, def),
             Mac(_) => (),
             _ => visit::walk_item(self, item),
         }
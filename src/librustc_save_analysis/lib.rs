@@ -38,7 +38,7 @@ use syntax::parse::lexer::comments::strip_doc_comment_decoration;
 use syntax::print::pprust;
 use syntax::visit::{self, Visitor};
 use syntax::print::pprust::{arg_to_string, ty_to_string};
-use syntax::source_map::MacroAttribute;
+use syntax::source_map::{MacroAttribute, MacroBang};
 use syntax_pos::*;
 
 use json_dumper::JsonDumper;
@@ -705,8 +705,7 @@ impl<'l, 'tcx: 'l> SaveContext<'l, 'tcx> {
         }
 
         let def = self.get_path_def(id);
-        let span = path_seg.ident.span;
-        filter!(self.span_utils, span);
+        let span = self.ref_span(path_seg.ident.span)?;
         let span = self.span_from_span(span);
 
         match def {
@@ -876,6 +875,26 @@ impl<'l, 'tcx: 'l> SaveContext<'l, 'tcx> {
         })
     }
 
+    /// Returns the span a reference written at `span` is recorded at.
+    ///
+    /// A reference inside the expansion of a bang macro, e.g. a function
+    /// called from a `macro_rules!` body, is attributed to the outermost call
+    /// site of the macro, as that is the only place it is visible in the
+    /// source. References in other generated code, e.g. derives, are skipped.
+    fn ref_span(&self, span: Span) -> Option<Span> {
+        if !generated_code(span) {
+            filter!(self.span_utils, span);
+            return Some(span);
+        }
+        match span.source_callee()?.format {
+            MacroBang(_) => {}
+            _ => return None,
+        }
+        let callsite = span.source_callsite();
+        filter!(self.span_utils, callsite);
+        Some(callsite)
+    }
+
     fn lookup_ref_id(&self, ref_id: NodeId) -> Option<DefId> {
         match self.get_path_def(ref_id) {
             HirDef::PrimTy(_) | HirDef::SelfTy(..) | HirDef::Err => None,
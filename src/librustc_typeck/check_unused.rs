@@ -1,19 +1,39 @@
 use crate::lint;
 use rustc::ty::TyCtxt;
+use rustc::ty::query::Providers;
 
 use errors::Applicability;
 use syntax::ast;
 use syntax_pos::Span;
 
-use rustc::hir::def_id::{DefId, LOCAL_CRATE};
+use rustc::hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc::hir::itemlikevisit::ItemLikeVisitor;
 use rustc::hir::print::visibility_qualified;
 use rustc::hir;
 use rustc::util::nodemap::DefIdSet;
 
 use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
+
+pub fn provide(providers: &mut Providers<'_>) {
+    *providers = Providers {
+        unused_trait_imports,
+        unused_extern_crates,
+        ..*providers
+    };
+}
 
 pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    unused_trait_imports_lint(tcx);
+    unused_crates_lint(tcx);
+}
+
+fn unused_trait_imports<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    cnum: CrateNum,
+) -> Lrc<Vec<(DefId, Span)>> {
+    assert_eq!(cnum, LOCAL_CRATE);
+
     let mut used_trait_imports = DefIdSet::default();
     for &body_id in tcx.hir().krate().bodies.keys() {
         let item_def_id = tcx.hir().body_owner_def_id(body_id);
@@ -22,10 +42,22 @@ pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
         used_trait_imports.extend(imports.iter());
     }
 
-    let mut visitor = CheckVisitor { tcx, used_trait_imports };
+    let mut visitor = CheckVisitor { tcx, used_trait_imports, unused: vec![] };
     tcx.hir().krate().visit_all_item_likes(&mut visitor);
 
-    unused_crates_lint(tcx);
+    Lrc::new(visitor.unused)
+}
+
+fn unused_trait_imports_lint<'tcx>(tcx: TyCtxt<'_, 'tcx, 'tcx>) {
+    for &(def_id, span) in tcx.unused_trait_imports(LOCAL_CRATE).iter() {
+        let id = tcx.hir().as_local_node_id(def_id).unwrap();
+        let msg = if let Ok(snippet) = tcx.sess.source_map().span_to_snippet(span) {
+            format!("unused import: `{}`", snippet)
+        } else {
+            "unused import".to_owned()
+        };
+        tcx.lint_node(lint::builtin::UNUSED_IMPORTS, id, span, &msg);
+    }
 }
 
 impl<'a, 'tcx, 'v> ItemLikeVisitor<'v> for CheckVisitor<'a, 'tcx> {
@@ -48,10 +80,11 @@ impl<'a, 'tcx, 'v> ItemLikeVisitor<'v> for CheckVisitor<'a, 'tcx> {
 struct CheckVisitor<'a, 'tcx: 'a> {
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     used_trait_imports: DefIdSet,
+    unused: Vec<(DefId, Span)>,
 }
 
 impl<'a, 'tcx> CheckVisitor<'a, 'tcx> {
-    fn check_import(&self, id: ast::NodeId, span: Span) {
+    fn check_import(&mut self, id: ast::NodeId, span: Span) {
         let def_id = self.tcx.hir().local_def_id(id);
         if !self.tcx.maybe_unused_trait_import(def_id) {
             return;
@@ -62,22 +95,19 @@ impl<'a, 'tcx> CheckVisitor<'a, 'tcx> {
             return;
         }
 
-        let msg = if let Ok(snippet) = self.tcx.sess.source_map().span_to_snippet(span) {
-            format!("unused import: `{}`", snippet)
-        } else {
-            "unused import".to_owned()
-        };
-        self.tcx.lint_node(lint::builtin::UNUSED_IMPORTS, id, span, &msg);
+        self.unused.push((import_def_id, span));
     }
 }
 
-fn unused_crates_lint<'tcx>(tcx: TyCtxt<'_, 'tcx, 'tcx>) {
-    let lint = lint::builtin::UNUSED_EXTERN_CRATES;
+/// Collects the crates that are completely unused.  These we can always
+/// suggest removing (no matter which edition we are in).
+fn unused_extern_crates<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    cnum: CrateNum,
+) -> Lrc<Vec<(DefId, Span)>> {
+    assert_eq!(cnum, LOCAL_CRATE);
 
-    // Collect first the crates that are completely unused.  These we
-    // can always suggest removing (no matter which edition we are
-    // in).
-    let unused_extern_crates: FxHashMap<DefId, Span> =
+    let unused_extern_crates: Vec<_> =
         tcx.maybe_unused_extern_crates(LOCAL_CRATE)
         .iter()
         .filter(|&&(def_id, _)| {
@@ -113,6 +143,15 @@ fn unused_crates_lint<'tcx>(tcx: TyCtxt<'_, 'tcx, 'tcx>) {
         .cloned()
         .collect();
 
+    Lrc::new(unused_extern_crates)
+}
+
+fn unused_crates_lint<'tcx>(tcx: TyCtxt<'_, 'tcx, 'tcx>) {
+    let lint = lint::builtin::UNUSED_EXTERN_CRATES;
+
+    let unused_extern_crates: FxHashMap<DefId, Span> =
+        tcx.unused_extern_crates(LOCAL_CRATE).iter().cloned().collect();
+
     // Collect all the extern crates (in a reliable order).
     let mut crates_to_lint = vec![];
     tcx.hir().krate().visit_all_item_likes(&mut CollectExternCrateVisitor {
@@ -312,6 +312,7 @@ pub fn provide(providers: &mut Providers<'_>) {
     variance::provide(providers);
     outlives::provide(providers);
     impl_wf_check::provide(providers);
+    check_unused::provide(providers);
 }
 
 pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>)
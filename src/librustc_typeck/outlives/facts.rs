@@ -0,0 +1,87 @@
+//! Collects the outlives relationships known to hold in the body of a
+//! function, for the `body_outlives_facts` query.
+
+use rustc::hir::def_id::DefId;
+use rustc::middle::free_region::{OutlivesFact, OutlivesSource};
+use rustc::traits::query::outlives_bounds::OutlivesBound;
+use rustc::ty::{self, TyCtxt};
+use rustc_data_structures::sync::Lrc;
+use std::iter;
+
+pub fn body_outlives_facts<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    def_id: DefId,
+) -> Lrc<Vec<OutlivesFact>> {
+    let closure_base_def_id = tcx.closure_base_def_id(def_id);
+    if closure_base_def_id != def_id {
+        return tcx.body_outlives_facts(closure_base_def_id);
+    }
+
+    let mut facts = vec![];
+
+    // The where-clauses of the function and of its parents. Bounds that
+    // mention late-bound regions only hold under their binder, so they are
+    // not facts about the body.
+    let mut predicates = tcx.predicates_of(def_id);
+    loop {
+        for &(ref predicate, span) in &predicates.predicates {
+            let (longer, shorter) = match *predicate {
+                ty::Predicate::RegionOutlives(ref data) => match data.no_bound_vars() {
+                    Some(ty::OutlivesPredicate(a, b)) => (a.to_string(), b.to_string()),
+                    None => continue,
+                },
+                ty::Predicate::TypeOutlives(ref data) => match data.no_bound_vars() {
+                    Some(ty::OutlivesPredicate(a, b)) => (a.to_string(), b.to_string()),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            facts.push(OutlivesFact {
+                longer,
+                shorter,
+                source: OutlivesSource::WhereClause(span),
+            });
+        }
+        match predicates.parent {
+            Some(parent) => predicates = tcx.predicates_of(parent),
+            None => break,
+        }
+    }
+
+    // The bounds implied by the argument and return types, which only
+    // functions with a body have.
+    let node_id = tcx.hir().as_local_node_id(def_id).expect("expected local def-id");
+    let fn_decl = match tcx.hir().fn_decl(node_id) {
+        Some(fn_decl) => fn_decl,
+        None => return Lrc::new(facts),
+    };
+    let body_id = tcx.hir().node_to_hir_id(node_id);
+    let param_env = tcx.param_env(def_id);
+    let fn_sig = tcx.liberate_late_bound_regions(def_id, &tcx.fn_sig(def_id));
+    let spans = fn_decl.inputs.iter()
+        .map(|ty| ty.span)
+        .chain(iter::once(fn_decl.output.span()));
+
+    tcx.infer_ctxt().enter(|infcx| {
+        for (&ty, span) in fn_sig.inputs_and_output.iter().zip(spans) {
+            for bound in infcx.implied_outlives_bounds(param_env, body_id, ty, span) {
+                let (longer, shorter) = match bound {
+                    OutlivesBound::RegionSubRegion(a, b) => (b.to_string(), a.to_string()),
+                    OutlivesBound::RegionSubParam(a, b) => (b.to_string(), a.to_string()),
+                    OutlivesBound::RegionSubProjection(a, b) => (b.to_string(), a.to_string()),
+                };
+                let fact = OutlivesFact {
+                    longer,
+                    shorter,
+                    source: OutlivesSource::ImpliedBound(span),
+                };
+                // A type can imply the same bound more than once.
+                if !facts.contains(&fact) {
+                    facts.push(fact);
+                }
+            }
+        }
+    });
+
+    Lrc::new(facts)
+}
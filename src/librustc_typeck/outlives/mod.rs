@@ -7,6 +7,7 @@ use rustc::ty::{self, CratePredicatesMap, TyCtxt};
 use rustc_data_structures::sync::Lrc;
 
 mod explicit;
+mod facts;
 mod implicit_infer;
 /// Code to write unit test for outlives.
 pub mod test;
@@ -16,6 +17,7 @@ pub fn provide(providers: &mut Providers<'_>) {
     *providers = Providers {
         inferred_outlives_of,
         inferred_outlives_crate,
+        body_outlives_facts: facts::body_outlives_facts,
         ..*providers
     };
 }
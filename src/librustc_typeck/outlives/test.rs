@@ -1,5 +1,7 @@
 use rustc::hir;
+use rustc::hir::def_id::DefId;
 use rustc::hir::itemlikevisit::ItemLikeVisitor;
+use rustc::middle::free_region::OutlivesSource;
 use rustc::ty::TyCtxt;
 
 pub fn test_inferred_outlives<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
@@ -12,6 +14,28 @@ struct OutlivesTest<'a, 'tcx: 'a> {
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
 }
 
+impl<'a, 'tcx> OutlivesTest<'a, 'tcx> {
+    // For unit testing: check for a special "rustc_outlives_facts" attribute
+    // on a function and report an error listing the outlives facts that hold
+    // in its body if found.
+    fn test_body_outlives_facts(&self, def_id: DefId) {
+        if !self.tcx.has_attr(def_id, "rustc_outlives_facts") {
+            return;
+        }
+        let facts = self.tcx.body_outlives_facts(def_id);
+        let span = self.tcx.def_span(def_id);
+        let mut err = self.tcx.sess.struct_span_err(span, "rustc_outlives_facts");
+        for fact in facts.iter() {
+            let (span, source) = match fact.source {
+                OutlivesSource::WhereClause(span) => (span, "where-clause"),
+                OutlivesSource::ImpliedBound(span) => (span, "implied bound"),
+            };
+            err.span_note(span, &format!("{} `{}: {}`", source, fact.longer, fact.shorter));
+        }
+        err.emit();
+    }
+}
+
 impl<'a, 'tcx> ItemLikeVisitor<'tcx> for OutlivesTest<'a, 'tcx> {
     fn visit_item(&mut self, item: &'tcx hir::Item) {
         let item_def_id = self.tcx.hir().local_def_id(item.id);
@@ -28,8 +52,14 @@ impl<'a, 'tcx> ItemLikeVisitor<'tcx> for OutlivesTest<'a, 'tcx> {
                 inferred_outlives_of
             );
         }
+
+        self.test_body_outlives_facts(item_def_id);
     }
 
     fn visit_trait_item(&mut self, _: &'tcx hir::TraitItem) {}
-    fn visit_impl_item(&mut self, _: &'tcx hir::ImplItem) {}
+
+    fn visit_impl_item(&mut self, impl_item: &'tcx hir::ImplItem) {
+        let def_id = self.tcx.hir().local_def_id(impl_item.id);
+        self.test_body_outlives_facts(def_id);
+    }
 }
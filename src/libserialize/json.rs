@@ -255,6 +255,50 @@ pub enum ParserError {
 // Builder and Parser have the same errors.
 pub type BuilderError = ParserError;
 
+/// The full context of the last syntax error of a `Parser`, see
+/// `Parser::error_detail`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SyntaxErrorDetail {
+    pub code: ErrorCode,
+    pub line: usize,
+    pub col: usize,
+    /// The byte offset of the offending character in the input, or the length
+    /// of the input if the error is an unexpected end of input.
+    pub offset: usize,
+    /// The tokens the parser would have accepted at that point. This is empty
+    /// if the input was well-formed but invalid, like an out of range number.
+    pub expected: &'static [&'static str],
+}
+
+/// Options of a `Parser` accepting more than strict JSON. All of them are
+/// disabled by default.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParserOptions {
+    /// Accept a comma after the last element of an array or object.
+    pub trailing_commas: bool,
+    /// Accept `// line` and `/* block */` comments wherever whitespace is
+    /// allowed.
+    pub comments: bool,
+}
+
+impl ParserOptions {
+    /// Options for configuration files, accepting both trailing commas and
+    /// comments.
+    pub fn lenient() -> ParserOptions {
+        ParserOptions { trailing_commas: true, comments: true }
+    }
+}
+
+const EXPECTED_VALUE: &[&str] = &["value"];
+const EXPECTED_KEY: &[&str] = &["string key"];
+const EXPECTED_KEY_OR_END: &[&str] = &["string key", "`}`"];
+const EXPECTED_COLON: &[&str] = &["`:`"];
+const EXPECTED_ARRAY_COMMA: &[&str] = &["`,`", "`]`"];
+const EXPECTED_OBJECT_COMMA: &[&str] = &["`,`", "`}`"];
+const EXPECTED_DIGIT: &[&str] = &["digit"];
+const EXPECTED_HEX_DIGIT: &[&str] = &["hex digit"];
+const EXPECTED_EOF: &[&str] = &["end of input"];
+
 #[derive(Clone, PartialEq, Debug)]
 pub enum DecoderError {
     ParseError(ParserError),
@@ -268,6 +312,32 @@ pub enum DecoderError {
 pub enum EncoderError {
     FmtError(fmt::Error),
     BadHashmapKey,
+    /// A NaN or infinite float was encoded with `NonFiniteFloats::Error`.
+    NonFiniteFloat,
+}
+
+/// How an encoder writes floats that JSON numbers cannot represent, i.e.,
+/// NaN and the infinities.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NonFiniteFloats {
+    /// Write `null`, which decodes as NaN. This is the default.
+    Null,
+    /// Fail with `EncoderError::NonFiniteFloat`.
+    Error,
+    /// Write the strings `"NaN"`, `"inf"` and `"-inf"`, which decode back to
+    /// the same values.
+    String,
+}
+
+/// How an encoder writes finite floats.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FloatFormat {
+    /// The shortest representation that decodes back to the same value,
+    /// with a `.0` added to integral values. This is the default.
+    Shortest,
+    /// Exactly this many digits after the decimal point, rounding the value
+    /// if needed.
+    Precision(usize),
 }
 
 /// Returns a readable error string for a given error code.
@@ -314,6 +384,67 @@ pub fn encode<T: crate::Encodable>(object: &T) -> Result<string::String, Encoder
     Ok(s)
 }
 
+/// Shortcut function to encode a `T` into a canonical JSON `String`, see
+/// `Encoder::new_canonical`.
+pub fn encode_canonical<T: crate::Encodable>(object: &T)
+                                             -> Result<string::String, EncoderError> {
+    let mut s = String::new();
+    {
+        let mut encoder = Encoder::new_canonical(&mut s);
+        object.encode(&mut encoder)?;
+    }
+    Ok(s)
+}
+
+/// Shortcut function to encode a `T` as JSON straight into an `io::Write`.
+///
+/// The output is written as it is produced, without building the whole
+/// document in memory first. As it is written in many small pieces, `writer`
+/// should usually be buffered. Encoder errors are returned as
+/// `ErrorKind::InvalidInput`.
+pub fn encode_to_writer<T, W>(object: &T, writer: &mut W) -> io::Result<()>
+    where T: crate::Encodable, W: io::Write + ?Sized
+{
+    with_io_writer(writer, |wr| object.encode(&mut Encoder::new(wr)))
+}
+
+/// Like `encode_to_writer`, but the output is pretty-printed like with a
+/// `PrettyEncoder`.
+pub fn encode_pretty_to_writer<T, W>(object: &T, writer: &mut W) -> io::Result<()>
+    where T: crate::Encodable, W: io::Write + ?Sized
+{
+    with_io_writer(writer, |wr| object.encode(&mut PrettyEncoder::new(wr)))
+}
+
+/// Adapts an `io::Write` to `fmt::Write`, keeping the I/O error, which a
+/// `fmt::Error` can't carry.
+struct IoWriter<'a, W: ?Sized + 'a> {
+    writer: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<'a, W: io::Write + ?Sized> fmt::Write for IoWriter<'a, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.writer.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+fn with_io_writer<W, F>(writer: &mut W, f: F) -> io::Result<()>
+    where W: io::Write + ?Sized, F: FnOnce(&mut dyn fmt::Write) -> EncodeResult
+{
+    let mut wr = IoWriter { writer, error: None };
+    match f(&mut wr) {
+        Ok(()) => Ok(()),
+        Err(err) => match wr.error {
+            Some(err) => Err(err),
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, err)),
+        },
+    }
+}
+
 impl fmt::Display for ErrorCode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         error_str(*self).fmt(f)
@@ -331,6 +462,23 @@ impl fmt::Display for ParserError {
     }
 }
 
+impl fmt::Display for SyntaxErrorDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at line {} column {}", self.code, self.line, self.col)?;
+        for (i, expected) in self.expected.iter().enumerate() {
+            let sep = if i == 0 {
+                ": expected "
+            } else if i == self.expected.len() - 1 {
+                " or "
+            } else {
+                ", "
+            };
+            write!(f, "{}{}", sep, expected)?;
+        }
+        Ok(())
+    }
+}
+
 impl fmt::Display for DecoderError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // FIXME this should be a nicer error
@@ -451,26 +599,94 @@ fn fmt_number_or_null(v: f64) -> string::String {
     }
 }
 
+fn emit_float(wr: &mut dyn fmt::Write,
+              v: f64,
+              is_emitting_map_key: bool,
+              non_finite_floats: NonFiniteFloats,
+              float_format: FloatFormat) -> EncodeResult {
+    let formatted = match (v.is_finite(), non_finite_floats, float_format) {
+        (false, NonFiniteFloats::Error, _) => return Err(EncoderError::NonFiniteFloat),
+        (false, NonFiniteFloats::String, _) => {
+            // Written as a string whether or not this is a map key.
+            write!(wr, "\"{}\"", v)?;
+            return Ok(());
+        }
+        (true, _, FloatFormat::Precision(digits)) => format!("{:.*}", digits, v),
+        _ => fmt_number_or_null(v),
+    };
+    if is_emitting_map_key {
+        write!(wr, "\"{}\"", formatted)?;
+    } else {
+        write!(wr, "{}", formatted)?;
+    }
+    Ok(())
+}
+
 /// A structure for implementing serialization to JSON.
 pub struct Encoder<'a> {
     writer: &'a mut (dyn fmt::Write+'a),
     is_emitting_map_key: bool,
+    canonical: bool,
+    // In canonical mode, the output of the map keys and values currently
+    // being encoded, innermost last, and the entries of the maps currently
+    // being encoded, so that they can be sorted before being written out.
+    scratch: Vec<string::String>,
+    map_entries: Vec<Vec<(string::String, string::String)>>,
+    non_finite_floats: NonFiniteFloats,
+    float_format: FloatFormat,
 }
 
 impl<'a> Encoder<'a> {
     /// Creates a new JSON encoder whose output will be written to the writer
     /// specified.
     pub fn new(writer: &'a mut dyn fmt::Write) -> Encoder<'a> {
-        Encoder { writer: writer, is_emitting_map_key: false, }
+        Encoder {
+            writer: writer,
+            is_emitting_map_key: false,
+            canonical: false,
+            scratch: Vec::new(),
+            map_entries: Vec::new(),
+            non_finite_floats: NonFiniteFloats::Null,
+            float_format: FloatFormat::Shortest,
+        }
+    }
+
+    /// Creates a new JSON encoder producing canonical output: the entries of
+    /// every map are sorted by their encoded key and negative zero is written
+    /// as `0.0`, so that equal values always encode to the same string no
+    /// matter how they were built. The output has no insignificant whitespace.
+    ///
+    /// This makes the output suitable for hashing, e.g., for cache keys.
+    /// Note that sets are encoded as sequences and so keep their iteration
+    /// order; use an ordered set to get canonical output for them.
+    pub fn new_canonical(writer: &'a mut dyn fmt::Write) -> Encoder<'a> {
+        Encoder { canonical: true, ..Encoder::new(writer) }
+    }
+
+    /// Sets how NaN and infinite floats are written.
+    pub fn set_non_finite_floats(&mut self, non_finite_floats: NonFiniteFloats) {
+        self.non_finite_floats = non_finite_floats;
+    }
+
+    /// Sets how finite floats are written.
+    pub fn set_float_format(&mut self, float_format: FloatFormat) {
+        self.float_format = float_format;
+    }
+
+    fn out(&mut self) -> &mut dyn fmt::Write {
+        match self.scratch.last_mut() {
+            Some(buf) => buf,
+            None => &mut *self.writer,
+        }
     }
 }
 
 macro_rules! emit_enquoted_if_mapkey {
     ($enc:ident,$e:expr) => ({
         if $enc.is_emitting_map_key {
-            write!($enc.writer, "\"{}\"", $e)?;
+            write!($enc.out(), "\"{}\"", $e)?;
         } else {
-            write!($enc.writer, "{}", $e)?;
+            write!($enc.out(), "{}", $e)?;
         }
         Ok(())
     })
@@ -481,7 +697,7 @@ impl<'a> crate::Encoder for Encoder<'a> {
 
     fn emit_unit(&mut self) -> EncodeResult {
         if self.is_emitting_map_key { return Err(EncoderError::BadHashmapKey); }
-        write!(self.writer, "null")?;
+        write!(self.out(), "null")?;
         Ok(())
     }
 
@@ -502,25 +718,29 @@ impl<'a> crate::Encoder for Encoder<'a> {
     fn emit_bool(&mut self, v: bool) -> EncodeResult {
         if self.is_emitting_map_key { return Err(EncoderError::BadHashmapKey); }
         if v {
-            write!(self.writer, "true")?;
+            write!(self.out(), "true")?;
         } else {
-            write!(self.writer, "false")?;
+            write!(self.out(), "false")?;
         }
         Ok(())
     }
 
     fn emit_f64(&mut self, v: f64) -> EncodeResult {
-        emit_enquoted_if_mapkey!(self, fmt_number_or_null(v))
+        // `-0.0 == 0.0`, so this only normalizes the sign of zero.
+        let v = if self.canonical && v == 0.0 { 0.0 } else { v };
+        let (is_emitting_map_key, non_finite_floats, float_format) =
+            (self.is_emitting_map_key, self.non_finite_floats, self.float_format);
+        emit_float(self.out(), v, is_emitting_map_key, non_finite_floats, float_format)
     }
     fn emit_f32(&mut self, v: f32) -> EncodeResult {
         self.emit_f64(v as f64)
     }
 
     fn emit_char(&mut self, v: char) -> EncodeResult {
-        escape_char(self.writer, v)
+        escape_char(self.out(), v)
     }
     fn emit_str(&mut self, v: &str) -> EncodeResult {
-        escape_str(self.writer, v)
+        escape_str(self.out(), v)
     }
 
     fn emit_enum<F>(&mut self, _name: &str, f: F) -> EncodeResult where
@@ -540,14 +760,14 @@ impl<'a> crate::Encoder for Encoder<'a> {
         // Bunny => "Bunny"
         // Kangaroo(34,"William") => {"variant": "Kangaroo", "fields": [34,"William"]}
         if cnt == 0 {
-            escape_str(self.writer, name)
+            escape_str(self.out(), name)
         } else {
             if self.is_emitting_map_key { return Err(EncoderError::BadHashmapKey); }
-            write!(self.writer, "{{\"variant\":")?;
-            escape_str(self.writer, name)?;
-            write!(self.writer, ",\"fields\":[")?;
+            write!(self.out(), "{{\"variant\":")?;
+            escape_str(self.out(), name)?;
+            write!(self.out(), ",\"fields\":[")?;
             f(self)?;
-            write!(self.writer, "]}}")?;
+            write!(self.out(), "]}}")?;
             Ok(())
         }
     }
@@ -557,7 +777,7 @@ impl<'a> crate::Encoder for Encoder<'a> {
     {
         if self.is_emitting_map_key { return Err(EncoderError::BadHashmapKey); }
         if idx != 0 {
-            write!(self.writer, ",")?;
+            write!(self.out(), ",")?;
         }
         f(self)
     }
@@ -587,9 +807,9 @@ impl<'a> crate::Encoder for Encoder<'a> {
         F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
     {
         if self.is_emitting_map_key { return Err(EncoderError::BadHashmapKey); }
-        write!(self.writer, "{{")?;
+        write!(self.out(), "{{")?;
         f(self)?;
-        write!(self.writer, "}}")?;
+        write!(self.out(), "}}")?;
         Ok(())
     }
 
@@ -597,9 +817,9 @@ impl<'a> crate::Encoder for Encoder<'a> {
         F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
     {
         if self.is_emitting_map_key { return Err(EncoderError::BadHashmapKey); }
-        if idx != 0 { write!(self.writer, ",")?; }
-        escape_str(self.writer, name)?;
-        write!(self.writer, ":")?;
+        if idx != 0 { write!(self.out(), ",")?; }
+        escape_str(self.out(), name)?;
+        write!(self.out(), ":")?;
         f(self)
     }
 
@@ -650,9 +870,9 @@ impl<'a> crate::Encoder for Encoder<'a> {
         F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
     {
         if self.is_emitting_map_key { return Err(EncoderError::BadHashmapKey); }
-        write!(self.writer, "[")?;
+        write!(self.out(), "[")?;
         f(self)?;
-        write!(self.writer, "]")?;
+        write!(self.out(), "]")?;
         Ok(())
     }
 
@@ -661,7 +881,7 @@ impl<'a> crate::Encoder for Encoder<'a> {
     {
         if self.is_emitting_map_key { return Err(EncoderError::BadHashmapKey); }
         if idx != 0 {
-            write!(self.writer, ",")?;
+            write!(self.out(), ",")?;
         }
         f(self)
     }
@@ -670,9 +890,23 @@ impl<'a> crate::Encoder for Encoder<'a> {
         F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
     {
         if self.is_emitting_map_key { return Err(EncoderError::BadHashmapKey); }
-        write!(self.writer, "{{")?;
+        if !self.canonical {
+            write!(self.out(), "{{")?;
+            f(self)?;
+            write!(self.out(), "}}")?;
+            return Ok(());
+        }
+
+        self.map_entries.push(Vec::new());
         f(self)?;
-        write!(self.writer, "}}")?;
+        let mut entries = self.map_entries.pop().unwrap();
+        entries.sort();
+        write!(self.out(), "{{")?;
+        for (idx, (key, value)) in entries.iter().enumerate() {
+            if idx != 0 { write!(self.out(), ",")? }
+            write!(self.out(), "{}:{}", key, value)?;
+        }
+        write!(self.out(), "}}")?;
         Ok(())
     }
 
@@ -680,10 +914,18 @@ impl<'a> crate::Encoder for Encoder<'a> {
         F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
     {
         if self.is_emitting_map_key { return Err(EncoderError::BadHashmapKey); }
-        if idx != 0 { write!(self.writer, ",")? }
+        if self.canonical {
+            self.scratch.push(string::String::new());
+        } else if idx != 0 {
+            write!(self.out(), ",")?
+        }
         self.is_emitting_map_key = true;
         f(self)?;
         self.is_emitting_map_key = false;
+        if self.canonical {
+            let key = self.scratch.pop().unwrap();
+            self.map_entries.last_mut().unwrap().push((key, string::String::new()));
+        }
         Ok(())
     }
 
@@ -691,8 +933,16 @@ impl<'a> crate::Encoder for Encoder<'a> {
         F: FnOnce(&mut Encoder<'a>) -> EncodeResult,
     {
         if self.is_emitting_map_key { return Err(EncoderError::BadHashmapKey); }
-        write!(self.writer, ":")?;
-        f(self)
+        if !self.canonical {
+            write!(self.out(), ":")?;
+            return f(self);
+        }
+
+        self.scratch.push(string::String::new());
+        f(self)?;
+        let value = self.scratch.pop().unwrap();
+        self.map_entries.last_mut().unwrap().last_mut().unwrap().1 = value;
+        Ok(())
     }
 }
 
@@ -703,6 +953,8 @@ pub struct PrettyEncoder<'a> {
     curr_indent: usize,
     indent: usize,
     is_emitting_map_key: bool,
+    non_finite_floats: NonFiniteFloats,
+    float_format: FloatFormat,
 }
 
 impl<'a> PrettyEncoder<'a> {
@@ -713,6 +965,8 @@ impl<'a> PrettyEncoder<'a> {
             curr_indent: 0,
             indent: 2,
             is_emitting_map_key: false,
+            non_finite_floats: NonFiniteFloats::Null,
+            float_format: FloatFormat::Shortest,
         }
     }
 
@@ -724,6 +978,20 @@ impl<'a> PrettyEncoder<'a> {
         self.indent = indent;
         self.curr_indent = level * self.indent;
     }
+
+    /// Sets how NaN and infinite floats are written.
+    pub fn set_non_finite_floats(&mut self, non_finite_floats: NonFiniteFloats) {
+        self.non_finite_floats = non_finite_floats;
+    }
+
+    /// Sets how finite floats are written.
+    pub fn set_float_format(&mut self, float_format: FloatFormat) {
+        self.float_format = float_format;
+    }
+
+    fn out(&mut self) -> &mut dyn fmt::Write {
+        &mut *self.writer
+    }
 }
 
 impl<'a> crate::Encoder for PrettyEncoder<'a> {
@@ -760,7 +1028,8 @@ impl<'a> crate::Encoder for PrettyEncoder<'a> {
     }
 
     fn emit_f64(&mut self, v: f64) -> EncodeResult {
-        emit_enquoted_if_mapkey!(self, fmt_number_or_null(v))
+        emit_float(self.writer, v, self.is_emitting_map_key,
+                   self.non_finite_floats, self.float_format)
     }
     fn emit_f32(&mut self, v: f32) -> EncodeResult {
         self.emit_f64(v as f64)
@@ -1146,21 +1415,21 @@ impl Json {
         }
     }
 
-    /// If the Json value is a number, returns or cast it to a `i64`;
+    /// If the Json value is an integer that fits in an `i64`, returns it;
     /// returns `None` otherwise.
     pub fn as_i64(&self) -> Option<i64> {
         match *self {
             Json::I64(n) => Some(n),
-            Json::U64(n) => Some(n as i64),
+            Json::U64(n) if n <= i64::max_value() as u64 => Some(n as i64),
             _ => None
         }
     }
 
-    /// If the Json value is a number, returns or cast it to a `u64`;
+    /// If the Json value is an integer that fits in a `u64`, returns it;
     /// returns `None` otherwise.
     pub fn as_u64(&self) -> Option<u64> {
         match *self {
-            Json::I64(n) => Some(n as u64),
+            Json::I64(n) if n >= 0 => Some(n as u64),
             Json::U64(n) => Some(n),
             _ => None
         }
@@ -1403,6 +1672,10 @@ pub struct Parser<T> {
     ch: Option<char>,
     line: usize,
     col: usize,
+    // The byte offset of `ch`.
+    offset: usize,
+    options: ParserOptions,
+    error_detail: Option<SyntaxErrorDetail>,
     // We maintain a stack representing where we are in the logical structure
     // of the JSON stream.
     stack: Stack,
@@ -1419,13 +1692,16 @@ impl<T: Iterator<Item=char>> Iterator for Parser<T> {
         }
 
         if self.state == ParseBeforeFinish {
-            self.parse_whitespace();
+            if let Err(e) = self.parse_whitespace() {
+                self.state = ParseFinished;
+                return Some(Error(e));
+            }
             // Make sure there is no trailing characters.
             if self.eof() {
                 self.state = ParseFinished;
                 return None;
             } else {
-                return Some(self.error_event(TrailingCharacters));
+                return Some(self.error_event(TrailingCharacters, EXPECTED_EOF));
             }
         }
 
@@ -1436,11 +1712,19 @@ impl<T: Iterator<Item=char>> Iterator for Parser<T> {
 impl<T: Iterator<Item=char>> Parser<T> {
     /// Creates the JSON parser.
     pub fn new(rdr: T) -> Parser<T> {
+        Parser::with_options(rdr, ParserOptions::default())
+    }
+
+    /// Creates a JSON parser accepting the extensions enabled in `options`.
+    pub fn with_options(rdr: T, options: ParserOptions) -> Parser<T> {
         let mut p = Parser {
             rdr,
-            ch: Some('\x00'),
+            ch: None,
             line: 1,
             col: 0,
+            offset: 0,
+            options,
+            error_detail: None,
             stack: Stack::new(),
             state: ParseStart,
         };
@@ -1454,9 +1738,18 @@ impl<T: Iterator<Item=char>> Parser<T> {
         &self.stack
     }
 
+    /// Returns the byte offset, the expected tokens and the rest of the
+    /// context of the last `SyntaxError` returned by the parser.
+    pub fn error_detail(&self) -> Option<&SyntaxErrorDetail> {
+        self.error_detail.as_ref()
+    }
+
     fn eof(&self) -> bool { self.ch.is_none() }
     fn ch_or_null(&self) -> char { self.ch.unwrap_or('\x00') }
     fn bump(&mut self) {
+        if let Some(c) = self.ch {
+            self.offset += c.len_utf8();
+        }
         self.ch = self.rdr.next();
 
         if self.ch_is('\n') {
@@ -1475,15 +1768,59 @@ impl<T: Iterator<Item=char>> Parser<T> {
         self.ch == Some(c)
     }
 
-    fn error<U>(&self, reason: ErrorCode) -> Result<U, ParserError> {
-        Err(SyntaxError(reason, self.line, self.col))
+    fn syntax_error(&mut self, reason: ErrorCode,
+                    expected: &'static [&'static str]) -> ParserError {
+        self.error_detail = Some(SyntaxErrorDetail {
+            code: reason,
+            line: self.line,
+            col: self.col,
+            offset: self.offset,
+            expected,
+        });
+        SyntaxError(reason, self.line, self.col)
     }
 
-    fn parse_whitespace(&mut self) {
-        while self.ch_is(' ') ||
-              self.ch_is('\n') ||
-              self.ch_is('\t') ||
-              self.ch_is('\r') { self.bump(); }
+    fn error<U>(&mut self, reason: ErrorCode,
+                expected: &'static [&'static str]) -> Result<U, ParserError> {
+        Err(self.syntax_error(reason, expected))
+    }
+
+    fn parse_whitespace(&mut self) -> Result<(), ParserError> {
+        loop {
+            match self.ch {
+                Some(' ') | Some('\n') | Some('\t') | Some('\r') => self.bump(),
+                Some('/') if self.options.comments => self.parse_comment()?,
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn parse_comment(&mut self) -> Result<(), ParserError> {
+        match self.next_char() {
+            Some('/') => {
+                while !self.eof() && !self.ch_is('\n') {
+                    self.bump();
+                }
+                Ok(())
+            }
+            Some('*') => {
+                self.bump();
+                loop {
+                    match self.ch {
+                        Some('*') => {
+                            self.bump();
+                            if self.ch_is('/') {
+                                self.bump();
+                                return Ok(());
+                            }
+                        }
+                        Some(_) => self.bump(),
+                        None => return self.error(InvalidSyntax, &["`*/`"]),
+                    }
+                }
+            }
+            _ => self.error(InvalidSyntax, &["`/`", "`*`"]),
+        }
     }
 
     fn parse_number(&mut self) -> JsonEvent {
@@ -1526,7 +1863,7 @@ impl<T: Iterator<Item=char>> Parser<T> {
 
             // Make sure we didn't underflow.
             if res > 0 {
-                Error(SyntaxError(InvalidNumber, self.line, self.col))
+                Error(self.syntax_error(InvalidNumber, &[]))
             } else {
                 I64Value(res)
             }
@@ -1537,7 +1874,6 @@ impl<T: Iterator<Item=char>> Parser<T> {
 
     fn parse_u64(&mut self) -> Result<u64, ParserError> {
         let mut accum = 0u64;
-        let last_accum = 0; // necessary to detect overflow.
 
         match self.ch_or_null() {
             '0' => {
@@ -1545,18 +1881,19 @@ impl<T: Iterator<Item=char>> Parser<T> {
 
                 // A leading '0' must be the only digit before the decimal point.
                 if let '0' ..= '9' = self.ch_or_null() {
-                    return self.error(InvalidNumber)
+                    return self.error(InvalidNumber, &[])
                 }
             },
             '1' ..= '9' => {
                 while !self.eof() {
                     match self.ch_or_null() {
                         c @ '0' ..= '9' => {
-                            accum = accum.wrapping_mul(10);
-                            accum = accum.wrapping_add((c as u64) - ('0' as u64));
-
-                            // Detect overflow by comparing to the last value.
-                            if accum <= last_accum { return self.error(InvalidNumber); }
+                            let digit = (c as u64) - ('0' as u64);
+                            accum = match accum.checked_mul(10)
+                                               .and_then(|accum| accum.checked_add(digit)) {
+                                Some(accum) => accum,
+                                None => return self.error(InvalidNumber, &[]),
+                            };
 
                             self.bump();
                         }
@@ -1564,7 +1901,7 @@ impl<T: Iterator<Item=char>> Parser<T> {
                     }
                 }
             }
-            _ => return self.error(InvalidNumber),
+            _ => return self.error(InvalidNumber, EXPECTED_DIGIT),
         }
 
         Ok(accum)
@@ -1576,7 +1913,7 @@ impl<T: Iterator<Item=char>> Parser<T> {
         // Make sure a digit follows the decimal place.
         match self.ch_or_null() {
             '0' ..= '9' => (),
-             _ => return self.error(InvalidNumber)
+             _ => return self.error(InvalidNumber, EXPECTED_DIGIT)
         }
 
         let mut dec = 1.0;
@@ -1610,7 +1947,7 @@ impl<T: Iterator<Item=char>> Parser<T> {
         // Make sure a digit follows the exponent place.
         match self.ch_or_null() {
             '0' ..= '9' => (),
-            _ => return self.error(InvalidNumber)
+            _ => return self.error(InvalidNumber, EXPECTED_DIGIT)
         }
         while !self.eof() {
             match self.ch_or_null() {
@@ -1647,7 +1984,7 @@ impl<T: Iterator<Item=char>> Parser<T> {
                 'd' | 'D' => n * 16 + 13,
                 'e' | 'E' => n * 16 + 14,
                 'f' | 'F' => n * 16 + 15,
-                _ => return self.error(InvalidEscape)
+                _ => return self.error(InvalidEscape, EXPECTED_HEX_DIGIT)
             };
 
             i += 1;
@@ -1655,7 +1992,7 @@ impl<T: Iterator<Item=char>> Parser<T> {
 
         // Error out if we didn't parse 4 digits.
         if i != 4 {
-            return self.error(InvalidEscape);
+            return self.error(InvalidEscape, EXPECTED_HEX_DIGIT);
         }
 
         Ok(n)
@@ -1668,7 +2005,7 @@ impl<T: Iterator<Item=char>> Parser<T> {
         loop {
             self.bump();
             if self.eof() {
-                return self.error(EOFWhileParsingString);
+                return self.error(EOFWhileParsingString, &["`\"`"]);
             }
 
             if escape {
@@ -1683,7 +2020,7 @@ impl<T: Iterator<Item=char>> Parser<T> {
                     't' => res.push('\t'),
                     'u' => match self.decode_hex_escape()? {
                         0xDC00 ..= 0xDFFF => {
-                            return self.error(LoneLeadingSurrogateInHexEscape)
+                            return self.error(LoneLeadingSurrogateInHexEscape, &[])
                         }
 
                         // Non-BMP characters are encoded as a sequence of
@@ -1691,12 +2028,12 @@ impl<T: Iterator<Item=char>> Parser<T> {
                         n1 @ 0xD800 ..= 0xDBFF => {
                             match (self.next_char(), self.next_char()) {
                                 (Some('\\'), Some('u')) => (),
-                                _ => return self.error(UnexpectedEndOfHexEscape),
+                                _ => return self.error(UnexpectedEndOfHexEscape, &["`\\u`"]),
                             }
 
                             let n2 = self.decode_hex_escape()?;
                             if n2 < 0xDC00 || n2 > 0xDFFF {
-                                return self.error(LoneLeadingSurrogateInHexEscape)
+                                return self.error(LoneLeadingSurrogateInHexEscape, &[])
                             }
                             let c = (((n1 - 0xD800) as u32) << 10 |
                                      (n2 - 0xDC00) as u32) + 0x1_0000;
@@ -1705,10 +2042,10 @@ impl<T: Iterator<Item=char>> Parser<T> {
 
                         n => match char::from_u32(n as u32) {
                             Some(c) => res.push(c),
-                            None => return self.error(InvalidUnicodeCodePoint),
+                            None => return self.error(InvalidUnicodeCodePoint, &[]),
                         },
                     },
-                    _ => return self.error(InvalidEscape),
+                    _ => return self.error(InvalidEscape, &["escape sequence"]),
                 }
                 escape = false;
             } else if self.ch_is('\\') {
@@ -1740,7 +2077,10 @@ impl<T: Iterator<Item=char>> Parser<T> {
             // ParseArray(false) and ParseObject(false), which always return,
             // so there is no risk of getting stuck in an infinite loop.
             // All other paths return before the end of the loop's iteration.
-            self.parse_whitespace();
+            if let Err(e) = self.parse_whitespace() {
+                self.state = ParseFinished;
+                return Error(e);
+            }
 
             match self.state {
                 ParseStart => {
@@ -1767,7 +2107,7 @@ impl<T: Iterator<Item=char>> Parser<T> {
                     }
                 }
                 _ => {
-                    return self.error_event(InvalidSyntax);
+                    return self.error_event(InvalidSyntax, &[]);
                 }
             }
         }
@@ -1786,9 +2126,12 @@ impl<T: Iterator<Item=char>> Parser<T> {
 
     fn parse_array(&mut self, first: bool) -> JsonEvent {
         if self.ch_is(']') {
-            if !first {
-                self.error_event(InvalidSyntax)
+            if !first && !self.options.trailing_commas {
+                self.error_event(InvalidSyntax, EXPECTED_VALUE)
             } else {
+                if !first {
+                    self.stack.pop();
+                }
                 self.state = if self.stack.is_empty() {
                     ParseBeforeFinish
                 } else if self.stack.last_is_index() {
@@ -1832,20 +2175,21 @@ impl<T: Iterator<Item=char>> Parser<T> {
             self.bump();
             Some(ArrayEnd)
         } else if self.eof() {
-            Some(self.error_event(EOFWhileParsingArray))
+            Some(self.error_event(EOFWhileParsingArray, EXPECTED_ARRAY_COMMA))
         } else {
-            Some(self.error_event(InvalidSyntax))
+            Some(self.error_event(InvalidSyntax, EXPECTED_ARRAY_COMMA))
         }
     }
 
     fn parse_object(&mut self, first: bool) -> JsonEvent {
+        let expected = if first || self.options.trailing_commas {
+            EXPECTED_KEY_OR_END
+        } else {
+            EXPECTED_KEY
+        };
         if self.ch_is('}') {
-            if !first {
-                if self.stack.is_empty() {
-                    return self.error_event(TrailingComma);
-                } else {
-                    self.stack.pop();
-                }
+            if !first && !self.options.trailing_commas {
+                return self.error_event(TrailingComma, expected);
             }
             self.state = if self.stack.is_empty() {
                 ParseBeforeFinish
@@ -1858,10 +2202,10 @@ impl<T: Iterator<Item=char>> Parser<T> {
             return ObjectEnd;
         }
         if self.eof() {
-            return self.error_event(EOFWhileParsingObject);
+            return self.error_event(EOFWhileParsingObject, expected);
         }
         if !self.ch_is('"') {
-            return self.error_event(KeyMustBeAString);
+            return self.error_event(KeyMustBeAString, expected);
         }
         let s = match self.parse_str() {
             Ok(s) => s,
@@ -1870,15 +2214,21 @@ impl<T: Iterator<Item=char>> Parser<T> {
                 return Error(e);
             }
         };
-        self.parse_whitespace();
+        if let Err(e) = self.parse_whitespace() {
+            self.state = ParseFinished;
+            return Error(e);
+        }
         if self.eof() {
-            return self.error_event(EOFWhileParsingObject);
+            return self.error_event(EOFWhileParsingObject, EXPECTED_COLON);
         } else if self.ch_or_null() != ':' {
-            return self.error_event(ExpectedColon);
+            return self.error_event(ExpectedColon, EXPECTED_COLON);
         }
         self.stack.push_key(s);
         self.bump();
-        self.parse_whitespace();
+        if let Err(e) = self.parse_whitespace() {
+            self.state = ParseFinished;
+            return Error(e);
+        }
 
         let val = self.parse_value();
 
@@ -1903,18 +2253,18 @@ impl<T: Iterator<Item=char>> Parser<T> {
             self.bump();
             ObjectEnd
         } else if self.eof() {
-            self.error_event(EOFWhileParsingObject)
+            self.error_event(EOFWhileParsingObject, EXPECTED_OBJECT_COMMA)
         } else {
-            self.error_event(InvalidSyntax)
+            self.error_event(InvalidSyntax, EXPECTED_OBJECT_COMMA)
         }
     }
 
     fn parse_value(&mut self) -> JsonEvent {
-        if self.eof() { return self.error_event(EOFWhileParsingValue); }
+        if self.eof() { return self.error_event(EOFWhileParsingValue, EXPECTED_VALUE); }
         match self.ch_or_null() {
-            'n' => { self.parse_ident("ull", NullValue) }
-            't' => { self.parse_ident("rue", BooleanValue(true)) }
-            'f' => { self.parse_ident("alse", BooleanValue(false)) }
+            'n' => { self.parse_ident("ull", &["`null`"], NullValue) }
+            't' => { self.parse_ident("rue", &["`true`"], BooleanValue(true)) }
+            'f' => { self.parse_ident("alse", &["`false`"], BooleanValue(false)) }
             '0' ..= '9' | '-' => self.parse_number(),
             '"' => match self.parse_str() {
                 Ok(s) => StringValue(s),
@@ -1928,22 +2278,24 @@ impl<T: Iterator<Item=char>> Parser<T> {
                 self.bump();
                 ObjectStart
             }
-            _ => { self.error_event(InvalidSyntax) }
+            _ => { self.error_event(InvalidSyntax, EXPECTED_VALUE) }
         }
     }
 
-    fn parse_ident(&mut self, ident: &str, value: JsonEvent) -> JsonEvent {
+    fn parse_ident(&mut self, ident: &str, expected: &'static [&'static str],
+                   value: JsonEvent) -> JsonEvent {
         if ident.chars().all(|c| Some(c) == self.next_char()) {
             self.bump();
             value
         } else {
-            Error(SyntaxError(InvalidSyntax, self.line, self.col))
+            Error(self.syntax_error(InvalidSyntax, expected))
         }
     }
 
-    fn error_event(&mut self, reason: ErrorCode) -> JsonEvent {
+    fn error_event(&mut self, reason: ErrorCode,
+                   expected: &'static [&'static str]) -> JsonEvent {
         self.state = ParseFinished;
-        Error(SyntaxError(reason, self.line, self.col))
+        Error(self.syntax_error(reason, expected))
     }
 }
 
@@ -1959,6 +2311,17 @@ impl<T: Iterator<Item=char>> Builder<T> {
         Builder { parser: Parser::new(src), token: None, }
     }
 
+    /// Creates a JSON Builder accepting the extensions enabled in `options`.
+    pub fn with_options(src: T, options: ParserOptions) -> Builder<T> {
+        Builder { parser: Parser::with_options(src, options), token: None, }
+    }
+
+    /// Returns the context of the last `SyntaxError` returned by `build`, see
+    /// `Parser::error_detail`.
+    pub fn error_detail(&self) -> Option<&SyntaxErrorDetail> {
+        self.parser.error_detail()
+    }
+
     // Decode a Json value from a Parser.
     pub fn build(&mut self) -> Result<Json, BuilderError> {
         self.bump();
@@ -1991,9 +2354,9 @@ impl<T: Iterator<Item=char>> Builder<T> {
             Some(Error(ref e)) => Err(e.clone()),
             Some(ArrayStart) => self.build_array(),
             Some(ObjectStart) => self.build_object(),
-            Some(ObjectEnd) => self.parser.error(InvalidSyntax),
-            Some(ArrayEnd) => self.parser.error(InvalidSyntax),
-            None => self.parser.error(EOFWhileParsingValue),
+            Some(ObjectEnd) => self.parser.error(InvalidSyntax, EXPECTED_VALUE),
+            Some(ArrayEnd) => self.parser.error(InvalidSyntax, EXPECTED_VALUE),
+            None => self.parser.error(EOFWhileParsingValue, EXPECTED_VALUE),
         }
     }
 
@@ -2035,7 +2398,7 @@ impl<T: Iterator<Item=char>> Builder<T> {
             }
             self.bump();
         }
-        self.parser.error(EOFWhileParsingObject)
+        self.parser.error(EOFWhileParsingObject, EXPECTED_OBJECT_COMMA)
     }
 }
 
@@ -2060,6 +2423,24 @@ pub fn from_str(s: &str) -> Result<Json, BuilderError> {
     builder.build()
 }
 
+/// Decodes a json value from a string, accepting the extensions enabled in
+/// `options`.
+///
+/// ```
+/// # #![feature(rustc_private)]
+/// extern crate serialize as rustc_serialize;
+/// use rustc_serialize::json::{self, ParserOptions};
+///
+/// let config = "{\n    // The port.\n    \"port\": 80,\n}";
+/// assert!(json::from_str(config).is_err());
+/// let config = json::from_str_with_options(config, ParserOptions::lenient()).unwrap();
+/// assert_eq!(config["port"].as_u64(), Some(80));
+/// ```
+pub fn from_str_with_options(s: &str, options: ParserOptions) -> Result<Json, BuilderError> {
+    let mut builder = Builder::with_options(s.chars(), options);
+    builder.build()
+}
+
 /// A structure to decode JSON to values in rust.
 pub struct Decoder {
     stack: Vec<Json>,
@@ -2096,11 +2477,30 @@ macro_rules! expect {
 }
 
 macro_rules! read_primitive {
-    ($name:ident, $ty:ty) => {
+    ($name:ident, $ty:ident) => {
         fn $name(&mut self) -> DecodeResult<$ty> {
+            // Integers that don't fit in the target type are an error rather
+            // than being silently truncated.
             match self.pop() {
-                Json::I64(f) => Ok(f as $ty),
-                Json::U64(f) => Ok(f as $ty),
+                Json::I64(f) => {
+                    let fits = if f < 0 {
+                        f as i128 >= $ty::min_value() as i128
+                    } else {
+                        f as u128 <= $ty::max_value() as u128
+                    };
+                    if fits {
+                        Ok(f as $ty)
+                    } else {
+                        Err(ExpectedError(stringify!($ty).to_owned(), f.to_string()))
+                    }
+                }
+                Json::U64(f) => {
+                    if f as u128 <= $ty::max_value() as u128 {
+                        Ok(f as $ty)
+                    } else {
+                        Err(ExpectedError(stringify!($ty).to_owned(), f.to_string()))
+                    }
+                }
                 Json::F64(f) => Err(ExpectedError("Integer".to_owned(), f.to_string())),
                 // re: #12967.. a type w/ numeric keys (ie HashMap<usize, V> etc)
                 // is going to have a string here, as per JSON spec.
@@ -2371,11 +2771,53 @@ impl crate::Decoder for Decoder {
 }
 
 /// A trait for converting values to JSON
+///
+/// It can be derived with `#[derive(ToJson)]` behind `#![feature(derive_to_json)]`, which
+/// lays values out like `encode` does.
 pub trait ToJson {
     /// Converts the value of `self` to an instance of JSON
     fn to_json(&self) -> Json;
 }
 
+/// Builds a `Json` value from JSON-like syntax.
+///
+/// Arrays are written `[a, b, ...]` and objects `{key: value, ...}`, where a key is any
+/// expression implementing `ToString`. `null` is `Json::Null`, and any other value is converted
+/// with `ToJson`. Keys and values that aren't literals, arrays or objects must be a single token
+/// tree, so wrap longer expressions, including negative numbers, in parentheses.
+///
+/// ```rust
+/// # #![feature(rustc_private)]
+/// extern crate serialize;
+/// use serialize::json;
+///
+/// # fn main() {
+/// let name = "madoka";
+/// let value = json!({"name": name, "ids": [1, 2, (-3)], "parent": null});
+/// assert_eq!(value.to_string(), r#"{"ids":[1,2,-3],"name":"madoka","parent":null}"#);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! json {
+    (null) => {
+        $crate::json::Json::Null
+    };
+    ([$($value:tt),* $(,)*]) => {
+        $crate::json::Json::Array(vec![$($crate::json!($value)),*])
+    };
+    ({$($key:tt : $value:tt),* $(,)*}) => {{
+        let mut object = $crate::json::Object::new();
+        $(
+            object.insert(::std::string::ToString::to_string(&$key), $crate::json!($value));
+        )*
+        $crate::json::Json::Object(object)
+    }};
+    ($value:expr) => {{
+        use $crate::json::ToJson;
+        ($value).to_json()
+    }};
+}
+
 macro_rules! to_json_impl_i64 {
     ($($t:ty), +) => (
         $(impl ToJson for $t {
@@ -10,11 +10,30 @@ pub type EncodeResult = Result<(), !>;
 
 pub struct Encoder {
     pub data: Vec<u8>,
+    canonical: bool,
+    // In canonical mode, the start positions of the entries of the maps
+    // currently being encoded, innermost last.
+    map_entries: Vec<Vec<usize>>,
 }
 
 impl Encoder {
     pub fn new(data: Vec<u8>) -> Encoder {
-        Encoder { data }
+        Encoder {
+            data,
+            canonical: false,
+            map_entries: Vec::new(),
+        }
+    }
+
+    /// Creates an encoder producing canonical output: the entries of every
+    /// map are sorted by their encoding, and floats have a single encoding for
+    /// zero and for NaN, so that equal values always encode to the same bytes.
+    ///
+    /// Sorting moves the encoded entries around after the fact, so this must
+    /// not be used with encoders that record positions in the output, such as
+    /// those writing type shorthands.
+    pub fn new_canonical(data: Vec<u8>) -> Encoder {
+        Encoder { canonical: true, ..Encoder::new(data) }
     }
 
     pub fn into_inner(self) -> Vec<u8> {
@@ -122,12 +141,22 @@ impl serialize::Encoder for Encoder {
 
     #[inline]
     fn emit_f64(&mut self, v: f64) -> EncodeResult {
+        let v = if self.canonical {
+            if v.is_nan() { ::std::f64::NAN } else if v == 0.0 { 0.0 } else { v }
+        } else {
+            v
+        };
         let as_u64: u64 = unsafe { ::std::mem::transmute(v) };
         self.emit_u64(as_u64)
     }
 
     #[inline]
     fn emit_f32(&mut self, v: f32) -> EncodeResult {
+        let v = if self.canonical {
+            if v.is_nan() { ::std::f32::NAN } else if v == 0.0 { 0.0 } else { v }
+        } else {
+            v
+        };
         let as_u32: u32 = unsafe { ::std::mem::transmute(v) };
         self.emit_u32(as_u32)
     }
@@ -143,6 +172,43 @@ impl serialize::Encoder for Encoder {
         self.emit_raw_bytes(v.as_bytes());
         Ok(())
     }
+
+    fn emit_map<F>(&mut self, len: usize, f: F) -> EncodeResult
+        where F: FnOnce(&mut Self) -> EncodeResult
+    {
+        self.emit_usize(len)?;
+        if !self.canonical {
+            return f(self);
+        }
+
+        let start = self.data.len();
+        self.map_entries.push(Vec::new());
+        f(self)?;
+        let starts = self.map_entries.pop().unwrap();
+
+        // Keys are unique, so sorting by the encoding of whole entries is the
+        // same as sorting by the encoding of their keys.
+        let encoded = self.data.split_off(start);
+        let mut entries: Vec<&[u8]> = starts.iter().enumerate().map(|(idx, &entry_start)| {
+            let entry_end = starts.get(idx + 1).cloned().unwrap_or(start + encoded.len());
+            &encoded[entry_start - start..entry_end - start]
+        }).collect();
+        entries.sort();
+        for entry in entries {
+            self.data.extend_from_slice(entry);
+        }
+        Ok(())
+    }
+
+    fn emit_map_elt_key<F>(&mut self, _idx: usize, f: F) -> EncodeResult
+        where F: FnOnce(&mut Self) -> EncodeResult
+    {
+        if self.canonical {
+            let position = self.data.len();
+            self.map_entries.last_mut().unwrap().push(position);
+        }
+        f(self)
+    }
 }
 
 impl Encoder {
@@ -324,3 +390,20 @@ impl<'a> serialize::Decoder for Decoder<'a> {
         err.to_string()
     }
 }
+
+impl<'a> serialize::BorrowDecoder<'a> for Decoder<'a> {
+    #[inline]
+    fn read_str_borrowed(&mut self) -> Result<&'a str, Self::Error> {
+        let bytes = self.read_bytes_borrowed()?;
+        ::std::str::from_utf8(bytes).map_err(|err| err.to_string())
+    }
+
+    #[inline]
+    fn read_bytes_borrowed(&mut self) -> Result<&'a [u8], Self::Error> {
+        let len = self.read_usize()?;
+        let data = self.data;
+        let bytes = &data[self.position..self.position + len];
+        self.position += len;
+        Ok(bytes)
+    }
+}
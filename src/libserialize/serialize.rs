@@ -296,6 +296,20 @@ pub trait Decoder {
     fn error(&mut self, err: &str) -> Self::Error;
 }
 
+/// A decoder reading from an in-memory buffer, which can return strings and
+/// byte sequences that borrow from that buffer instead of copying them.
+///
+/// The string returned by `Decoder::read_str` can at most borrow from the
+/// decoder, so it has to be copied to outlive the call. The methods of this
+/// trait return data that lives as long as the buffer, `'de`.
+pub trait BorrowDecoder<'de>: Decoder {
+    /// Reads a string, as encoded by `Encoder::emit_str`.
+    fn read_str_borrowed(&mut self) -> Result<&'de str, Self::Error>;
+
+    /// Reads a sequence of bytes, as encoded for `[u8]` and `Vec<u8>`.
+    fn read_bytes_borrowed(&mut self) -> Result<&'de [u8], Self::Error>;
+}
+
 pub trait Encodable {
     fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error>;
 }
@@ -1,3 +1,5 @@
+#![feature(derive_to_json)]
+
 extern crate serialize as rustc_serialize;
 
 use rustc_serialize::{Encodable, Decodable};
@@ -8,7 +10,7 @@ use json::ParserError::*;
 use json::DecoderError::*;
 use json::JsonEvent::*;
 use json::{Json, from_str, DecodeResult, DecoderError, JsonEvent, Parser, StackElement,
-           Decoder, Encoder, EncoderError};
+           Decoder, Encoder, EncoderError, Builder, ParserOptions, SyntaxErrorDetail};
 
 use Animal::*;
 use std::{i64, u64, f32, f64};
@@ -353,6 +355,8 @@ fn test_read_number() {
     assert_eq!(from_str("1e+"), Err(SyntaxError(InvalidNumber, 1, 4)));
 
     assert_eq!(from_str("18446744073709551616"), Err(SyntaxError(InvalidNumber, 1, 20)));
+    assert_eq!(from_str("18446744073709551617"), Err(SyntaxError(InvalidNumber, 1, 20)));
+    assert_eq!(from_str("99999999999999999999"), Err(SyntaxError(InvalidNumber, 1, 20)));
     assert_eq!(from_str("-9223372036854775809"), Err(SyntaxError(InvalidNumber, 1, 21)));
 
     assert_eq!(from_str("3"), Ok(U64(3)));
@@ -407,6 +411,28 @@ fn test_decode_numbers() {
     let res: DecodeResult<i64> = json::decode("765.25");
     assert_eq!(res, Err(ExpectedError("Integer".to_string(),
                                       "765.25".to_string())));
+
+    // Integers beyond the range of the target type are not truncated.
+    let v: u64 = json::decode("9007199254740993").unwrap();
+    assert_eq!(v, 9007199254740993);
+
+    let res: DecodeResult<i64> = json::decode("9223372036854775808");
+    assert_eq!(res, Err(ExpectedError("i64".to_string(),
+                                      "9223372036854775808".to_string())));
+
+    let res: DecodeResult<u64> = json::decode("-1");
+    assert_eq!(res, Err(ExpectedError("u64".to_string(), "-1".to_string())));
+
+    let res: DecodeResult<u8> = json::decode("256");
+    assert_eq!(res, Err(ExpectedError("u8".to_string(), "256".to_string())));
+
+    let res: DecodeResult<i8> = json::decode("-129");
+    assert_eq!(res, Err(ExpectedError("i8".to_string(), "-129".to_string())));
+
+    let v: i128 = json::decode("-9223372036854775808").unwrap();
+    assert_eq!(v, i64::MIN as i128);
+    let v: u128 = json::decode("18446744073709551615").unwrap();
+    assert_eq!(v, u64::MAX as u128);
 }
 
 #[test]
@@ -625,6 +651,60 @@ fn test_multiline_errors() {
         Err(SyntaxError(EOFWhileParsingObject, 3, 8)));
 }
 
+#[test]
+fn test_error_detail() {
+    let mut builder = Builder::new("{\n  \"foo\":\n \"bar\"".chars());
+    assert_eq!(builder.build(), Err(SyntaxError(EOFWhileParsingObject, 3, 8)));
+    assert_eq!(builder.error_detail(), Some(&SyntaxErrorDetail {
+        code: EOFWhileParsingObject,
+        line: 3,
+        col: 8,
+        offset: 17,
+        expected: &["`,`", "`}`"],
+    }));
+
+    let mut builder = Builder::new("[\"é\" 1]".chars());
+    assert_eq!(builder.build(), Err(SyntaxError(InvalidSyntax, 1, 6)));
+    let detail = builder.error_detail().unwrap();
+    assert_eq!(detail.offset, 6);
+    assert_eq!(detail.to_string(), "invalid syntax at line 1 column 6: expected `,` or `]`");
+
+    let mut builder = Builder::new("-9223372036854775809".chars());
+    assert!(builder.build().is_err());
+    assert_eq!(builder.error_detail().unwrap().expected, &[] as &[&str]);
+    assert_eq!(builder.error_detail().unwrap().to_string(),
+               "invalid number at line 1 column 21");
+}
+
+#[test]
+fn test_parser_options() {
+    let lenient = ParserOptions::lenient();
+    let trailing_commas = ParserOptions { trailing_commas: true, ..ParserOptions::default() };
+
+    assert_eq!(from_str("[1, 2,]"), Err(SyntaxError(InvalidSyntax, 1, 7)));
+    assert_eq!(json::from_str_with_options("[1, 2,]", trailing_commas),
+               Ok(Array(vec![U64(1), U64(2)])));
+    assert_eq!(json::from_str_with_options("{\"a\": [{\"b\": 1,},], \"c\": [],}",
+                                           trailing_commas),
+               from_str("{\"a\": [{\"b\": 1}], \"c\": []}"));
+    assert_eq!(json::from_str_with_options("[,]", trailing_commas),
+               Err(SyntaxError(InvalidSyntax, 1, 2)));
+    assert_eq!(json::from_str_with_options("[1] // c", trailing_commas),
+               Err(SyntaxError(TrailingCharacters, 1, 5)));
+
+    assert_eq!(json::from_str_with_options("// head\n[1, // one\n 2 /* two */] /**/", lenient),
+               Ok(Array(vec![U64(1), U64(2)])));
+    assert_eq!(json::from_str_with_options("{/* a */\"a\"/**/:/***/1}", lenient),
+               from_str("{\"a\": 1}"));
+
+    let mut builder = Builder::with_options("/x".chars(), lenient);
+    assert_eq!(builder.build(), Err(SyntaxError(InvalidSyntax, 1, 2)));
+    assert_eq!(builder.error_detail().unwrap().expected, &["`/`", "`*`"]);
+    let mut builder = Builder::with_options("[1 /* open *".chars(), lenient);
+    assert_eq!(builder.build(), Err(SyntaxError(InvalidSyntax, 1, 13)));
+    assert_eq!(builder.error_detail().unwrap().expected, &["`*/`"]);
+}
+
 #[derive(RustcDecodable)]
 #[allow(dead_code)]
 struct DecodeStruct {
@@ -802,6 +882,9 @@ fn test_as_i64(){
     let json_value = from_str("-12").unwrap();
     let json_num = json_value.as_i64();
     assert_eq!(json_num, Some(-12));
+
+    let json_value = from_str("18446744073709551615").unwrap();
+    assert_eq!(json_value.as_i64(), None);
 }
 
 #[test]
@@ -809,6 +892,9 @@ fn test_as_u64(){
     let json_value = from_str("12").unwrap();
     let json_num = json_value.as_u64();
     assert_eq!(json_num, Some(12));
+
+    let json_value = from_str("-12").unwrap();
+    assert_eq!(json_value.as_u64(), None);
 }
 
 #[test]
@@ -1280,3 +1366,166 @@ fn test_encode_hashmap_with_arbitrary_key() {
         _ => panic!("expected bad hash map key")
     }
 }
+
+#[test]
+fn test_encode_canonical() {
+    use std::collections::HashMap;
+
+    let mut a = HashMap::new();
+    let mut b = HashMap::new();
+    for i in 0..50 {
+        a.insert(i.to_string(), vec![i as f64 / 2.0]);
+        b.insert((49 - i).to_string(), vec![(49 - i) as f64 / 2.0]);
+    }
+    let encoded = json::encode_canonical(&a).unwrap();
+    assert_eq!(encoded, json::encode_canonical(&b).unwrap());
+    assert!(encoded.starts_with("{\"0\":[0.0],\"1\":[0.5],\"10\":[5.0],"));
+
+    let decoded: HashMap<String, Vec<f64>> = json::decode(&encoded).unwrap();
+    assert_eq!(decoded, a);
+
+    // Nested maps are sorted too, and the sign of zero is normalized.
+    let mut outer = HashMap::new();
+    let mut inner = HashMap::new();
+    inner.insert(2u32, -0.0f64);
+    inner.insert(1u32, 1.5f64);
+    outer.insert("b".to_string(), inner.clone());
+    outer.insert("a".to_string(), inner);
+    assert_eq!(json::encode_canonical(&outer).unwrap(),
+               "{\"a\":{\"1\":1.5,\"2\":0.0},\"b\":{\"1\":1.5,\"2\":0.0}}");
+}
+
+#[test]
+fn test_encode_float_options() {
+    use json::{FloatFormat, NonFiniteFloats, PrettyEncoder};
+
+    let values = vec![1.0f64, f64::NAN, f64::INFINITY, f64::NEG_INFINITY];
+
+    let mut s = String::new();
+    values.encode(&mut Encoder::new(&mut s)).unwrap();
+    assert_eq!(s, "[1.0,null,null,null]");
+
+    let mut s = String::new();
+    {
+        let mut encoder = Encoder::new(&mut s);
+        encoder.set_non_finite_floats(NonFiniteFloats::Error);
+        match values.encode(&mut encoder) {
+            Err(EncoderError::NonFiniteFloat) => {}
+            r => panic!("expected a non-finite float error, got {:?}", r),
+        }
+    }
+
+    let mut s = String::new();
+    {
+        let mut encoder = Encoder::new(&mut s);
+        encoder.set_non_finite_floats(NonFiniteFloats::String);
+        values.encode(&mut encoder).unwrap();
+    }
+    assert_eq!(s, "[1.0,\"NaN\",\"inf\",\"-inf\"]");
+    let decoded: Vec<f64> = json::decode(&s).unwrap();
+    assert_eq!(decoded[0], 1.0);
+    assert!(decoded[1].is_nan());
+    assert_eq!(decoded[2], f64::INFINITY);
+    assert_eq!(decoded[3], f64::NEG_INFINITY);
+
+    let mut s = String::new();
+    {
+        let mut encoder = PrettyEncoder::new(&mut s);
+        encoder.set_non_finite_floats(NonFiniteFloats::String);
+        encoder.set_float_format(FloatFormat::Precision(2));
+        vec![2.0f64 / 3.0, 5.0, f64::NAN].encode(&mut encoder).unwrap();
+    }
+    assert_eq!(s, "[\n  0.67,\n  5.00,\n  \"NaN\"\n]");
+}
+
+#[test]
+fn test_encode_to_writer() {
+    use std::collections::HashMap;
+    use std::io::{self, ErrorKind};
+
+    let value = vec![Some(1u32), None];
+    let mut out = Vec::new();
+    json::encode_to_writer(&value, &mut out).unwrap();
+    assert_eq!(out, json::encode(&value).unwrap().into_bytes());
+
+    out.clear();
+    json::encode_pretty_to_writer(&value, &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "[\n  1,\n  null\n]");
+
+    // I/O errors are passed through.
+    let mut buf = [0u8; 4];
+    let mut full = io::Cursor::new(&mut buf[..]);
+    let err = json::encode_to_writer(&value, &mut full).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::WriteZero);
+
+    // Encoder errors become `InvalidInput`.
+    let mut map = HashMap::new();
+    map.insert(vec![1u8], 1u8);
+    let err = json::encode_to_writer(&map, &mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[derive(ToJson, RustcEncodable)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(ToJson, RustcEncodable)]
+struct Pair(u8, string::String);
+
+#[derive(ToJson, RustcEncodable)]
+struct Unit;
+
+#[derive(ToJson, RustcEncodable)]
+enum Shape {
+    Dot,
+    Circle(Point, u32),
+}
+
+#[test]
+fn test_derive_to_json() {
+    use json::ToJson;
+
+    assert_eq!(Point { x: 1, y: -2 }.to_json().to_string(), r#"{"x":1,"y":-2}"#);
+    assert_eq!(Pair(3, "a".to_string()).to_json().to_string(), r#"{"_field0":3,"_field1":"a"}"#);
+    assert_eq!(Unit.to_json(), Object(BTreeMap::new()));
+    assert_eq!(Shape::Dot.to_json().to_string(), r#""Dot""#);
+    let circle = Shape::Circle(Point { x: 0, y: 0 }, 5);
+    assert_eq!(circle.to_json().to_string(),
+               r#"{"fields":[{"x":0,"y":0},5],"variant":"Circle"}"#);
+
+    // The layout matches `json::encode`.
+    assert_eq!(from_str(&json::encode(&circle).unwrap()).unwrap(), circle.to_json());
+    assert_eq!(from_str(&json::encode(&Shape::Dot).unwrap()).unwrap(), Shape::Dot.to_json());
+    let pair = Pair(3, "a".to_string());
+    assert_eq!(from_str(&json::encode(&pair).unwrap()).unwrap(), pair.to_json());
+    assert_eq!(from_str(&json::encode(&Unit).unwrap()).unwrap(), Unit.to_json());
+}
+
+#[test]
+fn test_json_macro() {
+    let name = "madoka";
+    let value = json!({
+        "name": name,
+        "ids": [1, 2, (-3)],
+        "nested": {"empty": [], "none": null, "flag": true},
+        (1 + 1): 2.5,
+    });
+
+    let mut nested = BTreeMap::new();
+    nested.insert("empty".to_string(), Array(vec![]));
+    nested.insert("none".to_string(), Null);
+    nested.insert("flag".to_string(), Boolean(true));
+    let mut expected = BTreeMap::new();
+    expected.insert("name".to_string(), String("madoka".to_string()));
+    expected.insert("ids".to_string(), Array(vec![I64(1), I64(2), I64(-3)]));
+    expected.insert("nested".to_string(), Object(nested));
+    expected.insert("2".to_string(), F64(2.5));
+    assert_eq!(value, Object(expected));
+
+    assert_eq!(json!(null), Null);
+    assert_eq!(json!([]), Array(vec![]));
+    assert_eq!(json!({}), Object(BTreeMap::new()));
+    assert_eq!(json!(vec![1u8, 2]), Array(vec![U64(1), U64(2)]));
+}
@@ -1,6 +1,6 @@
 extern crate serialize as rustc_serialize;
 
-use rustc_serialize::{Encodable, Decodable};
+use rustc_serialize::{BorrowDecoder, Encodable, Decodable};
 use rustc_serialize::opaque::{Encoder, Decoder};
 use std::fmt::Debug;
 
@@ -280,3 +280,63 @@ fn test_tuples() {
     check_round_trip(vec![(1234567isize, 100000000000000u64, 99999999999999i64)]);
     check_round_trip(vec![(String::new(), "some string".to_string())]);
 }
+
+#[test]
+fn test_canonical_hash_map() {
+    use std::collections::HashMap;
+
+    let encode = |map: &HashMap<String, Vec<u32>>| {
+        let mut encoder = Encoder::new_canonical(Vec::new());
+        map.encode(&mut encoder).unwrap();
+        encoder.into_inner()
+    };
+
+    // Two maps with the same contents but built in a different order, and
+    // so with a different iteration order.
+    let mut a = HashMap::new();
+    let mut b = HashMap::new();
+    for i in 0..100 {
+        a.insert(i.to_string(), vec![i; i as usize % 3]);
+        b.insert((99 - i).to_string(), vec![99 - i; (99 - i) as usize % 3]);
+    }
+    let data = encode(&a);
+    assert_eq!(data, encode(&b));
+
+    let mut decoder = Decoder::new(&data[..], 0);
+    let decoded: HashMap<String, Vec<u32>> = Decodable::decode(&mut decoder).unwrap();
+    assert_eq!(decoded, a);
+}
+
+#[test]
+fn test_canonical_floats() {
+    let encode = |v: f64| {
+        let mut encoder = Encoder::new_canonical(Vec::new());
+        v.encode(&mut encoder).unwrap();
+        encoder.into_inner()
+    };
+    assert_eq!(encode(-0.0), encode(0.0));
+    assert_eq!(encode(std::f64::NAN), encode(-std::f64::NAN));
+    assert_ne!(encode(1.0), encode(-1.0));
+}
+
+#[test]
+fn test_borrowed_reads() {
+    let mut encoder = Encoder::new(Vec::new());
+    "hello".encode(&mut encoder).unwrap();
+    vec![1u8, 2, 3].encode(&mut encoder).unwrap();
+    String::new().encode(&mut encoder).unwrap();
+    let data = encoder.into_inner();
+
+    let (s, bytes, empty) = {
+        let mut decoder = Decoder::new(&data[..], 0);
+        (decoder.read_str_borrowed().unwrap(),
+         decoder.read_bytes_borrowed().unwrap(),
+         decoder.read_str_borrowed().unwrap())
+    };
+    // The results outlive the decoder and point into the encoded data.
+    assert_eq!(s, "hello");
+    assert_eq!(bytes, [1, 2, 3]);
+    assert_eq!(empty, "");
+    let start = data.as_ptr() as usize;
+    assert!(start <= s.as_ptr() as usize && (s.as_ptr() as usize) < start + data.len());
+}
@@ -8,9 +8,13 @@
 #![stable(feature = "rust1", since = "1.0.0")]
 
 use fmt;
+use collections::hash_map::RandomState;
+use convert::TryFrom;
 use ffi::OsString;
+use hash::{BuildHasher, Hasher};
 use io::{self, SeekFrom, Seek, Read, Initializer, Write};
 use path::{Path, PathBuf};
+use sync::atomic::{AtomicUsize, Ordering};
 use sys::fs as fs_imp;
 use sys_common::{AsInnerMut, FromInner, AsInner, IntoInner};
 use time::SystemTime;
@@ -219,6 +223,17 @@ fn initial_buffer_size(file: &File) -> usize {
     file.metadata().map(|m| m.len() as usize + 1).unwrap_or(0)
 }
 
+/// The number of bytes between the cursor of a regular file and its end, if
+/// it fits in a `usize`.
+fn remaining_len(file: &File) -> Option<usize> {
+    let metadata = file.metadata().ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    let pos = file.inner.seek(SeekFrom::Current(0)).ok()?;
+    usize::try_from(metadata.len().saturating_sub(pos)).ok()
+}
+
 /// Read the entire contents of a file into a bytes vector.
 ///
 /// This is a convenience function for using [`File::open`] and [`read_to_end`]
@@ -338,6 +353,115 @@ pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result
     inner(path.as_ref(), contents.as_ref())
 }
 
+/// Atomically replace the contents of a file.
+///
+/// The closure `f` is given a freshly created temporary file in the same
+/// directory as `path`. If it returns successfully, the temporary file is
+/// flushed to disk and then renamed over `path`, so that other readers see
+/// either the old contents or the new contents, but never a partially written
+/// file. If `f` or any of the subsequent steps fails, or `f` panics, the
+/// temporary file is removed and `path` is left untouched.
+///
+/// If `path` already exists, its permissions are copied to the new file
+/// before `f` is called.
+///
+/// Creating the temporary file next to `path` ensures the final rename never
+/// crosses a filesystem boundary. Note that the durability of the rename
+/// itself (as opposed to the file contents) depends on the platform and
+/// filesystem.
+///
+/// # Errors
+///
+/// This function will return an error if `path` has no file name, if the
+/// temporary file cannot be created, if `f` returns an error, or if the
+/// contents cannot be synced or renamed into place.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_atomic_write)]
+/// use std::fs;
+/// use std::io::Write;
+///
+/// fn main() -> std::io::Result<()> {
+///     fs::atomic_write("state.json", |file| {
+///         file.write_all(b"{\"generation\": 2}")
+///     })?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_atomic_write", issue = "0")]
+pub fn atomic_write<P, F>(path: P, f: F) -> io::Result<()>
+    where P: AsRef<Path>, F: FnOnce(&mut File) -> io::Result<()>
+{
+    let path = path.as_ref();
+    let (mut file, tmp) = create_sibling_temp(path)?;
+    // Removes the temporary file on every path out of here, including a
+    // panic in `f`, unless it has been renamed into place.
+    let mut guard = TempFileGuard { path: tmp, armed: true };
+    f(&mut file)?;
+    file.sync_all()?;
+    drop(file);
+    rename(&guard.path, path)?;
+    guard.armed = false;
+    Ok(())
+}
+
+struct TempFileGuard {
+    path: PathBuf,
+    armed: bool,
+}
+
+impl Drop for TempFileGuard {
+    fn drop(&mut self) {
+        if self.armed {
+            let _ = remove_file(&self.path);
+        }
+    }
+}
+
+/// Creates a new, uniquely named file in the same directory as `path`,
+/// carrying over the permissions of `path` if it exists.
+fn create_sibling_temp(path: &Path) -> io::Result<(File, PathBuf)> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "path has no file name")
+    })?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let perms = metadata(path).ok().map(|m| m.permissions());
+
+    loop {
+        // The suffix mixes a process-wide counter with the random hash keys,
+        // so that concurrent writers in different processes are unlikely to
+        // collide. On targets without a source of randomness the keys are
+        // fixed, and a collision just means another trip around this loop.
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_usize(COUNTER.fetch_add(1, Ordering::Relaxed));
+        let mut tmp_name = OsString::from(".");
+        tmp_name.push(name);
+        tmp_name.push(format!(".{:016x}.tmp", hasher.finish()));
+        let tmp = dir.join(tmp_name);
+        match OpenOptions::new().write(true).create_new(true).open(&tmp) {
+            Ok(file) => {
+                if let Some(perms) = perms {
+                    if let Err(e) = file.set_permissions(perms) {
+                        let _ = remove_file(&tmp);
+                        return Err(e);
+                    }
+                }
+                return Ok((file, tmp))
+            }
+            // A stale temporary file from a crashed writer; pick a new name.
+            Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 impl File {
     /// Attempts to open a file in read-only mode.
     ///
@@ -479,6 +603,197 @@ impl File {
         self.inner.truncate(size)
     }
 
+    /// Reserves disk space for the first `len` bytes of the underlying file.
+    ///
+    /// After a successful call, writes within the first `len` bytes of the
+    /// file will not fail for lack of disk space. Unlike [`set_len`], the
+    /// size of the file as reported by [`metadata`] is not changed, and
+    /// calling this with a `len` smaller than the current allocation does not
+    /// release any space.
+    ///
+    /// This corresponds to `fallocate` with `FALLOC_FL_KEEP_SIZE` on Linux
+    /// and to `SetFileInformationByHandle` with `FileAllocationInfo` on
+    /// Windows. Note that this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    /// [`set_len`]: #method.set_len
+    /// [`metadata`]: #method.metadata
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file is not opened for
+    /// writing, if there is not enough space available, or if preallocation
+    /// is not supported by the platform or the filesystem.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_preallocate)]
+    /// use std::fs::File;
+    /// use std::io::Write;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let mut f = File::create("foo.txt")?;
+    ///     f.preallocate(1 << 20)?;
+    ///     f.write_all(b"hello")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Note that this method alters the underlying file, even though it takes
+    /// `&self` rather than `&mut self`.
+    #[unstable(feature = "file_preallocate", issue = "0")]
+    pub fn preallocate(&self, len: u64) -> io::Result<()> {
+        self.inner.preallocate(len)
+    }
+
+    /// Acquires an exclusive advisory lock on the file, blocking until it can
+    /// be acquired.
+    ///
+    /// At most one handle may hold an exclusive lock on a file at a time, and
+    /// no handle may hold a shared lock on it while it does. The lock is
+    /// released by [`unlock`], or when the file, and every handle duplicated
+    /// from it with [`try_clone`], is closed.
+    ///
+    /// The lock is advisory: it only coordinates processes that lock the file
+    /// too, and does not stop anyone from reading or writing it.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with `LOCK_EX`, and to `LockFileEx` over the whole file with
+    /// `LOCKFILE_EXCLUSIVE_LOCK` on Windows. Note that this
+    /// [may change in the future][changes].
+    ///
+    /// On Windows, the lock is mandatory rather than advisory: other handles
+    /// cannot read or write the file while it is held. Locking a file that
+    /// is already locked through the same handle also behaves differently: on
+    /// Unix the existing lock is converted, while on Windows a second lock is
+    /// taken, so that an exclusive lock blocks forever and a shared lock must
+    /// be unlocked twice.
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    /// [`unlock`]: #method.unlock
+    /// [`try_clone`]: #method.try_clone
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    /// use std::io::Write;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let mut f = File::create("cache.db")?;
+    ///     f.lock_exclusive()?;
+    ///     f.write_all(b"entries")?;
+    ///     f.unlock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.inner.lock(true, true)
+    }
+
+    /// Acquires a shared advisory lock on the file, blocking until it can be
+    /// acquired.
+    ///
+    /// Any number of handles may hold a shared lock on a file at the same
+    /// time, as long as none holds an exclusive lock on it. See
+    /// [`lock_exclusive`] for how the lock is released and how it behaves on
+    /// each platform.
+    ///
+    /// [`lock_exclusive`]: #method.lock_exclusive
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    /// use std::io::Read;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let mut f = File::open("cache.db")?;
+    ///     f.lock_shared()?;
+    ///     let mut contents = Vec::new();
+    ///     f.read_to_end(&mut contents)?;
+    ///     f.unlock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.inner.lock(false, true)
+    }
+
+    /// Tries to acquire an exclusive advisory lock on the file without
+    /// blocking.
+    ///
+    /// See [`lock_exclusive`] for the semantics of the lock.
+    ///
+    /// # Errors
+    ///
+    /// If the lock is held elsewhere, this function returns an error of kind
+    /// [`ErrorKind::WouldBlock`] instead of waiting for it to be released.
+    ///
+    /// [`lock_exclusive`]: #method.lock_exclusive
+    /// [`ErrorKind::WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    /// use std::io::ErrorKind;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("cache.db")?;
+    ///     match f.try_lock_exclusive() {
+    ///         Ok(()) => println!("got the lock"),
+    ///         Err(ref e) if e.kind() == ErrorKind::WouldBlock => println!("cache is busy"),
+    ///         Err(e) => return Err(e),
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn try_lock_exclusive(&self) -> io::Result<()> {
+        self.inner.lock(true, false)
+    }
+
+    /// Tries to acquire a shared advisory lock on the file without blocking.
+    ///
+    /// See [`lock_shared`] for the semantics of the lock.
+    ///
+    /// # Errors
+    ///
+    /// If an exclusive lock is held elsewhere, this function returns an error
+    /// of kind [`ErrorKind::WouldBlock`] instead of waiting for it to be
+    /// released.
+    ///
+    /// [`lock_shared`]: #method.lock_shared
+    /// [`ErrorKind::WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        self.inner.lock(false, false)
+    }
+
+    /// Releases the advisory lock held on the file through this handle.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with `LOCK_UN`, and to `UnlockFile` on Windows. On Windows, unlocking
+    /// a file that is not locked is an error. Note that this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.inner.unlock()
+    }
+
     /// Queries metadata about the underlying file.
     ///
     /// # Examples
@@ -579,6 +894,47 @@ impl File {
     pub fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
         self.inner.set_permissions(perm.0)
     }
+
+    /// Changes the last access and last modification times of the underlying
+    /// file.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `futimens` function on Unix
+    /// (`futimes` on macOS and iOS) and the `SetFileInformationByHandle`
+    /// function on Windows. Note that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the user lacks permission to
+    /// change the times of the underlying file, or if a time can't be
+    /// represented by the platform. It may also return an error in other
+    /// os-specific unspecified cases.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_set_times)]
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     use std::fs::File;
+    ///     use std::time::SystemTime;
+    ///
+    ///     let file = File::open("foo.txt")?;
+    ///     let now = SystemTime::now();
+    ///     file.set_times(now, now)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Note that this method alters the underlying file, even though it takes
+    /// `&self` rather than `&mut self`.
+    #[unstable(feature = "file_set_times", issue = "0")]
+    pub fn set_times(&self, atime: SystemTime, mtime: SystemTime) -> io::Result<()> {
+        self.inner.set_times(*atime.as_inner(), *mtime.as_inner())
+    }
 }
 
 impl AsInner<fs_imp::File> for File {
@@ -612,6 +968,10 @@ impl Read for File {
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
     }
+
+    fn remaining_hint(&self) -> Option<usize> {
+        remaining_len(self)
+    }
 }
 #[stable(feature = "rust1", since = "1.0.0")]
 impl Write for File {
@@ -636,6 +996,10 @@ impl Read for &File {
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
     }
+
+    fn remaining_hint(&self) -> Option<usize> {
+        remaining_len(self)
+    }
 }
 #[stable(feature = "rust1", since = "1.0.0")]
 impl Write for &File {
@@ -1608,7 +1972,54 @@ pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()>
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<u64> {
-    fs_imp::copy(from.as_ref(), to.as_ref())
+    fs_imp::copy(from.as_ref(), to.as_ref(), &mut |_| {})
+}
+
+/// Copies the contents of one file to another like [`copy`], calling
+/// `progress` with the number of bytes copied so far as the copy proceeds.
+///
+/// `progress` is called after each chunk is copied. The size of the chunks
+/// is unspecified, and `progress` may not be called at all for an empty file.
+/// If `progress` panics, the copy is stopped and the panic is propagated.
+///
+/// [`copy`]: fn.copy.html
+///
+/// # Platform-specific behavior
+///
+/// This function uses the same system calls as [`copy`]. On Linux, the data
+/// is copied with `copy_file_range` where it is available, and `progress` is
+/// called after every 16 MiB. On Windows, `progress` is called from the
+/// progress routine of `CopyFileEx`.
+///
+/// When `progress` panics, `to` is left partially written, except on Windows,
+/// where `CopyFileEx` deletes it.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function returns the same errors as [`copy`].
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_copy_progress)]
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     let total = fs::metadata("foo.txt")?.len();
+///     fs::copy_with_progress("foo.txt", "bar.txt", |copied| {
+///         println!("{}/{} bytes copied", copied, total);
+///     })?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_copy_progress", issue = "0")]
+pub fn copy_with_progress<P, Q, F>(from: P, to: Q, mut progress: F) -> io::Result<u64>
+    where P: AsRef<Path>, Q: AsRef<Path>, F: FnMut(u64)
+{
+    fs_imp::copy(from.as_ref(), to.as_ref(), &mut progress)
 }
 
 /// Creates a new hard link on the filesystem.
@@ -1995,6 +2406,45 @@ pub fn set_permissions<P: AsRef<Path>>(path: P, perm: Permissions)
     fs_imp::set_perm(path.as_ref(), perm.0)
 }
 
+/// Changes the last access and last modification times of a file or a
+/// directory.
+///
+/// # Platform-specific behavior
+///
+/// This function currently corresponds to the `utimensat` function on Unix
+/// (`utimes` on macOS and iOS) and the `SetFileInformationByHandle` function
+/// on Windows. Note that, this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error in the following situations, but is not
+/// limited to just these cases:
+///
+/// * `path` does not exist.
+/// * The user lacks the permission to change the times of the file.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(file_set_times)]
+///
+/// use std::fs;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// fn main() -> std::io::Result<()> {
+///     let time = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+///     fs::change_file_times("foo.txt", time, time)?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "file_set_times", issue = "0")]
+pub fn change_file_times<P: AsRef<Path>>(path: P, atime: SystemTime, mtime: SystemTime)
+                                         -> io::Result<()> {
+    fs_imp::set_times(path.as_ref(), *atime.as_inner(), *mtime.as_inner())
+}
+
 impl DirBuilder {
     /// Creates a new set of options with default mode/security settings for all
     /// platforms and also non-recursive.
@@ -2105,6 +2555,7 @@ mod tests {
     use str;
     use sys_common::io::test::{TempDir, tmpdir};
     use thread;
+    use time::{Duration, UNIX_EPOCH};
 
     #[cfg(windows)]
     use os::windows::fs::{symlink_dir, symlink_file};
@@ -2285,6 +2736,20 @@ mod tests {
         assert!(read_str == final_msg);
     }
 
+    #[test]
+    fn file_test_io_remaining_hint() {
+        let tmpdir = tmpdir();
+        let filename = &tmpdir.join("file_rt_io_file_test_remaining_hint.txt");
+        check!(fs::write(filename, b"qwer-asdf-zxcv"));
+
+        let mut file = check!(File::open(filename));
+        assert_eq!(file.remaining_hint(), Some(14));
+        check!(file.seek(SeekFrom::Start(5)));
+        assert_eq!((&file).remaining_hint(), Some(9));
+        check!(file.seek(SeekFrom::End(10)));
+        assert_eq!(file.remaining_hint(), Some(0));
+    }
+
     #[test]
     fn file_test_io_seek_shakedown() {
         //                   01234567890123
@@ -2784,6 +3249,23 @@ mod tests {
         assert_eq!(v, b"foo".to_vec());
     }
 
+    #[test]
+    fn copy_file_with_progress() {
+        let tmpdir = tmpdir();
+        let input = tmpdir.join("in.txt");
+        let output = tmpdir.join("out.txt");
+        let contents = vec![7u8; 100_000];
+
+        check!(check!(File::create(&input)).write_all(&contents));
+        let mut reported = Vec::new();
+        let copied = check!(fs::copy_with_progress(&input, &output, |n| reported.push(n)));
+
+        assert_eq!(copied, contents.len() as u64);
+        assert_eq!(reported.last(), Some(&copied));
+        assert!(reported.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(check!(fs::read(&output)), contents);
+    }
+
     #[test]
     fn copy_file_src_dir() {
         let tmpdir = tmpdir();
@@ -2964,6 +3446,27 @@ mod tests {
         check!(file.set_permissions(p));
     }
 
+    #[test]
+    fn set_times_works() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("in.txt");
+        let atime = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let mtime = UNIX_EPOCH + Duration::from_secs(1_500_000_000);
+
+        let file = check!(File::create(&path));
+        check!(file.set_times(atime, mtime));
+        let attr = check!(fs::metadata(&path));
+        assert_eq!(check!(attr.accessed()), atime);
+        assert_eq!(check!(attr.modified()), mtime);
+
+        check!(fs::change_file_times(&path, mtime, atime));
+        let attr = check!(fs::metadata(&path));
+        assert_eq!(check!(attr.accessed()), mtime);
+        assert_eq!(check!(attr.modified()), atime);
+
+        assert!(fs::change_file_times(&tmpdir.join("foo"), atime, mtime).is_err());
+    }
+
     #[test]
     fn sync_doesnt_kill_anything() {
         let tmpdir = tmpdir();
@@ -3011,6 +3514,105 @@ mod tests {
         assert_eq!(v, b"fo\0\0\0\0wut".to_vec());
     }
 
+    #[test]
+    fn atomic_write_replaces_contents() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("state");
+
+        check!(fs::write(&path, b"old"));
+        check!(fs::atomic_write(&path, |f| f.write_all(b"new contents")));
+        assert_eq!(check!(fs::read(&path)), b"new contents");
+
+        // A failing writer leaves the original untouched and cleans up.
+        let err = fs::atomic_write(&path, |f| {
+            f.write_all(b"partial")?;
+            Err(::io::Error::new(ErrorKind::Other, "boom"))
+        });
+        assert!(err.is_err());
+        assert_eq!(check!(fs::read(&path)), b"new contents");
+        assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 1);
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "emscripten", ignore)]
+    fn atomic_write_cleans_up_on_panic() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("state");
+
+        let result = ::panic::catch_unwind(|| {
+            let _ = fs::atomic_write(&path, |_| panic!("boom"));
+        });
+        assert!(result.is_err());
+        assert!(!path.exists());
+        assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 0);
+    }
+
+    #[test]
+    fn atomic_write_creates_file() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("fresh");
+
+        check!(fs::atomic_write(&path, |f| f.write_all(b"hi")));
+        assert_eq!(check!(fs::read(&path)), b"hi");
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "macos", windows))]
+    fn file_lock_conflicts() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("lock");
+
+        let a = check!(File::create(&path));
+        let b = check!(File::open(&path));
+        let c = check!(File::open(&path));
+
+        check!(a.lock_exclusive());
+        assert_eq!(b.try_lock_shared().unwrap_err().kind(), ErrorKind::WouldBlock);
+        assert_eq!(b.try_lock_exclusive().unwrap_err().kind(), ErrorKind::WouldBlock);
+        check!(a.unlock());
+
+        check!(b.try_lock_shared());
+        check!(c.lock_shared());
+        assert_eq!(a.try_lock_exclusive().unwrap_err().kind(), ErrorKind::WouldBlock);
+        check!(b.unlock());
+        check!(c.unlock());
+        check!(a.try_lock_exclusive());
+        check!(a.unlock());
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "macos", windows))]
+    fn file_lock_released_on_close() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("lock");
+
+        let a = check!(File::create(&path));
+        check!(a.lock_exclusive());
+        drop(a);
+        let b = check!(File::open(&path));
+        check!(b.try_lock_exclusive());
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", windows))]
+    fn preallocate_keeps_len() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("in.txt");
+
+        let mut file = check!(File::create(&path));
+        check!(file.write(b"foo"));
+        match file.preallocate(4096) {
+            Ok(()) => {}
+            // Not every filesystem a test directory may live on (tmpfs on
+            // old kernels, network shares) supports preallocation.
+            Err(..) => return,
+        }
+        assert_eq!(check!(file.metadata()).len(), 3);
+        check!(file.write(b"bar"));
+        check!(file.sync_all());
+        assert_eq!(check!(file.metadata()).len(), 6);
+    }
+
     #[test]
     fn open_flavors() {
         use fs::OpenOptions as OO;
@@ -797,6 +797,25 @@ impl<W> fmt::Display for IntoInnerError<W> {
 pub struct LineWriter<W: Write> {
     inner: BufWriter<W>,
     need_flush: bool,
+    policy: FlushPolicy,
+}
+
+/// When a [`LineWriter`] flushes its buffer, besides when the buffer is full,
+/// when it is dropped and when [`flush`] is called.
+///
+/// [`LineWriter`]: struct.LineWriter.html
+/// [`flush`]: trait.Write.html#tymethod.flush
+#[unstable(feature = "line_writer_policy", issue = "0")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// Flush after each write containing a newline, up to the last newline.
+    /// This is the default.
+    Newline,
+    /// Flush after each write which leaves at least this many bytes in the
+    /// buffer, whether or not they contain a newline.
+    Threshold(usize),
+    /// Never flush on a write except when the buffer is full.
+    Manual,
 }
 
 impl<W: Write> LineWriter<W> {
@@ -840,9 +859,48 @@ impl<W: Write> LineWriter<W> {
         LineWriter {
             inner: BufWriter::with_capacity(cap, inner),
             need_flush: false,
+            policy: FlushPolicy::Newline,
         }
     }
 
+    /// Sets when this writer flushes its buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(line_writer_policy)]
+    /// use std::io::prelude::*;
+    /// use std::io::{FlushPolicy, LineWriter};
+    ///
+    /// let mut writer = LineWriter::new(Vec::new());
+    /// writer.set_flush_policy(FlushPolicy::Threshold(8));
+    ///
+    /// writer.write_all(b"one\n").unwrap();
+    /// assert_eq!(writer.get_ref().len(), 0);
+    /// assert_eq!(writer.buffered_len(), 4);
+    ///
+    /// writer.write_all(b"two\n").unwrap();
+    /// assert_eq!(writer.get_ref(), b"one\ntwo\n");
+    /// assert_eq!(writer.buffered_len(), 0);
+    /// ```
+    #[unstable(feature = "line_writer_policy", issue = "0")]
+    pub fn set_flush_policy(&mut self, policy: FlushPolicy) {
+        self.policy = policy;
+    }
+
+    /// Returns when this writer flushes its buffer.
+    #[unstable(feature = "line_writer_policy", issue = "0")]
+    pub fn flush_policy(&self) -> FlushPolicy {
+        self.policy
+    }
+
+    /// Returns the number of bytes written to this writer but not yet to the
+    /// underlying writer.
+    #[unstable(feature = "line_writer_policy", issue = "0")]
+    pub fn buffered_len(&self) -> usize {
+        self.inner.buf.len()
+    }
+
     /// Gets a reference to the underlying writer.
     ///
     /// # Examples
@@ -910,10 +968,12 @@ impl<W: Write> LineWriter<W> {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn into_inner(self) -> Result<W, IntoInnerError<LineWriter<W>>> {
+        let policy = self.policy;
         self.inner.into_inner().map_err(|IntoInnerError(buf, e)| {
             IntoInnerError(LineWriter {
                 inner: buf,
                 need_flush: false,
+                policy,
             }, e)
         })
     }
@@ -926,6 +986,21 @@ impl<W: Write> Write for LineWriter<W> {
             self.flush()?;
         }
 
+        match self.policy {
+            FlushPolicy::Newline => {}
+            FlushPolicy::Threshold(threshold) => {
+                // As below, errors from flushing are ignored once some data has
+                // been written, and flushing is tried again on the next write.
+                let n = self.inner.write(buf)?;
+                if self.inner.buf.len() >= threshold {
+                    self.need_flush = true;
+                    let _ = self.flush();
+                }
+                return Ok(n);
+            }
+            FlushPolicy::Manual => return self.inner.write(buf),
+        }
+
         // Find the last newline character in the buffer provided. If found then
         // we're going to write all the data up to that point and then flush,
         // otherwise we just write the whole block to the underlying writer.
@@ -978,7 +1053,7 @@ impl<W: Write> fmt::Debug for LineWriter<W> where W: fmt::Debug {
 #[cfg(test)]
 mod tests {
     use io::prelude::*;
-    use io::{self, BufReader, BufWriter, LineWriter, SeekFrom};
+    use io::{self, BufReader, BufWriter, FlushPolicy, LineWriter, SeekFrom};
     use sync::atomic::{AtomicUsize, Ordering};
     use thread;
     use test;
@@ -1243,6 +1318,27 @@ mod tests {
         assert_eq!(*writer.get_ref(), [0, 1, 0, b'\n', 1, b'\n', 2, 3, b'\n']);
     }
 
+    #[test]
+    fn test_line_buffer_flush_policy() {
+        let mut writer = LineWriter::new(Vec::new());
+        assert_eq!(writer.flush_policy(), FlushPolicy::Newline);
+
+        writer.set_flush_policy(FlushPolicy::Manual);
+        writer.write(&[0, b'\n']).unwrap();
+        assert_eq!(*writer.get_ref(), []);
+        assert_eq!(writer.buffered_len(), 2);
+        writer.flush().unwrap();
+        assert_eq!(*writer.get_ref(), [0, b'\n']);
+        assert_eq!(writer.buffered_len(), 0);
+
+        writer.set_flush_policy(FlushPolicy::Threshold(3));
+        writer.write(&[1, 2]).unwrap();
+        assert_eq!(*writer.get_ref(), [0, b'\n']);
+        writer.write(&[3]).unwrap();
+        assert_eq!(*writer.get_ref(), [0, b'\n', 1, 2, 3]);
+        assert_eq!(writer.buffered_len(), 0);
+    }
+
     #[test]
     fn test_read_line() {
         let in_buf: &[u8] = b"a\nb\nc";
@@ -0,0 +1,368 @@
+//! Checksumming adapters for readers and writers.
+
+use fmt;
+use io::{self, Read, Write};
+
+/// A running checksum or digest over a stream of bytes.
+///
+/// Implementations are fed data incrementally through [`update`] and can
+/// report the digest of everything seen so far at any point through
+/// [`digest`].
+///
+/// [`update`]: #tymethod.update
+/// [`digest`]: #tymethod.digest
+#[unstable(feature = "io_checksum", issue = "0")]
+pub trait Digest {
+    /// The type of the computed digest.
+    type Output;
+
+    /// Feeds `data` into the digest.
+    fn update(&mut self, data: &[u8]);
+
+    /// Returns the digest of all data fed in so far.
+    fn digest(&self) -> Self::Output;
+}
+
+/// The CRC-32 checksum (IEEE 802.3 polynomial), as used by zlib, gzip, zip
+/// and PNG.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(io_checksum)]
+/// use std::io::{Crc32, Digest};
+///
+/// let mut crc = Crc32::new();
+/// crc.update(b"123456789");
+/// assert_eq!(crc.digest(), 0xcbf43926);
+/// ```
+#[unstable(feature = "io_checksum", issue = "0")]
+#[derive(Clone, Debug)]
+pub struct Crc32 {
+    state: u32,
+}
+
+// Remainders of the reflected polynomial 0xedb88320 for every nibble, which
+// keeps the table small at the cost of two lookups per byte.
+static CRC32_NIBBLE_TABLE: [u32; 16] = [
+    0x00000000, 0x1db71064, 0x3b6e20c8, 0x26d930ac,
+    0x76dc4190, 0x6b6b51f4, 0x4db26158, 0x5005713c,
+    0xedb88320, 0xf00f9344, 0xd6d6a3e8, 0xcb61b38c,
+    0x9b64c2b0, 0x86d3d2d4, 0xa00ae278, 0xbdbdf21c,
+];
+
+impl Crc32 {
+    /// Creates a new CRC-32 with no data fed in.
+    #[unstable(feature = "io_checksum", issue = "0")]
+    pub fn new() -> Crc32 {
+        Crc32 { state: !0 }
+    }
+}
+
+#[unstable(feature = "io_checksum", issue = "0")]
+impl Default for Crc32 {
+    fn default() -> Crc32 {
+        Crc32::new()
+    }
+}
+
+#[unstable(feature = "io_checksum", issue = "0")]
+impl Digest for Crc32 {
+    type Output = u32;
+
+    fn update(&mut self, data: &[u8]) {
+        let mut crc = self.state;
+        for &byte in data {
+            crc ^= byte as u32;
+            crc = (crc >> 4) ^ CRC32_NIBBLE_TABLE[(crc & 0xf) as usize];
+            crc = (crc >> 4) ^ CRC32_NIBBLE_TABLE[(crc & 0xf) as usize];
+        }
+        self.state = crc;
+    }
+
+    fn digest(&self) -> u32 {
+        !self.state
+    }
+}
+
+/// The Adler-32 checksum, as used by the zlib format.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(io_checksum)]
+/// use std::io::{Adler32, Digest};
+///
+/// let mut adler = Adler32::new();
+/// adler.update(b"Wikipedia");
+/// assert_eq!(adler.digest(), 0x11e60398);
+/// ```
+#[unstable(feature = "io_checksum", issue = "0")]
+#[derive(Clone, Debug)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+const ADLER32_MOD: u32 = 65521;
+// The largest number of bytes that can be summed before `b` may overflow a
+// `u32` and must be reduced.
+const ADLER32_NMAX: usize = 5552;
+
+impl Adler32 {
+    /// Creates a new Adler-32 with no data fed in.
+    #[unstable(feature = "io_checksum", issue = "0")]
+    pub fn new() -> Adler32 {
+        Adler32 { a: 1, b: 0 }
+    }
+}
+
+#[unstable(feature = "io_checksum", issue = "0")]
+impl Default for Adler32 {
+    fn default() -> Adler32 {
+        Adler32::new()
+    }
+}
+
+#[unstable(feature = "io_checksum", issue = "0")]
+impl Digest for Adler32 {
+    type Output = u32;
+
+    fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(ADLER32_NMAX) {
+            for &byte in chunk {
+                self.a += byte as u32;
+                self.b += self.a;
+            }
+            self.a %= ADLER32_MOD;
+            self.b %= ADLER32_MOD;
+        }
+    }
+
+    fn digest(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+/// A reader that feeds every byte read from the underlying reader into a
+/// [`Digest`].
+///
+/// [`Digest`]: trait.Digest.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(io_checksum)]
+/// use std::io::{self, Crc32, HashingReader};
+///
+/// let mut reader = HashingReader::new(&b"123456789"[..], Crc32::new());
+/// io::copy(&mut reader, &mut io::sink()).unwrap();
+/// assert_eq!(reader.digest(), 0xcbf43926);
+/// ```
+#[unstable(feature = "io_checksum", issue = "0")]
+pub struct HashingReader<R, D> {
+    inner: R,
+    digest: D,
+}
+
+impl<R, D: Digest> HashingReader<R, D> {
+    /// Wraps `inner`, feeding the data read through it into `digest`.
+    #[unstable(feature = "io_checksum", issue = "0")]
+    pub fn new(inner: R, digest: D) -> HashingReader<R, D> {
+        HashingReader { inner, digest }
+    }
+
+    /// Returns the digest of all data read so far.
+    #[unstable(feature = "io_checksum", issue = "0")]
+    pub fn digest(&self) -> D::Output {
+        self.digest.digest()
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[unstable(feature = "io_checksum", issue = "0")]
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// Data read directly from the underlying reader is not fed into the
+    /// digest.
+    #[unstable(feature = "io_checksum", issue = "0")]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this `HashingReader`, returning the underlying reader and the
+    /// digest state.
+    #[unstable(feature = "io_checksum", issue = "0")]
+    pub fn into_inner(self) -> (R, D) {
+        (self.inner, self.digest)
+    }
+}
+
+#[unstable(feature = "io_checksum", issue = "0")]
+impl<R: Read, D: Digest> Read for HashingReader<R, D> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.digest.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+#[unstable(feature = "io_checksum", issue = "0")]
+impl<R: fmt::Debug, D: fmt::Debug> fmt::Debug for HashingReader<R, D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HashingReader")
+            .field("inner", &self.inner)
+            .field("digest", &self.digest)
+            .finish()
+    }
+}
+
+/// A writer that feeds every byte written to the underlying writer into a
+/// [`Digest`].
+///
+/// Only the bytes the underlying writer reports as written are fed into the
+/// digest, so partial writes are accounted for correctly.
+///
+/// [`Digest`]: trait.Digest.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(io_checksum)]
+/// use std::io::{Adler32, HashingWriter, Write};
+///
+/// let mut writer = HashingWriter::new(Vec::new(), Adler32::new());
+/// writer.write_all(b"Wikipedia").unwrap();
+/// assert_eq!(writer.digest(), 0x11e60398);
+/// assert_eq!(writer.get_ref(), b"Wikipedia");
+/// ```
+#[unstable(feature = "io_checksum", issue = "0")]
+pub struct HashingWriter<W, D> {
+    inner: W,
+    digest: D,
+}
+
+impl<W, D: Digest> HashingWriter<W, D> {
+    /// Wraps `inner`, feeding the data written through it into `digest`.
+    #[unstable(feature = "io_checksum", issue = "0")]
+    pub fn new(inner: W, digest: D) -> HashingWriter<W, D> {
+        HashingWriter { inner, digest }
+    }
+
+    /// Returns the digest of all data written so far.
+    #[unstable(feature = "io_checksum", issue = "0")]
+    pub fn digest(&self) -> D::Output {
+        self.digest.digest()
+    }
+
+    /// Gets a reference to the underlying writer.
+    #[unstable(feature = "io_checksum", issue = "0")]
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// Data written directly to the underlying writer is not fed into the
+    /// digest.
+    #[unstable(feature = "io_checksum", issue = "0")]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this `HashingWriter`, returning the underlying writer and the
+    /// digest state.
+    #[unstable(feature = "io_checksum", issue = "0")]
+    pub fn into_inner(self) -> (W, D) {
+        (self.inner, self.digest)
+    }
+}
+
+#[unstable(feature = "io_checksum", issue = "0")]
+impl<W: Write, D: Digest> Write for HashingWriter<W, D> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.digest.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[unstable(feature = "io_checksum", issue = "0")]
+impl<W: fmt::Debug, D: fmt::Debug> fmt::Debug for HashingWriter<W, D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HashingWriter")
+            .field("inner", &self.inner)
+            .field("digest", &self.digest)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use io::prelude::*;
+    use io::{self, Adler32, Crc32, Digest, HashingReader, HashingWriter};
+
+    #[test]
+    fn crc32_known_values() {
+        let mut crc = Crc32::new();
+        assert_eq!(crc.digest(), 0);
+        crc.update(b"123456789");
+        assert_eq!(crc.digest(), 0xcbf43926);
+
+        // Feeding the data in pieces gives the same result.
+        let mut crc = Crc32::new();
+        crc.update(b"1234");
+        crc.update(b"");
+        crc.update(b"56789");
+        assert_eq!(crc.digest(), 0xcbf43926);
+    }
+
+    #[test]
+    fn adler32_known_values() {
+        let mut adler = Adler32::new();
+        assert_eq!(adler.digest(), 1);
+        adler.update(b"Wikipedia");
+        assert_eq!(adler.digest(), 0x11e60398);
+    }
+
+    #[test]
+    fn adler32_large_input() {
+        // Large enough to exercise the deferred modulo reduction.
+        let data = vec![0xff; 100_000];
+        let mut whole = Adler32::new();
+        whole.update(&data);
+        let mut pieces = Adler32::new();
+        for chunk in data.chunks(7) {
+            pieces.update(chunk);
+        }
+        assert_eq!(whole.digest(), pieces.digest());
+    }
+
+    #[test]
+    fn hashing_reader_and_writer_agree() {
+        let data = b"the quick brown fox jumps over the lazy dog";
+        let mut reader = HashingReader::new(&data[..], Crc32::new());
+        let mut writer = HashingWriter::new(Vec::new(), Crc32::new());
+        io::copy(&mut reader, &mut writer).unwrap();
+        assert_eq!(reader.digest(), writer.digest());
+        let (buf, _) = writer.into_inner();
+        assert_eq!(&buf[..], &data[..]);
+    }
+
+    #[test]
+    fn hashing_writer_counts_partial_writes() {
+        let mut buf = [0; 4];
+        let mut writer = HashingWriter::new(&mut buf[..], Crc32::new());
+        assert_eq!(writer.write(b"12345678").unwrap(), 4);
+        let mut expected = Crc32::new();
+        expected.update(b"1234");
+        assert_eq!(writer.digest(), expected.digest());
+    }
+}
@@ -244,6 +244,11 @@ impl<T> Read for Cursor<T> where T: AsRef<[u8]> {
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
     }
+
+    fn remaining_hint(&self) -> Option<usize> {
+        let len = self.inner.as_ref().len();
+        Some(len - cmp::min(self.pos, len as u64) as usize)
+    }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -0,0 +1,190 @@
+//! Deadline-enforcing I/O adapter.
+
+use fmt;
+use io::{self, BufRead, Read, Seek, SeekFrom, Write};
+use time::{Duration, Instant};
+
+/// An adapter that imposes an absolute deadline on every operation of an
+/// inner reader, writer or stream.
+///
+/// Once the deadline has passed, every operation fails with an error of kind
+/// [`ErrorKind::TimedOut`] without touching the inner object. This lets
+/// protocol code enforce an end-to-end latency budget the same way no matter
+/// what it is talking to.
+///
+/// The deadline is checked before each operation starts; an operation that
+/// blocks in the inner object is not interrupted. For sockets, combine this
+/// with a per-operation timeout derived from [`remaining`], e.g. by calling
+/// [`TcpStream::set_read_timeout`] with it before each read.
+///
+/// [`ErrorKind::TimedOut`]: enum.ErrorKind.html#variant.TimedOut
+/// [`remaining`]: #method.remaining
+/// [`TcpStream::set_read_timeout`]: ../net/struct.TcpStream.html#method.set_read_timeout
+///
+/// # Examples
+///
+/// ```
+/// #![feature(io_deadline)]
+/// use std::io::{self, Read, WithDeadline};
+/// use std::time::{Duration, Instant};
+///
+/// let mut reader = WithDeadline::new(&b"hello"[..], Instant::now() + Duration::from_secs(5));
+/// let mut buf = String::new();
+/// reader.read_to_string(&mut buf).unwrap();
+/// assert_eq!(buf, "hello");
+///
+/// // A deadline that is now has already passed.
+/// reader.set_deadline(Instant::now());
+/// let err = reader.read(&mut [0; 4]).unwrap_err();
+/// assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+/// ```
+#[unstable(feature = "io_deadline", issue = "0")]
+pub struct WithDeadline<T> {
+    inner: T,
+    deadline: Instant,
+}
+
+impl<T> WithDeadline<T> {
+    /// Wraps `inner` so that its operations fail after `deadline`.
+    #[unstable(feature = "io_deadline", issue = "0")]
+    pub fn new(inner: T, deadline: Instant) -> WithDeadline<T> {
+        WithDeadline { inner, deadline }
+    }
+
+    /// Returns the deadline.
+    #[unstable(feature = "io_deadline", issue = "0")]
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Replaces the deadline.
+    #[unstable(feature = "io_deadline", issue = "0")]
+    pub fn set_deadline(&mut self, deadline: Instant) {
+        self.deadline = deadline;
+    }
+
+    /// Returns the time left until the deadline, or `None` if it has
+    /// already passed.
+    #[unstable(feature = "io_deadline", issue = "0")]
+    pub fn remaining(&self) -> Option<Duration> {
+        let now = Instant::now();
+        if now < self.deadline {
+            Some(self.deadline - now)
+        } else {
+            None
+        }
+    }
+
+    /// Gets a reference to the inner object.
+    #[unstable(feature = "io_deadline", issue = "0")]
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the inner object.
+    ///
+    /// Operations performed directly on the inner object are not subject to
+    /// the deadline.
+    #[unstable(feature = "io_deadline", issue = "0")]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps this `WithDeadline`, returning the inner object.
+    #[unstable(feature = "io_deadline", issue = "0")]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn check(&self) -> io::Result<()> {
+        if Instant::now() >= self.deadline {
+            Err(io::Error::new(io::ErrorKind::TimedOut, "deadline has passed"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[unstable(feature = "io_deadline", issue = "0")]
+impl<T: Read> Read for WithDeadline<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.check()?;
+        self.inner.read(buf)
+    }
+}
+
+#[unstable(feature = "io_deadline", issue = "0")]
+impl<T: BufRead> BufRead for WithDeadline<T> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.check()?;
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+#[unstable(feature = "io_deadline", issue = "0")]
+impl<T: Write> Write for WithDeadline<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.check()?;
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.check()?;
+        self.inner.flush()
+    }
+}
+
+#[unstable(feature = "io_deadline", issue = "0")]
+impl<T: Seek> Seek for WithDeadline<T> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.check()?;
+        self.inner.seek(pos)
+    }
+}
+
+#[unstable(feature = "io_deadline", issue = "0")]
+impl<T: fmt::Debug> fmt::Debug for WithDeadline<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WithDeadline")
+            .field("inner", &self.inner)
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use io::prelude::*;
+    use io::{ErrorKind, WithDeadline};
+    use time::{Duration, Instant};
+
+    #[test]
+    fn operations_before_deadline() {
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let mut w = WithDeadline::new(Vec::new(), deadline);
+        w.write_all(b"abc").unwrap();
+        w.flush().unwrap();
+        assert!(w.remaining().is_some());
+        assert_eq!(w.into_inner(), b"abc");
+    }
+
+    #[test]
+    fn operations_after_deadline() {
+        // A deadline that is now has already passed.
+        let deadline = Instant::now();
+        let mut r = WithDeadline::new(&b"abc"[..], deadline);
+        assert_eq!(r.read(&mut [0; 3]).unwrap_err().kind(), ErrorKind::TimedOut);
+        assert_eq!(r.fill_buf().unwrap_err().kind(), ErrorKind::TimedOut);
+        assert!(r.remaining().is_none());
+        // Nothing was consumed from the inner reader.
+        assert_eq!(*r.get_ref(), b"abc");
+
+        let mut w = WithDeadline::new(Vec::new(), deadline);
+        assert_eq!(w.write(b"abc").unwrap_err().kind(), ErrorKind::TimedOut);
+        assert!(w.get_ref().is_empty());
+    }
+}
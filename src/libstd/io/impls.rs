@@ -24,6 +24,11 @@ impl<R: Read + ?Sized> Read for &mut R {
         (**self).initializer()
     }
 
+    #[inline]
+    fn remaining_hint(&self) -> Option<usize> {
+        (**self).remaining_hint()
+    }
+
     #[inline]
     fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
         (**self).read_to_end(buf)
@@ -103,6 +108,11 @@ impl<R: Read + ?Sized> Read for Box<R> {
         (**self).initializer()
     }
 
+    #[inline]
+    fn remaining_hint(&self) -> Option<usize> {
+        (**self).remaining_hint()
+    }
+
     #[inline]
     fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
         (**self).read_to_end(buf)
@@ -231,6 +241,11 @@ impl Read for &[u8] {
         Ok(())
     }
 
+    #[inline]
+    fn remaining_hint(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
     #[inline]
     fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
         buf.extend_from_slice(*self);
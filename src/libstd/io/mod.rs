@@ -259,6 +259,7 @@
 
 #![stable(feature = "rust1", since = "1.0.0")]
 
+use any::TypeId;
 use cmp;
 use fmt;
 use slice;
@@ -266,18 +267,29 @@ use str;
 use memchr;
 use ops::{Deref, DerefMut};
 use ptr;
+use result;
 use sys;
 
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::buffered::{BufReader, BufWriter, LineWriter};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::buffered::IntoInnerError;
+#[unstable(feature = "line_writer_policy", issue = "0")]
+pub use self::buffered::FlushPolicy;
+#[unstable(feature = "io_checksum", issue = "0")]
+pub use self::checksum::{Digest, Crc32, Adler32, HashingReader, HashingWriter};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::cursor::Cursor;
+#[unstable(feature = "io_deadline", issue = "0")]
+pub use self::deadline::WithDeadline;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::error::{Result, Error, ErrorKind};
+#[unstable(feature = "io_newlines", issue = "0")]
+pub use self::newline::{LineEnding, NormalizeNewlinesReader, NormalizeNewlinesWriter};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::util::{copy, sink, Sink, empty, Empty, repeat, Repeat};
+#[unstable(feature = "io_zero", issue = "0")]
+pub use self::util::{zero, Zero};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::stdio::{stdin, stdout, stderr, Stdin, Stdout, Stderr};
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -287,13 +299,18 @@ pub use self::stdio::{_print, _eprint};
 #[unstable(feature = "libstd_io_internals", issue = "42788")]
 #[doc(no_inline, hidden)]
 pub use self::stdio::{set_panic, set_print};
+#[unstable(feature = "stdio_capture", issue = "0")]
+pub use self::stdio::{capture_print, capture_eprint, CaptureGuard};
 
 pub mod prelude;
 mod buffered;
+mod checksum;
 mod cursor;
+mod deadline;
 mod error;
 mod impls;
 mod lazy;
+mod newline;
 mod util;
 mod stdio;
 
@@ -360,6 +377,12 @@ fn read_to_end_with_reservation<R: Read + ?Sized>(r: &mut R,
                                                   buf: &mut Vec<u8>,
                                                   reservation_size: usize) -> Result<usize>
 {
+    // Allocate one extra byte so the buffer doesn't need to grow before the
+    // final `read` call at the end of the stream.
+    if let Some(hint) = r.remaining_hint() {
+        buf.reserve(hint.saturating_add(1));
+    }
+
     let start_len = buf.len();
     let mut g = Guard { len: buf.len(), buf: buf };
     let ret;
@@ -566,6 +589,34 @@ pub trait Read {
         Initializer::zeroing()
     }
 
+    /// Returns the number of bytes left until EOF, if this reader can tell
+    /// cheaply, e.g. from the size of a file.
+    ///
+    /// This is only a hint: the stream may turn out to be shorter or longer,
+    /// e.g. if the file is modified concurrently. [`read_to_end`] uses it to
+    /// allocate its buffer once instead of growing it step by step.
+    ///
+    /// The default implementation returns `None`.
+    ///
+    /// [`read_to_end`]: #method.read_to_end
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(read_remaining_hint)]
+    /// use std::io::prelude::*;
+    ///
+    /// let mut reader: &[u8] = b"hello";
+    /// assert_eq!(reader.remaining_hint(), Some(5));
+    /// reader.read_exact(&mut [0; 2]).unwrap();
+    /// assert_eq!(reader.remaining_hint(), Some(3));
+    /// ```
+    #[unstable(feature = "read_remaining_hint", issue = "0")]
+    #[inline]
+    fn remaining_hint(&self) -> Option<usize> {
+        None
+    }
+
     /// Read all bytes until EOF in this source, placing them into `buf`.
     ///
     /// All bytes read from this source will be appended to the specified buffer
@@ -618,6 +669,55 @@ pub trait Read {
         read_to_end(self, buf)
     }
 
+    /// Read all bytes until EOF in this source, appending them to `buf`, but
+    /// fail if there are more than `max_bytes` of them.
+    ///
+    /// This is like [`read_to_end`], but safe to use on untrusted input, whose
+    /// length could otherwise exhaust memory. At most `max_bytes + 1` bytes
+    /// are read from this source.
+    ///
+    /// If successful, this function returns the number of bytes which were
+    /// read and appended to `buf`.
+    ///
+    /// # Errors
+    ///
+    /// If the source has more than `max_bytes` bytes, an error of the kind
+    /// [`ErrorKind::InvalidInput`] is returned, and the first `max_bytes`
+    /// bytes have been appended to `buf`.
+    ///
+    /// See [`read_to_end`] for other error semantics.
+    ///
+    /// [`read_to_end`]: #method.read_to_end
+    /// [`ErrorKind::InvalidInput`]: ../../std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(read_to_end_with_limit)]
+    /// use std::io::{self, prelude::*};
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut short: &[u8] = b"hello";
+    /// assert_eq!(short.read_to_end_with_limit(&mut buffer, 5).unwrap(), 5);
+    ///
+    /// let mut long: &[u8] = b"hello world";
+    /// let err = long.read_to_end_with_limit(&mut buffer, 5).unwrap_err();
+    /// assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    /// ```
+    #[unstable(feature = "read_to_end_with_limit", issue = "0")]
+    fn read_to_end_with_limit(&mut self, buf: &mut Vec<u8>, max_bytes: usize)
+                              -> Result<usize> {
+        let start_len = buf.len();
+        let mut take = Take { inner: self, limit: (max_bytes as u64).saturating_add(1) };
+        let n = take.read_to_end(buf)?;
+        if n > max_bytes {
+            buf.truncate(start_len + max_bytes);
+            return Err(Error::new(ErrorKind::InvalidInput,
+                                  "stream is longer than the limit"));
+        }
+        Ok(n)
+    }
+
     /// Read all bytes until EOF in this source, appending them to `buf`.
     ///
     /// If successful, this function returns the number of bytes which were read
@@ -670,6 +770,43 @@ pub trait Read {
         append_to_string(buf, |b| read_to_end(self, b))
     }
 
+    /// Read all bytes until EOF in this source, appending them to `buf` and
+    /// replacing any invalid UTF-8 sequences with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// This is like [`read_to_string`], but invalid UTF-8 is not an error: it
+    /// is converted the same way as by [`String::from_utf8_lossy`]. If
+    /// successful, this function returns the number of bytes which were read,
+    /// which may differ from the number of bytes appended to `buf`.
+    ///
+    /// # Errors
+    ///
+    /// See [`read_to_end`][readtoend] for error semantics. If an error is
+    /// returned, `buf` is unchanged.
+    ///
+    /// [`read_to_string`]: #method.read_to_string
+    /// [`String::from_utf8_lossy`]: ../string/struct.String.html#method.from_utf8_lossy
+    /// [readtoend]: #method.read_to_end
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(io_lossy_utf8)]
+    /// use std::io::prelude::*;
+    ///
+    /// let mut reader = &b"caf\xe9 ol\xc3\xa9"[..];
+    /// let mut buffer = String::new();
+    ///
+    /// assert_eq!(reader.read_to_string_lossy(&mut buffer).unwrap(), 9);
+    /// assert_eq!(buffer, "caf\u{FFFD} ol\u{e9}");
+    /// ```
+    #[unstable(feature = "io_lossy_utf8", issue = "0")]
+    fn read_to_string_lossy(&mut self, buf: &mut String) -> Result<usize> {
+        let mut bytes = Vec::new();
+        let n = read_to_end(self, &mut bytes)?;
+        buf.push_str(&String::from_utf8_lossy(&bytes));
+        Ok(n)
+    }
+
     /// Read the exact number of bytes required to fill `buf`.
     ///
     /// This function reads as many bytes as necessary to completely fill the
@@ -883,6 +1020,126 @@ pub trait Read {
     fn take(self, limit: u64) -> Take<Self> where Self: Sized {
         Take { inner: self, limit: limit }
     }
+
+    /// Gets the `TypeId` of `self`, for the downcasting methods of
+    /// `dyn Read`. It can't be overridden, since `Internal` can't be named
+    /// outside of this module.
+    #[doc(hidden)]
+    #[unstable(feature = "read_downcast", issue = "0")]
+    fn __read_type_id(&self, _: private::Internal) -> TypeId where Self: 'static {
+        TypeId::of::<Self>()
+    }
+}
+
+mod private {
+    #[derive(Debug)]
+    pub struct Internal;
+}
+
+/// Recovering the concrete type of a reader trait object, for instance to
+/// query capabilities that the `Read` trait doesn't describe.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(read_downcast)]
+/// use std::io::{Cursor, Read};
+///
+/// let mut reader: Box<dyn Read> = Box::new(Cursor::new(vec![1, 2, 3]));
+/// reader.read(&mut [0; 2]).unwrap();
+///
+/// if let Some(cursor) = reader.downcast_ref::<Cursor<Vec<u8>>>() {
+///     assert_eq!(cursor.position(), 2);
+/// }
+/// ```
+impl dyn Read + 'static {
+    /// Returns `true` if the boxed type is the same as `T`.
+    #[unstable(feature = "read_downcast", issue = "0")]
+    #[inline]
+    pub fn is<T: Read + 'static>(&self) -> bool {
+        TypeId::of::<T>() == self.__read_type_id(private::Internal)
+    }
+
+    /// Returns some reference to the boxed value if it is of type `T`, or
+    /// `None` if it isn't.
+    #[unstable(feature = "read_downcast", issue = "0")]
+    #[inline]
+    pub fn downcast_ref<T: Read + 'static>(&self) -> Option<&T> {
+        if self.is::<T>() {
+            unsafe {
+                Some(&*(self as *const dyn Read as *const T))
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Returns some mutable reference to the boxed value if it is of type
+    /// `T`, or `None` if it isn't.
+    #[unstable(feature = "read_downcast", issue = "0")]
+    #[inline]
+    pub fn downcast_mut<T: Read + 'static>(&mut self) -> Option<&mut T> {
+        if self.is::<T>() {
+            unsafe {
+                Some(&mut *(self as *mut dyn Read as *mut T))
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Attempts to downcast the box to a concrete type.
+    #[unstable(feature = "read_downcast", issue = "0")]
+    #[inline]
+    pub fn downcast<T: Read + 'static>(self: Box<Self>)
+                                       -> result::Result<Box<T>, Box<dyn Read>> {
+        if self.is::<T>() {
+            unsafe {
+                let raw: *mut dyn Read = Box::into_raw(self);
+                Ok(Box::from_raw(raw as *mut T))
+            }
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl dyn Read + 'static + Send {
+    /// Forwards to the method defined on the type `dyn Read`.
+    #[unstable(feature = "read_downcast", issue = "0")]
+    #[inline]
+    pub fn is<T: Read + 'static>(&self) -> bool {
+        <dyn Read + 'static>::is::<T>(self)
+    }
+
+    /// Forwards to the method defined on the type `dyn Read`.
+    #[unstable(feature = "read_downcast", issue = "0")]
+    #[inline]
+    pub fn downcast_ref<T: Read + 'static>(&self) -> Option<&T> {
+        <dyn Read + 'static>::downcast_ref::<T>(self)
+    }
+
+    /// Forwards to the method defined on the type `dyn Read`.
+    #[unstable(feature = "read_downcast", issue = "0")]
+    #[inline]
+    pub fn downcast_mut<T: Read + 'static>(&mut self) -> Option<&mut T> {
+        <dyn Read + 'static>::downcast_mut::<T>(self)
+    }
+
+    /// Attempts to downcast the box to a concrete type.
+    #[unstable(feature = "read_downcast", issue = "0")]
+    #[inline]
+    pub fn downcast<T: Read + 'static>(self: Box<Self>)
+                                       -> result::Result<Box<T>, Box<dyn Read + Send>> {
+        if self.is::<T>() {
+            unsafe {
+                let raw: *mut (dyn Read + Send) = Box::into_raw(self);
+                Ok(Box::from_raw(raw as *mut T))
+            }
+        } else {
+            Err(self)
+        }
+    }
 }
 
 /// A buffer type used with `Read::read_vectored`.
@@ -1329,6 +1586,111 @@ pub trait Seek {
     /// [`SeekFrom::Start`]: enum.SeekFrom.html#variant.Start
     #[stable(feature = "rust1", since = "1.0.0")]
     fn seek(&mut self, pos: SeekFrom) -> Result<u64>;
+
+    /// Rewind to the beginning of a stream.
+    ///
+    /// This is a convenience method, equivalent to `seek(SeekFrom::Start(0))`.
+    ///
+    /// # Errors
+    ///
+    /// Rewinding can fail, for example because it might involve flushing a
+    /// buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(seek_convenience)]
+    /// use std::io::{Cursor, Read, Seek};
+    ///
+    /// let mut cursor = Cursor::new(b"hello");
+    /// let mut buf = String::new();
+    /// cursor.read_to_string(&mut buf).unwrap();
+    ///
+    /// cursor.rewind().unwrap();
+    /// assert_eq!(cursor.position(), 0);
+    /// ```
+    #[unstable(feature = "seek_convenience", issue = "0")]
+    fn rewind(&mut self) -> Result<()> {
+        self.seek(SeekFrom::Start(0))?;
+        Ok(())
+    }
+
+    /// Returns the length of this stream (in bytes).
+    ///
+    /// This method is implemented using up to three seek operations. If this
+    /// method returns successfully, the seek position is unchanged (i.e. the
+    /// position before calling this method is the same as afterwards).
+    /// However, if this method returns an error, the seek position is
+    /// unspecified.
+    ///
+    /// If you need to obtain the length of *many* streams and you don't care
+    /// about the seek position afterwards, you can reduce the number of seek
+    /// operations by simply calling `seek(SeekFrom::End(0))` and using its
+    /// return value (it is also the stream length).
+    ///
+    /// Note that length of a stream can change over time (for example, when
+    /// data is appended to a file). So calling this method multiple times does
+    /// not necessarily return the same length each time.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(seek_convenience)]
+    /// use std::{
+    ///     io::{self, Seek},
+    ///     fs::File,
+    /// };
+    ///
+    /// fn main() -> io::Result<()> {
+    ///     let mut f = File::open("foo.txt")?;
+    ///
+    ///     let len = f.stream_len()?;
+    ///     println!("The file is currently {} bytes long", len);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "seek_convenience", issue = "0")]
+    fn stream_len(&mut self) -> Result<u64> {
+        let old_pos = self.stream_position()?;
+        let len = self.seek(SeekFrom::End(0))?;
+
+        // Avoid seeking a third time when we were already at the end of the
+        // stream. The branch is usually way cheaper than a seek operation.
+        if old_pos != len {
+            self.seek(SeekFrom::Start(old_pos))?;
+        }
+
+        Ok(len)
+    }
+
+    /// Returns the current seek position from the start of the stream.
+    ///
+    /// This is equivalent to `self.seek(SeekFrom::Current(0))`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(seek_convenience)]
+    /// use std::{
+    ///     io::{self, BufRead, BufReader, Seek},
+    ///     fs::File,
+    /// };
+    ///
+    /// fn main() -> io::Result<()> {
+    ///     let mut f = BufReader::new(File::open("foo.txt")?);
+    ///
+    ///     let before = f.stream_position()?;
+    ///     f.read_line(&mut String::new())?;
+    ///     let after = f.stream_position()?;
+    ///
+    ///     println!("The first line was {} bytes long", after - before);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "seek_convenience", issue = "0")]
+    fn stream_position(&mut self) -> Result<u64> {
+        self.seek(SeekFrom::Current(0))
+    }
 }
 
 /// Enumeration of possible methods to seek within an I/O object.
@@ -1389,6 +1751,80 @@ fn read_until<R: BufRead + ?Sized>(r: &mut R, delim: u8, buf: &mut Vec<u8>)
     }
 }
 
+fn read_until_seq<R: BufRead + ?Sized>(r: &mut R, delim: &[u8], buf: &mut Vec<u8>)
+                                       -> Result<usize> {
+    if delim.is_empty() {
+        return Err(Error::new(ErrorKind::InvalidInput, "empty delimiter"));
+    }
+    // Only bytes appended by this call may be part of the delimiter.
+    let start = buf.len();
+    let mut read = 0;
+    loop {
+        let (done, used) = {
+            let available = match r.fill_buf() {
+                Ok(n) => n,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e)
+            };
+            // The delimiter may begin in a previous buffer, so search the
+            // last bytes appended before this one too.
+            let prev_len = buf.len();
+            let from = cmp::max(start, prev_len.saturating_sub(delim.len() - 1));
+            buf.extend_from_slice(available);
+            match buf[from..].windows(delim.len()).position(|w| w == delim) {
+                Some(i) => {
+                    let end = from + i + delim.len();
+                    buf.truncate(end);
+                    (true, end - prev_len)
+                }
+                None => (false, available.len()),
+            }
+        };
+        r.consume(used);
+        read += used;
+        if done || used == 0 {
+            return Ok(read);
+        }
+    }
+}
+
+fn read_until_limited<R: BufRead + ?Sized>(r: &mut R, delim: u8, max_len: usize,
+                                           buf: &mut Vec<u8>) -> Result<usize> {
+    let mut read = 0;
+    loop {
+        let (done, used) = {
+            let available = match r.fill_buf() {
+                Ok(n) => n,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e)
+            };
+            if read == max_len {
+                if available.is_empty() {
+                    return Ok(read);
+                }
+                return Err(Error::new(ErrorKind::InvalidInput,
+                                      "delimiter not found within the length limit"));
+            }
+            let available = &available[..cmp::min(available.len(), max_len - read)];
+            match memchr::memchr(delim, available) {
+                Some(i) => {
+                    buf.extend_from_slice(&available[..=i]);
+                    (true, i + 1)
+                }
+                None => {
+                    buf.extend_from_slice(available);
+                    (false, available.len())
+                }
+            }
+        };
+        r.consume(used);
+        read += used;
+        if done || used == 0 {
+            return Ok(read);
+        }
+    }
+}
+
 /// A `BufRead` is a type of `Read`er which has an internal buffer, allowing it
 /// to perform extra ways of reading.
 ///
@@ -1568,6 +2004,93 @@ pub trait BufRead: Read {
         read_until(self, byte, buf)
     }
 
+    /// Read all bytes into `buf` until the delimiter sequence `delim` or EOF
+    /// is reached.
+    ///
+    /// This works like [`read_until`], but the delimiter can be several bytes
+    /// long, like the `"\r\n\r\n"` that ends the headers of an HTTP
+    /// message. The delimiter is found even if it is split across refills of
+    /// the internal buffer, and no bytes after it are consumed. Only bytes
+    /// read by this call are matched against the delimiter, not ones that
+    /// were already in `buf`.
+    ///
+    /// If successful, this function will return the total number of bytes
+    /// read, including the delimiter if it was found.
+    ///
+    /// # Errors
+    ///
+    /// This function has the same error semantics as [`read_until`], and will
+    /// also return an error of the kind [`ErrorKind::InvalidInput`] if `delim`
+    /// is empty.
+    ///
+    /// [`read_until`]: #method.read_until
+    /// [`ErrorKind::InvalidInput`]: enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(read_until_ext)]
+    /// use std::io::{self, BufRead};
+    ///
+    /// let mut cursor = io::Cursor::new(b"Host: a\r\nAccept: */*\r\n\r\nbody");
+    /// let mut headers = vec![];
+    ///
+    /// let num_bytes = cursor.read_until_seq(b"\r\n\r\n", &mut headers)
+    ///     .expect("reading from cursor won't fail");
+    /// assert_eq!(num_bytes, 24);
+    /// assert_eq!(headers, b"Host: a\r\nAccept: */*\r\n\r\n");
+    /// ```
+    #[unstable(feature = "read_until_ext", issue = "0")]
+    fn read_until_seq(&mut self, delim: &[u8], buf: &mut Vec<u8>) -> Result<usize> {
+        read_until_seq(self, delim, buf)
+    }
+
+    /// Read all bytes into `buf` until the delimiter `byte` or EOF is
+    /// reached, reading at most `max_len` bytes.
+    ///
+    /// This works like [`read_until`], but guards against input that never
+    /// contains the delimiter from growing `buf` without bound. `max_len`
+    /// includes the delimiter.
+    ///
+    /// If successful, this function will return the total number of bytes
+    /// read.
+    ///
+    /// # Errors
+    ///
+    /// This function has the same error semantics as [`read_until`], and will
+    /// also return an error of the kind [`ErrorKind::InvalidInput`] if neither
+    /// the delimiter nor EOF is found within `max_len` bytes. In that case the
+    /// `max_len` bytes read are appended to `buf`, and reading can continue
+    /// right after them.
+    ///
+    /// [`read_until`]: #method.read_until
+    /// [`ErrorKind::InvalidInput`]: enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(read_until_ext)]
+    /// use std::io::{self, BufRead, ErrorKind};
+    ///
+    /// let mut cursor = io::Cursor::new(b"short\na very long line\n");
+    /// let mut buf = vec![];
+    ///
+    /// let num_bytes = cursor.read_until_limited(b'\n', 8, &mut buf)
+    ///     .expect("reading from cursor won't fail");
+    /// assert_eq!(num_bytes, 6);
+    /// assert_eq!(buf, b"short\n");
+    /// buf.clear();
+    ///
+    /// let err = cursor.read_until_limited(b'\n', 8, &mut buf).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    /// assert_eq!(buf, b"a very l");
+    /// ```
+    #[unstable(feature = "read_until_ext", issue = "0")]
+    fn read_until_limited(&mut self, byte: u8, max_len: usize, buf: &mut Vec<u8>)
+                          -> Result<usize> {
+        read_until_limited(self, byte, max_len, buf)
+    }
+
     /// Read all bytes until a newline (the 0xA byte) is reached, and append
     /// them to the provided buffer.
     ///
@@ -1706,6 +2229,44 @@ pub trait BufRead: Read {
     fn lines(self) -> Lines<Self> where Self: Sized {
         Lines { buf: self }
     }
+
+    /// Returns an iterator over the UTF-8 encoded characters of this reader.
+    ///
+    /// The iterator returned from this function will yield instances of
+    /// [`io::Result`]`<`[`char`]`>`. A character whose encoding straddles two
+    /// calls to [`fill_buf`] is decoded as usual.
+    ///
+    /// # Errors
+    ///
+    /// An invalid or truncated UTF-8 sequence yields an error of the kind
+    /// [`ErrorKind::InvalidData`]. The bytes of that sequence are consumed, so
+    /// iteration may continue with the next character, e.g. to substitute
+    /// `U+FFFD REPLACEMENT CHARACTER` for it. Errors from [`fill_buf`] other
+    /// than [`ErrorKind::Interrupted`] are yielded as they are.
+    ///
+    /// [`io::Result`]: type.Result.html
+    /// [`char`]: ../primitive.char.html
+    /// [`fill_buf`]: #tymethod.fill_buf
+    /// [`ErrorKind::InvalidData`]: enum.ErrorKind.html#variant.InvalidData
+    /// [`ErrorKind::Interrupted`]: enum.ErrorKind.html#variant.Interrupted
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(io_lossy_utf8)]
+    /// use std::io::{self, BufRead};
+    ///
+    /// let cursor = io::Cursor::new(b"a\xffb\xc3\xa9");
+    ///
+    /// let text = cursor.chars()
+    ///     .map(|c| c.unwrap_or('\u{FFFD}'))
+    ///     .collect::<String>();
+    /// assert_eq!(text, "a\u{FFFD}b\u{e9}");
+    /// ```
+    #[unstable(feature = "io_lossy_utf8", issue = "0")]
+    fn chars(self) -> Chars<Self> where Self: Sized {
+        Chars { buf: self }
+    }
 }
 
 /// Adaptor to chain together two readers.
@@ -2029,6 +2590,10 @@ impl<T: Read> Read for Take<T> {
         self.inner.initializer()
     }
 
+    fn remaining_hint(&self) -> Option<usize> {
+        self.inner.remaining_hint().map(|hint| cmp::min(hint as u64, self.limit) as usize)
+    }
+
     fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
         let reservation_size = cmp::min(self.limit, 32) as usize;
 
@@ -2153,11 +2718,76 @@ impl<B: BufRead> Iterator for Lines<B> {
     }
 }
 
+/// An iterator over the `char`s of a `BufRead`.
+///
+/// This struct is generally created by calling [`chars`] on a `BufRead`.
+/// Please see the documentation of [`chars`] for more details.
+///
+/// [`chars`]: trait.BufRead.html#method.chars
+#[unstable(feature = "io_lossy_utf8", issue = "0")]
+#[derive(Debug)]
+pub struct Chars<B> {
+    buf: B,
+}
+
+impl<B: BufRead> Chars<B> {
+    /// Returns the next byte without consuming it, or `None` at EOF.
+    fn peek_byte(&mut self) -> Result<Option<u8>> {
+        loop {
+            return match self.buf.fill_buf() {
+                Ok(buf) => Ok(buf.first().cloned()),
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => Err(e),
+            };
+        }
+    }
+}
+
+#[unstable(feature = "io_lossy_utf8", issue = "0")]
+impl<B: BufRead> Iterator for Chars<B> {
+    type Item = Result<char>;
+
+    fn next(&mut self) -> Option<Result<char>> {
+        let first = match self.peek_byte() {
+            Ok(Some(b)) => b,
+            Ok(None) => return None,
+            Err(e) => return Some(Err(e)),
+        };
+        self.buf.consume(1);
+
+        let width = ::core::str::utf8_char_width(first);
+        if width == 0 {
+            return Some(Err(invalid_utf8()));
+        }
+        let mut bytes = [first, 0, 0, 0];
+        for i in 1..width {
+            // Only continuation bytes belong to this character; anything else
+            // is left in place to start the next one.
+            match self.peek_byte() {
+                Ok(Some(b)) if b & 0xC0 == 0x80 => {
+                    bytes[i] = b;
+                    self.buf.consume(1);
+                }
+                Ok(_) => break,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        match str::from_utf8(&bytes[..width]) {
+            Ok(s) => Some(Ok(s.chars().next().unwrap())),
+            Err(_) => Some(Err(invalid_utf8())),
+        }
+    }
+}
+
+fn invalid_utf8() -> Error {
+    Error::new(ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+}
+
 #[cfg(test)]
 mod tests {
     use io::prelude::*;
     use io;
-    use super::Cursor;
+    use super::{Cursor, SeekFrom};
     use test;
     use super::repeat;
 
@@ -2181,6 +2811,46 @@ mod tests {
         assert_eq!(v, []);
     }
 
+    #[test]
+    fn read_until_seq() {
+        // A small buffer splits the delimiter across refills.
+        let mut buf = io::BufReader::with_capacity(3, &b"ab\r\n\r\ncd\r\n"[..]);
+        let mut v = b"\r\n\r".to_vec();
+        assert_eq!(buf.read_until_seq(b"\r\n\r\n", &mut v).unwrap(), 6);
+        assert_eq!(v, b"\r\n\rab\r\n\r\n");
+        v.truncate(0);
+        assert_eq!(buf.read_until_seq(b"\r\n\r\n", &mut v).unwrap(), 4);
+        assert_eq!(v, b"cd\r\n");
+        v.truncate(0);
+        assert_eq!(buf.read_until_seq(b"\r\n\r\n", &mut v).unwrap(), 0);
+
+        let mut buf = Cursor::new(&b"aab"[..]);
+        assert_eq!(buf.read_until_seq(b"ab", &mut v).unwrap(), 3);
+        assert_eq!(v, b"aab");
+        assert_eq!(buf.read_until_seq(b"", &mut v).unwrap_err().kind(),
+                   io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn read_until_limited() {
+        let mut buf = io::BufReader::with_capacity(2, &b"123\n45678\n9"[..]);
+        let mut v = Vec::new();
+        assert_eq!(buf.read_until_limited(b'\n', 4, &mut v).unwrap(), 4);
+        assert_eq!(v, b"123\n");
+        v.truncate(0);
+        assert_eq!(buf.read_until_limited(b'\n', 4, &mut v).unwrap_err().kind(),
+                   io::ErrorKind::InvalidInput);
+        assert_eq!(v, b"4567");
+        v.truncate(0);
+        assert_eq!(buf.read_until_limited(b'\n', 4, &mut v).unwrap(), 2);
+        assert_eq!(v, b"8\n");
+        v.truncate(0);
+        assert_eq!(buf.read_until_limited(b'\n', 1, &mut v).unwrap(), 1);
+        assert_eq!(v, b"9");
+        v.truncate(0);
+        assert_eq!(buf.read_until_limited(b'\n', 1, &mut v).unwrap(), 0);
+    }
+
     #[test]
     fn split() {
         let buf = Cursor::new(&b"12"[..]);
@@ -2228,6 +2898,39 @@ mod tests {
         assert!(s.next().is_none());
     }
 
+    #[test]
+    fn chars() {
+        let buf = Cursor::new("a\u{e9}\u{20ac}\u{1f600}".as_bytes());
+        let s = buf.chars().map(|c| c.unwrap()).collect::<String>();
+        assert_eq!(s, "a\u{e9}\u{20ac}\u{1f600}");
+
+        // Invalid and truncated sequences are errors, but iteration continues.
+        let buf = Cursor::new(&b"\xffa\xe2\x82b\xed\xa0\x80c\xf0\x9f"[..]);
+        let s = buf.chars().map(|c| c.unwrap_or('?')).collect::<String>();
+        assert_eq!(s, "?a?b?c?");
+
+        let mut buf = Cursor::new(&b"\x80"[..]).chars();
+        assert_eq!(buf.next().unwrap().unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert!(buf.next().is_none());
+    }
+
+    #[test]
+    fn chars_across_buffers() {
+        // A reader that hands out one byte at a time.
+        let inner = Cursor::new("\u{e9}\u{1f600}".as_bytes());
+        let buf = io::BufReader::with_capacity(1, inner);
+        let s = buf.chars().map(|c| c.unwrap()).collect::<String>();
+        assert_eq!(s, "\u{e9}\u{1f600}");
+    }
+
+    #[test]
+    fn read_to_string_lossy() {
+        let mut c = Cursor::new(&b"ab\xffcd\xe2\x82"[..]);
+        let mut s = "x".to_string();
+        assert_eq!(c.read_to_string_lossy(&mut s).unwrap(), 7);
+        assert_eq!(s, "xab\u{FFFD}cd\u{FFFD}");
+    }
+
     #[test]
     fn read_to_end() {
         let mut c = Cursor::new(&b""[..]);
@@ -2249,6 +2952,42 @@ mod tests {
         assert_eq!(v, data);
     }
 
+    #[test]
+    fn read_to_end_with_limit() {
+        let mut v = Vec::new();
+        assert_eq!((&b"hello"[..]).read_to_end_with_limit(&mut v, 5).unwrap(), 5);
+        assert_eq!((&b""[..]).read_to_end_with_limit(&mut v, 0).unwrap(), 0);
+        assert_eq!(v, b"hello");
+
+        let mut c = Cursor::new(&b"12345678"[..]);
+        let err = c.read_to_end_with_limit(&mut v, 3).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(v, b"hello123");
+        assert_eq!(c.position(), 4);
+
+        // Readers without a size hint are cut off too.
+        let mut v = Vec::new();
+        let err = repeat(1).read_to_end_with_limit(&mut v, 100).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(v.len(), 100);
+    }
+
+    #[test]
+    fn remaining_hint() {
+        let mut c = Cursor::new(vec![0; 10]);
+        assert_eq!(c.remaining_hint(), Some(10));
+        c.set_position(4);
+        assert_eq!(c.remaining_hint(), Some(6));
+        assert_eq!(Read::take(&mut c, 2).remaining_hint(), Some(2));
+        c.set_position(20);
+        assert_eq!(c.remaining_hint(), Some(0));
+        assert_eq!(repeat(0).remaining_hint(), None);
+
+        let mut v = Vec::new();
+        Cursor::new(vec![1; 100]).read_to_end(&mut v).unwrap();
+        assert_eq!(v.capacity(), 101);
+    }
+
     #[test]
     fn read_to_string() {
         let mut c = Cursor::new(&b""[..]);
@@ -2372,6 +3111,28 @@ mod tests {
         assert_eq!("AB", s);
     }
 
+    #[test]
+    fn stream_len_and_position() {
+        let mut c = Cursor::new(vec![0; 15]);
+        assert_eq!(c.stream_len().unwrap(), 15);
+        assert_eq!(c.stream_position().unwrap(), 0);
+
+        c.seek(SeekFrom::End(0)).unwrap();
+        assert_eq!(c.stream_len().unwrap(), 15);
+        assert_eq!(c.stream_position().unwrap(), 15);
+
+        c.seek(SeekFrom::Start(10)).unwrap();
+        assert_eq!(c.stream_len().unwrap(), 15);
+        assert_eq!(c.stream_position().unwrap(), 10);
+
+        c.seek(SeekFrom::Start(100)).unwrap();
+        assert_eq!(c.stream_len().unwrap(), 15);
+        assert_eq!(c.stream_position().unwrap(), 100);
+
+        c.rewind().unwrap();
+        assert_eq!(c.stream_position().unwrap(), 0);
+    }
+
     #[bench]
     #[cfg_attr(target_os = "emscripten", ignore)]
     fn bench_read_to_end(b: &mut test::Bencher) {
@@ -2381,4 +3142,23 @@ mod tests {
             super::read_to_end(&mut lr, &mut vec)
         });
     }
+
+    #[test]
+    fn read_downcast() {
+        let mut reader: Box<dyn Read> = Box::new(Cursor::new(vec![1, 2, 3]));
+        assert_eq!(reader.read(&mut [0; 2]).unwrap(), 2);
+        assert!(reader.is::<Cursor<Vec<u8>>>());
+        assert!(!reader.is::<io::Empty>());
+        assert!(reader.downcast_ref::<io::Empty>().is_none());
+        reader.downcast_mut::<Cursor<Vec<u8>>>().unwrap().set_position(0);
+
+        let reader = match reader.downcast::<io::Repeat>() {
+            Ok(..) => panic!("expected error"),
+            Err(reader) => reader,
+        };
+        assert_eq!(reader.downcast::<Cursor<Vec<u8>>>().unwrap().position(), 0);
+
+        let reader: Box<dyn Read + Send> = Box::new(io::empty());
+        assert!(reader.downcast_ref::<io::Empty>().is_some());
+    }
 }
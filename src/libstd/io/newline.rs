@@ -0,0 +1,389 @@
+//! Line-ending normalizing adapters for readers and writers.
+
+use fmt;
+use io::{self, Read, Write};
+use memchr;
+
+/// The line ending a [`NormalizeNewlinesWriter`] writes.
+///
+/// [`NormalizeNewlinesWriter`]: struct.NormalizeNewlinesWriter.html
+#[unstable(feature = "io_newlines", issue = "0")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `"\n"`, as used on Unix.
+    Lf,
+    /// `"\r\n"`, as used on Windows.
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the line ending of the platform the program is running on.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn native() -> LineEnding {
+        if cfg!(windows) { LineEnding::CrLf } else { LineEnding::Lf }
+    }
+}
+
+/// A reader that converts `"\r\n"` line endings into `"\n"`.
+///
+/// A `'\r'` that is not followed by `'\n'` is passed through unchanged. A
+/// `"\r\n"` that is split across two reads of the underlying reader is still
+/// converted: a trailing `'\r'` is held back until the next byte is known.
+///
+/// Conversion can be turned off with [`set_pass_through`], so that the same
+/// reader type can be used whether or not the input needs normalizing.
+///
+/// [`set_pass_through`]: #method.set_pass_through
+///
+/// # Examples
+///
+/// ```
+/// #![feature(io_newlines)]
+/// use std::io::{NormalizeNewlinesReader, Read};
+///
+/// let mut reader = NormalizeNewlinesReader::new(&b"one\r\ntwo\rthree\r\n"[..]);
+/// let mut s = String::new();
+/// reader.read_to_string(&mut s).unwrap();
+/// assert_eq!(s, "one\ntwo\rthree\n");
+/// ```
+#[unstable(feature = "io_newlines", issue = "0")]
+pub struct NormalizeNewlinesReader<R> {
+    inner: R,
+    pass_through: bool,
+    /// A `'\r'` was read from `inner` but not returned yet.
+    cr: bool,
+    /// A byte that was read from `inner` after a held-back `'\r'` but did not
+    /// fit into the caller's buffer.
+    next: Option<u8>,
+}
+
+impl<R> NormalizeNewlinesReader<R> {
+    /// Wraps `inner`, converting the line endings read through it.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn new(inner: R) -> NormalizeNewlinesReader<R> {
+        NormalizeNewlinesReader { inner, pass_through: false, cr: false, next: None }
+    }
+
+    /// Returns whether data is passed through without conversion.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn pass_through(&self) -> bool {
+        self.pass_through
+    }
+
+    /// Turns the conversion off or back on.
+    ///
+    /// A `'\r'` held back before the conversion is turned off is still
+    /// returned by the next read.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn set_pass_through(&mut self, pass_through: bool) {
+        self.pass_through = pass_through;
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// Reading directly from the underlying reader skips any `'\r'` that is
+    /// held back by this reader.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this `NormalizeNewlinesReader`, returning the underlying
+    /// reader.
+    ///
+    /// Any `'\r'` held back by this reader is lost.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[unstable(feature = "io_newlines", issue = "0")]
+impl<R: Read> Read for NormalizeNewlinesReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if let Some(byte) = self.next.take() {
+            buf[0] = byte;
+            return Ok(1);
+        }
+        if self.pass_through {
+            if self.cr {
+                self.cr = false;
+                buf[0] = b'\r';
+                return Ok(1);
+            }
+            return self.inner.read(buf);
+        }
+
+        loop {
+            if self.cr && buf.len() == 1 {
+                // There is no room to read behind the held-back '\r'.
+                let mut byte = [0];
+                let n = self.inner.read(&mut byte)?;
+                self.cr = n == 1 && byte[0] == b'\r';
+                buf[0] = if n == 1 && byte[0] == b'\n' { b'\n' } else { b'\r' };
+                if n == 1 && byte[0] != b'\r' && byte[0] != b'\n' {
+                    self.next = Some(byte[0]);
+                }
+                return Ok(1);
+            }
+
+            let start = if self.cr {
+                buf[0] = b'\r';
+                1
+            } else {
+                0
+            };
+            let n = self.inner.read(&mut buf[start..])?;
+            self.cr = false;
+            if n == 0 {
+                return Ok(start);
+            }
+
+            let end = start + n;
+            let mut written = 0;
+            let mut i = 0;
+            while i < end {
+                let byte = buf[i];
+                i += 1;
+                if byte == b'\r' {
+                    if i == end {
+                        self.cr = true;
+                        break;
+                    }
+                    if buf[i] == b'\n' {
+                        continue;
+                    }
+                }
+                buf[written] = byte;
+                written += 1;
+            }
+
+            // Returning 0 would signal the end of the input, so if all that
+            // was read is a held-back '\r', read again.
+            if written > 0 {
+                return Ok(written);
+            }
+        }
+    }
+}
+
+#[unstable(feature = "io_newlines", issue = "0")]
+impl<R: fmt::Debug> fmt::Debug for NormalizeNewlinesReader<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("NormalizeNewlinesReader")
+            .field("inner", &self.inner)
+            .field("pass_through", &self.pass_through)
+            .finish()
+    }
+}
+
+/// A writer that writes each `'\n'` with a given [`LineEnding`].
+///
+/// With [`LineEnding::CrLf`], every `'\n'` that is not already preceded by
+/// `'\r'` is written as `"\r\n"`, also when the `'\r'` was written by an
+/// earlier call. With [`LineEnding::Lf`], data is passed through unchanged,
+/// so using [`LineEnding::native`] gives a writer that only converts where
+/// the platform needs it.
+///
+/// [`LineEnding`]: enum.LineEnding.html
+/// [`LineEnding::CrLf`]: enum.LineEnding.html#variant.CrLf
+/// [`LineEnding::Lf`]: enum.LineEnding.html#variant.Lf
+/// [`LineEnding::native`]: enum.LineEnding.html#method.native
+///
+/// # Examples
+///
+/// ```
+/// #![feature(io_newlines)]
+/// use std::io::{LineEnding, NormalizeNewlinesWriter, Write};
+///
+/// let mut writer = NormalizeNewlinesWriter::new(Vec::new(), LineEnding::CrLf);
+/// writer.write_all(b"one\ntwo\r\n").unwrap();
+/// assert_eq!(writer.get_ref(), b"one\r\ntwo\r\n");
+/// ```
+#[unstable(feature = "io_newlines", issue = "0")]
+pub struct NormalizeNewlinesWriter<W> {
+    inner: W,
+    ending: LineEnding,
+    /// The last byte written to `inner` was a `'\r'`.
+    cr: bool,
+}
+
+impl<W> NormalizeNewlinesWriter<W> {
+    /// Wraps `inner`, writing newlines with `ending`.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn new(inner: W, ending: LineEnding) -> NormalizeNewlinesWriter<W> {
+        NormalizeNewlinesWriter { inner, ending, cr: false }
+    }
+
+    /// Returns the line ending newlines are written with.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn line_ending(&self) -> LineEnding {
+        self.ending
+    }
+
+    /// Changes the line ending newlines are written with.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn set_line_ending(&mut self, ending: LineEnding) {
+        self.ending = ending;
+    }
+
+    /// Gets a reference to the underlying writer.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this `NormalizeNewlinesWriter`, returning the underlying
+    /// writer.
+    #[unstable(feature = "io_newlines", issue = "0")]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[unstable(feature = "io_newlines", issue = "0")]
+impl<W: Write> Write for NormalizeNewlinesWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let end = match self.ending {
+            LineEnding::Lf => buf.len(),
+            LineEnding::CrLf => {
+                if buf[0] == b'\n' && !self.cr {
+                    // The '\n' itself is written along with what follows it.
+                    if self.inner.write(b"\r")? == 0 {
+                        return Ok(0);
+                    }
+                    self.cr = true;
+                }
+                // Stop in front of the next '\n' that needs a '\r'.
+                let mut end = 1;
+                loop {
+                    match memchr::memchr(b'\n', &buf[end..]) {
+                        Some(i) if buf[end + i - 1] == b'\r' => end += i + 1,
+                        Some(i) => break end + i,
+                        None => break buf.len(),
+                    }
+                }
+            }
+        };
+
+        let n = self.inner.write(&buf[..end])?;
+        if n > 0 {
+            self.cr = buf[n - 1] == b'\r';
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[unstable(feature = "io_newlines", issue = "0")]
+impl<W: fmt::Debug> fmt::Debug for NormalizeNewlinesWriter<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("NormalizeNewlinesWriter")
+            .field("inner", &self.inner)
+            .field("ending", &self.ending)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use io::prelude::*;
+    use io::{self, LineEnding, NormalizeNewlinesReader, NormalizeNewlinesWriter};
+
+    /// Returns the bytes of `chunks` one chunk per read.
+    struct Chunks<'a>(&'a [&'a [u8]]);
+
+    impl<'a> Read for Chunks<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((chunk, rest)) => {
+                    buf[..chunk.len()].copy_from_slice(chunk);
+                    self.0 = rest;
+                    Ok(chunk.len())
+                }
+                None => Ok(0),
+            }
+        }
+    }
+
+    fn read_all<R: Read>(mut reader: R, buf_len: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut buf = vec![0; buf_len];
+        loop {
+            match reader.read(&mut buf).unwrap() {
+                0 => return out,
+                n => out.extend_from_slice(&buf[..n]),
+            }
+        }
+    }
+
+    #[test]
+    fn reader_converts_crlf() {
+        let input = b"a\r\nb\rc\r\r\nd\r";
+        for &buf_len in &[1, 2, 3, 64] {
+            let reader = NormalizeNewlinesReader::new(&input[..]);
+            assert_eq!(read_all(reader, buf_len), b"a\nb\rc\r\nd\r", "buf_len {}", buf_len);
+        }
+    }
+
+    #[test]
+    fn reader_split_crlf() {
+        let chunks: &[&[u8]] = &[b"a\r", b"\nb\r", b"\r", b"\n", b"\r", b"c"];
+        let reader = NormalizeNewlinesReader::new(Chunks(chunks));
+        assert_eq!(read_all(reader, 64), b"a\nb\r\n\rc");
+    }
+
+    #[test]
+    fn reader_pass_through() {
+        let mut reader = NormalizeNewlinesReader::new(&b"a\r\nb\r\n"[..]);
+        reader.set_pass_through(true);
+        assert_eq!(read_all(reader, 64), b"a\r\nb\r\n");
+    }
+
+    #[test]
+    fn writer_converts_lf() {
+        let mut writer = NormalizeNewlinesWriter::new(Vec::new(), LineEnding::CrLf);
+        writer.write_all(b"\na\nb\r\n\n").unwrap();
+        // A '\r' written by an earlier call still counts.
+        writer.write_all(b"c\r").unwrap();
+        writer.write_all(b"\nd").unwrap();
+        assert_eq!(writer.get_ref(), b"\r\na\r\nb\r\n\r\nc\r\nd");
+
+        let mut writer = NormalizeNewlinesWriter::new(Vec::new(), LineEnding::Lf);
+        writer.write_all(b"a\nb\r\n").unwrap();
+        assert_eq!(writer.into_inner(), b"a\nb\r\n");
+    }
+
+    #[test]
+    fn writer_partial_writes() {
+        let mut buf = [0; 8];
+        {
+            let mut writer = NormalizeNewlinesWriter::new(&mut buf[..], LineEnding::CrLf);
+            writer.write_all(b"ab\ncd\n").unwrap();
+            assert!(writer.write_all(b"e").is_err());
+        }
+        assert_eq!(&buf, b"ab\r\ncd\r\n");
+    }
+}
@@ -4,6 +4,7 @@ use cell::RefCell;
 use fmt;
 use io::lazy::Lazy;
 use io::{self, Initializer, BufReader, LineWriter};
+use marker::PhantomData;
 use sync::{Arc, Mutex, MutexGuard};
 use sys::stdio;
 use sys_common::remutex::{ReentrantMutex, ReentrantMutexGuard};
@@ -701,6 +702,95 @@ pub fn set_print(sink: Option<Box<dyn Write + Send>>) -> Option<Box<dyn Write +
     })
 }
 
+/// Redirects the output of `print!` and `println!` on the current thread to
+/// `sink` until the returned guard is dropped.
+///
+/// When the guard is dropped, `sink` is flushed and dropped, and the output
+/// goes back to wherever it went before, which may be an enclosing capture.
+/// Guards should therefore be dropped in the reverse order of their creation.
+///
+/// Only the output of the macros is captured: writing to [`stdout`] directly,
+/// or printing from other threads, still goes to the process's stdout.
+///
+/// [`stdout`]: fn.stdout.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(stdio_capture)]
+/// use std::io::{self, Write};
+/// use std::sync::{Arc, Mutex};
+///
+/// struct Shared(Arc<Mutex<Vec<u8>>>);
+///
+/// impl Write for Shared {
+///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+///         self.0.lock().unwrap().write(buf)
+///     }
+///     fn flush(&mut self) -> io::Result<()> { Ok(()) }
+/// }
+///
+/// let buf = Arc::new(Mutex::new(Vec::new()));
+/// {
+///     let _guard = io::capture_print(Box::new(Shared(buf.clone())));
+///     println!("captured");
+/// }
+/// assert_eq!(&buf.lock().unwrap()[..], b"captured\n");
+/// ```
+#[unstable(feature = "stdio_capture", issue = "0")]
+pub fn capture_print(sink: Box<dyn Write + Send>) -> CaptureGuard {
+    CaptureGuard::new(set_print, sink)
+}
+
+/// Redirects the output of `eprint!`, `eprintln!` and panic messages on the
+/// current thread to `sink` until the returned guard is dropped.
+///
+/// See [`capture_print`] for the details.
+///
+/// [`capture_print`]: fn.capture_print.html
+#[unstable(feature = "stdio_capture", issue = "0")]
+pub fn capture_eprint(sink: Box<dyn Write + Send>) -> CaptureGuard {
+    CaptureGuard::new(set_panic, sink)
+}
+
+/// A guard which restores the previous output handle of the current thread
+/// when dropped.
+///
+/// This structure is created by the [`capture_print`] and [`capture_eprint`]
+/// functions.
+///
+/// [`capture_print`]: fn.capture_print.html
+/// [`capture_eprint`]: fn.capture_eprint.html
+#[unstable(feature = "stdio_capture", issue = "0")]
+#[must_use = "the output is only captured until the guard is dropped"]
+pub struct CaptureGuard {
+    set: fn(Option<Box<dyn Write + Send>>) -> Option<Box<dyn Write + Send>>,
+    previous: Option<Box<dyn Write + Send>>,
+    // The handle is thread-local, so it must be restored on the same thread.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl CaptureGuard {
+    fn new(set: fn(Option<Box<dyn Write + Send>>) -> Option<Box<dyn Write + Send>>,
+           sink: Box<dyn Write + Send>) -> CaptureGuard {
+        CaptureGuard { set, previous: set(Some(sink)), _not_send: PhantomData }
+    }
+}
+
+#[unstable(feature = "stdio_capture", issue = "0")]
+impl Drop for CaptureGuard {
+    fn drop(&mut self) {
+        (self.set)(self.previous.take());
+    }
+}
+
+#[unstable(feature = "stdio_capture", issue = "0")]
+impl fmt::Debug for CaptureGuard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("CaptureGuard { .. }")
+    }
+}
+
 /// Write `args` to output stream `local_s` if possible, `global_s`
 /// otherwise. `label` identifies the stream in a panic message.
 ///
@@ -780,6 +870,36 @@ mod tests {
 
     fn assert_unwind_safe<T: UnwindSafe + RefUnwindSafe>() {}
 
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    #[test]
+    fn capture_nested() {
+        let outer = Arc::new(Mutex::new(Vec::new()));
+        let inner = Arc::new(Mutex::new(Vec::new()));
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        {
+            let _outer = capture_print(Box::new(Shared(outer.clone())));
+            let _errors = capture_eprint(Box::new(Shared(errors.clone())));
+            print!("a");
+            {
+                let _inner = capture_print(Box::new(Shared(inner.clone())));
+                println!("b");
+                eprint!("c");
+            }
+            print!("d");
+        }
+        assert_eq!(&outer.lock().unwrap()[..], b"ad");
+        assert_eq!(&inner.lock().unwrap()[..], b"b\n");
+        assert_eq!(&errors.lock().unwrap()[..], b"c");
+    }
+
     #[test]
     #[cfg_attr(target_os = "emscripten", ignore)]
     fn panic_doesnt_poison() {
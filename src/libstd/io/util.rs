@@ -174,6 +174,79 @@ impl fmt::Debug for Repeat {
     }
 }
 
+/// A reader which yields zeros forever.
+///
+/// This struct is generally created by calling [`zero`][zero]. Please
+/// see the documentation of `zero()` for more details.
+///
+/// [zero]: fn.zero.html
+#[unstable(feature = "io_zero", issue = "0")]
+pub struct Zero { _priv: () }
+
+/// Creates an instance of a reader that infinitely yields zeros.
+///
+/// All reads from this reader will succeed by filling the specified buffer
+/// with zeros. Unlike [`repeat(0)`][repeat], it also implements [`BufRead`],
+/// handing out zeros from a static buffer.
+///
+/// [repeat]: fn.repeat.html
+/// [`BufRead`]: trait.BufRead.html
+///
+/// # Examples
+///
+/// ```
+/// #![feature(io_zero)]
+/// use std::io::{self, Read};
+///
+/// let mut buffer = [1; 3];
+/// io::zero().read_exact(&mut buffer).unwrap();
+/// assert_eq!(buffer, [0, 0, 0]);
+/// ```
+#[unstable(feature = "io_zero", issue = "0")]
+pub fn zero() -> Zero { Zero { _priv: () } }
+
+static ZEROS: [u8; 1024] = [0; 1024];
+
+#[unstable(feature = "io_zero", issue = "0")]
+impl Read for Zero {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        for slot in &mut *buf {
+            *slot = 0;
+        }
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoVecMut<'_>]) -> io::Result<usize> {
+        let mut nwritten = 0;
+        for buf in bufs {
+            nwritten += self.read(buf)?;
+        }
+        Ok(nwritten)
+    }
+
+    #[inline]
+    unsafe fn initializer(&self) -> Initializer {
+        Initializer::nop()
+    }
+}
+
+#[unstable(feature = "io_zero", issue = "0")]
+impl BufRead for Zero {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> { Ok(&ZEROS) }
+    #[inline]
+    fn consume(&mut self, _amt: usize) {}
+}
+
+#[unstable(feature = "io_zero", issue = "0")]
+impl fmt::Debug for Zero {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("Zero { .. }")
+    }
+}
+
 /// A writer which will move data into the void.
 ///
 /// This struct is generally created by calling [`sink`][sink]. Please
@@ -225,7 +298,7 @@ impl fmt::Debug for Sink {
 #[cfg(test)]
 mod tests {
     use io::prelude::*;
-    use io::{copy, sink, empty, repeat};
+    use io::{copy, sink, empty, repeat, zero};
 
     #[test]
     fn copy_copies() {
@@ -263,6 +336,18 @@ mod tests {
         assert!(b.iter().all(|b| *b == 4));
     }
 
+    #[test]
+    fn zero_zeros() {
+        let mut z = zero();
+        let mut b = [1; 1024];
+        assert_eq!(z.read(&mut b).unwrap(), 1024);
+        assert!(b.iter().all(|b| *b == 0));
+
+        assert!(!z.fill_buf().unwrap().is_empty());
+        assert!(z.fill_buf().unwrap().iter().all(|b| *b == 0));
+        assert_eq!(zero().take(3000).bytes().count(), 3000);
+    }
+
     #[test]
     fn take_some_bytes() {
         assert_eq!(repeat(4).take(100).bytes().count(), 100);
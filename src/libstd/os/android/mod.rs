@@ -4,3 +4,5 @@
 
 pub mod raw;
 pub mod fs;
+#[path = "../linux/net.rs"]
+pub mod net;
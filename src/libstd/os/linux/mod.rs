@@ -4,3 +4,5 @@
 
 pub mod raw;
 pub mod fs;
+#[cfg(target_os = "linux")]
+pub mod net;
@@ -0,0 +1,463 @@
+//! Networking extensions for Linux and Android.
+//!
+//! Scatter/gather I/O and ancillary data on UDP sockets through `sendmsg(2)`
+//! and `recvmsg(2)`.
+
+#![unstable(feature = "udp_msg", issue = "0")]
+
+use io::{self, IoVec, IoVecMut};
+use libc::{self, c_int, c_uint};
+use mem;
+use net::{self, IpAddr, Ipv4Addr, Ipv6Addr};
+use os::unix::io::AsRawFd;
+use ptr;
+use slice;
+use sys::cvt;
+use sys_common::{self, AsInner, IntoInner};
+
+// Not all of these are exported by every version of `libc` we build
+// against; the values are the same on every Linux architecture.
+const IP_TTL: c_int = 2;
+const IP_PKTINFO: c_int = 8;
+const IP_RECVTTL: c_int = 12;
+const IPV6_RECVPKTINFO: c_int = 49;
+const IPV6_PKTINFO: c_int = 50;
+const IPV6_RECVHOPLIMIT: c_int = 51;
+const IPV6_HOPLIMIT: c_int = 52;
+
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct in_pktinfo {
+    ipi_ifindex: c_int,
+    ipi_spec_dst: libc::in_addr,
+    ipi_addr: libc::in_addr,
+}
+
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct in6_pktinfo {
+    ipi6_addr: libc::in6_addr,
+    ipi6_ifindex: c_uint,
+}
+
+/// Rounds `len` up to the alignment of control message headers, like
+/// `CMSG_ALIGN`.
+fn cmsg_align(len: usize) -> usize {
+    let align = mem::size_of::<usize>();
+    (len + align - 1) & !(align - 1)
+}
+
+/// Linux-specific extensions to [`UdpSocket`] for sending and receiving
+/// datagrams with `sendmsg(2)` and `recvmsg(2)`.
+///
+/// These allow a datagram to be gathered from, or scattered into, several
+/// buffers, and give access to the ancillary data ("control messages")
+/// passed along with it, such as the TTL or the destination address of a
+/// received packet.
+///
+/// [`UdpSocket`]: ../../../../std/net/struct.UdpSocket.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(udp_msg, iovec)]
+/// use std::io::IoVecMut;
+/// use std::net::UdpSocket;
+/// use std::os::linux::net::{ControlMessages, UdpSocketExt};
+///
+/// let socket = UdpSocket::bind("127.0.0.1:34254").expect("couldn't bind to address");
+/// socket.set_recv_ttl(true).expect("couldn't enable TTL reporting");
+///
+/// let (mut header, mut body) = ([0; 4], [0; 1024]);
+/// let mut control = [0; 64];
+/// let msg = socket.recv_msg(&mut [IoVecMut::new(&mut header), IoVecMut::new(&mut body)],
+///                           &mut control)
+///                 .expect("didn't receive data");
+/// for cmsg in ControlMessages::new(&control[..msg.control_len()]) {
+///     if let Some(ttl) = cmsg.ttl() {
+///         println!("received {} bytes with TTL {}", msg.bytes(), ttl);
+///     }
+/// }
+/// ```
+#[unstable(feature = "udp_msg", issue = "0")]
+pub trait UdpSocketExt {
+    /// Sends a datagram gathered from `bufs`, along with the encoded
+    /// control messages in `control`.
+    ///
+    /// If `addr` is `None` the socket must be connected, and the datagram
+    /// is sent to its peer. Control messages can be encoded with
+    /// [`ControlMessage::encode`].
+    ///
+    /// On success, returns the number of bytes sent.
+    ///
+    /// [`ControlMessage::encode`]: struct.ControlMessage.html#method.encode
+    fn send_msg(&self,
+                bufs: &[IoVec<'_>],
+                addr: Option<&net::SocketAddr>,
+                control: &[u8])
+                -> io::Result<usize>;
+
+    /// Receives a datagram, scattering it into `bufs` and storing its
+    /// control messages in `control`.
+    ///
+    /// The control messages can be read back with [`ControlMessages`]
+    /// from the first [`control_len`] bytes of `control`.
+    ///
+    /// [`ControlMessages`]: struct.ControlMessages.html
+    /// [`control_len`]: struct.RecvMsg.html#method.control_len
+    fn recv_msg(&self,
+                bufs: &mut [IoVecMut<'_>],
+                control: &mut [u8])
+                -> io::Result<RecvMsg>;
+
+    /// Sets whether the TTL (or, for IPv6 sockets, the hop limit) of each
+    /// received datagram is reported as a control message.
+    ///
+    /// This sets the `IP_RECVTTL` or `IPV6_RECVHOPLIMIT` option.
+    fn set_recv_ttl(&self, on: bool) -> io::Result<()>;
+
+    /// Sets whether the destination address and receiving interface of
+    /// each received datagram are reported as a control message.
+    ///
+    /// This sets the `IP_PKTINFO` or `IPV6_RECVPKTINFO` option.
+    fn set_recv_packet_info(&self, on: bool) -> io::Result<()>;
+}
+
+#[unstable(feature = "udp_msg", issue = "0")]
+impl UdpSocketExt for net::UdpSocket {
+    fn send_msg(&self,
+                bufs: &[IoVec<'_>],
+                addr: Option<&net::SocketAddr>,
+                control: &[u8])
+                -> io::Result<usize> {
+        unsafe {
+            let mut msg: libc::msghdr = mem::zeroed();
+            if let Some(addr) = addr {
+                let (name, namelen) = addr.into_inner();
+                msg.msg_name = name as *mut _;
+                msg.msg_namelen = namelen;
+            }
+            // `IoVec` is guaranteed to be ABI compatible with `iovec`.
+            msg.msg_iov = bufs.as_ptr() as *mut libc::iovec;
+            msg.msg_iovlen = bufs.len() as _;
+            if !control.is_empty() {
+                msg.msg_control = control.as_ptr() as *mut _;
+                msg.msg_controllen = control.len() as _;
+            }
+            let ret = cvt(libc::sendmsg(self.as_raw_fd(), &msg, libc::MSG_NOSIGNAL))?;
+            Ok(ret as usize)
+        }
+    }
+
+    fn recv_msg(&self,
+                bufs: &mut [IoVecMut<'_>],
+                control: &mut [u8])
+                -> io::Result<RecvMsg> {
+        unsafe {
+            let mut storage: libc::sockaddr_storage = mem::zeroed();
+            let mut msg: libc::msghdr = mem::zeroed();
+            msg.msg_name = &mut storage as *mut _ as *mut _;
+            msg.msg_namelen = mem::size_of_val(&storage) as libc::socklen_t;
+            msg.msg_iov = bufs.as_mut_ptr() as *mut libc::iovec;
+            msg.msg_iovlen = bufs.len() as _;
+            if !control.is_empty() {
+                msg.msg_control = control.as_mut_ptr() as *mut _;
+                msg.msg_controllen = control.len() as _;
+            }
+            let ret = cvt(libc::recvmsg(self.as_raw_fd(), &mut msg, 0))?;
+
+            let addr = if msg.msg_namelen == 0 {
+                None
+            } else {
+                Some(sys_common::net::sockaddr_to_addr(&storage,
+                                                       msg.msg_namelen as usize)?)
+            };
+            Ok(RecvMsg {
+                bytes: ret as usize,
+                addr,
+                control_len: msg.msg_controllen as usize,
+                flags: msg.msg_flags,
+            })
+        }
+    }
+
+    fn set_recv_ttl(&self, on: bool) -> io::Result<()> {
+        let (level, opt) = match self.local_addr()? {
+            net::SocketAddr::V4(..) => (libc::IPPROTO_IP, IP_RECVTTL),
+            net::SocketAddr::V6(..) => (libc::IPPROTO_IPV6, IPV6_RECVHOPLIMIT),
+        };
+        sys_common::net::setsockopt(self.as_inner().socket(), level, opt, on as c_int)
+    }
+
+    fn set_recv_packet_info(&self, on: bool) -> io::Result<()> {
+        let (level, opt) = match self.local_addr()? {
+            net::SocketAddr::V4(..) => (libc::IPPROTO_IP, IP_PKTINFO),
+            net::SocketAddr::V6(..) => (libc::IPPROTO_IPV6, IPV6_RECVPKTINFO),
+        };
+        sys_common::net::setsockopt(self.as_inner().socket(), level, opt, on as c_int)
+    }
+}
+
+/// The result of [`UdpSocketExt::recv_msg`].
+///
+/// [`UdpSocketExt::recv_msg`]: trait.UdpSocketExt.html#tymethod.recv_msg
+#[unstable(feature = "udp_msg", issue = "0")]
+#[derive(Debug, Clone)]
+pub struct RecvMsg {
+    bytes: usize,
+    addr: Option<net::SocketAddr>,
+    control_len: usize,
+    flags: c_int,
+}
+
+impl RecvMsg {
+    /// Returns the number of bytes of the datagram that were stored in
+    /// the buffers.
+    #[unstable(feature = "udp_msg", issue = "0")]
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Returns the address the datagram came from.
+    #[unstable(feature = "udp_msg", issue = "0")]
+    pub fn addr(&self) -> Option<net::SocketAddr> {
+        self.addr
+    }
+
+    /// Returns the number of bytes of control messages that were stored in
+    /// the control buffer.
+    #[unstable(feature = "udp_msg", issue = "0")]
+    pub fn control_len(&self) -> usize {
+        self.control_len
+    }
+
+    /// Returns `true` if the datagram was larger than the buffers and the
+    /// rest of it was discarded.
+    #[unstable(feature = "udp_msg", issue = "0")]
+    pub fn is_truncated(&self) -> bool {
+        self.flags & libc::MSG_TRUNC != 0
+    }
+
+    /// Returns `true` if some control messages were discarded because the
+    /// control buffer was too small.
+    #[unstable(feature = "udp_msg", issue = "0")]
+    pub fn is_control_truncated(&self) -> bool {
+        self.flags & libc::MSG_CTRUNC != 0
+    }
+}
+
+/// A single control message, as yielded by [`ControlMessages`].
+///
+/// [`ControlMessages`]: struct.ControlMessages.html
+#[unstable(feature = "udp_msg", issue = "0")]
+#[derive(Debug, Clone, Copy)]
+pub struct ControlMessage<'a> {
+    level: i32,
+    ty: i32,
+    data: &'a [u8],
+}
+
+impl<'a> ControlMessage<'a> {
+    /// Returns the protocol level of the message, e.g. `IPPROTO_IP`.
+    #[unstable(feature = "udp_msg", issue = "0")]
+    pub fn level(&self) -> i32 {
+        self.level
+    }
+
+    /// Returns the protocol-specific type of the message, e.g. `IP_TTL`.
+    #[unstable(feature = "udp_msg", issue = "0")]
+    pub fn ty(&self) -> i32 {
+        self.ty
+    }
+
+    /// Returns the payload of the message.
+    #[unstable(feature = "udp_msg", issue = "0")]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the TTL or hop limit carried by an `IP_TTL` or
+    /// `IPV6_HOPLIMIT` message.
+    #[unstable(feature = "udp_msg", issue = "0")]
+    pub fn ttl(&self) -> Option<u32> {
+        match (self.level, self.ty) {
+            (libc::IPPROTO_IP, IP_TTL) |
+            (libc::IPPROTO_IPV6, IPV6_HOPLIMIT) => {
+                self.read::<c_int>().map(|ttl| ttl as u32)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the destination address of the datagram and the index of
+    /// the interface it was received on, carried by an `IP_PKTINFO` or
+    /// `IPV6_PKTINFO` message.
+    #[unstable(feature = "udp_msg", issue = "0")]
+    pub fn packet_info(&self) -> Option<(IpAddr, u32)> {
+        match (self.level, self.ty) {
+            (libc::IPPROTO_IP, IP_PKTINFO) => {
+                self.read::<in_pktinfo>().map(|info| {
+                    let addr = Ipv4Addr::from(u32::from_be(info.ipi_addr.s_addr));
+                    (IpAddr::V4(addr), info.ipi_ifindex as u32)
+                })
+            }
+            (libc::IPPROTO_IPV6, IPV6_PKTINFO) => {
+                self.read::<in6_pktinfo>().map(|info| {
+                    let addr = Ipv6Addr::from(info.ipi6_addr.s6_addr);
+                    (IpAddr::V6(addr), info.ipi6_ifindex as u32)
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn read<T: Copy>(&self) -> Option<T> {
+        if self.data.len() < mem::size_of::<T>() {
+            return None;
+        }
+        // The control buffer is provided by the caller, so the payload
+        // isn't necessarily aligned.
+        Some(unsafe { ptr::read_unaligned(self.data.as_ptr() as *const T) })
+    }
+
+    /// Appends a control message with the given level, type and payload to
+    /// `buf`, in the format expected by [`UdpSocketExt::send_msg`].
+    ///
+    /// [`UdpSocketExt::send_msg`]: trait.UdpSocketExt.html#tymethod.send_msg
+    #[unstable(feature = "udp_msg", issue = "0")]
+    pub fn encode(level: i32, ty: i32, data: &[u8], buf: &mut Vec<u8>) {
+        let hdr_len = mem::size_of::<libc::cmsghdr>();
+        let data_start = cmsg_align(hdr_len);
+        let start = buf.len();
+
+        let mut hdr: libc::cmsghdr = unsafe { mem::zeroed() };
+        hdr.cmsg_len = (data_start + data.len()) as _;
+        hdr.cmsg_level = level;
+        hdr.cmsg_type = ty;
+        buf.extend_from_slice(unsafe {
+            slice::from_raw_parts(&hdr as *const _ as *const u8, hdr_len)
+        });
+        buf.resize(start + data_start, 0);
+        buf.extend_from_slice(data);
+        buf.resize(start + data_start + cmsg_align(data.len()), 0);
+    }
+}
+
+/// An iterator over the control messages stored in a buffer by
+/// [`UdpSocketExt::recv_msg`] or encoded with [`ControlMessage::encode`].
+///
+/// Iteration stops at the first malformed message.
+///
+/// [`UdpSocketExt::recv_msg`]: trait.UdpSocketExt.html#tymethod.recv_msg
+/// [`ControlMessage::encode`]: struct.ControlMessage.html#method.encode
+#[unstable(feature = "udp_msg", issue = "0")]
+#[derive(Debug, Clone)]
+pub struct ControlMessages<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> ControlMessages<'a> {
+    /// Creates an iterator over the control messages in `buf`.
+    #[unstable(feature = "udp_msg", issue = "0")]
+    pub fn new(buf: &'a [u8]) -> ControlMessages<'a> {
+        ControlMessages { buf }
+    }
+}
+
+#[unstable(feature = "udp_msg", issue = "0")]
+impl<'a> Iterator for ControlMessages<'a> {
+    type Item = ControlMessage<'a>;
+
+    fn next(&mut self) -> Option<ControlMessage<'a>> {
+        let hdr_len = mem::size_of::<libc::cmsghdr>();
+        if self.buf.len() < hdr_len {
+            return None;
+        }
+        let hdr = unsafe {
+            ptr::read_unaligned(self.buf.as_ptr() as *const libc::cmsghdr)
+        };
+        let len = hdr.cmsg_len as usize;
+        if len < cmsg_align(hdr_len) || len > self.buf.len() {
+            self.buf = &[];
+            return None;
+        }
+
+        let data = &self.buf[cmsg_align(hdr_len)..len];
+        let next = cmsg_align(len);
+        self.buf = if next < self.buf.len() { &self.buf[next..] } else { &[] };
+        Some(ControlMessage { level: hdr.cmsg_level, ty: hdr.cmsg_type, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! or_panic {
+        ($e:expr) => {
+            match $e {
+                Ok(e) => e,
+                Err(e) => panic!("{}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn udp_send_recv_msg() {
+        use io::{IoVec, IoVecMut};
+        use net::{IpAddr, Ipv4Addr, UdpSocket};
+
+        let sender = or_panic!(UdpSocket::bind("127.0.0.1:0"));
+        let receiver = or_panic!(UdpSocket::bind("127.0.0.1:0"));
+        or_panic!(receiver.set_recv_ttl(true));
+        or_panic!(receiver.set_recv_packet_info(true));
+
+        let receiver_addr = or_panic!(receiver.local_addr());
+        let sent = or_panic!(sender.send_msg(&[IoVec::new(b"hello"), IoVec::new(b" world")],
+                                             Some(&receiver_addr),
+                                             &[]));
+        assert_eq!(sent, 11);
+
+        let (mut first, mut second) = ([0; 4], [0; 16]);
+        let mut control = [0; 128];
+        let msg = or_panic!(receiver.recv_msg(&mut [IoVecMut::new(&mut first),
+                                                    IoVecMut::new(&mut second)],
+                                              &mut control));
+        assert_eq!(msg.bytes(), 11);
+        assert_eq!(&first, b"hell");
+        assert_eq!(&second[..7], b"o world");
+        assert_eq!(msg.addr(), Some(or_panic!(sender.local_addr())));
+        assert!(!msg.is_truncated());
+        assert!(!msg.is_control_truncated());
+
+        let cmsgs = ControlMessages::new(&control[..msg.control_len()]);
+        let ttl = cmsgs.clone().filter_map(|cmsg| cmsg.ttl()).next();
+        assert!(ttl.map_or(false, |ttl| ttl > 0));
+        let (dst, _) = cmsgs.filter_map(|cmsg| cmsg.packet_info()).next().unwrap();
+        assert_eq!(dst, IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
+
+        // A datagram larger than the buffers is reported as truncated.
+        or_panic!(sender.send_msg(&[IoVec::new(b"hello world")], Some(&receiver_addr), &[]));
+        let msg = or_panic!(receiver.recv_msg(&mut [IoVecMut::new(&mut first)], &mut []));
+        assert_eq!(msg.bytes(), 4);
+        assert!(msg.is_truncated());
+    }
+
+    #[test]
+    fn control_message_round_trip() {
+        let mut buf = Vec::new();
+        ControlMessage::encode(1, 2, b"abc", &mut buf);
+        ControlMessage::encode(3, 4, b"", &mut buf);
+        assert_eq!(buf.len() % mem::size_of::<usize>(), 0);
+
+        let cmsgs: Vec<_> = ControlMessages::new(&buf).collect();
+        assert_eq!(cmsgs.len(), 2);
+        assert_eq!((cmsgs[0].level(), cmsgs[0].ty(), cmsgs[0].data()), (1, 2, &b"abc"[..]));
+        assert_eq!((cmsgs[1].level(), cmsgs[1].ty(), cmsgs[1].data()), (3, 4, &b""[..]));
+
+        // Truncated messages are not yielded.
+        assert_eq!(ControlMessages::new(&buf[..buf.len() - 1]).count(), 1);
+    }
+}
@@ -1764,6 +1764,28 @@ mod tests {
         assert!(Command::new("/bin/ls").uid(0).gid(0).spawn().is_err());
     }
 
+    #[test]
+    #[cfg_attr(target_os = "android", ignore)]
+    #[cfg(unix)]
+    fn setsid_works() {
+        use os::unix::prelude::*;
+        use libc;
+        use io;
+
+        let mut cmd = Command::new("/bin/sh");
+        cmd.arg("-c").arg("true").setsid(true);
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::getsid(0) == libc::getpid() {
+                    Ok(())
+                } else {
+                    Err(io::Error::from_raw_os_error(libc::EPERM))
+                }
+            });
+        }
+        assert!(cmd.status().unwrap().success());
+    }
+
     #[test]
     #[cfg_attr(target_os = "android", ignore)]
     fn test_process_status() {
@@ -153,6 +153,8 @@
 
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use alloc_crate::sync::{Arc, Weak};
+#[unstable(feature = "shared_vec", issue = "0")]
+pub use alloc_crate::sync::SharedVec;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use core::sync::atomic;
 
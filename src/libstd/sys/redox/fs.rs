@@ -9,6 +9,7 @@ use sys::fd::FileDesc;
 use sys::time::SystemTime;
 use sys::{cvt, syscall};
 use sys_common::{AsInner, FromInner};
+use sys_common::io::copy_with_progress;
 
 pub struct File(FileDesc);
 
@@ -271,6 +272,18 @@ impl File {
         Ok(())
     }
 
+    pub fn preallocate(&self, _len: u64) -> io::Result<()> {
+        Err(Error::new(ErrorKind::Other, "preallocation is not supported on redox"))
+    }
+
+    pub fn lock(&self, _exclusive: bool, _wait: bool) -> io::Result<()> {
+        Err(Error::new(ErrorKind::Other, "file locking is not supported on redox"))
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        Err(Error::new(ErrorKind::Other, "file locking is not supported on redox"))
+    }
+
     pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.0.read(buf)
     }
@@ -306,6 +319,12 @@ impl File {
         set_perm(&self.path()?, perm)
     }
 
+    pub fn set_times(&self, atime: SystemTime, mtime: SystemTime) -> io::Result<()> {
+        let times = [atime.as_timespec(), mtime.as_timespec()];
+        cvt(syscall::futimens(self.0.raw(), &times))?;
+        Ok(())
+    }
+
     pub fn path(&self) -> io::Result<PathBuf> {
         let mut buf: [u8; 4096] = [0; 4096];
         let count = cvt(syscall::fpath(*self.fd().as_inner() as usize, &mut buf))?;
@@ -443,6 +462,12 @@ pub fn stat(p: &Path) -> io::Result<FileAttr> {
     file.file_attr()
 }
 
+pub fn set_times(p: &Path, atime: SystemTime, mtime: SystemTime) -> io::Result<()> {
+    let fd = cvt(syscall::open(p.to_str().unwrap(), syscall::O_CLOEXEC | syscall::O_STAT))?;
+    let file = File(FileDesc::new(fd));
+    file.set_times(atime, mtime)
+}
+
 pub fn lstat(p: &Path) -> io::Result<FileAttr> {
     let fd = cvt(syscall::open(p.to_str().unwrap(),
                                syscall::O_CLOEXEC | syscall::O_STAT | syscall::O_NOFOLLOW))?;
@@ -456,7 +481,7 @@ pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
     file.path()
 }
 
-pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
+pub fn copy(from: &Path, to: &Path, progress: &mut dyn FnMut(u64)) -> io::Result<u64> {
     use fs::{File, set_permissions};
     if !from.is_file() {
         return Err(Error::new(ErrorKind::InvalidInput,
@@ -467,7 +492,7 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     let mut writer = File::create(to)?;
     let perm = reader.metadata()?.permissions();
 
-    let ret = io::copy(&mut reader, &mut writer)?;
+    let ret = copy_with_progress(&mut reader, &mut writer, progress)?;
     set_permissions(to, perm)?;
     Ok(ret)
 }
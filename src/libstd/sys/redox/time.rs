@@ -161,6 +161,10 @@ impl fmt::Debug for Instant {
 }
 
 impl SystemTime {
+    pub fn as_timespec(&self) -> syscall::TimeSpec {
+        self.t.t
+    }
+
     pub fn now() -> SystemTime {
         SystemTime { t: now(syscall::CLOCK_REALTIME) }
     }
@@ -24,6 +24,36 @@ pub trait CommandExt {
     #[stable(feature = "rust1", since = "1.0.0")]
     fn gid(&mut self, id: u32) -> &mut process::Command;
 
+    /// Runs the child process in a new session, detached from the
+    /// controlling terminal of the parent. This translates to a `setsid`
+    /// call in the child process, and failure of the call will cause the
+    /// spawn to fail.
+    ///
+    /// The child is then not affected by signals sent to the process group of
+    /// the parent, for example when the user presses Ctrl-C in a terminal, and
+    /// keeps running after the session of the parent ends.
+    ///
+    /// On Fuchsia and L4Re, which have no sessions, spawning a command with
+    /// this set fails with an error of kind [`io::ErrorKind::Other`].
+    ///
+    /// [`io::ErrorKind::Other`]: ../../../io/enum.ErrorKind.html#variant.Other
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_setsid)]
+    ///
+    /// use std::os::unix::process::CommandExt;
+    /// use std::process::Command;
+    ///
+    /// let child = Command::new("long-running-server")
+    ///     .setsid(true)
+    ///     .spawn()
+    ///     .expect("failed to start server");
+    /// ```
+    #[unstable(feature = "process_setsid", issue = "0")]
+    fn setsid(&mut self, setsid: bool) -> &mut process::Command;
+
     /// Schedules a closure to be run just before the `exec` function is
     /// invoked.
     ///
@@ -117,6 +147,11 @@ impl CommandExt for process::Command {
         self
     }
 
+    fn setsid(&mut self, setsid: bool) -> &mut process::Command {
+        self.as_inner_mut().setsid(setsid);
+        self
+    }
+
     unsafe fn pre_exec<F>(&mut self, f: F) -> &mut process::Command
         where F: FnMut() -> io::Result<()> + Send + Sync + 'static
     {
@@ -12,6 +12,7 @@ use sys::fd::FileDesc;
 use sys::time::SystemTime;
 use sys::{cvt, cvt_r};
 use sys_common::{AsInner, FromInner};
+use sys_common::io::copy_with_progress;
 
 #[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "l4re"))]
 use libc::{stat64, fstat64, lstat64, off64_t, ftruncate64, lseek64, dirent64, readdir64_r, open64};
@@ -550,6 +551,51 @@ impl File {
         }).map(|_| ());
     }
 
+    #[cfg(target_os = "linux")]
+    pub fn preallocate(&self, len: u64) -> io::Result<()> {
+        if len > libc::off_t::max_value() as u64 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "preallocation length too large"));
+        }
+        cvt_r(|| unsafe {
+            libc::fallocate(self.0.raw(), libc::FALLOC_FL_KEEP_SIZE, 0, len as libc::off_t)
+        }).map(|_| ())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn preallocate(&self, _len: u64) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Other,
+                           "preallocation is not supported on this platform"))
+    }
+
+    #[cfg(not(target_os = "solaris"))]
+    pub fn lock(&self, exclusive: bool, wait: bool) -> io::Result<()> {
+        let mut operation = if exclusive { libc::LOCK_EX } else { libc::LOCK_SH };
+        if !wait {
+            operation |= libc::LOCK_NB;
+        }
+        cvt_r(|| unsafe { libc::flock(self.0.raw(), operation) })?;
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "solaris"))]
+    pub fn unlock(&self) -> io::Result<()> {
+        cvt_r(|| unsafe { libc::flock(self.0.raw(), libc::LOCK_UN) })?;
+        Ok(())
+    }
+
+    #[cfg(target_os = "solaris")]
+    pub fn lock(&self, _exclusive: bool, _wait: bool) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Other,
+                           "file locking is not supported on this platform"))
+    }
+
+    #[cfg(target_os = "solaris")]
+    pub fn unlock(&self) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Other,
+                           "file locking is not supported on this platform"))
+    }
+
     pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.0.read(buf)
     }
@@ -594,6 +640,27 @@ impl File {
         cvt_r(|| unsafe { libc::fchmod(self.0.raw(), perm.mode) })?;
         Ok(())
     }
+
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    pub fn set_times(&self, atime: SystemTime, mtime: SystemTime) -> io::Result<()> {
+        let times = [atime.as_timespec(), mtime.as_timespec()];
+        cvt(unsafe { libc::futimens(self.0.raw(), times.as_ptr()) })?;
+        Ok(())
+    }
+
+    // `futimens` is only available on recent versions of macOS.
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    pub fn set_times(&self, atime: SystemTime, mtime: SystemTime) -> io::Result<()> {
+        let times = [timeval(atime), timeval(mtime)];
+        cvt(unsafe { libc::futimes(self.0.raw(), times.as_ptr()) })?;
+        Ok(())
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn timeval(time: SystemTime) -> libc::timeval {
+    let t = time.as_timespec();
+    libc::timeval { tv_sec: t.tv_sec, tv_usec: (t.tv_nsec / 1000) as libc::suseconds_t }
 }
 
 impl DirBuilder {
@@ -724,6 +791,22 @@ pub fn set_perm(p: &Path, perm: FilePermissions) -> io::Result<()> {
     Ok(())
 }
 
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+pub fn set_times(p: &Path, atime: SystemTime, mtime: SystemTime) -> io::Result<()> {
+    let p = cstr(p)?;
+    let times = [atime.as_timespec(), mtime.as_timespec()];
+    cvt(unsafe { libc::utimensat(libc::AT_FDCWD, p.as_ptr(), times.as_ptr(), 0) })?;
+    Ok(())
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn set_times(p: &Path, atime: SystemTime, mtime: SystemTime) -> io::Result<()> {
+    let p = cstr(p)?;
+    let times = [timeval(atime), timeval(mtime)];
+    cvt(unsafe { libc::utimes(p.as_ptr(), times.as_ptr()) })?;
+    Ok(())
+}
+
 pub fn rmdir(p: &Path) -> io::Result<()> {
     let p = cstr(p)?;
     cvt(unsafe { libc::rmdir(p.as_ptr()) })?;
@@ -824,7 +907,7 @@ pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
 }
 
 #[cfg(not(any(target_os = "linux", target_os = "android")))]
-pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
+pub fn copy(from: &Path, to: &Path, progress: &mut dyn FnMut(u64)) -> io::Result<u64> {
     use fs::File;
     if !from.is_file() {
         return Err(Error::new(ErrorKind::InvalidInput,
@@ -835,13 +918,13 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     let mut writer = File::create(to)?;
     let perm = reader.metadata()?.permissions();
 
-    let ret = io::copy(&mut reader, &mut writer)?;
+    let ret = copy_with_progress(&mut reader, &mut writer, progress)?;
     writer.set_permissions(perm)?;
     Ok(ret)
 }
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
-pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
+pub fn copy(from: &Path, to: &Path, progress: &mut dyn FnMut(u64)) -> io::Result<u64> {
     use cmp;
     use fs::File;
     use sync::atomic::{AtomicBool, Ordering};
@@ -850,6 +933,10 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     // We store the availability in a global to avoid unnecessary syscalls
     static HAS_COPY_FILE_RANGE: AtomicBool = AtomicBool::new(true);
 
+    // Copy at most this many bytes per `copy_file_range` call, so that
+    // `progress` is called regularly.
+    const CHUNK_SIZE: u64 = 16 * 1024 * 1024;
+
     unsafe fn copy_file_range(
         fd_in: libc::c_int,
         off_in: *mut libc::loff_t,
@@ -885,7 +972,7 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     let mut written = 0u64;
     while written < len {
         let copy_result = if has_copy_file_range {
-            let bytes_to_copy = cmp::min(len - written, usize::max_value() as u64) as usize;
+            let bytes_to_copy = cmp::min(len - written, CHUNK_SIZE) as usize;
             let copy_result = unsafe {
                 // We actually don't have to adjust the offsets,
                 // because copy_file_range adjusts the file offset automatically
@@ -910,7 +997,10 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
             Err(io::Error::from_raw_os_error(libc::ENOSYS))
         };
         match copy_result {
-            Ok(ret) => written += ret as u64,
+            Ok(ret) => {
+                written += ret as u64;
+                progress(written);
+            }
             Err(err) => {
                 match err.raw_os_error() {
                     Some(os_err) if os_err == libc::ENOSYS
@@ -921,7 +1011,7 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
                         // - Files are mounted on different fs (EXDEV)
                         // - copy_file_range is disallowed, for example by seccomp (EPERM)
                         assert_eq!(written, 0);
-                        let ret = io::copy(&mut reader, &mut writer)?;
+                        let ret = copy_with_progress(&mut reader, &mut writer, progress)?;
                         writer.set_permissions(perm)?;
                         return Ok(ret)
                     },
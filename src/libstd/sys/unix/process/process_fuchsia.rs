@@ -19,6 +19,10 @@ impl Command {
             return Err(io::Error::new(io::ErrorKind::InvalidInput,
                                       "nul byte found in provided data"));
         }
+        if self.get_setsid() {
+            return Err(io::Error::new(io::ErrorKind::Other,
+                                      "setsid is not supported on this platform"));
+        }
 
         let (ours, theirs) = self.setup_io(default, needs_stdin)?;
 
@@ -32,6 +36,10 @@ impl Command {
             return io::Error::new(io::ErrorKind::InvalidInput,
                                   "nul byte found in provided data")
         }
+        if self.get_setsid() {
+            return io::Error::new(io::ErrorKind::Other,
+                                  "setsid is not supported on this platform")
+        }
 
         match self.setup_io(default, true) {
             Ok((_, _)) => {
@@ -20,6 +20,10 @@ impl Command {
             return Err(io::Error::new(ErrorKind::InvalidInput,
                                       "nul byte found in provided data"));
         }
+        if cfg!(target_os = "l4re") && self.get_setsid() {
+            return Err(io::Error::new(ErrorKind::Other,
+                                      "setsid is not supported on this platform"));
+        }
 
         let (ours, theirs) = self.setup_io(default, needs_stdin)?;
 
@@ -113,6 +117,10 @@ impl Command {
             return io::Error::new(ErrorKind::InvalidInput,
                                   "nul byte found in provided data")
         }
+        if cfg!(target_os = "l4re") && self.get_setsid() {
+            return io::Error::new(ErrorKind::Other,
+                                  "setsid is not supported on this platform")
+        }
 
         match self.setup_io(default, true) {
             Ok((_, theirs)) => {
@@ -199,6 +207,9 @@ impl Command {
 
                 t!(cvt(libc::setuid(u as uid_t)));
             }
+            if self.get_setsid() {
+                t!(cvt(libc::setsid()));
+            }
         }
         if let Some(ref cwd) = *self.get_cwd() {
             t!(cvt(libc::chdir(cwd.as_ptr())));
@@ -283,6 +294,7 @@ impl Command {
 
         if self.get_gid().is_some() ||
             self.get_uid().is_some() ||
+            self.get_setsid() ||
             self.env_saw_path() ||
             self.get_closures().len() != 0 {
             return Ok(None)
@@ -172,6 +172,10 @@ mod inner {
     }
 
     impl SystemTime {
+        pub fn as_timespec(&self) -> libc::timespec {
+            self.t.t
+        }
+
         pub fn now() -> SystemTime {
             use ptr;
 
@@ -312,6 +316,10 @@ mod inner {
     }
 
     impl SystemTime {
+        pub fn as_timespec(&self) -> libc::timespec {
+            self.t.t
+        }
+
         pub fn now() -> SystemTime {
             SystemTime { t: now(libc::CLOCK_REALTIME) }
         }
@@ -196,6 +196,18 @@ impl File {
         match self.0 {}
     }
 
+    pub fn preallocate(&self, _len: u64) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn lock(&self, _exclusive: bool, _wait: bool) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
     pub fn read(&self, _buf: &mut [u8]) -> io::Result<usize> {
         match self.0 {}
     }
@@ -220,6 +232,10 @@ impl File {
         match self.0 {}
     }
 
+    pub fn set_times(&self, _atime: SystemTime, _mtime: SystemTime) -> io::Result<()> {
+        match self.0 {}
+    }
+
     pub fn diverge(&self) -> ! {
         match self.0 {}
     }
@@ -257,6 +273,10 @@ pub fn set_perm(_p: &Path, perm: FilePermissions) -> io::Result<()> {
     match perm.0 {}
 }
 
+pub fn set_times(_p: &Path, _atime: SystemTime, _mtime: SystemTime) -> io::Result<()> {
+    unsupported()
+}
+
 pub fn rmdir(_p: &Path) -> io::Result<()> {
     unsupported()
 }
@@ -289,6 +309,6 @@ pub fn canonicalize(_p: &Path) -> io::Result<PathBuf> {
     unsupported()
 }
 
-pub fn copy(_from: &Path, _to: &Path) -> io::Result<u64> {
+pub fn copy(_from: &Path, _to: &Path, _progress: &mut dyn FnMut(u64)) -> io::Result<u64> {
     unsupported()
 }
@@ -1,9 +1,12 @@
 use os::windows::prelude::*;
 
+use any::Any;
+use cmp;
 use ffi::OsString;
 use fmt;
 use io::{self, Error, SeekFrom};
 use mem;
+use panic::{self, AssertUnwindSafe};
 use path::{Path, PathBuf};
 use ptr;
 use slice;
@@ -284,6 +287,23 @@ impl File {
         Ok(())
     }
 
+    pub fn preallocate(&self, len: u64) -> io::Result<()> {
+        // `FileAllocationInfo` can also shrink the allocation (and with it the
+        // file), so never ask for less than what the file already holds.
+        let len = cmp::max(len, self.file_attr()?.size());
+        let mut info = c::FILE_ALLOCATION_INFO {
+            AllocationSize: len as c::LARGE_INTEGER,
+        };
+        let size = mem::size_of_val(&info);
+        cvt(unsafe {
+            c::SetFileInformationByHandle(self.handle.raw(),
+                                          c::FileAllocationInfo,
+                                          &mut info as *mut _ as *mut _,
+                                          size as c::DWORD)
+        })?;
+        Ok(())
+    }
+
     pub fn file_attr(&self) -> io::Result<FileAttr> {
         unsafe {
             let mut info: c::BY_HANDLE_FILE_INFORMATION = mem::zeroed();
@@ -342,6 +362,34 @@ impl File {
         Ok(newpos as u64)
     }
 
+    pub fn lock(&self, exclusive: bool, wait: bool) -> io::Result<()> {
+        let mut flags = 0;
+        if exclusive {
+            flags |= c::LOCKFILE_EXCLUSIVE_LOCK;
+        }
+        if !wait {
+            flags |= c::LOCKFILE_FAIL_IMMEDIATELY;
+        }
+        // Lock the whole file, including anything it may grow to.
+        let mut overlapped: c::OVERLAPPED = unsafe { mem::zeroed() };
+        match cvt(unsafe {
+            c::LockFileEx(self.handle.raw(), flags, 0, !0, !0, &mut overlapped)
+        }) {
+            // A lock held elsewhere is reported like `flock` reports it on
+            // Unix. ERROR_LOCK_VIOLATION isn't mapped to `WouldBlock` in
+            // general, since other I/O on a locked range fails with it too.
+            Err(ref e) if !wait && e.raw_os_error() == Some(c::ERROR_LOCK_VIOLATION as i32) => {
+                Err(io::Error::from(io::ErrorKind::WouldBlock))
+            }
+            result => result.map(|_| ()),
+        }
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        cvt(unsafe { c::UnlockFile(self.handle.raw(), 0, 0, !0, !0) })?;
+        Ok(())
+    }
+
     pub fn duplicate(&self) -> io::Result<File> {
         Ok(File {
             handle: self.handle.duplicate(0, true, c::DUPLICATE_SAME_ACCESS)?,
@@ -423,6 +471,26 @@ impl File {
         })?;
         Ok(())
     }
+
+    pub fn set_times(&self, atime: SystemTime, mtime: SystemTime) -> io::Result<()> {
+        // Zero leaves the creation and change times and the attributes
+        // untouched.
+        let mut info = c::FILE_BASIC_INFO {
+            CreationTime: 0,
+            LastAccessTime: atime.intervals(),
+            LastWriteTime: mtime.intervals(),
+            ChangeTime: 0,
+            FileAttributes: 0,
+        };
+        let size = mem::size_of_val(&info);
+        cvt(unsafe {
+            c::SetFileInformationByHandle(self.handle.raw(),
+                                          c::FileBasicInfo,
+                                          &mut info as *mut _ as *mut _,
+                                          size as c::DWORD)
+        })?;
+        Ok(())
+    }
 }
 
 impl FromInner<c::HANDLE> for File {
@@ -687,6 +755,15 @@ pub fn lstat(path: &Path) -> io::Result<FileAttr> {
     file.file_attr()
 }
 
+pub fn set_times(p: &Path, atime: SystemTime, mtime: SystemTime) -> io::Result<()> {
+    let mut opts = OpenOptions::new();
+    opts.access_mode(c::FILE_WRITE_ATTRIBUTES);
+    // This flag is so we can open directories too
+    opts.custom_flags(c::FILE_FLAG_BACKUP_SEMANTICS);
+    let file = File::open(p, &opts)?;
+    file.set_times(atime, mtime)
+}
+
 pub fn set_perm(p: &Path, perm: FilePermissions) -> io::Result<()> {
     let p = to_u16s(p)?;
     unsafe {
@@ -714,7 +791,15 @@ pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
     get_path(&f)
 }
 
-pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
+pub fn copy(from: &Path, to: &Path, progress: &mut dyn FnMut(u64)) -> io::Result<u64> {
+    struct CopyState<'a> {
+        size: i64,
+        progress: &'a mut dyn FnMut(u64),
+        // A panic in `progress` can't unwind through `CopyFileExW`, so the
+        // copy is cancelled and the panic resumed afterwards.
+        panic: Option<Box<dyn Any + Send>>,
+    }
+
     unsafe extern "system" fn callback(
         _TotalFileSize: c::LARGE_INTEGER,
         _TotalBytesTransferred: c::LARGE_INTEGER,
@@ -726,17 +811,32 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
         _hDestinationFile: c::HANDLE,
         lpData: c::LPVOID,
     ) -> c::DWORD {
-        if dwStreamNumber == 1 {*(lpData as *mut i64) = StreamBytesTransferred;}
+        if dwStreamNumber == 1 {
+            let state = &mut *(lpData as *mut CopyState);
+            state.size = StreamBytesTransferred;
+            let progress = &mut state.progress;
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                progress(StreamBytesTransferred as u64)
+            }));
+            if let Err(payload) = result {
+                state.panic = Some(payload);
+                return c::PROGRESS_CANCEL;
+            }
+        }
         c::PROGRESS_CONTINUE
     }
     let pfrom = to_u16s(from)?;
     let pto = to_u16s(to)?;
-    let mut size = 0i64;
-    cvt(unsafe {
+    let mut state = CopyState { size: 0, progress, panic: None };
+    let result = cvt(unsafe {
         c::CopyFileExW(pfrom.as_ptr(), pto.as_ptr(), Some(callback),
-                       &mut size as *mut _ as *mut _, ptr::null_mut(), 0)
-    })?;
-    Ok(size as u64)
+                       &mut state as *mut _ as *mut _, ptr::null_mut(), 0)
+    });
+    if let Some(payload) = state.panic {
+        panic::resume_unwind(payload);
+    }
+    result?;
+    Ok(state.size as u64)
 }
 
 #[allow(dead_code)]
@@ -92,7 +92,7 @@ impl SystemTime {
         }
     }
 
-    fn intervals(&self) -> i64 {
+    pub fn intervals(&self) -> i64 {
         (self.t.dwLowDateTime as i64) | ((self.t.dwHighDateTime as i64) << 32)
     }
 
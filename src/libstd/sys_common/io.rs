@@ -1,5 +1,37 @@
+use io::{self, ErrorKind, Read, Write};
+use mem;
+
 pub const DEFAULT_BUF_SIZE: usize = 8 * 1024;
 
+/// Like `io::copy`, but calls `progress` with the number of bytes copied so
+/// far after each chunk.
+#[allow(dead_code)] // not used on all platforms
+pub fn copy_with_progress<R: ?Sized, W: ?Sized>(reader: &mut R,
+                                                writer: &mut W,
+                                                progress: &mut dyn FnMut(u64))
+                                                -> io::Result<u64>
+    where R: Read, W: Write
+{
+    let mut buf = unsafe {
+        let mut buf: [u8; DEFAULT_BUF_SIZE] = mem::uninitialized();
+        reader.initializer().initialize(&mut buf);
+        buf
+    };
+
+    let mut written = 0;
+    loop {
+        let len = match reader.read(&mut buf) {
+            Ok(0) => return Ok(written),
+            Ok(len) => len,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        writer.write_all(&buf[..len])?;
+        written += len as u64;
+        progress(written);
+    }
+}
+
 #[cfg(test)]
 #[allow(dead_code)] // not used on emscripten
 pub mod test {
@@ -174,7 +174,7 @@ use sys_common::mutex;
 use sys_common::thread_info;
 use sys_common::thread;
 use sys_common::{AsInner, IntoInner};
-use time::Duration;
+use time::{Duration, Instant};
 
 ////////////////////////////////////////////////////////////////////////////////
 // Thread-local storage
@@ -778,6 +778,43 @@ pub fn sleep(dur: Duration) {
     imp::Thread::sleep(dur)
 }
 
+/// Puts the current thread to sleep until the specified deadline has passed.
+///
+/// The deadline is an [`Instant`], which is measured with a monotonic clock,
+/// so the sleep is not affected by changes to the system time. If the
+/// deadline has already passed, this returns immediately.
+///
+/// Computing each deadline from the previous one, rather than sleeping for a
+/// fixed duration after each piece of work, keeps periodic tasks from drifting
+/// by the time the work itself takes.
+///
+/// [`Instant`]: ../time/struct.Instant.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(thread_sleep_until)]
+/// use std::thread;
+/// use std::time::{Duration, Instant};
+///
+/// let interval = Duration::from_millis(100);
+/// let mut next_tick = Instant::now() + interval;
+///
+/// loop {
+///     // Do some periodic work...
+///
+///     thread::sleep_until(next_tick);
+///     next_tick += interval;
+/// }
+/// ```
+#[unstable(feature = "thread_sleep_until", issue = "0")]
+pub fn sleep_until(deadline: Instant) {
+    let now = Instant::now();
+    if deadline > now {
+        sleep(deadline - now)
+    }
+}
+
 // constants for park/unpark
 const EMPTY: usize = 0;
 const PARKED: usize = 1;
@@ -1487,7 +1524,7 @@ mod tests {
     use result;
     use super::{Builder};
     use thread;
-    use time::Duration;
+    use time::{Duration, Instant};
     use u32;
 
     // !!! These tests are dangerous. If something is buggy, they will hang, !!!
@@ -1716,6 +1753,16 @@ mod tests {
         thread::sleep(Duration::from_millis(2));
     }
 
+    #[test]
+    fn sleep_until_smoke() {
+        let deadline = Instant::now() + Duration::from_millis(2);
+        thread::sleep_until(deadline);
+        assert!(Instant::now() >= deadline);
+
+        // A deadline in the past returns immediately.
+        thread::sleep_until(deadline);
+    }
+
     #[test]
     fn test_thread_id_equal() {
         assert!(thread::current().id() == thread::current().id());
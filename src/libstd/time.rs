@@ -17,7 +17,7 @@ use error::Error;
 use fmt;
 use ops::{Add, Sub, AddAssign, SubAssign};
 use sys::time;
-use sys_common::FromInner;
+use sys_common::{AsInner, FromInner};
 use sys_common::mutex::Mutex;
 
 #[stable(feature = "time", since = "1.3.0")]
@@ -596,6 +596,12 @@ impl FromInner<time::SystemTime> for SystemTime {
     }
 }
 
+impl AsInner<time::SystemTime> for SystemTime {
+    fn as_inner(&self) -> &time::SystemTime {
+        &self.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Instant, SystemTime, Duration, UNIX_EPOCH};
@@ -25,11 +25,13 @@ use crate::ptr::P;
 use crate::symbol::Symbol;
 use crate::ThinVec;
 use crate::tokenstream::{TokenStream, TokenTree, DelimSpan};
+use crate::visit::{self, Visitor};
 use crate::GLOBALS;
 
 use log::debug;
 use syntax_pos::{FileName, Span};
 
+use std::cmp;
 use std::iter;
 use std::ops::DerefMut;
 
@@ -203,6 +205,40 @@ impl Attribute {
     pub fn is_value_str(&self) -> bool {
         self.value_str().is_some()
     }
+
+    /// For an attribute of a tool, like `#[rustfmt::skip]`, returns the name
+    /// of the tool, otherwise returns `None`.
+    pub fn tool(&self) -> Option<Ident> {
+        if self.path.segments.len() > 1 {
+            Some(self.path.segments[0].ident)
+        } else {
+            None
+        }
+    }
+}
+
+impl ast::Crate {
+    /// Returns the attributes of tools, like `#[rustfmt::skip]`, on the crate
+    /// and on everything in it. Attributes inside macro invocations are not
+    /// included.
+    pub fn tool_attrs(&self) -> Vec<&Attribute> {
+        struct ToolAttrCollector<'a> {
+            attrs: Vec<&'a Attribute>,
+        }
+
+        impl<'a> Visitor<'a> for ToolAttrCollector<'a> {
+            fn visit_attribute(&mut self, attr: &'a Attribute) {
+                if attr.tool().is_some() {
+                    self.attrs.push(attr);
+                }
+            }
+            fn visit_mac(&mut self, _mac: &'a ast::Mac) {}
+        }
+
+        let mut collector = ToolAttrCollector { attrs: Vec::new() };
+        visit::walk_crate(&mut collector, self);
+        collector.attrs
+    }
 }
 
 impl MetaItem {
@@ -457,6 +493,92 @@ pub fn first_attr_value_str_by_name(attrs: &[Attribute], name: &str) -> Option<S
         .and_then(|at| at.value_str())
 }
 
+/// How a piece of documentation was written.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DocCommentKind {
+    /// A `///` or `//!` comment.
+    Line,
+    /// A `/** */` or `/*! */` comment.
+    Block,
+    /// A `#[doc = "..."]` or `#![doc = "..."]` attribute.
+    Attr,
+}
+
+/// A piece of documentation attached to a node, as returned by
+/// `doc_comments_for`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocFragment {
+    /// Whether this documents the enclosing node (`//!`) or the following
+    /// one (`///`).
+    pub style: AttrStyle,
+    pub kind: DocCommentKind,
+    /// The span of the comment or attribute in the source.
+    pub span: Span,
+    /// The text with the comment decoration and the common indentation
+    /// removed.
+    pub text: String,
+}
+
+/// Returns the documentation among `attrs`, in source order.
+///
+/// The attributes of a module include its inner attributes, so for a
+/// module this returns its `//!` docs as well as the `///` docs of the
+/// `mod` item. The common indentation of consecutive line comments of the
+/// same style is removed as a whole, so that indented code blocks in them
+/// keep their relative indentation.
+pub fn doc_comments_for(attrs: &[Attribute]) -> Vec<DocFragment> {
+    let mut fragments: Vec<DocFragment> = attrs.iter().filter_map(|attr| {
+        if !attr.check_name("doc") {
+            return None;
+        }
+        let value = attr.value_str()?;
+        let value = value.as_str();
+        let (kind, text) = if !attr.is_sugared_doc {
+            (DocCommentKind::Attr, value.to_string())
+        } else if value.starts_with("//") {
+            (DocCommentKind::Line, strip_doc_comment_decoration(&value))
+        } else {
+            (DocCommentKind::Block, strip_doc_comment_decoration(&value))
+        };
+        Some(DocFragment { style: attr.style, kind, span: attr.span, text })
+    }).collect();
+
+    let mut start = 0;
+    while start < fragments.len() {
+        let (style, kind) = (fragments[start].style, fragments[start].kind);
+        let end = if kind == DocCommentKind::Line {
+            fragments[start..].iter()
+                .position(|f| f.kind != kind || f.style != style)
+                .map_or(fragments.len(), |len| start + len)
+        } else {
+            start + 1
+        };
+        unindent_doc_fragments(&mut fragments[start..end]);
+        start = end;
+    }
+    fragments
+}
+
+/// Removes the indentation common to all non-blank lines of `fragments`.
+fn unindent_doc_fragments(fragments: &mut [DocFragment]) {
+    let is_indent = |c: char| c == ' ' || c == '\t';
+    let indent = fragments.iter()
+        .flat_map(|f| f.text.lines())
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches(is_indent).len())
+        .min()
+        .unwrap_or(0);
+    if indent == 0 {
+        return;
+    }
+    for fragment in fragments {
+        fragment.text = fragment.text.lines().map(|line| {
+            let len = line.len() - line.trim_start_matches(is_indent).len();
+            &line[cmp::min(len, indent)..]
+        }).collect::<Vec<_>>().join("\n");
+    }
+}
+
 impl MetaItem {
     fn tokens(&self) -> TokenStream {
         let mut idents = vec![];
@@ -860,3 +982,74 @@ impl NestedMetaItem {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::parser_testing::string_to_crate;
+    use crate::with_globals;
+
+    #[test]
+    fn doc_comments() {
+        with_globals(|| {
+            let krate = string_to_crate("/// Outer\n\
+                                         ///\n\
+                                         ///     code\n\
+                                         #[doc = \"  attr\"]\n\
+                                         #[doc(hidden)]\n\
+                                         mod m {\n\
+                                         //! Inner\n\
+                                         /**\n   Block\n     text\n */\n\
+                                         fn f() {}\n\
+                                         }".to_string());
+            let item = &krate.module.items[0];
+            let docs = doc_comments_for(&item.attrs);
+            let summary = docs.iter().map(|f| (f.style, f.kind, &f.text[..])).collect::<Vec<_>>();
+            assert_eq!(summary, [
+                (AttrStyle::Outer, DocCommentKind::Line, "Outer"),
+                (AttrStyle::Outer, DocCommentKind::Line, ""),
+                (AttrStyle::Outer, DocCommentKind::Line, "    code"),
+                (AttrStyle::Outer, DocCommentKind::Attr, "attr"),
+                (AttrStyle::Inner, DocCommentKind::Line, "Inner"),
+            ]);
+            assert_eq!(docs[0].span.hi().0 - docs[0].span.lo().0, "/// Outer".len() as u32);
+
+            let f = match item.node {
+                ast::ItemKind::Mod(ref m) => &m.items[0],
+                _ => panic!("expected a module"),
+            };
+            let docs = doc_comments_for(&f.attrs);
+            assert_eq!(docs.len(), 1);
+            assert_eq!((docs[0].style, docs[0].kind), (AttrStyle::Outer, DocCommentKind::Block));
+            assert_eq!(docs[0].text, "Block\n  text");
+        })
+    }
+
+    #[test]
+    fn tool_attrs() {
+        with_globals(|| {
+            let krate = string_to_crate("#![my_tool::config(level = 3)]\n\
+                                         #[inline]\n\
+                                         #[rustfmt::skip]\n\
+                                         fn f() {\n\
+                                             #[my_tool::skip]\n\
+                                             let _x = 1;\n\
+                                             m!(#[my_tool::hidden] struct S;);\n\
+                                         }".to_string());
+            let attrs = krate.tool_attrs()
+                             .iter()
+                             .map(|attr| (attr.tool().unwrap().to_string(), attr.path.to_string()))
+                             .collect::<Vec<_>>();
+            // Attributes are visited after the things they are attached to.
+            assert_eq!(attrs, [
+                ("my_tool".to_string(), "my_tool::skip".to_string()),
+                ("rustfmt".to_string(), "rustfmt::skip".to_string()),
+                ("my_tool".to_string(), "my_tool::config".to_string()),
+            ]);
+
+            let f = &krate.module.items[0];
+            assert!(f.attrs[0].tool().is_none());
+            assert_eq!(f.attrs[1].tool().map(|tool| tool.to_string()), Some("rustfmt".to_string()));
+        })
+    }
+}
@@ -11,11 +11,14 @@ use crate::ast;
 use crate::edition::Edition;
 use crate::mut_visit::*;
 use crate::parse::{token, ParseSess};
+use crate::print::pprust;
 use crate::ptr::P;
 use crate::util::map_in_place::MapInPlace;
 
 use errors::Applicability;
+use rustc_data_structures::fx::FxHashSet;
 use smallvec::SmallVec;
+use syntax_pos::Span;
 
 /// A folder that strips out items that do not belong in the current configuration.
 pub struct StripUnconfigured<'a> {
@@ -23,6 +26,97 @@ pub struct StripUnconfigured<'a> {
     pub features: Option<&'a Features>,
 }
 
+/// The evaluation of a single `#[cfg]` or `#[cfg_attr]` attribute, recorded in
+/// `sess.cfg_decisions` for `-Z explain-cfg`.
+pub struct CfgDecision {
+    /// `"cfg"` or `"cfg_attr"`.
+    pub attr: &'static str,
+    pub span: Span,
+    pub predicate: ast::MetaItem,
+    /// For `cfg_attr`, the attributes it expands to if the predicate holds.
+    pub attrs: Vec<String>,
+    pub active: bool,
+}
+
+/// A configuration option mentioned by a predicate in the report of
+/// `-Z explain-cfg`.
+#[derive(RustcEncodable)]
+pub struct CfgFlagReport {
+    pub name: String,
+    pub value: Option<String>,
+    /// Whether the option is set in the current configuration.
+    pub set: bool,
+}
+
+/// An evaluated attribute in the report of `-Z explain-cfg`.
+#[derive(RustcEncodable)]
+pub struct CfgDecisionReport {
+    pub attr: &'static str,
+    pub span: String,
+    pub predicate: String,
+    pub flags: Vec<CfgFlagReport>,
+    pub attrs: Vec<String>,
+    /// For `cfg`, whether the node was kept; for `cfg_attr`, whether its
+    /// attributes were applied.
+    pub active: bool,
+}
+
+/// Lists the `cfg` and `cfg_attr` attributes that were evaluated while the
+/// crate was parsed and expanded, in the order they were first evaluated.
+/// Decisions are only known if they were recorded in `sess.cfg_decisions`.
+pub fn cfg_report(sess: &ParseSess) -> Vec<CfgDecisionReport> {
+    let source_map = sess.source_map();
+    let decisions = sess.cfg_decisions.borrow();
+    let decisions = match *decisions {
+        Some(ref decisions) => decisions,
+        None => return vec![],
+    };
+
+    // The crate attributes are configured twice, and the expansion of a macro
+    // may evaluate the same attribute many times, always with the same result.
+    let mut seen = FxHashSet::default();
+    let decisions = decisions.iter().filter(|decision| seen.insert((decision.span, decision.attr)));
+    decisions.map(|decision| {
+        let mut flags = vec![];
+        cfg_flags(&decision.predicate, &mut flags);
+        let flags = flags.into_iter().map(|mi| {
+            let name = mi.ident().map(|ident| ident.name);
+            CfgFlagReport {
+                name: pprust::path_to_string(&mi.ident),
+                value: mi.value_str().map(|value| value.to_string()),
+                set: name.map_or(false, |name| sess.config.contains(&(name, mi.value_str()))),
+            }
+        }).collect();
+        CfgDecisionReport {
+            attr: decision.attr,
+            span: source_map.span_to_string(decision.span),
+            predicate: pprust::meta_item_to_string(&decision.predicate),
+            flags,
+            attrs: decision.attrs.clone(),
+            active: decision.active,
+        }
+    }).collect()
+}
+
+/// Collects the configuration options a predicate refers to, looking through
+/// `any`, `all` and `not`.
+fn cfg_flags<'a>(cfg: &'a ast::MetaItem, flags: &mut Vec<&'a ast::MetaItem>) {
+    match cfg.node {
+        ast::MetaItemKind::List(ref mis) => {
+            for mi in mis.iter().filter_map(|mi| mi.meta_item()) {
+                cfg_flags(mi, flags);
+            }
+        }
+        ast::MetaItemKind::Word | ast::MetaItemKind::NameValue(..) => {
+            let name = |mi: &ast::MetaItem| pprust::path_to_string(&mi.ident);
+            if !flags.iter().any(|flag| name(flag) == name(cfg) &&
+                                        flag.value_str() == cfg.value_str()) {
+                flags.push(cfg);
+            }
+        }
+    }
+}
+
 // `cfg_attr`-process the crate's attributes and compute the crate's features.
 pub fn features(mut krate: ast::Crate, sess: &ParseSess, edition: Edition)
                 -> (ast::Crate, Features) {
@@ -127,7 +221,21 @@ impl<'a> StripUnconfigured<'a> {
             // FIXME: Emit unused attribute lint here.
         }
 
-        if attr::cfg_matches(&cfg_predicate, self.sess, self.features) {
+        let active = attr::cfg_matches(&cfg_predicate, self.sess, self.features);
+        if let Some(ref mut decisions) = *self.sess.cfg_decisions.borrow_mut() {
+            decisions.push(CfgDecision {
+                attr: "cfg_attr",
+                span: attr.span,
+                predicate: cfg_predicate.clone(),
+                attrs: expanded_attrs.iter().map(|(path, tokens, _)| {
+                    format!("{}{}", pprust::path_to_string(path),
+                            pprust::tokens_to_string(tokens.clone()))
+                }).collect(),
+                active,
+            });
+        }
+
+        if active {
             // We call `process_cfg_attr` recursively in case there's a
             // `cfg_attr` inside of another `cfg_attr`. E.g.
             //  `#[cfg_attr(false, cfg_attr(true, some_attr))]`.
@@ -186,7 +294,19 @@ impl<'a> StripUnconfigured<'a> {
             }
 
             match nested_meta_items[0].meta_item() {
-                Some(meta_item) => attr::cfg_matches(meta_item, self.sess, self.features),
+                Some(meta_item) => {
+                    let active = attr::cfg_matches(meta_item, self.sess, self.features);
+                    if let Some(ref mut decisions) = *self.sess.cfg_decisions.borrow_mut() {
+                        decisions.push(CfgDecision {
+                            attr: "cfg",
+                            span: attr.span,
+                            predicate: meta_item.clone(),
+                            attrs: vec![],
+                            active,
+                        });
+                    }
+                    active
+                }
                 None => error(nested_meta_items[0].span,
                               "`cfg` predicate key cannot be a literal", ""),
             }
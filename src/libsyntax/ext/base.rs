@@ -964,6 +964,9 @@ impl<'a> ExtCtxt<'a> {
     pub fn set_trace_macros(&mut self, x: bool) {
         self.ecfg.trace_mac = x
     }
+    pub fn trace_macros_detailed(&self) -> bool {
+        self.ecfg.trace_mac && self.ecfg.trace_mac_detailed
+    }
     pub fn ident_of(&self, st: &str) -> ast::Ident {
         ast::Ident::from_str(st)
     }
@@ -7,6 +7,7 @@ use crate::ext::base::*;
 use crate::ext::derive::{add_derived_markers, collect_derives};
 use crate::ext::hygiene::{self, Mark, SyntaxContext};
 use crate::ext::placeholders::{placeholder, PlaceholderExpander};
+use crate::ext::tt::transcribe::TranscribedSpans;
 use crate::feature_gate::{self, Features, GateIssue, is_builtin_attr, emit_feature_err};
 use crate::mut_visit::*;
 use crate::parse::{DirectoryOwnership, PResult, ParseSess};
@@ -1623,9 +1624,11 @@ pub struct ExpansionConfig<'feat> {
     pub features: Option<&'feat Features>,
     pub recursion_limit: usize,
     pub trace_mac: bool,
+    pub trace_mac_detailed: bool,
     pub should_test: bool, // If false, strip `#[test]` nodes
     pub single_step: bool,
     pub keep_macs: bool,
+    pub transcribed_spans: TranscribedSpans,
 }
 
 macro_rules! feature_tests {
@@ -1648,9 +1651,11 @@ impl<'feat> ExpansionConfig<'feat> {
             features: None,
             recursion_limit: 1024,
             trace_mac: false,
+            trace_mac_detailed: false,
             should_test: false,
             single_step: false,
             keep_macs: false,
+            transcribed_spans: TranscribedSpans::Definition,
         }
     }
 
@@ -1665,6 +1670,7 @@ impl<'feat> ExpansionConfig<'feat> {
         fn enable_format_args_nl = format_args_nl,
         fn macros_in_extern_enabled = macros_in_extern,
         fn proc_macro_hygiene = proc_macro_hygiene,
+        fn enable_derive_to_json = derive_to_json,
     }
 
     fn enable_custom_inner_attributes(&self) -> bool {
@@ -126,7 +126,11 @@ pub fn expand_include_str(cx: &mut ExtCtxt<'_>, sp: Span, tts: &[tokenstream::To
         None => return DummyResult::expr(sp)
     };
     let file = res_rel_file(cx, sp, file);
-    match fs::read_to_string(&file) {
+    let src = match cx.source_map().overlay_source(&file) {
+        Some(src) => Ok(src),
+        None => fs::read_to_string(&file),
+    };
+    match src {
         Ok(src) => {
             let interned_src = Symbol::intern(&src);
 
@@ -154,7 +158,11 @@ pub fn expand_include_bytes(cx: &mut ExtCtxt<'_>, sp: Span, tts: &[tokenstream::
         None => return DummyResult::expr(sp)
     };
     let file = res_rel_file(cx, sp, file);
-    match fs::read(&file) {
+    let bytes = match cx.source_map().overlay_source(&file) {
+        Some(src) => Ok(src.into_bytes()),
+        None => fs::read(&file),
+    };
+    match bytes {
         Ok(bytes) => {
             // Add the contents to the source map if it contains UTF-8.
             let (contents, bytes) = match String::from_utf8(bytes) {
@@ -669,6 +669,11 @@ pub fn parse(
     let mut cur_items = smallvec![MatcherPosHandle::Ref(&mut initial)];
     let mut next_items = Vec::new();
 
+    // The span of the first token, and whether any input has been consumed yet, used to point out
+    // the matched prefix in ambiguity errors.
+    let start_span = parser.span;
+    let mut consumed = false;
+
     loop {
         // Matcher positions black-box parsed by parser.rs (`parser`)
         let mut bb_items = SmallVec::new();
@@ -735,25 +740,9 @@ pub fn parse(
         // Another possibility is that we need to call out to parse some rust nonterminal
         // (black-box) parser. However, if there is not EXACTLY ONE of these, something is wrong.
         if (!bb_items.is_empty() && !next_items.is_empty()) || bb_items.len() > 1 {
-            let nts = bb_items
-                .iter()
-                .map(|item| match item.top_elts.get_tt(item.idx) {
-                    TokenTree::MetaVarDecl(_, bind, name) => format!("{} ('{}')", name, bind),
-                    _ => panic!(),
-                })
-                .collect::<Vec<String>>()
-                .join(" or ");
-
             return Error(
                 parser.span,
-                format!(
-                    "local ambiguity: multiple parsing options: {}",
-                    match next_items.len() {
-                        0 => format!("built-in NTs {}.", nts),
-                        1 => format!("built-in NTs {} or 1 other option.", nts),
-                        n => format!("built-in NTs {} or {} other options.", nts, n),
-                    }
-                ),
+                ambiguity_msg(sess, &parser, start_span, consumed, &bb_items, next_items.len()),
             );
         }
         // If there are no possible next positions AND we aren't waiting for the black-box parser,
@@ -770,6 +759,7 @@ pub fn parse(
             // Now process the next token
             cur_items.extend(next_items.drain(..));
             parser.bump();
+            consumed = true;
         }
         // Finally, we have the case where we need to call the black-box parser to get some
         // nonterminal.
@@ -785,6 +775,7 @@ pub fn parse(
                 );
                 item.idx += 1;
                 item.match_cur += 1;
+                consumed = true;
             } else {
                 unreachable!()
             }
@@ -795,6 +786,83 @@ pub fn parse(
     }
 }
 
+/// Describes a local ambiguity: the nonterminals competing for the current token, how many other
+/// matcher positions expect that token literally, and where in the input the ambiguity occurs.
+fn ambiguity_msg(
+    sess: &ParseSess,
+    parser: &Parser<'_>,
+    start_span: Span,
+    consumed: bool,
+    bb_items: &[MatcherPosHandle<'_, '_>],
+    other_options: usize,
+) -> String {
+    let nts = bb_items
+        .iter()
+        .map(|item| match item.top_elts.get_tt(item.idx) {
+            TokenTree::MetaVarDecl(_, bind, name) => format!("{} ('{}')", name, bind),
+            _ => panic!(),
+        })
+        .collect::<Vec<String>>()
+        .join(" or ");
+    let token = pprust::token_to_string(&parser.token);
+    let options = match other_options {
+        0 => format!("built-in NTs {}", nts),
+        1 => format!("built-in NTs {} or 1 other option matching `{}` literally", nts, token),
+        n => format!("built-in NTs {} or {} other options matching `{}` literally", nts, n, token),
+    };
+    let position = if !consumed {
+        "at the start of the macro input".to_string()
+    } else {
+        match sess.source_map().span_to_snippet(start_span.to(parser.prev_span)) {
+            Ok(ref prefix) if !start_span.is_dummy() => format!("after `{}`", prefix),
+            _ => "after a partial match".to_string(),
+        }
+    };
+    format!(
+        "local ambiguity: multiple parsing options for the token `{}` {}: {}.",
+        token, position, options
+    )
+}
+
+/// The result of `match_only`: which rule of a macro an invocation would select.
+#[derive(Debug)]
+pub enum MatchResult {
+    /// The input matches the rule with the given (zero-based) index, binding the given metavars.
+    Matched(usize, FxHashMap<Ident, Rc<NamedMatch>>),
+    /// No rule matches. Like `ParseResult::Failure`, this describes the failure of the rule that
+    /// got furthest into the input.
+    NoMatch(syntax_pos::Span, Token, &'static str),
+    /// A rule is ambiguous or malformed; expansion would abort compilation with this error.
+    Error(syntax_pos::Span, String),
+}
+
+/// Tries the rules of a macro against `tts` in order, the way `macro_rules!` expansion does, and
+/// reports which one would be selected without transcribing or expanding anything.
+///
+/// Each element of `lhses` is the delimited left-hand side of one rule, as produced by
+/// `quoted::parse` with `expect_matchers` set. Out-of-line modules in `item` fragments are not
+/// loaded. As during expansion, a nonterminal that starts to parse but fails is a fatal error,
+/// emitted to `sess`.
+pub fn match_only(sess: &ParseSess, lhses: &[TokenTree], tts: &TokenStream) -> MatchResult {
+    // Which rule's failure should we report? (the one furthest along)
+    let mut best_fail = (syntax_pos::DUMMY_SP, token::Eof, "macro has no rules");
+    for (i, lhs) in lhses.iter().enumerate() {
+        let ms = match *lhs {
+            TokenTree::Delimited(_, ref delim) => &delim.tts[..],
+            _ => sess.span_diagnostic.span_bug(lhs.span(), "malformed macro lhs"),
+        };
+        match parse(sess, tts.clone(), ms, None, false) {
+            Success(bindings) => return MatchResult::Matched(i, bindings),
+            Failure(sp, tok, t) => if sp.lo() >= best_fail.0.lo() {
+                best_fail = (sp, tok, t);
+            },
+            Error(sp, msg) => return MatchResult::Error(sp, msg),
+        }
+    }
+    let (sp, tok, t) = best_fail;
+    MatchResult::NoMatch(sp, tok, t)
+}
+
 /// The token is an identifier, but not `_`.
 /// We prohibit passing `_` to macros expecting `ident` for now.
 fn get_macro_ident(token: &Token) -> Option<(Ident, bool)> {
@@ -944,3 +1012,69 @@ fn parse_nt<'a>(p: &mut Parser<'a>, sp: Span, name: &str) -> Nonterminal {
         _ => p.span_bug(sp, "invalid fragment specifier"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::DUMMY_NODE_ID;
+    use crate::edition::Edition;
+    use crate::ext::tt::quoted;
+    use crate::feature_gate::Features;
+    use crate::parse::parse_stream_from_source_str;
+    use crate::source_map::FilePathMapping;
+    use crate::with_globals;
+    use syntax_pos::FileName;
+
+    fn stream(sess: &ParseSess, src: &str) -> TokenStream {
+        parse_stream_from_source_str(FileName::Custom(src.to_string()), src.to_string(),
+                                     sess, None)
+    }
+
+    fn lhses(sess: &ParseSess, src: &str) -> Vec<TokenTree> {
+        quoted::parse(stream(sess, src), true, sess, &Features::new(), &[],
+                      Edition::Edition2015, DUMMY_NODE_ID)
+    }
+
+    #[test]
+    fn match_only_selects_rule() {
+        with_globals(|| {
+            let sess = ParseSess::new(FilePathMapping::empty());
+            let rules = lhses(&sess, "(a $e:expr) ($i:ident) (b $($t:tt)*)");
+
+            match match_only(&sess, &rules, &stream(&sess, "a 1 + 2")) {
+                MatchResult::Matched(0, bindings) => assert_eq!(bindings.len(), 1),
+                _ => panic!("expected the first rule to match"),
+            }
+            match match_only(&sess, &rules, &stream(&sess, "x")) {
+                MatchResult::Matched(1, _) => {}
+                _ => panic!("expected the second rule to match"),
+            }
+            match match_only(&sess, &rules, &stream(&sess, "b c d")) {
+                MatchResult::Matched(2, _) => {}
+                _ => panic!("expected the third rule to match"),
+            }
+            match match_only(&sess, &rules, &stream(&sess, "c d")) {
+                MatchResult::NoMatch(_, tok, _) => {
+                    assert_eq!(pprust::token_to_string(&tok), "d")
+                }
+                _ => panic!("expected no rule to match"),
+            }
+        })
+    }
+
+    #[test]
+    fn ambiguity_message() {
+        with_globals(|| {
+            let sess = ParseSess::new(FilePathMapping::empty());
+            let rules = lhses(&sess, "(a $($i:ident)* $j:ident)");
+            match match_only(&sess, &rules, &stream(&sess, "a b")) {
+                MatchResult::Error(_, msg) => assert_eq!(
+                    msg,
+                    "local ambiguity: multiple parsing options for the token `b` after `a`: \
+                     built-in NTs ident ('i') or ident ('j')."
+                ),
+                _ => panic!("expected an ambiguity error"),
+            }
+        })
+    }
+}
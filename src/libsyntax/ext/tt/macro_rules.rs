@@ -4,15 +4,16 @@ use crate::ext::base::{DummyResult, ExtCtxt, MacResult, SyntaxExtension};
 use crate::ext::base::{NormalTT, TTMacroExpander};
 use crate::ext::expand::{AstFragment, AstFragmentKind};
 use crate::ext::tt::macro_parser::{Success, Error, Failure};
-use crate::ext::tt::macro_parser::{MatchedSeq, MatchedNonterminal};
+use crate::ext::tt::macro_parser::{MatchedSeq, MatchedNonterminal, NamedMatch};
 use crate::ext::tt::macro_parser::{parse, parse_failure_msg};
 use crate::ext::tt::quoted;
-use crate::ext::tt::transcribe::transcribe;
+use crate::ext::tt::transcribe::{transcribe, TranscribedSpans};
 use crate::feature_gate::Features;
 use crate::parse::{Directory, ParseSess};
 use crate::parse::parser::Parser;
 use crate::parse::token::{self, NtTT};
 use crate::parse::token::Token::*;
+use crate::print::pprust;
 use crate::symbol::Symbol;
 use crate::tokenstream::{DelimSpan, TokenStream, TokenTree};
 
@@ -116,6 +117,34 @@ fn trace_macros_note(cx: &mut ExtCtxt<'_>, sp: Span, message: String) {
     cx.expansions.entry(sp).or_default().push(message);
 }
 
+/// A record of a single `macro_rules!` expansion, collected in
+/// `ParseSess::expansion_traces` under `-Z trace-macros=detailed`.
+#[derive(Clone, Debug)]
+pub struct ExpansionTrace {
+    /// The span of the macro invocation.
+    pub call_site: Span,
+    pub macro_name: ast::Ident,
+    /// The index of the rule (arm) that matched, counting from zero.
+    pub rule: usize,
+    /// The metavariables bound by the matched rule, sorted by name, with
+    /// their fragments pretty-printed. Repetitions are printed as `[a, b]`.
+    pub bindings: Vec<(ast::Ident, String)>,
+    /// The tokens passed to the macro.
+    pub input: TokenStream,
+    /// The tokens produced by transcribing the matched rule.
+    pub output: TokenStream,
+}
+
+fn named_match_to_string(matched: &NamedMatch) -> String {
+    match *matched {
+        MatchedNonterminal(ref nt) => pprust::nonterminal_to_string(nt),
+        MatchedSeq(ref matches, _) => {
+            let parts = matches.iter().map(named_match_to_string).collect::<Vec<_>>();
+            format!("[{}]", parts.join(", "))
+        }
+    }
+}
+
 /// Given `lhses` and `rhses`, this is the new macro we create
 fn generic_extension<'cx>(cx: &'cx mut ExtCtxt<'_>,
                           sp: Span,
@@ -150,12 +179,25 @@ fn generic_extension<'cx>(cx: &'cx mut ExtCtxt<'_>,
                 let arm_span = rhses[i].span();
 
                 let rhs_spans = rhs.iter().map(|t| t.span()).collect::<Vec<_>>();
+
+                let bindings = if cx.trace_macros_detailed() {
+                    let mut bindings = named_matches.iter()
+                        .map(|(ident, matched)| (*ident, named_match_to_string(matched)))
+                        .collect::<Vec<_>>();
+                    bindings.sort_by(|&(a, _), &(b, _)| (*a.as_str()).cmp(&*b.as_str()));
+                    Some(bindings)
+                } else {
+                    None
+                };
+
                 // rhs has holes ( `$id` and `$(...)` that need filled)
                 let mut tts = transcribe(cx, Some(named_matches), rhs);
 
                 // Replace all the tokens for the corresponding positions in the macro, to maintain
                 // proper positions in error reporting, while maintaining the macro_backtrace.
-                if rhs_spans.len() == tts.len() {
+                // Not done when tokens are deliberately attributed to the call site.
+                if rhs_spans.len() == tts.len() &&
+                   cx.ecfg.transcribed_spans == TranscribedSpans::Definition {
                     tts = tts.map_enumerated(|i, mut tt| {
                         let mut sp = rhs_spans[i];
                         sp = sp.with_ctxt(tt.span().ctxt());
@@ -165,9 +207,28 @@ fn generic_extension<'cx>(cx: &'cx mut ExtCtxt<'_>,
                 }
 
                 if cx.trace_macros() {
+                    if let Some(ref bindings) = bindings {
+                        trace_macros_note(cx, sp, format!("matched rule #{}", i));
+                        for &(ident, ref fragment) in bindings {
+                            trace_macros_note(cx, sp, format!("with `${}` = `{}`", ident, fragment));
+                        }
+                    }
                     trace_macros_note(cx, sp, format!("to `{}`", tts));
                 }
 
+                if let Some(bindings) = bindings {
+                    if let Some(ref mut traces) = *cx.parse_sess.expansion_traces.borrow_mut() {
+                        traces.push(ExpansionTrace {
+                            call_site: sp,
+                            macro_name: name,
+                            rule: i,
+                            bindings,
+                            input: arg.clone(),
+                            output: tts.clone(),
+                        });
+                    }
+                }
+
                 let directory = Directory {
                     path: Cow::from(cx.current_expansion.module.directory.as_path()),
                     ownership: cx.current_expansion.directory_ownership,
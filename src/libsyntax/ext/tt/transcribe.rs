@@ -8,7 +8,7 @@ use crate::parse::token::{self, Token, NtTT};
 use crate::tokenstream::{DelimSpan, TokenStream, TokenTree, TreeAndJoint};
 
 use smallvec::{smallvec, SmallVec};
-use syntax_pos::DUMMY_SP;
+use syntax_pos::{Span, DUMMY_SP};
 
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::sync::Lrc;
@@ -16,6 +16,20 @@ use std::mem;
 use std::ops::Add;
 use std::rc::Rc;
 
+/// Which source positions are given to tokens copied out of a `macro_rules!` body.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TranscribedSpans {
+    /// Tokens keep their position in the macro definition. This is what
+    /// diagnostics expect and is the default.
+    Definition,
+    /// The position of each token is replaced with the position of the macro
+    /// invocation, so that coverage and profiling tools attribute generated
+    /// code to the call site. Hygiene is unaffected. The position of the token
+    /// within the macro body is lost; only the span of the whole definition
+    /// stays reachable, through the `def_site` of the expansion info.
+    CallSite,
+}
+
 // An iterator over the token trees in a delimited token tree (`{ ... }`) or a sequence (`$(...)`).
 enum Frame {
     Delimited {
@@ -165,13 +179,15 @@ pub fn transcribe(cx: &ExtCtxt<'_>,
                 }
             }
             quoted::TokenTree::Delimited(mut span, delimited) => {
+                span = DelimSpan::from_pair(transcribed_span(cx, span.open),
+                                            transcribed_span(cx, span.close));
                 span = span.apply_mark(cx.current_expansion.mark);
                 stack.push(Frame::Delimited { forest: delimited, idx: 0, span: span });
                 result_stack.push(mem::replace(&mut result, Vec::new()));
             }
             quoted::TokenTree::Token(sp, tok) => {
                 let mut marker = Marker(cx.current_expansion.mark);
-                let mut tt = TokenTree::Token(sp, tok);
+                let mut tt = TokenTree::Token(transcribed_span(cx, sp), tok);
                 noop_visit_tt(&mut tt, &mut marker);
                 result.push(tt.into());
             }
@@ -180,6 +196,22 @@ pub fn transcribe(cx: &ExtCtxt<'_>,
     }
 }
 
+/// Returns the span to give a token taken from the macro body, according to the
+/// configured `TranscribedSpans` policy.
+fn transcribed_span(cx: &ExtCtxt<'_>, sp: Span) -> Span {
+    match cx.ecfg.transcribed_spans {
+        TranscribedSpans::Definition => sp,
+        TranscribedSpans::CallSite => {
+            let call_site = cx.call_site();
+            if call_site.is_dummy() {
+                sp
+            } else {
+                sp.with_lo(call_site.lo()).with_hi(call_site.hi())
+            }
+        }
+    }
+}
+
 fn lookup_cur_matched(ident: Ident,
                       interpolations: &FxHashMap<Ident, Rc<NamedMatch>>,
                       repeats: &[(usize, usize)])
@@ -31,6 +31,7 @@ use rustc_target::spec::abi::Abi;
 use syntax_pos::{Span, DUMMY_SP};
 use log::debug;
 
+use std::collections::BTreeMap;
 use std::env;
 
 macro_rules! set {
@@ -471,6 +472,15 @@ declare_features! (
 
     // #[repr(align(X))] on enums
     (active, repr_align_enum, "1.34.0", Some(57996), None),
+
+    // #[unsafe_reasons(...)] on unsafe blocks
+    (active, unsafe_reasons, "1.34.0", None, None),
+
+    // #![register_tool(...)] to accept the attributes of other tools
+    (active, register_tool, "1.34.0", None, None),
+
+    // #[derive(ToJson)]
+    (active, derive_to_json, "1.34.0", None, None),
 );
 
 declare_features! (
@@ -882,6 +892,12 @@ pub const BUILTIN_ATTRIBUTES: &[(&str, AttributeType, AttributeTemplate, Attribu
                                   and possibly buggy",
                                  cfg_fn!(plugin))),
 
+    ("register_tool", CrateLevel, template!(List: "tool1, tool2, ..."),
+                                 Gated(Stability::Unstable,
+                                       "register_tool",
+                                       "registering tools for tool attributes is experimental",
+                                       cfg_fn!(register_tool))),
+
     ("no_std", CrateLevel, template!(Word), Ungated),
     ("no_core", CrateLevel, template!(Word), Gated(Stability::Unstable,
                                   "no_core",
@@ -946,6 +962,12 @@ pub const BUILTIN_ATTRIBUTES: &[(&str, AttributeType, AttributeTemplate, Attribu
                                       is just used for rustc unit tests \
                                       and will never be stable",
                                      cfg_fn!(rustc_attrs))),
+    ("rustc_outlives_facts", Normal, template!(Word), Gated(Stability::Unstable,
+                                     "rustc_attrs",
+                                     "the `#[rustc_outlives_facts]` attribute \
+                                      is just used for rustc unit tests \
+                                      and will never be stable",
+                                     cfg_fn!(rustc_attrs))),
     ("rustc_variance", Normal, template!(Word), Gated(Stability::Unstable,
                                      "rustc_attrs",
                                      "the `#[rustc_variance]` attribute \
@@ -1104,6 +1126,12 @@ pub const BUILTIN_ATTRIBUTES: &[(&str, AttributeType, AttributeTemplate, Attribu
                                             "allow_internal_unsafe",
                                             EXPLAIN_ALLOW_INTERNAL_UNSAFE,
                                             cfg_fn!(allow_internal_unsafe))),
+    ("unsafe_reasons", Whitelisted, template!(List: "reason1, reason2, ..."),
+                                   Gated(Stability::Unstable,
+                                         "unsafe_reasons",
+                                         "restricting the unsafe operations allowed in an \
+                                          `unsafe` block is experimental",
+                                         cfg_fn!(unsafe_reasons))),
 
     ("fundamental", Whitelisted, template!(Word), Gated(Stability::Unstable,
                                        "fundamental",
@@ -1320,6 +1348,8 @@ macro_rules! gate_feature_fn {
         if !has_feature && !span.allows_unstable($name) {
             leveled_feature_err(cx.parse_sess, name, span, GateIssue::Language, explain, level)
                 .emit();
+        } else {
+            record_feature_use(cx.parse_sess, name, span);
         }
     }}
 }
@@ -1408,6 +1438,16 @@ fn find_lang_feature_issue(feature: &str) -> Option<u32> {
     }
 }
 
+/// Records that `span` needed `feature`, for `-Z report-feature-gates`.
+///
+/// Gates that fail record themselves through `feature_err`. Code that checks
+/// a feature by hand and lets the use through must call this itself.
+pub fn record_feature_use(sess: &ParseSess, feature: &str, span: Span) {
+    if let Some(ref mut uses) = *sess.feature_gate_uses.borrow_mut() {
+        uses.push((Symbol::intern(feature), span));
+    }
+}
+
 pub enum GateIssue {
     Language,
     Library(Option<u32>)
@@ -1433,6 +1473,8 @@ pub fn feature_err<'a>(sess: &'a ParseSess, feature: &str, span: Span, issue: Ga
 
 fn leveled_feature_err<'a>(sess: &'a ParseSess, feature: &str, span: Span, issue: GateIssue,
                            explain: &str, level: GateStrength) -> DiagnosticBuilder<'a> {
+    record_feature_use(sess, feature, span);
+
     let diag = &sess.span_diagnostic;
 
     let issue = match issue {
@@ -1494,6 +1536,9 @@ pub const EXPLAIN_CONCAT_IDENTS: &str =
 pub const EXPLAIN_FORMAT_ARGS_NL: &str =
     "`format_args_nl` is only for internal language use and is subject to change";
 
+pub const EXPLAIN_DERIVE_TO_JSON: &str =
+    "`#[derive(ToJson)]` is experimental and subject to change";
+
 pub const EXPLAIN_TRACE_MACROS: &str =
     "`trace_macros` is not stable enough for use and is subject to change";
 pub const EXPLAIN_ALLOW_INTERNAL_UNSTABLE: &str =
@@ -2155,6 +2200,104 @@ pub fn check_crate(krate: &ast::Crate,
     visit::walk_crate(visitor, krate);
 }
 
+/// A feature in the report of `-Z report-feature-gates`.
+#[derive(RustcEncodable)]
+pub struct FeatureGateReport {
+    pub name: String,
+    /// `"lang"` for language features, `"lib"` for anything else.
+    pub kind: &'static str,
+    /// For language features, one of `"active"`, `"accepted"`, `"removed"`
+    /// and `"stable_removed"`. For library features, `"unstable"` or
+    /// `"stable"`. `None` if no crate in the dependency graph defines the
+    /// feature.
+    pub status: Option<&'static str>,
+    /// The version in which the feature got its current status.
+    pub since: Option<String>,
+    /// Only known for language features.
+    pub tracking_issue: Option<u32>,
+    /// Where the feature is enabled with `#![feature]`, if it is.
+    pub declared_at: Option<String>,
+    /// The spans that needed the feature, whether the check passed or not.
+    pub uses: Vec<String>,
+}
+
+fn lang_feature_info(feature: &str) -> Option<(&'static str, &'static str, Option<u32>)> {
+    if let Some(&(_, since, issue, ..)) = ACTIVE_FEATURES.iter().find(|f| f.0 == feature) {
+        return Some(("active", since, issue));
+    }
+    let inactive = [
+        ("accepted", ACCEPTED_FEATURES),
+        ("removed", REMOVED_FEATURES),
+        ("stable_removed", STABLE_REMOVED_FEATURES),
+    ];
+    inactive.iter().filter_map(|&(status, features)| {
+        features.iter()
+            .find(|f| f.0 == feature)
+            .map(|&(_, since, issue, _)| (status, since, issue))
+    }).next()
+}
+
+/// Lists every feature the crate declares or that was checked by a feature
+/// gate, sorted by name. Uses are only known if they were recorded in
+/// `sess.feature_gate_uses` while the crate was parsed, expanded and checked.
+///
+/// Library features are only known to the stability checker, so
+/// `lib_feature` is asked for them. It returns `Some(since)` for a feature
+/// defined by some crate, where `since` is `None` while the feature is
+/// unstable, and `None` for features nobody defines.
+pub fn feature_gate_report<F>(features: &Features, sess: &ParseSess, lib_feature: F)
+                              -> Vec<FeatureGateReport>
+    where F: Fn(Symbol) -> Option<Option<Symbol>>
+{
+    fn entry<'r>(report: &'r mut BTreeMap<String, FeatureGateReport>,
+                 name: Symbol,
+                 lib_feature: &dyn Fn(Symbol) -> Option<Option<Symbol>>)
+                 -> &'r mut FeatureGateReport {
+        report.entry(name.as_str().to_string()).or_insert_with(|| {
+            let (kind, status, since, tracking_issue) = match lang_feature_info(&name.as_str()) {
+                Some((status, since, issue)) => {
+                    ("lang", Some(status), Some(since.to_string()), issue)
+                }
+                None => match lib_feature(name) {
+                    Some(Some(since)) => ("lib", Some("stable"), Some(since.to_string()), None),
+                    Some(None) => ("lib", Some("unstable"), None, None),
+                    None => ("lib", None, None, None),
+                },
+            };
+            FeatureGateReport {
+                name: name.as_str().to_string(),
+                kind,
+                status,
+                since,
+                tracking_issue,
+                declared_at: None,
+                uses: vec![],
+            }
+        })
+    }
+
+    let source_map = sess.source_map();
+    let mut report = BTreeMap::new();
+    let declared = features.declared_lang_features.iter().map(|&(name, span, _)| (name, span))
+        .chain(features.declared_lib_features.iter().cloned());
+    for (name, span) in declared {
+        entry(&mut report, name, &lib_feature).declared_at =
+            Some(source_map.span_to_string(span));
+    }
+    if let Some(ref uses) = *sess.feature_gate_uses.borrow() {
+        for &(name, span) in uses {
+            let uses = &mut entry(&mut report, name, &lib_feature).uses;
+            let span = source_map.span_to_string(span);
+            // The same node may be checked more than once.
+            if uses.last() != Some(&span) {
+                uses.push(span);
+            }
+        }
+    }
+
+    report.into_iter().map(|(_, feature)| feature).collect()
+}
+
 #[derive(Clone, Copy, Hash)]
 pub enum UnstableFeatures {
     /// Hard errors for unstable features are active, as on beta/stable channels.
@@ -116,12 +116,14 @@ pub mod diagnostics {
 pub mod diagnostic_list;
 
 pub mod util {
+    pub mod ast_index;
     pub mod lev_distance;
     pub mod node_count;
     pub mod parser;
     #[cfg(test)]
     pub mod parser_testing;
     pub mod map_in_place;
+    pub mod roundtrip;
 }
 
 pub mod json;
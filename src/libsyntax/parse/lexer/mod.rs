@@ -174,7 +174,7 @@ impl<'a> StringReader<'a> {
         self.ch.is_none()
     }
 
-    fn fail_unterminated_raw_string(&self, pos: BytePos, hash_count: u16) {
+    fn fail_unterminated_raw_string(&mut self, pos: BytePos, hash_count: u16) {
         let mut err = self.struct_span_fatal(pos, pos, "unterminated raw string");
         err.span_label(self.mk_sp(pos, pos), "unterminated raw string");
 
@@ -183,14 +183,25 @@ impl<'a> StringReader<'a> {
                               "#".repeat(hash_count as usize)));
         }
 
-        err.emit();
-        FatalError.raise();
+        self.push_fatal(err);
     }
 
     fn fatal(&self, m: &str) -> FatalError {
         self.fatal_span(self.peek_span, m)
     }
 
+    /// Records a fatal lexical error. The current token is still scanned, but
+    /// `advance_token` fails once it is done, so that the caller decides
+    /// whether to emit or buffer the error. Only the first error is kept, as
+    /// nothing after it would have been reported if lexing stopped there.
+    fn push_fatal(&mut self, mut err: DiagnosticBuilder<'a>) {
+        if self.fatal_errs.is_empty() {
+            self.fatal_errs.push(err);
+        } else {
+            err.cancel();
+        }
+    }
+
     pub fn emit_fatal_errors(&mut self) {
         for err in &mut self.fatal_errs {
             err.emit();
@@ -325,11 +336,6 @@ impl<'a> StringReader<'a> {
     }
 
 
-    /// Report a fatal error spanning [`from_pos`, `to_pos`).
-    fn fatal_span_(&self, from_pos: BytePos, to_pos: BytePos, m: &str) -> FatalError {
-        self.fatal_span(self.mk_sp(from_pos, to_pos), m)
-    }
-
     /// Report a lexical error spanning [`from_pos`, `to_pos`).
     fn err_span_(&self, from_pos: BytePos, to_pos: BytePos, m: &str) {
         self.err_span(self.mk_sp(from_pos, to_pos), m)
@@ -348,16 +354,6 @@ impl<'a> StringReader<'a> {
         }
     }
 
-    /// Report a lexical error spanning [`from_pos`, `to_pos`), appending an
-    /// escaped character to the error message
-    fn fatal_span_char(&self, from_pos: BytePos, to_pos: BytePos, m: &str, c: char) -> FatalError {
-        let mut m = m.to_string();
-        m.push_str(": ");
-        Self::push_escaped_char_for_msg(&mut m, c);
-
-        self.fatal_span_(from_pos, to_pos, &m[..])
-    }
-
     fn struct_span_fatal(&self, from_pos: BytePos, to_pos: BytePos, m: &str)
         -> DiagnosticBuilder<'a>
     {
@@ -395,11 +391,13 @@ impl<'a> StringReader<'a> {
 
     /// Report a lexical error spanning [`from_pos`, `to_pos`), appending the
     /// offending string to the error message
-    fn fatal_span_verbose(&self, from_pos: BytePos, to_pos: BytePos, mut m: String) -> FatalError {
+    fn struct_fatal_span_verbose(&self, from_pos: BytePos, to_pos: BytePos, mut m: String)
+        -> DiagnosticBuilder<'a>
+    {
         m.push_str(": ");
         m.push_str(&self.src[self.src_index(from_pos)..self.src_index(to_pos)]);
 
-        self.fatal_span_(from_pos, to_pos, &m[..])
+        self.struct_span_fatal(from_pos, to_pos, &m[..])
     }
 
     /// Advance peek_tok and peek_span to refer to the next token, and
@@ -430,7 +428,11 @@ impl<'a> StringReader<'a> {
             }
         }
 
-        Ok(())
+        if self.fatal_errs.is_empty() {
+            Ok(())
+        } else {
+            Err(())
+        }
     }
 
     #[inline]
@@ -734,7 +736,9 @@ impl<'a> StringReader<'a> {
                     "unterminated block comment"
                 };
                 let last_bpos = self.pos;
-                self.fatal_span_(start_bpos, last_bpos, msg).raise();
+                let err = self.struct_span_fatal(start_bpos, last_bpos, msg);
+                self.push_fatal(err);
+                return None;
             }
             let n = self.ch.unwrap();
             match n {
@@ -894,9 +898,11 @@ impl<'a> StringReader<'a> {
         for _ in 0..n_digits {
             if self.is_eof() {
                 let last_bpos = self.pos;
-                self.fatal_span_(start_bpos,
-                                 last_bpos,
-                                 "unterminated numeric character escape").raise();
+                let err = self.struct_span_fatal(start_bpos,
+                                                 last_bpos,
+                                                 "unterminated numeric character escape");
+                self.push_fatal(err);
+                return false;
             }
             if self.ch_is(delim) {
                 let last_bpos = self.pos;
@@ -1137,9 +1143,11 @@ impl<'a> StringReader<'a> {
                     }
                 },
                 None => {
-                    self.fatal_span_(start_bpos,
-                                     self.pos,
-                                     "unterminated unicode escape (found EOF)").raise();
+                    let err = self.struct_span_fatal(start_bpos,
+                                                     self.pos,
+                                                     "unterminated unicode escape (found EOF)");
+                    self.push_fatal(err);
+                    return false;
                 }
             }
             self.bump();
@@ -1245,24 +1253,23 @@ impl<'a> StringReader<'a> {
                     self.bump();
                 }
 
-                return Ok(self.with_str_from(start, |string| {
-                    // FIXME: perform NFKC normalization here. (Issue #2253)
-                    let ident = self.mk_ident(string);
+                // FIXME: perform NFKC normalization here. (Issue #2253)
+                let ident = self.with_str_from(start, |string| self.mk_ident(string));
 
-                    if is_raw_ident && (ident.is_path_segment_keyword() ||
-                                        ident.name == keywords::Underscore.name()) {
-                        self.fatal_span_(raw_start, self.pos,
-                            &format!("`r#{}` is not currently supported.", ident.name)
-                        ).raise();
-                    }
+                if is_raw_ident && (ident.is_path_segment_keyword() ||
+                                    ident.name == keywords::Underscore.name()) {
+                    let err = self.struct_span_fatal(raw_start, self.pos,
+                        &format!("`r#{}` is not currently supported.", ident.name));
+                    self.push_fatal(err);
+                    return Err(());
+                }
 
-                    if is_raw_ident {
-                        let span = self.mk_sp(raw_start, self.pos);
-                        self.sess.raw_identifier_spans.borrow_mut().push(span);
-                    }
+                if is_raw_ident {
+                    let span = self.mk_sp(raw_start, self.pos);
+                    self.sess.raw_identifier_spans.borrow_mut().push(span);
+                }
 
-                    token::Ident(ident, is_raw_ident)
-                }));
+                return Ok(token::Ident(ident, is_raw_ident));
             }
         }
 
@@ -1478,8 +1485,10 @@ impl<'a> StringReader<'a> {
                         }
                     }
 
-                    self.fatal_span_verbose(start_with_quote, pos,
-                        String::from("character literal may only contain one codepoint")).raise();
+                    let err = self.struct_fatal_span_verbose(start_with_quote, pos,
+                        String::from("character literal may only contain one codepoint"));
+                    self.push_fatal(err);
+                    return Err(());
                 }
 
                 let id = if valid {
@@ -1496,9 +1505,9 @@ impl<'a> StringReader<'a> {
             'b' => {
                 self.bump();
                 let lit = match self.ch {
-                    Some('\'') => self.scan_byte(),
-                    Some('"') => self.scan_byte_string(),
-                    Some('r') => self.scan_raw_byte_string(),
+                    Some('\'') => self.scan_byte()?,
+                    Some('"') => self.scan_byte_string()?,
+                    Some('r') => self.scan_raw_byte_string()?,
                     _ => unreachable!(),  // Should have been a token::Ident above.
                 };
                 let suffix = self.scan_optional_raw_name();
@@ -1513,9 +1522,11 @@ impl<'a> StringReader<'a> {
                 while !self.ch_is('"') {
                     if self.is_eof() {
                         let last_bpos = self.pos;
-                        self.fatal_span_(start_bpos,
-                                         last_bpos,
-                                         "unterminated double quote string").raise();
+                        let err = self.struct_span_fatal(start_bpos,
+                                                         last_bpos,
+                                                         "unterminated double quote string");
+                        self.push_fatal(err);
+                        return Err(());
                     }
 
                     let ch_start = self.pos;
@@ -1541,10 +1552,13 @@ impl<'a> StringReader<'a> {
                 while self.ch_is('#') {
                     if hash_count == 65535 {
                         let bpos = self.next_pos;
-                        self.fatal_span_(start_bpos,
-                                         bpos,
-                                         "too many `#` symbols: raw strings may be \
-                                         delimited by up to 65535 `#` symbols").raise();
+                        let err = self.struct_span_fatal(start_bpos,
+                                                         bpos,
+                                                         "too many `#` symbols: raw strings may \
+                                                         be delimited by up to 65535 `#` \
+                                                         symbols");
+                        self.push_fatal(err);
+                        return Err(());
                     }
                     self.bump();
                     hash_count += 1;
@@ -1552,14 +1566,17 @@ impl<'a> StringReader<'a> {
 
                 if self.is_eof() {
                     self.fail_unterminated_raw_string(start_bpos, hash_count);
+                    return Err(());
                 } else if !self.ch_is('"') {
                     let last_bpos = self.pos;
                     let curr_char = self.ch.unwrap();
-                    self.fatal_span_char(start_bpos,
-                                         last_bpos,
-                                         "found invalid character; only `#` is allowed \
-                                         in raw string delimitation",
-                                         curr_char).raise();
+                    let err = self.struct_fatal_span_char(start_bpos,
+                                                          last_bpos,
+                                                          "found invalid character; only `#` \
+                                                          is allowed in raw string delimitation",
+                                                          curr_char);
+                    self.push_fatal(err);
+                    return Err(());
                 }
                 self.bump();
                 let content_start_bpos = self.pos;
@@ -1568,6 +1585,7 @@ impl<'a> StringReader<'a> {
                 'outer: loop {
                     if self.is_eof() {
                         self.fail_unterminated_raw_string(start_bpos, hash_count);
+                        return Err(());
                     }
                     // if self.ch_is('"') {
                     // content_end_bpos = self.pos;
@@ -1711,7 +1729,7 @@ impl<'a> StringReader<'a> {
         (self.ch_is('#') && self.nextch_is('!') && !self.nextnextch_is('['))
     }
 
-    fn scan_byte(&mut self) -> token::Lit {
+    fn scan_byte(&mut self) -> Result<token::Lit, ()> {
         self.bump();
         let start = self.pos;
 
@@ -1729,9 +1747,11 @@ impl<'a> StringReader<'a> {
             // character before position `start` are an
             // ascii single quote and ascii 'b'.
             let pos = self.pos;
-            self.fatal_span_verbose(start - BytePos(2),
-                                    pos,
-                                    "unterminated byte constant".to_string()).raise();
+            let err = self.struct_fatal_span_verbose(start - BytePos(2),
+                                                     pos,
+                                                     "unterminated byte constant".to_string());
+            self.push_fatal(err);
+            return Err(());
         }
 
         let id = if valid {
@@ -1741,7 +1761,7 @@ impl<'a> StringReader<'a> {
         };
         self.bump(); // advance ch past token
 
-        token::Byte(id)
+        Ok(token::Byte(id))
     }
 
     #[inline]
@@ -1749,7 +1769,7 @@ impl<'a> StringReader<'a> {
         self.scan_hex_digits(2, delim, below_0x7f_only)
     }
 
-    fn scan_byte_string(&mut self) -> token::Lit {
+    fn scan_byte_string(&mut self) -> Result<token::Lit, ()> {
         self.bump();
         let start = self.pos;
         let mut valid = true;
@@ -1757,7 +1777,11 @@ impl<'a> StringReader<'a> {
         while !self.ch_is('"') {
             if self.is_eof() {
                 let pos = self.pos;
-                self.fatal_span_(start, pos, "unterminated double quote byte string").raise();
+                let err = self.struct_span_fatal(start,
+                                                 pos,
+                                                 "unterminated double quote byte string");
+                self.push_fatal(err);
+                return Err(());
             }
 
             let ch_start = self.pos;
@@ -1777,20 +1801,22 @@ impl<'a> StringReader<'a> {
         };
         self.bump();
 
-        token::ByteStr(id)
+        Ok(token::ByteStr(id))
     }
 
-    fn scan_raw_byte_string(&mut self) -> token::Lit {
+    fn scan_raw_byte_string(&mut self) -> Result<token::Lit, ()> {
         let start_bpos = self.pos;
         self.bump();
         let mut hash_count = 0;
         while self.ch_is('#') {
             if hash_count == 65535 {
                 let bpos = self.next_pos;
-                self.fatal_span_(start_bpos,
-                                 bpos,
-                                 "too many `#` symbols: raw byte strings may be \
-                                 delimited by up to 65535 `#` symbols").raise();
+                let err = self.struct_span_fatal(start_bpos,
+                                                 bpos,
+                                                 "too many `#` symbols: raw byte strings may be \
+                                                 delimited by up to 65535 `#` symbols");
+                self.push_fatal(err);
+                return Err(());
             }
             self.bump();
             hash_count += 1;
@@ -1798,14 +1824,17 @@ impl<'a> StringReader<'a> {
 
         if self.is_eof() {
             self.fail_unterminated_raw_string(start_bpos, hash_count);
+            return Err(());
         } else if !self.ch_is('"') {
             let pos = self.pos;
             let ch = self.ch.unwrap();
-            self.fatal_span_char(start_bpos,
-                                        pos,
-                                        "found invalid character; only `#` is allowed in raw \
-                                         string delimitation",
-                                        ch).raise();
+            let err = self.struct_fatal_span_char(start_bpos,
+                                                  pos,
+                                                  "found invalid character; only `#` is allowed \
+                                                   in raw string delimitation",
+                                                  ch);
+            self.push_fatal(err);
+            return Err(());
         }
         self.bump();
         let content_start_bpos = self.pos;
@@ -1814,6 +1843,7 @@ impl<'a> StringReader<'a> {
             match self.ch {
                 None => {
                     self.fail_unterminated_raw_string(start_bpos, hash_count);
+                    return Err(());
                 }
                 Some('"') => {
                     content_end_bpos = self.pos;
@@ -1837,7 +1867,7 @@ impl<'a> StringReader<'a> {
 
         self.bump();
 
-        token::ByteStrRaw(self.name_from_to(content_start_bpos, content_end_bpos), hash_count)
+        Ok(token::ByteStrRaw(self.name_from_to(content_start_bpos, content_end_bpos), hash_count))
     }
 }
 
@@ -1929,6 +1959,9 @@ mod tests {
             raw_identifier_spans: Lock::new(Vec::new()),
             registered_diagnostics: Lock::new(ErrorMap::new()),
             buffered_lints: Lock::new(vec![]),
+            feature_gate_uses: Lock::new(None),
+            cfg_decisions: Lock::new(None),
+            expansion_traces: Lock::new(None),
         }
     }
 
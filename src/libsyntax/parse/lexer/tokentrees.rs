@@ -4,7 +4,16 @@ use crate::parse::{token, PResult};
 use crate::tokenstream::{DelimSpan, IsJoint::*, TokenStream, TokenTree, TreeAndJoint};
 
 impl<'a> StringReader<'a> {
+    // Move to the next token. If it can't be lexed, the errors are left in
+    // `fatal_errs` and the reader stops at `Eof`.
+    crate fn real_token_or_eof(&mut self) {
+        if self.try_real_token().is_err() {
+            self.token = token::Eof;
+        }
+    }
+
     // Parse a stream of tokens into a list of `TokenTree`s, up to an `Eof`.
+    // If lexing fails, this stops early and leaves the errors in `fatal_errs`.
     crate fn parse_all_token_trees(&mut self) -> PResult<'a, TokenStream> {
         let mut tts = Vec::new();
 
@@ -22,6 +31,11 @@ impl<'a> StringReader<'a> {
             if let token::CloseDelim(..) = self.token {
                 return TokenStream::new(tts);
             }
+            // Lexing failed, so this is not an un-closed delimiter. The
+            // lexing errors are reported once all the trees are closed.
+            if !self.fatal_errs.is_empty() {
+                return TokenStream::new(tts);
+            }
 
             match self.parse_token_tree() {
                 Ok(tree) => tts.push(tree),
@@ -73,7 +87,7 @@ impl<'a> StringReader<'a> {
 
                 // Parse the open delimiter.
                 self.open_braces.push((delim, self.span));
-                self.real_token();
+                self.real_token_or_eof();
 
                 // Parse the token trees within the delimiters.
                 // We stop at any delimiter so we can try to recover if the user
@@ -97,7 +111,7 @@ impl<'a> StringReader<'a> {
                             );
                         }
                         // Parse the close delimiter.
-                        self.real_token();
+                        self.real_token_or_eof();
                     }
                     // Incorrect delimiter.
                     token::CloseDelim(other) => {
@@ -142,7 +156,7 @@ impl<'a> StringReader<'a> {
                         //     bar(baz(
                         // }  // Incorrect delimiter but matches the earlier `{`
                         if !self.open_braces.iter().any(|&(b, _)| b == other) {
-                            self.real_token();
+                            self.real_token_or_eof();
                         }
                     }
                     token::Eof => {
@@ -174,7 +188,7 @@ impl<'a> StringReader<'a> {
                 // source span as the joint-ness is a property of the raw source
                 // rather than wanting to take `override_span` into account.
                 let raw = self.span_src_raw;
-                self.real_token();
+                self.real_token_or_eof();
                 let is_joint = raw.hi() == self.span_src_raw.lo() && token::is_op(&self.token);
                 Ok((tt, if is_joint { Joint } else { NonJoint }))
             }
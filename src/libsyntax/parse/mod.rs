@@ -1,7 +1,9 @@
 //! The main parser interface.
 
 use crate::ast::{self, CrateConfig, NodeId};
+use crate::config::CfgDecision;
 use crate::early_buffered_lints::{BufferedEarlyLint, BufferedEarlyLintId};
+use crate::ext::tt::macro_rules::ExpansionTrace;
 use crate::source_map::{SourceMap, FilePathMapping};
 use crate::feature_gate::UnstableFeatures;
 use crate::parse::parser::Parser;
@@ -10,10 +12,14 @@ use crate::syntax::parse::parser::emit_unclosed_delims;
 use crate::tokenstream::{TokenStream, TokenTree};
 use crate::diagnostics::plugin::ErrorMap;
 use crate::print::pprust::token_to_string;
+use crate::ptr::P;
 
 use errors::{FatalError, Level, Handler, ColorConfig, Diagnostic, DiagnosticBuilder};
 use rustc_data_structures::sync::{Lrc, Lock};
+use rustc_data_structures::OnDrop;
 use syntax_pos::{Span, SourceFile, FileName, MultiSpan};
+use syntax_pos::edition::Edition;
+use syntax_pos::hygiene;
 use log::debug;
 
 use rustc_data_structures::fx::FxHashSet;
@@ -32,6 +38,7 @@ pub mod token;
 pub mod attr;
 
 pub mod classify;
+pub mod reparse;
 
 /// Info about a parsing session.
 pub struct ParseSess {
@@ -47,6 +54,15 @@ pub struct ParseSess {
     included_mod_stack: Lock<Vec<PathBuf>>,
     source_map: Lrc<SourceMap>,
     pub buffered_lints: Lock<Vec<BufferedEarlyLint>>,
+    /// Features checked by feature gates and the spans requiring them, if
+    /// they are being recorded (for `-Z report-feature-gates`).
+    pub feature_gate_uses: Lock<Option<Vec<(Symbol, Span)>>>,
+    /// The outcomes of `#[cfg]` and `#[cfg_attr]` evaluation, if they are
+    /// being recorded (for `-Z explain-cfg`).
+    pub cfg_decisions: Lock<Option<Vec<CfgDecision>>>,
+    /// Records of `macro_rules!` expansions, if they are being recorded (for
+    /// `-Z trace-macros=detailed`).
+    pub expansion_traces: Lock<Option<Vec<ExpansionTrace>>>,
 }
 
 impl ParseSess {
@@ -70,6 +86,9 @@ impl ParseSess {
             included_mod_stack: Lock::new(vec![]),
             source_map,
             buffered_lints: Lock::new(vec![]),
+            feature_gate_uses: Lock::new(None),
+            cfg_decisions: Lock::new(None),
+            expansion_traces: Lock::new(None),
         }
     }
 
@@ -137,6 +156,78 @@ pub fn parse_crate_attrs_from_source_str(name: FileName, source: String, sess: &
     new_parser_from_source_str(sess, name, source).parse_inner_attributes()
 }
 
+/// Parses a single expression, which must make up the whole source, as code
+/// of the given edition.
+///
+/// Feature gates aren't checked while parsing, so no feature context is
+/// needed.
+pub fn parse_expr_from_source_str(name: FileName,
+                                  source: String,
+                                  sess: &ParseSess,
+                                  edition: Edition)
+                                  -> PResult<'_, P<ast::Expr>> {
+    parse_fragment_from_source_str(name, source, sess, edition, |parser| parser.parse_expr())
+}
+
+/// Parses a single type, which must make up the whole source, as code of the
+/// given edition.
+pub fn parse_ty_from_source_str(name: FileName,
+                                source: String,
+                                sess: &ParseSess,
+                                edition: Edition)
+                                -> PResult<'_, P<ast::Ty>> {
+    parse_fragment_from_source_str(name, source, sess, edition, |parser| parser.parse_ty())
+}
+
+/// Parses a single statement, including its trailing semicolon if any, which
+/// must make up the whole source, as code of the given edition.
+///
+/// Returns `Ok(None)` if the source contains no statement, e.g. if it is
+/// empty.
+pub fn parse_stmt_from_source_str(name: FileName,
+                                  source: String,
+                                  sess: &ParseSess,
+                                  edition: Edition)
+                                  -> PResult<'_, Option<ast::Stmt>> {
+    parse_fragment_from_source_str(name, source, sess, edition,
+                                   |parser| parser.parse_full_stmt(false))
+}
+
+/// Parses a fragment of code with `f`, reporting an error if it is followed by
+/// anything. Unlike `new_parser_from_source_str`, errors from lexing the
+/// source, such as unbalanced delimiters or an unterminated string literal,
+/// are returned rather than being fatal.
+///
+/// The edition is only in effect while parsing. Checks made later on the
+/// returned AST see the edition of the code it is put in.
+fn parse_fragment_from_source_str<'a, T, F>(name: FileName,
+                                            source: String,
+                                            sess: &'a ParseSess,
+                                            edition: Edition,
+                                            f: F)
+                                            -> PResult<'a, T>
+    where F: FnOnce(&mut Parser<'a>) -> PResult<'a, T>
+{
+    // Code that doesn't come from a macro expansion has the default edition.
+    let default_edition = hygiene::default_edition();
+    hygiene::set_default_edition(edition);
+    let _reset_edition = OnDrop(|| hygiene::set_default_edition(default_edition));
+
+    let mut parser = match maybe_new_parser_from_source_str(sess, name, source) {
+        Ok(parser) => parser,
+        Err(mut errors) => {
+            let last = errors.pop().expect("lexing failed without an error");
+            for error in errors {
+                DiagnosticBuilder::new_diagnostic(&sess.span_diagnostic, error).emit();
+            }
+            return Err(DiagnosticBuilder::new_diagnostic(&sess.span_diagnostic, last));
+        }
+    };
+    let fragment = f(&mut parser)?;
+    parser.expect(&token::Eof)?;
+    Ok(fragment)
+}
+
 pub fn parse_stream_from_source_str(
     name: FileName,
     source: String,
@@ -274,9 +365,18 @@ pub fn maybe_file_to_stream(
     override_span: Option<Span>,
 ) -> Result<(TokenStream, Vec<lexer::UnmatchedBrace>), Vec<Diagnostic>> {
     let mut srdr = lexer::StringReader::new_or_buffered_errs(sess, source_file, override_span)?;
-    srdr.real_token();
+    srdr.real_token_or_eof();
 
-    match srdr.parse_all_token_trees() {
+    let result = srdr.parse_all_token_trees();
+    let lex_errors = srdr.buffer_fatal_errors();
+    if !lex_errors.is_empty() {
+        if let Err(mut err) = result {
+            err.cancel();
+        }
+        return Err(lex_errors);
+    }
+
+    match result {
         Ok(stream) => Ok((stream, srdr.unmatched_braces)),
         Err(err) => {
             let mut buffer = Vec::with_capacity(1);
@@ -792,7 +892,6 @@ mod tests {
     use super::*;
     use crate::ast::{self, Ident, PatKind};
     use crate::attr::first_attr_value_str_by_name;
-    use crate::ptr::P;
     use crate::print::pprust::item_to_string;
     use crate::tokenstream::{DelimSpan, TokenTree};
     use crate::util::parser_testing::string_to_stream;
@@ -1082,4 +1181,44 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn parse_fragments() {
+        with_globals(|| {
+            let sess = ParseSess::new(FilePathMapping::empty());
+            let name = || FileName::Custom("fragment".to_string());
+
+            let parse_expr = |source: &str, edition| {
+                parse_expr_from_source_str(name(), source.to_string(), &sess, edition)
+            };
+            let edition = Edition::Edition2015;
+
+            let expr = parse_expr("a + b * 2", edition);
+            assert!(match expr.unwrap().node { ast::ExprKind::Binary(..) => true, _ => false });
+
+            let ty = parse_ty_from_source_str(name(), "Vec<&'a str>".to_string(), &sess, edition);
+            assert!(match ty.unwrap().node { ast::TyKind::Path(..) => true, _ => false });
+
+            let stmt = parse_stmt_from_source_str(name(), "let x = 1;".to_string(), &sess,
+                                                  edition);
+            assert!(match stmt.unwrap().unwrap().node { ast::StmtKind::Local(..) => true,
+                                                        _ => false });
+            let stmt = parse_stmt_from_source_str(name(), "".to_string(), &sess, edition);
+            assert!(stmt.unwrap().is_none());
+
+            // `try` is only reserved from the 2018 edition on.
+            let expr = parse_expr("try", Edition::Edition2015);
+            assert!(match expr.unwrap().node { ast::ExprKind::Path(..) => true, _ => false });
+            parse_expr("try", Edition::Edition2018).unwrap_err().cancel();
+            assert_eq!(hygiene::default_edition(), Edition::Edition2015);
+
+            // Trailing tokens and lexing errors are errors, not panics.
+            parse_expr("a b", edition).unwrap_err().cancel();
+            let ty = parse_ty_from_source_str(name(), "u8)".to_string(), &sess, edition);
+            ty.unwrap_err().cancel();
+            parse_expr("f(\"unterminated", edition).unwrap_err().cancel();
+            parse_expr("b'ab'", edition).unwrap_err().cancel();
+            parse_expr("/* unterminated", edition).unwrap_err().cancel();
+        });
+    }
 }
@@ -0,0 +1,386 @@
+//! Incremental reparsing of a single item after an edit.
+//!
+//! Reparsing a whole file on every keystroke doesn't scale to large files, but
+//! most edits made in an editor stay within a single item. `reparse_item`
+//! finds the innermost module-level item enclosing an edit, parses just the
+//! new text of that item and splices the result into the crate, moving the
+//! spans of everything else in the file over to the new contents.
+
+use crate::ast::{self, Crate, Item, ItemKind, NodeId, DUMMY_NODE_ID};
+use crate::attr;
+use crate::mut_visit::{self, MutVisitor};
+use crate::parse::{lexer, Directory, DirectoryOwnership, ParseSess, PResult};
+use crate::parse::parser::Parser;
+use crate::parse::token;
+use crate::ptr::P;
+
+use rustc_data_structures::sync::Lrc;
+use smallvec::SmallVec;
+use syntax_pos::{BytePos, FileName, Pos, SourceFile, Span};
+
+use std::borrow::Cow;
+use std::mem;
+use std::ops::Range;
+
+/// The result of a successful `reparse_item`.
+pub struct ReparsedItem {
+    /// The new version of the edited file, which all spans of the crate that
+    /// pointed into the old version now point into.
+    pub source_file: Lrc<SourceFile>,
+    /// The span of the reparsed item, including its outer attributes.
+    pub span: Span,
+    /// The `NodeId`s of the replaced item and everything in it, if they had
+    /// been assigned.
+    pub removed: Vec<NodeId>,
+    /// The `NodeId`s given to the new item and everything in it.
+    pub added: Vec<NodeId>,
+}
+
+/// Reparses the item of `krate` enclosing an edit of `old_file`, which
+/// replaced the bytes `edited` of its contents and resulted in `new_src`.
+///
+/// The new contents are registered in the source map in place of `old_file`,
+/// the innermost item (in a module, not an impl or trait) strictly enclosing
+/// `edited` is reparsed from them and replaces the old item in `krate`, and
+/// all other spans into `old_file` are moved to the corresponding positions
+/// in the new file.
+///
+/// If the old item had `NodeId`s assigned, the new item is given fresh ones
+/// from `next_node_id`; otherwise it is left with `DUMMY_NODE_ID`s, as after
+/// parsing.
+///
+/// Returns `Ok(None)`, leaving `krate` untouched, when the edit can't be
+/// handled by reparsing a single item: when it isn't strictly inside one,
+/// unbalances its delimiters, or turns it into something other than exactly
+/// one item. The caller should then reparse the whole crate, which picks up
+/// the new contents from the source map. Syntax errors within the item are
+/// returned as `Err`.
+pub fn reparse_item<'a>(sess: &'a ParseSess,
+                        krate: &mut Crate,
+                        old_file: &SourceFile,
+                        edited: Range<usize>,
+                        new_src: String,
+                        next_node_id: &mut dyn FnMut() -> NodeId)
+                        -> PResult<'a, Option<ReparsedItem>> {
+    let old_len = (old_file.end_pos - old_file.start_pos).to_usize();
+    assert!(edited.start <= edited.end && edited.end <= old_len,
+            "edited range {:?} out of bounds of a file of length {}", edited, old_len);
+    // The text after the edit is unchanged, so it has the same length.
+    let unchanged_suffix = old_len - edited.end;
+    assert!(new_src.len() >= edited.start + unchanged_suffix,
+            "new source is too short for an edit of {:?}", edited);
+    let new_end = new_src.len() - unchanged_suffix;
+
+    let edit_lo = old_file.start_pos + BytePos::from_usize(edited.start);
+    let edit_hi = old_file.start_pos + BytePos::from_usize(edited.end);
+    let mut path = vec![];
+    if !find_enclosing_item(&krate.module.items, edit_lo, edit_hi, &mut path) {
+        return Ok(None);
+    }
+    let (old_extent, directory) = {
+        let (item, enclosing) = item_at_path(&krate.module.items, &path);
+        (item_extent(item), item_directory(old_file, &enclosing))
+    };
+
+    let new_file = sess.source_map().replace_source_file(old_file, new_src);
+    let mut shift = ShiftSpans {
+        old_lo: old_file.start_pos,
+        old_hi: old_file.end_pos,
+        edit_lo,
+        edit_hi,
+        new_lo: new_file.start_pos,
+        growth: new_end as isize - edited.end as isize,
+    };
+    let new_extent = old_extent.with_lo(shift.shift(old_extent.lo()))
+                               .with_hi(shift.shift(old_extent.hi()));
+
+    let mut new_item = match parse_item_at(sess, new_extent, directory)? {
+        Some(item) => item,
+        None => return Ok(None),
+    };
+
+    let slot = item_slot_at_path(&mut krate.module.items, &path);
+    let mut added = vec![];
+    if slot.id != DUMMY_NODE_ID {
+        let mut assign = AssignIds { next_node_id, assigned: &mut added };
+        new_item = assign.flat_map_item(new_item).pop().unwrap();
+    }
+    let old_item = mem::replace(slot, new_item);
+
+    let mut removed = CollectIds(vec![]);
+    removed.flat_map_item(old_item);
+    let removed = removed.0.into_iter().filter(|&id| id != DUMMY_NODE_ID).collect();
+
+    shift.visit_crate(krate);
+
+    Ok(Some(ReparsedItem {
+        source_file: new_file,
+        span: new_extent,
+        removed,
+        added,
+    }))
+}
+
+/// The span of `item` including its outer attributes and doc comments, which
+/// aren't part of `item.span`.
+fn item_extent(item: &Item) -> Span {
+    let lo = item.attrs.iter()
+        .filter(|attr| attr.style == ast::AttrStyle::Outer)
+        .map(|attr| attr.span.lo())
+        .fold(item.span.lo(), |lo, attr_lo| if attr_lo < lo { attr_lo } else { lo });
+    item.span.with_lo(lo)
+}
+
+/// Finds the innermost item in `items` or in the inline modules among them
+/// whose extent strictly encloses `lo..hi`, pushing the indices leading to it
+/// onto `path`.
+///
+/// An edit touching the boundary of an item may well be adding text before or
+/// after it rather than changing it, so that isn't considered enclosed.
+fn find_enclosing_item(items: &[P<Item>], lo: BytePos, hi: BytePos, path: &mut Vec<usize>)
+                       -> bool {
+    for (i, item) in items.iter().enumerate() {
+        let extent = item_extent(item);
+        if extent.ctxt() != syntax_pos::NO_EXPANSION || !(extent.lo() < lo && hi < extent.hi()) {
+            continue;
+        }
+
+        path.push(i);
+        if let ItemKind::Mod(ref module) = item.node {
+            if module.inline {
+                find_enclosing_item(&module.items, lo, hi, path);
+            }
+        }
+        return true;
+    }
+    false
+}
+
+/// Returns the item at `path` along with the inline modules enclosing it.
+fn item_at_path<'i>(items: &'i [P<Item>], path: &[usize]) -> (&'i Item, Vec<&'i Item>) {
+    let mut enclosing = vec![];
+    let mut item = &items[path[0]];
+    for &i in &path[1..] {
+        enclosing.push(&**item);
+        item = match item.node {
+            ItemKind::Mod(ref module) => &module.items[i],
+            _ => unreachable!(),
+        };
+    }
+    (item, enclosing)
+}
+
+fn item_slot_at_path<'i>(items: &'i mut Vec<P<Item>>, path: &[usize]) -> &'i mut P<Item> {
+    let mut slot = &mut items[path[0]];
+    for &i in &path[1..] {
+        slot = match slot.node {
+            ItemKind::Mod(ref mut module) => &mut module.items[i],
+            _ => unreachable!(),
+        };
+    }
+    slot
+}
+
+/// The directory that `mod foo;` declarations in an item nested in the
+/// inline modules `enclosing` of `file` are relative to, as the parser would
+/// have computed it.
+fn item_directory<'a>(file: &SourceFile, enclosing: &[&Item]) -> Option<Directory<'a>> {
+    let mut path = match file.unmapped_path {
+        Some(FileName::Real(ref path)) => path.clone(),
+        _ => return None,
+    };
+    path.pop();
+    for module in enclosing {
+        match attr::first_attr_value_str_by_name(&module.attrs, "path") {
+            Some(module_path) => path.push(&*module_path.as_str()),
+            None => path.push(&*module.ident.as_str()),
+        }
+    }
+    Some(Directory {
+        path: Cow::from(path),
+        ownership: DirectoryOwnership::Owned { relative: None },
+    })
+}
+
+/// Parses the text at `span` as a single item.
+fn parse_item_at<'a>(sess: &'a ParseSess, span: Span, directory: Option<Directory<'a>>)
+                     -> PResult<'a, Option<P<Item>>> {
+    let mut reader = lexer::StringReader::retokenize(sess, span);
+    reader.real_token();
+    // Unbalanced delimiters mean that the edit changed where the item ends.
+    let stream = match reader.parse_all_token_trees() {
+        Ok(stream) => stream,
+        Err(mut err) => {
+            err.cancel();
+            return Ok(None);
+        }
+    };
+    if !reader.unmatched_braces.is_empty() {
+        return Ok(None);
+    }
+
+    let mut parser = Parser::new(sess, stream, directory, true, false);
+    let item = parser.parse_item()?;
+    if parser.token != token::Eof {
+        return Ok(None);
+    }
+    Ok(item)
+}
+
+/// Moves spans into the old version of a file to the corresponding positions
+/// in the new version. Positions within the edited range have no counterpart,
+/// but no spans outside of the reparsed item can point there.
+struct ShiftSpans {
+    old_lo: BytePos,
+    old_hi: BytePos,
+    edit_lo: BytePos,
+    edit_hi: BytePos,
+    new_lo: BytePos,
+    growth: isize,
+}
+
+impl ShiftSpans {
+    fn shift(&self, pos: BytePos) -> BytePos {
+        let offset = (pos - self.old_lo).to_usize();
+        if pos <= self.edit_lo {
+            self.new_lo + BytePos::from_usize(offset)
+        } else {
+            debug_assert!(pos >= self.edit_hi);
+            self.new_lo + BytePos::from_usize((offset as isize + self.growth) as usize)
+        }
+    }
+}
+
+impl MutVisitor for ShiftSpans {
+    fn visit_span(&mut self, span: &mut Span) {
+        if self.old_lo <= span.lo() && span.hi() <= self.old_hi {
+            *span = span.with_lo(self.shift(span.lo())).with_hi(self.shift(span.hi()));
+        }
+    }
+
+    fn flat_map_item(&mut self, mut item: P<Item>) -> SmallVec<[P<Item>; 1]> {
+        if let Some(ref mut tokens) = item.tokens {
+            self.visit_tts(tokens);
+        }
+        mut_visit::noop_flat_map_item(item, self)
+    }
+
+    fn flat_map_trait_item(&mut self, mut item: ast::TraitItem)
+                           -> SmallVec<[ast::TraitItem; 1]> {
+        if let Some(ref mut tokens) = item.tokens {
+            self.visit_tts(tokens);
+        }
+        mut_visit::noop_flat_map_trait_item(item, self)
+    }
+
+    fn flat_map_impl_item(&mut self, mut item: ast::ImplItem)
+                          -> SmallVec<[ast::ImplItem; 1]> {
+        if let Some(ref mut tokens) = item.tokens {
+            self.visit_tts(tokens);
+        }
+        mut_visit::noop_flat_map_impl_item(item, self)
+    }
+
+    fn visit_mac(&mut self, mac: &mut ast::Mac) {
+        mut_visit::noop_visit_mac(mac, self)
+    }
+}
+
+struct CollectIds(Vec<NodeId>);
+
+impl MutVisitor for CollectIds {
+    fn visit_id(&mut self, id: &mut NodeId) {
+        self.0.push(*id);
+    }
+
+    fn visit_mac(&mut self, mac: &mut ast::Mac) {
+        mut_visit::noop_visit_mac(mac, self)
+    }
+}
+
+struct AssignIds<'f, 'v> {
+    next_node_id: &'f mut dyn FnMut() -> NodeId,
+    assigned: &'v mut Vec<NodeId>,
+}
+
+impl<'f, 'v> MutVisitor for AssignIds<'f, 'v> {
+    fn visit_id(&mut self, id: &mut NodeId) {
+        *id = (self.next_node_id)();
+        self.assigned.push(*id);
+    }
+
+    fn visit_mac(&mut self, mac: &mut ast::Mac) {
+        mut_visit::noop_visit_mac(mac, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse_crate_from_source_str;
+    use crate::source_map::FilePathMapping;
+    use crate::with_globals;
+
+    fn reparse(src: &str, edited: Range<usize>, new_src: &str)
+               -> (ParseSess, Crate, Option<ReparsedItem>) {
+        let sess = ParseSess::new(FilePathMapping::empty());
+        let mut krate = parse_crate_from_source_str(FileName::Custom("test".to_string()),
+                                                    src.to_string(),
+                                                    &sess).unwrap();
+        let old_file = sess.source_map().files()[0].clone();
+        let mut next_id = 0;
+        let reparsed = reparse_item(&sess, &mut krate, &old_file, edited, new_src.to_string(),
+                                    &mut || { next_id += 1; NodeId::from_u32(next_id) })
+            .unwrap();
+        (sess, krate, reparsed)
+    }
+
+    #[test]
+    fn reparse_fn_body() {
+        with_globals(|| {
+            let src = "fn a() {}\n/// b\nfn b() { 1 }\nfn c() {}\n";
+            let new_src = "fn a() {}\n/// b\nfn b() { 1 + 2 }\nfn c() {}\n";
+            let (sess, krate, reparsed) = reparse(src, 25..26, new_src);
+            let reparsed = reparsed.unwrap();
+
+            let source_map = sess.source_map();
+            assert_eq!(source_map.span_to_snippet(reparsed.span).unwrap(),
+                       "/// b\nfn b() { 1 + 2 }");
+            // Spans before and after the edit point into the new file.
+            let snippets: Vec<_> = krate.module.items.iter()
+                .map(|item| source_map.span_to_snippet(item.span).unwrap())
+                .collect();
+            assert_eq!(snippets, ["fn a() {}", "fn b() { 1 + 2 }", "fn c() {}"]);
+            assert_eq!(source_map.lookup_char_pos(krate.module.items[2].span.lo()).line, 4);
+            // The crate was never assigned ids, so none were replaced.
+            assert!(reparsed.removed.is_empty() && reparsed.added.is_empty());
+        })
+    }
+
+    #[test]
+    fn reparse_in_inline_module() {
+        with_globals(|| {
+            let src = "mod m {\n    fn f() {}\n}\n";
+            let new_src = "mod m {\n    fn f(x: u8) {}\n}\n";
+            let (sess, krate, reparsed) = reparse(src, 17..17, new_src);
+            let reparsed = reparsed.unwrap();
+            assert_eq!(sess.source_map().span_to_snippet(reparsed.span).unwrap(),
+                       "fn f(x: u8) {}");
+            assert_eq!(sess.source_map().span_to_snippet(krate.module.items[0].span).unwrap(),
+                       "mod m {\n    fn f(x: u8) {}\n}");
+        })
+    }
+
+    #[test]
+    fn edits_not_within_one_item_are_rejected() {
+        with_globals(|| {
+            let src = "fn a() {}\nfn b() {}\n";
+            // Spans the boundary between two items.
+            assert!(reparse(src, 8..12, "fn a() {n b() {}\n").2.is_none());
+            // Splits an item in two.
+            assert!(reparse(src, 8..8, "fn a() {} fn z() {}\nfn b() {}\n").2.is_none());
+            // Unbalances its delimiters.
+            assert!(reparse(src, 8..8, "fn a() {{}\nfn b() {}\n").2.is_none());
+        })
+    }
+}
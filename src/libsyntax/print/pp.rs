@@ -133,6 +133,14 @@
 //! In this implementation (following the paper, again) the SCAN process is the
 //! methods called `Printer::pretty_print_*`, and the 'PRINT' process is the
 //! method called `Printer::print`.
+//!
+//! # Using the printer
+//!
+//! Nothing here is specific to Rust syntax: `pprust` is just one client that
+//! feeds the printer through the convenience methods at the end of `Printer`.
+//! Other clients can do the same, writing to any `io::Write` with
+//! `mk_printer`, to a `String` or a custom `PrintSink` with
+//! `mk_printer_to_sink`, or most simply through `print_to_string`.
 
 use std::collections::VecDeque;
 use std::fmt;
@@ -140,6 +148,48 @@ use std::io;
 use std::borrow::Cow;
 use log::debug;
 
+/// Where a `Printer` writes its output.
+///
+/// The printer hands its sink words, runs of spaces and newlines in the order
+/// they appear in the output, so a sink can, for example, count lines or keep
+/// track of columns without having to parse anything.
+pub trait PrintSink {
+    /// Writes `s` at the current position.
+    fn write_str(&mut self, s: &str) -> io::Result<()>;
+
+    /// Ends the current line. The indentation of the next line, if any, is
+    /// written with `write_str` once something is printed on it.
+    fn write_newline(&mut self) -> io::Result<()> {
+        self.write_str("\n")
+    }
+}
+
+impl PrintSink for String {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl<'a, S: PrintSink + ?Sized> PrintSink for &'a mut S {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        (**self).write_str(s)
+    }
+
+    fn write_newline(&mut self) -> io::Result<()> {
+        (**self).write_newline()
+    }
+}
+
+/// Adapts an `io::Write` into a `PrintSink`.
+struct WriteSink<W>(W);
+
+impl<W: io::Write> PrintSink for WriteSink<W> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.0.write_all(s.as_bytes())
+    }
+}
+
 /// How to break. Described in more detail in the module docs.
 #[derive(Clone, Copy, PartialEq)]
 pub enum Breaks {
@@ -236,7 +286,15 @@ pub struct PrintStackElem {
 
 const SIZE_INFINITY: isize = 0xffff;
 
+/// Creates a printer that writes to `out`, keeping lines within `linewidth`
+/// columns where possible.
 pub fn mk_printer<'a>(out: Box<dyn io::Write+'a>, linewidth: usize) -> Printer<'a> {
+    mk_printer_to_sink(Box::new(WriteSink(out)), linewidth)
+}
+
+/// Creates a printer that writes to the sink `out`, keeping lines within
+/// `linewidth` columns where possible.
+pub fn mk_printer_to_sink<'a>(out: Box<dyn PrintSink+'a>, linewidth: usize) -> Printer<'a> {
     // Yes 55, it makes the ring buffers big enough to never fall behind.
     let n: usize = 55 * linewidth;
     debug!("mk_printer {}", linewidth);
@@ -258,8 +316,64 @@ pub fn mk_printer<'a>(out: Box<dyn io::Write+'a>, linewidth: usize) -> Printer<'
     }
 }
 
+/// Runs `f` on a fresh printer with the given line width, and returns
+/// everything it printed.
+///
+/// The printer is flushed with `eof` after `f` returns, so `f` must have
+/// closed every box it opened.
+///
+/// # Examples
+///
+/// ```ignore (needs the `rustc_private` crate `syntax`)
+/// let s = pp::print_to_string(20, |p| {
+///     p.cbox(4)?;
+///     p.word("foo(aaaa,")?;
+///     p.space()?;
+///     p.word("bbbb,")?;
+///     p.space()?;
+///     p.word("cccc)")?;
+///     p.end()
+/// }).unwrap();
+/// assert_eq!(s, "foo(aaaa,\n    bbbb,\n    cccc)");
+/// ```
+pub fn print_to_string<F>(linewidth: usize, f: F) -> io::Result<String>
+    where F: FnOnce(&mut Printer<'_>) -> io::Result<()>
+{
+    let mut out = String::new();
+    {
+        let mut printer = mk_printer_to_sink(Box::new(&mut out), linewidth);
+        f(&mut printer)?;
+        printer.eof()?;
+    }
+    Ok(out)
+}
+
+/// The pretty-printing engine.
+///
+/// The output is described to the printer as a stream of tokens: words,
+/// breaks where a line may be broken, and boxes grouping them, opened by
+/// `rbox`, `ibox` or `cbox` and closed by `end`. The printer then decides
+/// which breaks to turn into newlines:
+///
+/// - If a whole box fits on the rest of the line, none of its breaks are
+///   taken.
+/// - Otherwise, every break of a consistent box is taken, while a break of an
+///   inconsistent box is only taken if what follows it up to the next break
+///   would not fit on the line.
+/// - A break that is taken starts a new line, indented by the indentation of
+///   its box plus the break's own offset. One that is not taken prints its
+///   blank space instead.
+///
+/// Callers must uphold a few invariants, which are not checked:
+///
+/// - Every box is closed by exactly one `end`.
+/// - `eof` is called once everything has been printed and all boxes are
+///   closed. Tokens are buffered until it is known how to print them, so
+///   output may be missing until then.
+/// - Words do not contain newlines, which would throw off the printer's idea
+///   of the current column. Use `hardbreak` instead.
 pub struct Printer<'a> {
-    out: Box<dyn io::Write+'a>,
+    out: Box<dyn PrintSink+'a>,
     buf_max_len: usize,
     /// Width of lines we're constrained to
     margin: isize,
@@ -495,7 +609,7 @@ impl<'a> Printer<'a> {
 
     pub fn print_newline(&mut self, amount: isize) -> io::Result<()> {
         debug!("NEWLINE {}", amount);
-        let ret = write!(self.out, "\n");
+        let ret = self.out.write_newline();
         self.pending_indentation = 0;
         self.indent(amount);
         ret
@@ -581,10 +695,10 @@ impl<'a> Printer<'a> {
         // assert!(len <= space);
         self.space -= len;
         while self.pending_indentation > 0 {
-            write!(self.out, " ")?;
+            self.out.write_str(" ")?;
             self.pending_indentation -= 1;
         }
-        write!(self.out, "{}", s)
+        self.out.write_str(&s)
     }
 
     pub fn print(&mut self, token: Token, l: isize) -> io::Result<()> {
@@ -671,3 +785,91 @@ impl<'a> Printer<'a> {
         Self::hardbreak_tok_offset(0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call_list(p: &mut Printer<'_>, args: &[&'static str]) -> io::Result<()> {
+        p.word("foo(")?;
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                p.word(",")?;
+                p.space()?;
+            }
+            p.word(*arg)?;
+        }
+        p.word(")")
+    }
+
+    #[test]
+    fn box_that_fits_is_not_broken() {
+        let s = print_to_string(40, |p| {
+            p.cbox(4)?;
+            call_list(p, &["aaaa", "bbbb", "cccc", "dddd"])?;
+            p.end()
+        }).unwrap();
+        assert_eq!(s, "foo(aaaa, bbbb, cccc, dddd)");
+    }
+
+    #[test]
+    fn inconsistent_box_breaks_where_needed() {
+        let s = print_to_string(20, |p| {
+            p.ibox(4)?;
+            call_list(p, &["aaaa", "bbbb", "cccc", "dddd"])?;
+            p.end()
+        }).unwrap();
+        assert_eq!(s, "foo(aaaa, bbbb,\n    cccc, dddd)");
+    }
+
+    #[test]
+    fn consistent_box_breaks_everywhere() {
+        let s = print_to_string(20, |p| {
+            p.cbox(4)?;
+            call_list(p, &["aaaa", "bbbb", "cccc", "dddd"])?;
+            p.end()
+        }).unwrap();
+        assert_eq!(s, "foo(aaaa,\n    bbbb,\n    cccc,\n    dddd)");
+    }
+
+    #[test]
+    fn hardbreak_always_breaks() {
+        let s = print_to_string(80, |p| {
+            p.word("a")?;
+            p.hardbreak()?;
+            p.word("b")
+        }).unwrap();
+        assert_eq!(s, "a\nb");
+    }
+
+    #[test]
+    fn custom_sink() {
+        #[derive(Default)]
+        struct Lines(Vec<String>);
+
+        impl PrintSink for Lines {
+            fn write_str(&mut self, s: &str) -> io::Result<()> {
+                if self.0.is_empty() {
+                    self.0.push(String::new());
+                }
+                self.0.last_mut().unwrap().push_str(s);
+                Ok(())
+            }
+
+            fn write_newline(&mut self) -> io::Result<()> {
+                self.0.push(String::new());
+                Ok(())
+            }
+        }
+
+        let mut lines = Lines::default();
+        {
+            let mut p = mk_printer_to_sink(Box::new(&mut lines), 20);
+            p.ibox(4).unwrap();
+            call_list(&mut p, &["aaaa", "bbbb", "cccc", "dddd"]).unwrap();
+            p.end().unwrap();
+            p.eof().unwrap();
+        }
+        assert_eq!(lines.0, ["foo(aaaa, bbbb,", "    cccc, dddd)"]);
+    }
+}
@@ -92,6 +92,16 @@ impl FileLoader for RealFileLoader {
     }
 }
 
+/// In-memory contents that replace those of a file on disk, e.g., an unsaved
+/// editor buffer. See `SourceMap::set_overlay`.
+#[derive(Clone, Debug)]
+pub struct FileOverlay {
+    pub src: String,
+    /// An opaque version number chosen by the client, so that it can tell
+    /// which contents of a buffer a compilation saw.
+    pub version: u64,
+}
+
 // This is a SourceFile identifier that is used to correlate SourceFiles between
 // subsequent compilation sessions (which is something we need to do during
 // incremental compilation).
@@ -131,6 +141,9 @@ pub(super) struct SourceMapFiles {
 pub struct SourceMap {
     pub(super) files: Lock<SourceMapFiles>,
     file_loader: Box<dyn FileLoader + Sync + Send>,
+    // Files whose contents are taken from memory instead of the file loader,
+    // keyed by absolute path.
+    overlays: Lock<FxHashMap<PathBuf, FileOverlay>>,
     // This is used to apply the file path remapping as specified via
     // --remap-path-prefix to all SourceFiles allocated within this SourceMap.
     path_mapping: FilePathMapping,
@@ -141,6 +154,7 @@ impl SourceMap {
         SourceMap {
             files: Default::default(),
             file_loader: Box::new(RealFileLoader),
+            overlays: Default::default(),
             path_mapping,
         }
     }
@@ -151,6 +165,7 @@ impl SourceMap {
         SourceMap {
             files: Default::default(),
             file_loader: file_loader,
+            overlays: Default::default(),
             path_mapping,
         }
     }
@@ -160,15 +175,58 @@ impl SourceMap {
     }
 
     pub fn file_exists(&self, path: &Path) -> bool {
-        self.file_loader.file_exists(path)
+        self.overlays.borrow().contains_key(&self.overlay_key(path)) ||
+            self.file_loader.file_exists(path)
+    }
+
+    /// Reads the contents of `path`, from its overlay if it has one and from
+    /// the file loader otherwise.
+    pub fn read_file(&self, path: &Path) -> io::Result<String> {
+        match self.overlay_source(path) {
+            Some(src) => Ok(src),
+            None => self.file_loader.read_file(path),
+        }
     }
 
     pub fn load_file(&self, path: &Path) -> io::Result<Lrc<SourceFile>> {
-        let src = self.file_loader.read_file(path)?;
+        let src = self.read_file(path)?;
         let filename = path.to_owned().into();
         Ok(self.new_source_file(filename, src))
     }
 
+    fn overlay_key(&self, path: &Path) -> PathBuf {
+        self.file_loader.abs_path(path).unwrap_or_else(|| path.to_path_buf())
+    }
+
+    /// Makes `src` the contents of `path` for all subsequent loads through
+    /// this `SourceMap`, whether or not the file exists on disk. Returns the
+    /// version of the overlay this replaces, if any.
+    ///
+    /// Files that were already loaded are not affected.
+    pub fn set_overlay(&self, path: &Path, src: String, version: u64) -> Option<u64> {
+        let key = self.overlay_key(path);
+        self.overlays.borrow_mut()
+            .insert(key, FileOverlay { src, version })
+            .map(|old| old.version)
+    }
+
+    /// Removes the overlay of `path`, so that it is read from the file loader
+    /// again. Returns the removed overlay, if any.
+    pub fn remove_overlay(&self, path: &Path) -> Option<FileOverlay> {
+        let key = self.overlay_key(path);
+        self.overlays.borrow_mut().remove(&key)
+    }
+
+    /// Returns the version of the overlay of `path`, if it has one.
+    pub fn overlay_version(&self, path: &Path) -> Option<u64> {
+        self.overlays.borrow().get(&self.overlay_key(path)).map(|overlay| overlay.version)
+    }
+
+    /// Returns the contents of the overlay of `path`, if it has one.
+    pub fn overlay_source(&self, path: &Path) -> Option<String> {
+        self.overlays.borrow().get(&self.overlay_key(path)).map(|overlay| overlay.src.clone())
+    }
+
     pub fn files(&self) -> MappedLockGuard<'_, Vec<Lrc<SourceFile>>> {
         LockGuard::map(self.files.borrow(), |files| &mut files.source_files)
     }
@@ -233,6 +291,30 @@ impl SourceMap {
         }
     }
 
+    /// Registers `src` as the new contents of the local file `old`.
+    ///
+    /// The new `SourceFile` is allocated after all existing ones, so spans
+    /// into the old contents stay valid, but it takes the place of `old` for
+    /// lookups by stable id, including the deduplication in `new_source_file`.
+    pub fn replace_source_file(&self, old: &SourceFile, src: String) -> Lrc<SourceFile> {
+        let unmapped_path = old.unmapped_path.clone()
+            .expect("SourceMap::replace_source_file called for imported SourceFile?");
+        let source_file = Lrc::new(SourceFile::new(
+            old.name.clone(),
+            old.name_was_remapped,
+            unmapped_path,
+            src,
+            Pos::from_usize(self.next_start_pos()),
+        ));
+
+        let mut files = self.files.borrow_mut();
+
+        files.source_files.push(source_file.clone());
+        files.stable_id_to_source_file.insert(StableSourceFileId::new(old), source_file.clone());
+
+        source_file
+    }
+
     /// Allocates a new SourceFile representing a source file from an external
     /// crate. The source code of such an "imported source_file" is not available,
     /// but we still know enough to generate accurate debuginfo location
@@ -291,6 +373,62 @@ impl SourceMap {
         source_file
     }
 
+    /// Takes a snapshot of the files in this source map, which includes their
+    /// sources if `include_src` is true and they are known.
+    pub fn snapshot(&self, include_src: bool) -> SourceMapSnapshot {
+        let files = self.files.borrow().source_files.iter().map(|file| {
+            let start_pos = file.start_pos;
+            let src = if !include_src {
+                None
+            } else if let Some(ref src) = file.src {
+                Some(String::clone(src))
+            } else if let ExternalSource::Present(ref src) = *file.external_src.borrow() {
+                Some(src.clone())
+            } else {
+                None
+            };
+            SnapshotFile {
+                name: file.name.clone(),
+                name_was_remapped: file.name_was_remapped,
+                src_hash: file.src_hash,
+                name_hash: file.name_hash,
+                start_pos,
+                source_len: (file.end_pos - start_pos).to_usize(),
+                src,
+                lines: file.lines.iter().map(|&pos| pos - start_pos).collect(),
+                multibyte_chars: file.multibyte_chars.iter().map(|mbc| {
+                    MultiByteChar { pos: mbc.pos - start_pos, bytes: mbc.bytes }
+                }).collect(),
+                non_narrow_chars: file.non_narrow_chars.iter().map(|&swc| swc - start_pos).collect(),
+            }
+        }).collect();
+        SourceMapSnapshot { files }
+    }
+
+    /// Loads the files of `snapshot` into this source map, after the files it
+    /// already contains. They are treated like files imported from another
+    /// crate, but with their sources available if the snapshot has them.
+    pub fn load_snapshot(&self, snapshot: SourceMapSnapshot) -> LoadedSnapshot {
+        let files = snapshot.files.into_iter().map(|file| {
+            let source_file = self.new_imported_source_file(
+                file.name,
+                file.name_was_remapped,
+                0,
+                file.src_hash,
+                file.name_hash,
+                file.source_len,
+                file.lines,
+                file.multibyte_chars,
+                file.non_narrow_chars,
+            );
+            if let Some(src) = file.src {
+                *source_file.external_src.borrow_mut() = ExternalSource::Present(src);
+            }
+            (file.start_pos, source_file)
+        }).collect();
+        LoadedSnapshot { files }
+    }
+
     pub fn mk_substr_filename(&self, sp: Span) -> String {
         let pos = self.lookup_char_pos(sp.lo());
         format!("<{}:{}:{}>",
@@ -980,6 +1118,67 @@ impl SourceMapper for SourceMap {
     }
 }
 
+/// A file in a `SourceMapSnapshot`. The positions of lines and characters are
+/// relative to the start of the file.
+#[derive(Clone, RustcEncodable, RustcDecodable)]
+pub struct SnapshotFile {
+    pub name: FileName,
+    pub name_was_remapped: bool,
+    pub src_hash: u128,
+    pub name_hash: u128,
+    /// The start of the file in the source map the snapshot was taken of.
+    pub start_pos: BytePos,
+    pub source_len: usize,
+    /// The source of the file, if the snapshot includes sources.
+    pub src: Option<String>,
+    pub lines: Vec<BytePos>,
+    pub multibyte_chars: Vec<MultiByteChar>,
+    pub non_narrow_chars: Vec<NonNarrowChar>,
+}
+
+/// The files of a `SourceMap`, detached from it so that they can be encoded
+/// along with data containing spans, and loaded into another source map to
+/// interpret those spans later.
+///
+/// Files are recorded under their names after `--remap-path-prefix` has been
+/// applied, so a snapshot contains no paths that were remapped away.
+#[derive(Clone, RustcEncodable, RustcDecodable)]
+pub struct SourceMapSnapshot {
+    pub files: Vec<SnapshotFile>,
+}
+
+/// The files of a `SourceMapSnapshot` once loaded into a `SourceMap`.
+pub struct LoadedSnapshot {
+    /// The start of each file in the snapshotted source map, along with the
+    /// file it was loaded as, in order.
+    files: Vec<(BytePos, Lrc<SourceFile>)>,
+}
+
+impl LoadedSnapshot {
+    pub fn files(&self) -> impl Iterator<Item = &Lrc<SourceFile>> {
+        self.files.iter().map(|(_, file)| file)
+    }
+
+    /// Translates a position in the snapshotted source map into the source
+    /// map the snapshot was loaded into.
+    pub fn translate_pos(&self, pos: BytePos) -> Option<BytePos> {
+        let idx = match self.files.binary_search_by_key(&pos, |&(start_pos, _)| start_pos) {
+            Ok(idx) => idx,
+            Err(0) => return None,
+            Err(idx) => idx - 1,
+        };
+        let (start_pos, ref file) = self.files[idx];
+        let new_pos = file.start_pos + (pos - start_pos);
+        if new_pos <= file.end_pos { Some(new_pos) } else { None }
+    }
+
+    /// Translates a span in the snapshotted source map into the source map
+    /// the snapshot was loaded into. The syntax context is kept as it is.
+    pub fn translate_span(&self, span: Span) -> Option<Span> {
+        Some(span.with_lo(self.translate_pos(span.lo())?).with_hi(self.translate_pos(span.hi())?))
+    }
+}
+
 #[derive(Clone)]
 pub struct FilePathMapping {
     mapping: Vec<(PathBuf, PathBuf)>,
@@ -1024,6 +1223,27 @@ mod tests {
     use super::*;
     use rustc_data_structures::sync::Lrc;
 
+    #[test]
+    fn overlays() {
+        let sm = SourceMap::new(FilePathMapping::empty());
+        let path = Path::new("/this/file/does/not/exist.rs");
+        assert!(!sm.file_exists(path));
+        assert!(sm.load_file(path).is_err());
+
+        assert_eq!(sm.set_overlay(path, "fn main() {}".to_string(), 1), None);
+        assert!(sm.file_exists(path));
+        assert_eq!(sm.overlay_version(path), Some(1));
+        let file = sm.load_file(path).unwrap();
+        assert_eq!(file.src.as_ref().unwrap().as_str(), "fn main() {}");
+
+        assert_eq!(sm.set_overlay(path, "fn main() { }".to_string(), 2), Some(1));
+        assert_eq!(sm.read_file(path).unwrap(), "fn main() { }");
+
+        assert_eq!(sm.remove_overlay(path).map(|overlay| overlay.version), Some(2));
+        assert!(!sm.file_exists(path));
+        assert_eq!(sm.overlay_version(path), None);
+    }
+
     fn init_source_map() -> SourceMap {
         let sm = SourceMap::new(FilePathMapping::empty());
         sm.new_source_file(PathBuf::from("blork.rs").into(),
@@ -1189,6 +1409,41 @@ mod tests {
         assert!(sm.merge_spans(span1, span2).is_none());
     }
 
+    #[test]
+    fn snapshot_roundtrip() {
+        use rustc_serialize::{Decodable, Encodable, opaque};
+
+        let mapping = FilePathMapping::new(vec![
+            (PathBuf::from("/home/dev/project"), PathBuf::from("/src")),
+        ]);
+        let sm = SourceMap::new(mapping);
+        let text = "fn main() {\n    let c = 'é';\n}\n";
+        let file = sm.new_source_file(PathBuf::from("/home/dev/project/main.rs").into(),
+                                      text.to_owned());
+        let span = sm.span_substr(&file, text, "let c", 0);
+
+        let mut encoder = opaque::Encoder::new(Vec::new());
+        sm.snapshot(true).encode(&mut encoder).unwrap();
+        let data = encoder.into_inner();
+        let mut decoder = opaque::Decoder::new(&data, 0);
+        let snapshot = SourceMapSnapshot::decode(&mut decoder).unwrap();
+
+        let sm2 = SourceMap::new(FilePathMapping::empty());
+        sm2.new_source_file(PathBuf::from("other.rs").into(), "// padding\n".to_owned());
+        let loaded = sm2.load_snapshot(snapshot);
+
+        let file = loaded.files().next().unwrap();
+        assert_eq!(file.name, PathBuf::from("/src").join("main.rs").into());
+        assert!(file.name_was_remapped);
+
+        let span2 = loaded.translate_span(span).unwrap();
+        assert_ne!(span2, span);
+        assert_eq!(sm2.span_to_snippet(span2).unwrap(), "let c");
+        let loc = sm2.lookup_char_pos(span2.lo());
+        assert_eq!((loc.line, loc.col), (2, CharPos(4)));
+        assert_eq!(loaded.translate_pos(BytePos(text.len() as u32 + 1)), None);
+    }
+
     /// Returns the span corresponding to the `n`th occurrence of
     /// `substring` in `source_text`.
     trait SourceMapExtension {
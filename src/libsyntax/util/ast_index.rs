@@ -0,0 +1,270 @@
+//! An index of the nodes of an expanded crate by `NodeId`.
+//!
+//! Passes that run on the AST after expansion, like early lints and
+//! save-analysis, often need to know where a node is or what contains it.
+//! `AstIndex` answers these questions without walking the crate again for
+//! every lookup. It must be built after node ids have been assigned, i.e.
+//! after expansion; nodes with `DUMMY_NODE_ID` are not indexed.
+
+use crate::ast::*;
+use crate::visit::{self, Visitor};
+
+use rustc_data_structures::fx::FxHashMap;
+use syntax_pos::Span;
+
+use std::cmp::Ordering;
+
+#[derive(Copy, Clone, Debug)]
+struct Entry {
+    parent: Option<NodeId>,
+    span: Span,
+    /// The innermost item containing the node, or the node itself.
+    item: NodeId,
+    /// The position of the node in a depth-first, pre-order walk of the crate.
+    pre: u32,
+    /// One past the position of the last descendant of the node.
+    post: u32,
+}
+
+pub struct AstIndex {
+    entries: FxHashMap<NodeId, Entry>,
+}
+
+impl AstIndex {
+    pub fn new(krate: &Crate) -> AstIndex {
+        let mut builder = IndexBuilder {
+            entries: FxHashMap::default(),
+            parent: None,
+            item: CRATE_NODE_ID,
+            next: 0,
+        };
+        builder.with_node(CRATE_NODE_ID, krate.span, true, |this| {
+            visit::walk_crate(this, krate)
+        });
+        AstIndex { entries: builder.entries }
+    }
+
+    /// The number of nodes in the index.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.entries.contains_key(&id)
+    }
+
+    /// Returns the closest indexed node containing `id`. The crate root has
+    /// no parent.
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.entries.get(&id).and_then(|entry| entry.parent)
+    }
+
+    pub fn span(&self, id: NodeId) -> Option<Span> {
+        self.entries.get(&id).map(|entry| entry.span)
+    }
+
+    /// Returns the innermost item, trait item, impl item or foreign item
+    /// containing `id`, which is `id` itself if it is one. Nodes outside of
+    /// any item, like the attributes of the crate, belong to the crate root.
+    pub fn enclosing_item(&self, id: NodeId) -> Option<NodeId> {
+        self.entries.get(&id).map(|entry| entry.item)
+    }
+
+    /// Iterates over the strict ancestors of `id`, innermost first.
+    pub fn ancestors(&self, id: NodeId) -> Ancestors<'_> {
+        Ancestors { index: self, next: self.parent(id) }
+    }
+
+    /// Returns whether `ancestor` contains `id`, or is `id`.
+    pub fn is_descendant_of(&self, id: NodeId, ancestor: NodeId) -> bool {
+        match (self.entries.get(&id), self.entries.get(&ancestor)) {
+            (Some(node), Some(ancestor)) => ancestor.pre <= node.pre && node.pre < ancestor.post,
+            _ => false,
+        }
+    }
+
+    /// Compares the positions of two nodes in a depth-first, pre-order walk of
+    /// the crate, in which every node comes before its descendants. Returns
+    /// `None` if either node is not in the index.
+    pub fn dfs_cmp(&self, a: NodeId, b: NodeId) -> Option<Ordering> {
+        match (self.entries.get(&a), self.entries.get(&b)) {
+            (Some(a), Some(b)) => Some(a.pre.cmp(&b.pre)),
+            _ => None,
+        }
+    }
+}
+
+pub struct Ancestors<'a> {
+    index: &'a AstIndex,
+    next: Option<NodeId>,
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.next?;
+        self.next = self.index.parent(id);
+        Some(id)
+    }
+}
+
+struct IndexBuilder {
+    entries: FxHashMap<NodeId, Entry>,
+    parent: Option<NodeId>,
+    item: NodeId,
+    next: u32,
+}
+
+impl IndexBuilder {
+    fn with_node<F: FnOnce(&mut Self)>(&mut self, id: NodeId, span: Span, is_item: bool, f: F) {
+        if id == DUMMY_NODE_ID {
+            return f(self);
+        }
+
+        let pre = self.next;
+        self.next += 1;
+        let item = if is_item { id } else { self.item };
+        self.entries.insert(id, Entry { parent: self.parent, span, item, pre, post: pre + 1 });
+
+        let parent = self.parent.replace(id);
+        let outer_item = std::mem::replace(&mut self.item, item);
+        f(self);
+        self.parent = parent;
+        self.item = outer_item;
+
+        let post = self.next;
+        self.entries.get_mut(&id).unwrap().post = post;
+    }
+}
+
+impl<'a> Visitor<'a> for IndexBuilder {
+    fn visit_item(&mut self, i: &'a Item) {
+        self.with_node(i.id, i.span, true, |this| visit::walk_item(this, i));
+    }
+    fn visit_foreign_item(&mut self, i: &'a ForeignItem) {
+        self.with_node(i.id, i.span, true, |this| visit::walk_foreign_item(this, i));
+    }
+    fn visit_trait_item(&mut self, i: &'a TraitItem) {
+        self.with_node(i.id, i.span, true, |this| visit::walk_trait_item(this, i));
+    }
+    fn visit_impl_item(&mut self, i: &'a ImplItem) {
+        self.with_node(i.id, i.span, true, |this| visit::walk_impl_item(this, i));
+    }
+    fn visit_local(&mut self, l: &'a Local) {
+        self.with_node(l.id, l.span, false, |this| visit::walk_local(this, l));
+    }
+    fn visit_block(&mut self, b: &'a Block) {
+        self.with_node(b.id, b.span, false, |this| visit::walk_block(this, b));
+    }
+    fn visit_stmt(&mut self, s: &'a Stmt) {
+        self.with_node(s.id, s.span, false, |this| visit::walk_stmt(this, s));
+    }
+    fn visit_pat(&mut self, p: &'a Pat) {
+        self.with_node(p.id, p.span, false, |this| visit::walk_pat(this, p));
+    }
+    fn visit_anon_const(&mut self, c: &'a AnonConst) {
+        self.with_node(c.id, c.value.span, false, |this| visit::walk_anon_const(this, c));
+    }
+    fn visit_expr(&mut self, e: &'a Expr) {
+        self.with_node(e.id, e.span, false, |this| visit::walk_expr(this, e));
+    }
+    fn visit_ty(&mut self, t: &'a Ty) {
+        self.with_node(t.id, t.span, false, |this| visit::walk_ty(this, t));
+    }
+    fn visit_generic_param(&mut self, p: &'a GenericParam) {
+        self.with_node(p.id, p.ident.span, false, |this| visit::walk_generic_param(this, p));
+    }
+    fn visit_variant_data(&mut self, s: &'a VariantData, _: Ident,
+                          _: &'a Generics, _: NodeId, span: Span) {
+        self.with_node(s.id(), span, false, |this| visit::walk_struct_def(this, s));
+    }
+    fn visit_struct_field(&mut self, s: &'a StructField) {
+        self.with_node(s.id, s.span, false, |this| visit::walk_struct_field(this, s));
+    }
+    fn visit_lifetime(&mut self, lifetime: &'a Lifetime) {
+        self.with_node(lifetime.id, lifetime.ident.span, false, |_| {});
+    }
+    fn visit_use_tree(&mut self, use_tree: &'a UseTree, id: NodeId, nested: bool) {
+        // The outermost use tree has the id of the `use` item.
+        if nested {
+            self.with_node(id, use_tree.span, false, |this| {
+                visit::walk_use_tree(this, use_tree, id)
+            });
+        } else {
+            visit::walk_use_tree(self, use_tree, id);
+        }
+    }
+    fn visit_mac(&mut self, _mac: &'a Mac) {
+        // Nothing to do: no macro invocations are left after expansion.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mut_visit::MutVisitor;
+    use crate::util::parser_testing::string_to_crate;
+    use crate::with_globals;
+
+    struct AssignIds(u32);
+
+    impl MutVisitor for AssignIds {
+        fn visit_id(&mut self, id: &mut NodeId) {
+            self.0 += 1;
+            *id = NodeId::from_u32(self.0);
+        }
+    }
+
+    fn find_expr(index: &AstIndex, krate: &Crate, text: &str) -> NodeId {
+        struct Finder<'a> { text: &'a str, found: Option<NodeId>, source: &'a str }
+        impl<'a, 'ast> Visitor<'ast> for Finder<'a> {
+            fn visit_expr(&mut self, e: &'ast Expr) {
+                let (lo, hi) = (e.span.lo().0 as usize, e.span.hi().0 as usize);
+                if self.found.is_none() && &self.source[lo..hi] == self.text {
+                    self.found = Some(e.id);
+                }
+                visit::walk_expr(self, e)
+            }
+        }
+        let mut finder = Finder { text, found: None, source: SOURCE };
+        visit::walk_crate(&mut finder, krate);
+        let id = finder.found.unwrap();
+        assert!(index.contains(id));
+        id
+    }
+
+    const SOURCE: &str = "mod m { fn f() -> u32 { let x = 1 + 2; x } } \
+                          impl S { fn g(&self) { h(3) } }";
+
+    #[test]
+    fn parents_and_items() {
+        with_globals(|| {
+            let mut krate = string_to_crate(SOURCE.to_string());
+            AssignIds(0).visit_crate(&mut krate);
+            let index = AstIndex::new(&krate);
+
+            let one = find_expr(&index, &krate, "1");
+            let sum = find_expr(&index, &krate, "1 + 2");
+            let three = find_expr(&index, &krate, "3");
+            assert_eq!(index.parent(one), Some(sum));
+            assert_eq!(index.parent(CRATE_NODE_ID), None);
+            assert_eq!(index.span(sum).map(|sp| sp.hi().0 - sp.lo().0), Some(5));
+
+            let f = index.enclosing_item(one).unwrap();
+            let m = index.enclosing_item(index.parent(f).unwrap()).unwrap();
+            assert_ne!(f, m);
+            assert_eq!(index.enclosing_item(m), Some(m));
+            assert_eq!(index.parent(m), Some(CRATE_NODE_ID));
+            assert_eq!(index.ancestors(one).last(), Some(CRATE_NODE_ID));
+            assert!(index.ancestors(one).any(|id| id == f));
+
+            assert!(index.is_descendant_of(one, f));
+            assert!(index.is_descendant_of(one, CRATE_NODE_ID));
+            assert!(!index.is_descendant_of(three, f));
+            assert_eq!(index.dfs_cmp(sum, one), Some(Ordering::Less));
+            assert_eq!(index.dfs_cmp(three, one), Some(Ordering::Greater));
+            assert_eq!(index.dfs_cmp(one, NodeId::from_u32(1_000_000)), None);
+        })
+    }
+}
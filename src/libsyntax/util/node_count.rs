@@ -1,141 +1,309 @@
-// Simply gives a rought count of the number of nodes in an AST.
+// Simply gives a rought count of the number of nodes in an AST, along with a
+// breakdown by kind and the sizes of the largest items for `-Z ast-stats`.
 
 use crate::visit::*;
 use crate::ast::*;
-use syntax_pos::Span;
+use syntax_pos::{BytePos, Span, NO_EXPANSION};
+
+use std::collections::BTreeMap;
+use std::fmt;
 
 pub struct NodeCounter {
     pub count: usize,
+    pub by_kind: BTreeMap<&'static str, usize>,
+    pub max_depth: usize,
+    /// The number of nodes whose span comes from a macro expansion.
+    pub from_expansion: usize,
+    /// The size of every item, trait item, impl item and foreign item, in
+    /// the order in which they were finished.
+    pub item_sizes: Vec<ItemSize>,
+    depth: usize,
+    item_spans: Vec<(BytePos, BytePos)>,
+    /// The names of the items being visited, with the count at their start.
+    open_items: Vec<(String, usize)>,
+}
+
+/// The number of nodes of an item, counting those of nested items too.
+#[derive(Clone, Debug, PartialEq, RustcEncodable)]
+pub struct ItemSize {
+    pub name: String,
+    pub nodes: usize,
 }
 
 impl NodeCounter {
     pub fn new() -> NodeCounter {
         NodeCounter {
             count: 0,
+            by_kind: BTreeMap::new(),
+            max_depth: 0,
+            from_expansion: 0,
+            item_sizes: Vec::new(),
+            depth: 0,
+            item_spans: Vec::new(),
+            open_items: Vec::new(),
+        }
+    }
+
+    fn enter(&mut self, kind: &'static str, span: Span) {
+        self.count += 1;
+        *self.by_kind.entry(kind).or_insert(0) += 1;
+        self.depth += 1;
+        if self.depth > self.max_depth {
+            self.max_depth = self.depth;
+        }
+        if span.ctxt() != NO_EXPANSION {
+            self.from_expansion += 1;
+        }
+    }
+
+    fn exit(&mut self) {
+        self.depth -= 1;
+    }
+
+    fn enter_item(&mut self, kind: &'static str, span: Span, ident: Ident, descr: &str) {
+        self.enter(kind, span);
+        // Items like impls and `use`s have no name.
+        let name = if ident.name.as_str().is_empty() {
+            descr.to_string()
+        } else {
+            ident.to_string()
+        };
+        self.open_items.push((name, self.count));
+    }
+
+    fn exit_item(&mut self) {
+        self.exit();
+        let (name, start) = self.open_items.pop().unwrap();
+        // `start` already includes the item itself.
+        self.item_sizes.push(ItemSize { name, nodes: self.count - start + 1 });
+    }
+
+    /// Returns the number of source bytes covered by items that were not
+    /// produced by a macro expansion, counting nested items only once.
+    fn bytes_covered(&self) -> usize {
+        let mut spans = self.item_spans.clone();
+        spans.sort();
+        let mut covered = 0;
+        let mut end = BytePos(0);
+        for (lo, hi) in spans {
+            let lo = if lo < end { end } else { lo };
+            if hi > lo {
+                covered += (hi - lo).0 as usize;
+                end = hi;
+            }
         }
+        covered
+    }
+}
+
+/// Statistics about the shape of a crate's AST at some point of compilation,
+/// as printed by `-Z ast-stats`.
+#[derive(RustcEncodable)]
+pub struct AstStats {
+    pub phase: &'static str,
+    pub total: usize,
+    pub by_kind: BTreeMap<&'static str, usize>,
+    pub max_depth: usize,
+    pub from_expansion: usize,
+    pub bytes_covered: usize,
+    /// The largest items, at most `LARGEST_ITEMS` of them, largest first.
+    pub largest_items: Vec<ItemSize>,
+}
+
+/// The number of items listed in `AstStats::largest_items`.
+pub const LARGEST_ITEMS: usize = 10;
+
+/// Collects the statistics of `krate`, labelling them with `phase`.
+pub fn ast_stats(krate: &Crate, phase: &'static str) -> AstStats {
+    let mut counter = NodeCounter::new();
+    walk_crate(&mut counter, krate);
+    let mut largest_items = counter.item_sizes;
+    largest_items.sort_by(|a, b| b.nodes.cmp(&a.nodes));
+    largest_items.truncate(LARGEST_ITEMS);
+    AstStats {
+        phase,
+        largest_items,
+        total: counter.count,
+        bytes_covered: counter.bytes_covered(),
+        by_kind: counter.by_kind,
+        max_depth: counter.max_depth,
+        from_expansion: counter.from_expansion,
+    }
+}
+
+impl fmt::Display for AstStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "AST stats ({}):", self.phase)?;
+        for (kind, count) in &self.by_kind {
+            writeln!(f, "    {:<16}{:>10}", kind, count)?;
+        }
+        writeln!(f, "    {:<16}{:>10}", "total", self.total)?;
+        writeln!(f, "    {:<16}{:>10}", "from expansion", self.from_expansion)?;
+        writeln!(f, "    {:<16}{:>10}", "max depth", self.max_depth)?;
+        write!(f, "    {:<16}{:>10}", "bytes covered", self.bytes_covered)?;
+        if !self.largest_items.is_empty() {
+            write!(f, "\n    largest items:")?;
+            for item in &self.largest_items {
+                write!(f, "\n        {:<20}{:>10}", item.name, item.nodes)?;
+            }
+        }
+        Ok(())
     }
 }
 
 impl<'ast> Visitor<'ast> for NodeCounter {
     fn visit_ident(&mut self, ident: Ident) {
-        self.count += 1;
+        self.enter("Ident", ident.span);
         walk_ident(self, ident);
+        self.exit();
     }
-    fn visit_mod(&mut self, m: &Mod, _s: Span, _a: &[Attribute], _n: NodeId) {
-        self.count += 1;
-        walk_mod(self, m)
+    fn visit_mod(&mut self, m: &Mod, s: Span, _a: &[Attribute], _n: NodeId) {
+        self.enter("Mod", s);
+        walk_mod(self, m);
+        self.exit();
     }
     fn visit_foreign_item(&mut self, i: &ForeignItem) {
-        self.count += 1;
-        walk_foreign_item(self, i)
+        self.enter_item("ForeignItem", i.span, i.ident, i.node.descriptive_variant());
+        walk_foreign_item(self, i);
+        self.exit_item();
     }
     fn visit_item(&mut self, i: &Item) {
-        self.count += 1;
-        walk_item(self, i)
+        self.enter_item("Item", i.span, i.ident, i.node.descriptive_variant());
+        if i.span.ctxt() == NO_EXPANSION {
+            self.item_spans.push((i.span.lo(), i.span.hi()));
+        }
+        walk_item(self, i);
+        self.exit_item();
     }
     fn visit_local(&mut self, l: &Local) {
-        self.count += 1;
-        walk_local(self, l)
+        self.enter("Local", l.span);
+        walk_local(self, l);
+        self.exit();
     }
     fn visit_block(&mut self, b: &Block) {
-        self.count += 1;
-        walk_block(self, b)
+        self.enter("Block", b.span);
+        walk_block(self, b);
+        self.exit();
     }
     fn visit_stmt(&mut self, s: &Stmt) {
-        self.count += 1;
-        walk_stmt(self, s)
+        self.enter("Stmt", s.span);
+        walk_stmt(self, s);
+        self.exit();
     }
     fn visit_arm(&mut self, a: &Arm) {
-        self.count += 1;
-        walk_arm(self, a)
+        self.enter("Arm", a.body.span);
+        walk_arm(self, a);
+        self.exit();
     }
     fn visit_pat(&mut self, p: &Pat) {
-        self.count += 1;
-        walk_pat(self, p)
+        self.enter("Pat", p.span);
+        walk_pat(self, p);
+        self.exit();
     }
     fn visit_expr(&mut self, ex: &Expr) {
-        self.count += 1;
-        walk_expr(self, ex)
+        self.enter("Expr", ex.span);
+        walk_expr(self, ex);
+        self.exit();
     }
     fn visit_ty(&mut self, t: &Ty) {
-        self.count += 1;
-        walk_ty(self, t)
+        self.enter("Ty", t.span);
+        walk_ty(self, t);
+        self.exit();
     }
     fn visit_generic_param(&mut self, param: &GenericParam) {
-        self.count += 1;
-        walk_generic_param(self, param)
+        self.enter("GenericParam", param.ident.span);
+        walk_generic_param(self, param);
+        self.exit();
     }
     fn visit_generics(&mut self, g: &Generics) {
-        self.count += 1;
-        walk_generics(self, g)
+        self.enter("Generics", g.span);
+        walk_generics(self, g);
+        self.exit();
     }
     fn visit_fn(&mut self, fk: FnKind<'_>, fd: &FnDecl, s: Span, _: NodeId) {
-        self.count += 1;
-        walk_fn(self, fk, fd, s)
+        self.enter("Fn", s);
+        walk_fn(self, fk, fd, s);
+        self.exit();
     }
     fn visit_trait_item(&mut self, ti: &TraitItem) {
-        self.count += 1;
-        walk_trait_item(self, ti)
+        self.enter_item("TraitItem", ti.span, ti.ident, "trait item");
+        walk_trait_item(self, ti);
+        self.exit_item();
     }
     fn visit_impl_item(&mut self, ii: &ImplItem) {
-        self.count += 1;
-        walk_impl_item(self, ii)
+        self.enter_item("ImplItem", ii.span, ii.ident, "impl item");
+        walk_impl_item(self, ii);
+        self.exit_item();
     }
     fn visit_trait_ref(&mut self, t: &TraitRef) {
-        self.count += 1;
-        walk_trait_ref(self, t)
+        self.enter("TraitRef", t.path.span);
+        walk_trait_ref(self, t);
+        self.exit();
     }
     fn visit_param_bound(&mut self, bounds: &GenericBound) {
-        self.count += 1;
-        walk_param_bound(self, bounds)
+        self.enter("GenericBound", bounds.span());
+        walk_param_bound(self, bounds);
+        self.exit();
     }
     fn visit_poly_trait_ref(&mut self, t: &PolyTraitRef, m: &TraitBoundModifier) {
-        self.count += 1;
-        walk_poly_trait_ref(self, t, m)
+        self.enter("PolyTraitRef", t.span);
+        walk_poly_trait_ref(self, t, m);
+        self.exit();
     }
     fn visit_variant_data(&mut self, s: &VariantData, _: Ident,
-                          _: &Generics, _: NodeId, _: Span) {
-        self.count += 1;
-        walk_struct_def(self, s)
+                          _: &Generics, _: NodeId, span: Span) {
+        self.enter("VariantData", span);
+        walk_struct_def(self, s);
+        self.exit();
     }
     fn visit_struct_field(&mut self, s: &StructField) {
-        self.count += 1;
-        walk_struct_field(self, s)
+        self.enter("StructField", s.span);
+        walk_struct_field(self, s);
+        self.exit();
     }
     fn visit_enum_def(&mut self, enum_definition: &EnumDef,
-                      generics: &Generics, item_id: NodeId, _: Span) {
-        self.count += 1;
-        walk_enum_def(self, enum_definition, generics, item_id)
+                      generics: &Generics, item_id: NodeId, span: Span) {
+        self.enter("EnumDef", span);
+        walk_enum_def(self, enum_definition, generics, item_id);
+        self.exit();
     }
     fn visit_variant(&mut self, v: &Variant, g: &Generics, item_id: NodeId) {
-        self.count += 1;
-        walk_variant(self, v, g, item_id)
+        self.enter("Variant", v.span);
+        walk_variant(self, v, g, item_id);
+        self.exit();
     }
     fn visit_lifetime(&mut self, lifetime: &Lifetime) {
-        self.count += 1;
-        walk_lifetime(self, lifetime)
+        self.enter("Lifetime", lifetime.ident.span);
+        walk_lifetime(self, lifetime);
+        self.exit();
     }
     fn visit_mac(&mut self, _mac: &Mac) {
-        self.count += 1;
-        walk_mac(self, _mac)
+        self.enter("Mac", _mac.span);
+        walk_mac(self, _mac);
+        self.exit();
     }
     fn visit_path(&mut self, path: &Path, _id: NodeId) {
-        self.count += 1;
-        walk_path(self, path)
+        self.enter("Path", path.span);
+        walk_path(self, path);
+        self.exit();
     }
     fn visit_use_tree(&mut self, use_tree: &UseTree, id: NodeId, _nested: bool) {
-        self.count += 1;
-        walk_use_tree(self, use_tree, id)
+        self.enter("UseTree", use_tree.span);
+        walk_use_tree(self, use_tree, id);
+        self.exit();
     }
     fn visit_generic_args(&mut self, path_span: Span, generic_args: &GenericArgs) {
-        self.count += 1;
-        walk_generic_args(self, path_span, generic_args)
+        self.enter("GenericArgs", generic_args.span());
+        walk_generic_args(self, path_span, generic_args);
+        self.exit();
     }
     fn visit_assoc_type_binding(&mut self, type_binding: &TypeBinding) {
-        self.count += 1;
-        walk_assoc_type_binding(self, type_binding)
+        self.enter("TypeBinding", type_binding.span);
+        walk_assoc_type_binding(self, type_binding);
+        self.exit();
     }
-    fn visit_attribute(&mut self, _attr: &Attribute) {
-        self.count += 1;
+    fn visit_attribute(&mut self, attr: &Attribute) {
+        self.enter("Attribute", attr.span);
+        self.exit();
     }
 }
@@ -0,0 +1,276 @@
+//! Checks that pretty-printing a crate and parsing the result gives back the
+//! same crate, for testing the parser and the pretty printer.
+//!
+//! The two crates are compared node by node, looking at the kind of each
+//! item, statement, expression, pattern and type, and at identifiers,
+//! literals, operators, attributes and the tokens of macro invocations.
+//! Parentheses are ignored, as the pretty printer is free to add them. The
+//! first difference is reported with the spans of the nodes on both sides.
+//!
+//! These functions must be called within `syntax::with_globals`.
+
+use crate::ast::*;
+use crate::parse::{self, ParseSess};
+use crate::print::pprust;
+use crate::source_map::SourceMap;
+use crate::visit::{self, Visitor};
+
+use syntax_pos::{FileName, Span};
+
+use std::mem::{self, Discriminant};
+
+/// The first difference between two crates found by `compare_crates`.
+#[derive(Debug)]
+pub struct Divergence {
+    /// The node of the original crate, or `None` if the reparsed crate has
+    /// more nodes than the original.
+    pub original: Option<Span>,
+    /// The node of the reparsed crate, or `None` if it has fewer nodes than
+    /// the original.
+    pub reparsed: Option<Span>,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum RoundTripError {
+    /// The source itself failed to parse. The errors have been emitted.
+    Parse,
+    /// The pretty-printed source failed to parse. The errors have been
+    /// emitted.
+    Reparse { printed: String },
+    /// The reparsed crate differs from the original.
+    Diverged { printed: String, divergence: Divergence },
+}
+
+/// Parses `src`, pretty-prints it, parses the result and compares the two
+/// crates. The pretty-printed source is added to the source map of `sess`
+/// as `<pretty-printed>`.
+pub fn check_round_trip(sess: &ParseSess, name: FileName, src: String)
+                        -> Result<(), RoundTripError> {
+    let original = match parse::parse_crate_from_source_str(name, src, sess) {
+        Ok(krate) => krate,
+        Err(mut err) => {
+            err.emit();
+            return Err(RoundTripError::Parse);
+        }
+    };
+    let printed = pprust::to_string(|s| s.print_mod(&original.module, &original.attrs));
+    let reparsed = match parse::parse_crate_from_source_str(
+        FileName::Custom("pretty-printed".to_string()), printed.clone(), sess,
+    ) {
+        Ok(krate) => krate,
+        Err(mut err) => {
+            err.emit();
+            return Err(RoundTripError::Reparse { printed });
+        }
+    };
+    match compare_crates(sess.source_map(), &original, &reparsed) {
+        Some(divergence) => Err(RoundTripError::Diverged { printed, divergence }),
+        None => Ok(()),
+    }
+}
+
+/// Compares two crates as described in the module documentation, returning
+/// the first difference, if any.
+pub fn compare_crates(source_map: &SourceMap, original: &Crate, reparsed: &Crate)
+                      -> Option<Divergence> {
+    let original = shapes(original);
+    let reparsed = shapes(reparsed);
+    let snippet = |span: Span| {
+        source_map.span_to_snippet(span).unwrap_or_else(|_| source_map.span_to_string(span))
+    };
+
+    let mut pairs = original.iter().zip(&reparsed);
+    if let Some((a, b)) = pairs.find(|(a, b)| a.0 != b.0) {
+        return Some(Divergence {
+            original: Some(a.1),
+            reparsed: Some(b.1),
+            message: format!("{} `{}` at {} was reparsed as {} `{}` at {}",
+                             a.0.kind(), snippet(a.1), source_map.span_to_string(a.1),
+                             b.0.kind(), snippet(b.1), source_map.span_to_string(b.1)),
+        });
+    }
+    if let Some(a) = original.get(reparsed.len()) {
+        return Some(Divergence {
+            original: Some(a.1),
+            reparsed: None,
+            message: format!("{} `{}` at {} is missing after reparsing",
+                             a.0.kind(), snippet(a.1), source_map.span_to_string(a.1)),
+        });
+    }
+    if let Some(b) = reparsed.get(original.len()) {
+        return Some(Divergence {
+            original: None,
+            reparsed: Some(b.1),
+            message: format!("reparsing added {} `{}` at {}",
+                             b.0.kind(), snippet(b.1), source_map.span_to_string(b.1)),
+        });
+    }
+    None
+}
+
+#[derive(PartialEq)]
+enum Shape {
+    Item(Discriminant<ItemKind>),
+    ForeignItem(Discriminant<ForeignItemKind>),
+    TraitItem(Discriminant<TraitItemKind>),
+    ImplItem(Discriminant<ImplItemKind>),
+    Stmt(Discriminant<StmtKind>),
+    Expr(Discriminant<ExprKind>),
+    Lit(LitKind),
+    BinOp(BinOpKind),
+    UnOp(Discriminant<UnOp>),
+    Pat(Discriminant<PatKind>),
+    Ty(Discriminant<TyKind>),
+    Ident(Name),
+    Lifetime(Name),
+    Attr(String),
+    Mac(String),
+}
+
+impl Shape {
+    fn kind(&self) -> &'static str {
+        match *self {
+            Shape::Item(..) => "item",
+            Shape::ForeignItem(..) => "foreign item",
+            Shape::TraitItem(..) => "trait item",
+            Shape::ImplItem(..) => "impl item",
+            Shape::Stmt(..) => "statement",
+            Shape::Expr(..) => "expression",
+            Shape::Lit(..) => "literal",
+            Shape::BinOp(..) | Shape::UnOp(..) => "operator",
+            Shape::Pat(..) => "pattern",
+            Shape::Ty(..) => "type",
+            Shape::Ident(..) => "identifier",
+            Shape::Lifetime(..) => "lifetime",
+            Shape::Attr(..) => "attribute",
+            Shape::Mac(..) => "macro invocation",
+        }
+    }
+}
+
+fn shapes(krate: &Crate) -> Vec<(Shape, Span)> {
+    let mut collector = ShapeCollector { shapes: Vec::new() };
+    visit::walk_crate(&mut collector, krate);
+    collector.shapes
+}
+
+struct ShapeCollector {
+    shapes: Vec<(Shape, Span)>,
+}
+
+impl<'a> Visitor<'a> for ShapeCollector {
+    fn visit_item(&mut self, i: &'a Item) {
+        self.shapes.push((Shape::Item(mem::discriminant(&i.node)), i.span));
+        visit::walk_item(self, i)
+    }
+    fn visit_foreign_item(&mut self, i: &'a ForeignItem) {
+        self.shapes.push((Shape::ForeignItem(mem::discriminant(&i.node)), i.span));
+        visit::walk_foreign_item(self, i)
+    }
+    fn visit_trait_item(&mut self, i: &'a TraitItem) {
+        self.shapes.push((Shape::TraitItem(mem::discriminant(&i.node)), i.span));
+        visit::walk_trait_item(self, i)
+    }
+    fn visit_impl_item(&mut self, i: &'a ImplItem) {
+        self.shapes.push((Shape::ImplItem(mem::discriminant(&i.node)), i.span));
+        visit::walk_impl_item(self, i)
+    }
+    fn visit_stmt(&mut self, s: &'a Stmt) {
+        self.shapes.push((Shape::Stmt(mem::discriminant(&s.node)), s.span));
+        visit::walk_stmt(self, s)
+    }
+    fn visit_expr(&mut self, e: &'a Expr) {
+        match e.node {
+            ExprKind::Paren(_) => {}
+            ExprKind::Lit(ref lit) => self.shapes.push((Shape::Lit(lit.node.clone()), e.span)),
+            ExprKind::Binary(op, ..) | ExprKind::AssignOp(op, ..) => {
+                self.shapes.push((Shape::Expr(mem::discriminant(&e.node)), e.span));
+                self.shapes.push((Shape::BinOp(op.node), op.span));
+            }
+            ExprKind::Unary(op, _) => {
+                self.shapes.push((Shape::Expr(mem::discriminant(&e.node)), e.span));
+                self.shapes.push((Shape::UnOp(mem::discriminant(&op)), e.span));
+            }
+            _ => self.shapes.push((Shape::Expr(mem::discriminant(&e.node)), e.span)),
+        }
+        visit::walk_expr(self, e)
+    }
+    fn visit_pat(&mut self, p: &'a Pat) {
+        if let PatKind::Paren(_) = p.node {} else {
+            self.shapes.push((Shape::Pat(mem::discriminant(&p.node)), p.span));
+        }
+        visit::walk_pat(self, p)
+    }
+    fn visit_ty(&mut self, t: &'a Ty) {
+        if let TyKind::Paren(_) = t.node {} else {
+            self.shapes.push((Shape::Ty(mem::discriminant(&t.node)), t.span));
+        }
+        visit::walk_ty(self, t)
+    }
+    fn visit_ident(&mut self, ident: Ident) {
+        self.shapes.push((Shape::Ident(ident.name), ident.span));
+    }
+    fn visit_lifetime(&mut self, lifetime: &'a Lifetime) {
+        self.shapes.push((Shape::Lifetime(lifetime.ident.name), lifetime.ident.span));
+    }
+    fn visit_attribute(&mut self, attr: &'a Attribute) {
+        self.shapes.push((Shape::Attr(pprust::attr_to_string(attr)), attr.span));
+    }
+    fn visit_mac(&mut self, mac: &'a Mac) {
+        let mac_string = format!("{}!{}", pprust::path_to_string(&mac.node.path),
+                                 pprust::tokens_to_string(mac.node.tts.clone()));
+        self.shapes.push((Shape::Mac(mac_string), mac.span));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_map::FilePathMapping;
+    use crate::with_globals;
+
+    fn parse(sess: &ParseSess, name: &str, src: &str) -> Crate {
+        parse::parse_crate_from_source_str(FileName::Custom(name.to_string()),
+                                           src.to_string(), sess).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        with_globals(|| {
+            let sess = ParseSess::new(FilePathMapping::empty());
+            let src = "#![allow(unused)]\n\
+                       /// Docs.\n\
+                       fn f<'a, T: Copy>(x: &'a T) -> (T, u8) { (*x, 1 + (2 * 3)) }\n\
+                       macro_rules! m { ($e:expr) => { $e } }\n\
+                       trait Tr { type A; fn g(&self) -> Self::A; }\n\
+                       fn h() { let _ = m!(vec![1, 2]); if let Some(y) = None::<u8> { -y; } }\n";
+            let result = check_round_trip(&sess, FileName::Custom("test".to_string()),
+                                          src.to_string());
+            assert!(result.is_ok(), "{:?}", result);
+        })
+    }
+
+    #[test]
+    fn divergence() {
+        with_globals(|| {
+            let sess = ParseSess::new(FilePathMapping::empty());
+            let a = parse(&sess, "a", "fn f() { let x = 1 + 2; }");
+            let b = parse(&sess, "b", "fn f() { let x = 1 - 2; }");
+            let divergence = compare_crates(sess.source_map(), &a, &b).unwrap();
+            assert!(divergence.message.starts_with("operator `+` at <a>:1:20"),
+                    "{}", divergence.message);
+            assert!(divergence.message.ends_with("as operator `-` at <b>:1:20: 1:21"),
+                    "{}", divergence.message);
+
+            let c = parse(&sess, "c", "fn f() { let x = 1 + 2; } fn g() {}");
+            let divergence = compare_crates(sess.source_map(), &a, &c).unwrap();
+            assert!(divergence.original.is_none());
+            assert!(divergence.message.starts_with("reparsing added item `fn g() {}`"),
+                    "{}", divergence.message);
+
+            let d = parse(&sess, "d", "fn f() { let x = (1 + 2); }");
+            assert!(compare_crates(sess.source_map(), &a, &d).is_none());
+        })
+    }
+}
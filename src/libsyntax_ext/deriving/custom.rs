@@ -13,7 +13,9 @@ use syntax::tokenstream;
 use syntax::visit::Visitor;
 use syntax_pos::DUMMY_SP;
 
-struct MarkAttrs<'a>(&'a [ast::Name]);
+/// Marks the helper attributes of a derive, found anywhere in the item it is
+/// applied to, as known and used.
+pub struct MarkAttrs<'a>(pub &'a [ast::Name]);
 
 impl<'a> Visitor<'a> for MarkAttrs<'a> {
     fn visit_attribute(&mut self, attr: &Attribute) {
@@ -42,78 +44,97 @@ impl MultiItemModifier for ProcMacroDerive {
               _meta_item: &ast::MetaItem,
               item: Annotatable)
               -> Vec<Annotatable> {
-        let item = match item {
-            Annotatable::Item(item) => item,
-            Annotatable::ImplItem(_) |
-            Annotatable::TraitItem(_) |
-            Annotatable::ForeignItem(_) |
-            Annotatable::Stmt(_) |
-            Annotatable::Expr(_) => {
-                ecx.span_err(span, "proc-macro derives may only be \
-                                    applied to a struct, enum, or union");
-                return Vec::new()
-            }
-        };
-        match item.node {
-            ItemKind::Struct(..) |
-            ItemKind::Enum(..) |
-            ItemKind::Union(..) => {},
-            _ => {
-                ecx.span_err(span, "proc-macro derives may only be \
-                                    applied to a struct, enum, or union");
-                return Vec::new()
+        expand_derive(ecx, span, &self.attrs, item, |ecx, input| {
+            let server = proc_macro_server::Rustc::new(ecx);
+            match self.client.run(&EXEC_STRATEGY, server, input) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    let msg = "proc-macro derive panicked";
+                    let mut err = ecx.struct_span_fatal(span, msg);
+                    if let Some(s) = e.as_str() {
+                        err.help(&format!("message: {}", s));
+                    }
+
+                    err.emit();
+                    FatalError.raise();
+                }
             }
+        })
+    }
+}
+
+/// Expands a derive applied to `item`, with `expander` mapping the tokens of
+/// the item to the tokens of the items to add after it.
+///
+/// The item must be a struct, enum or union. Its helper attributes, `attrs`,
+/// are marked as known and used, and the returned tokens must parse as items.
+/// This is shared by the derives of proc-macro crates and of plugins.
+pub fn expand_derive<F>(ecx: &mut ExtCtxt<'_>,
+                        span: Span,
+                        attrs: &[ast::Name],
+                        item: Annotatable,
+                        expander: F)
+                        -> Vec<Annotatable>
+    where F: FnOnce(&mut ExtCtxt<'_>, tokenstream::TokenStream) -> tokenstream::TokenStream
+{
+    let item = match item {
+        Annotatable::Item(item) => item,
+        Annotatable::ImplItem(_) |
+        Annotatable::TraitItem(_) |
+        Annotatable::ForeignItem(_) |
+        Annotatable::Stmt(_) |
+        Annotatable::Expr(_) => {
+            ecx.span_err(span, "proc-macro derives may only be \
+                                applied to a struct, enum, or union");
+            return Vec::new()
         }
+    };
+    match item.node {
+        ItemKind::Struct(..) |
+        ItemKind::Enum(..) |
+        ItemKind::Union(..) => {},
+        _ => {
+            ecx.span_err(span, "proc-macro derives may only be \
+                                applied to a struct, enum, or union");
+            return Vec::new()
+        }
+    }
 
-        // Mark attributes as known, and used.
-        MarkAttrs(&self.attrs).visit_item(&item);
+    // Mark attributes as known, and used.
+    MarkAttrs(attrs).visit_item(&item);
 
-        let token = Token::Interpolated(Lrc::new(token::NtItem(item)));
-        let input = tokenstream::TokenTree::Token(DUMMY_SP, token).into();
+    let token = Token::Interpolated(Lrc::new(token::NtItem(item)));
+    let input = tokenstream::TokenTree::Token(DUMMY_SP, token).into();
 
-        let server = proc_macro_server::Rustc::new(ecx);
-        let stream = match self.client.run(&EXEC_STRATEGY, server, input) {
-            Ok(stream) => stream,
-            Err(e) => {
-                let msg = "proc-macro derive panicked";
-                let mut err = ecx.struct_span_fatal(span, msg);
-                if let Some(s) = e.as_str() {
-                    err.help(&format!("message: {}", s));
-                }
+    let stream = expander(ecx, input);
 
-                err.emit();
-                FatalError.raise();
-            }
-        };
+    let error_count_before = ecx.parse_sess.span_diagnostic.err_count();
+    let msg = "proc-macro derive produced unparseable tokens";
 
-        let error_count_before = ecx.parse_sess.span_diagnostic.err_count();
-        let msg = "proc-macro derive produced unparseable tokens";
+    let mut parser = parse::stream_to_parser(ecx.parse_sess, stream);
+    let mut items = vec![];
 
-        let mut parser = parse::stream_to_parser(ecx.parse_sess, stream);
-        let mut items = vec![];
-
-        loop {
-            match parser.parse_item() {
-                Ok(None) => break,
-                Ok(Some(item)) => {
-                    items.push(Annotatable::Item(item))
-                }
-                Err(mut err) => {
-                    // FIXME: handle this better
-                    err.cancel();
-                    ecx.struct_span_fatal(span, msg).emit();
-                    FatalError.raise();
-                }
+    loop {
+        match parser.parse_item() {
+            Ok(None) => break,
+            Ok(Some(item)) => {
+                items.push(Annotatable::Item(item))
+            }
+            Err(mut err) => {
+                // FIXME: handle this better
+                err.cancel();
+                ecx.struct_span_fatal(span, msg).emit();
+                FatalError.raise();
             }
         }
+    }
 
 
-        // fail if there have been errors emitted
-        if ecx.parse_sess.span_diagnostic.err_count() > error_count_before {
-            ecx.struct_span_fatal(span, msg).emit();
-            FatalError.raise();
-        }
-
-        items
+    // fail if there have been errors emitted
+    if ecx.parse_sess.span_diagnostic.err_count() > error_count_before {
+        ecx.struct_span_fatal(span, msg).emit();
+        FatalError.raise();
     }
+
+    items
 }
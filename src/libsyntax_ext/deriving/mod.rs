@@ -28,6 +28,7 @@ pub mod decodable;
 pub mod hash;
 pub mod debug;
 pub mod default;
+pub mod to_json;
 pub mod custom;
 
 #[path="cmp/partial_eq.rs"]
@@ -71,6 +72,8 @@ derive_traits! {
 
     "RustcDecodable" => decodable::expand_deriving_rustc_decodable,
 
+    "ToJson" => to_json::expand_deriving_to_json,
+
     "PartialEq" => partial_eq::expand_deriving_partial_eq,
     "Eq" => eq::expand_deriving_eq,
     "PartialOrd" => partial_ord::expand_deriving_partial_ord,
@@ -0,0 +1,178 @@
+//! The compiler code necessary to implement the `#[derive(ToJson)]` extension.
+//!
+//! A type like:
+//!
+//! ```
+//! #![feature(derive_to_json)]
+//! #[derive(ToJson)]
+//! struct Node { id: usize, children: Vec<Node> }
+//! ```
+//!
+//! gets an implementation like:
+//!
+//! ```
+//! # struct Node { id: usize, children: Vec<Node> }
+//! impl ToJson for Node {
+//!     fn to_json(&self) -> Json {
+//!         let mut __object = Object::new();
+//!         __object.insert(ToString::to_string("id"), ToJson::to_json(&self.id));
+//!         __object.insert(ToString::to_string("children"), ToJson::to_json(&self.children));
+//!         Json::Object(__object)
+//!     }
+//! }
+//! ```
+//!
+//! The values are laid out like `json::encode` lays out the `RustcEncodable` encoding of the
+//! same type: structs become objects, with the fields of tuple structs named `_field0`,
+//! `_field1`, and so on. Fieldless enum variants become their name as a string, and other
+//! variants become `{"variant": name, "fields": [...]}`.
+
+use crate::deriving::generic::*;
+use crate::deriving::generic::ty::*;
+
+use syntax::ast::{Expr, Ident, MetaItem};
+use syntax::ext::base::{Annotatable, ExtCtxt};
+use syntax::ext::build::AstBuilder;
+use syntax::feature_gate;
+use syntax::ptr::P;
+use syntax::symbol::Symbol;
+use syntax_pos::Span;
+
+pub fn expand_deriving_to_json(cx: &mut ExtCtxt<'_>,
+                               span: Span,
+                               mitem: &MetaItem,
+                               item: &Annotatable,
+                               push: &mut dyn FnMut(Annotatable)) {
+    if !cx.ecfg.enable_derive_to_json() {
+        feature_gate::emit_feature_err(&cx.parse_sess,
+                                       "derive_to_json",
+                                       span,
+                                       feature_gate::GateIssue::Language,
+                                       feature_gate::EXPLAIN_DERIVE_TO_JSON);
+        return;
+    }
+
+    let trait_def = TraitDef {
+        span,
+        attributes: Vec::new(),
+        path: Path::new_(vec!["rustc_serialize", "json", "ToJson"], None, vec![],
+                         PathKind::Global),
+        additional_bounds: Vec::new(),
+        generics: LifetimeBounds::empty(),
+        is_unsafe: false,
+        supports_unions: false,
+        methods: vec![
+            MethodDef {
+                name: "to_json",
+                generics: LifetimeBounds::empty(),
+                explicit_self: borrowed_explicit_self(),
+                args: Vec::new(),
+                ret_ty: Literal(Path::new_(vec!["rustc_serialize", "json", "Json"], None,
+                                           vec![], PathKind::Global)),
+                attributes: Vec::new(),
+                is_unsafe: false,
+                unify_fieldless_variants: false,
+                combine_substructure: combine_substructure(Box::new(|a, b, c| {
+                    to_json_substructure(a, b, c)
+                })),
+            }
+        ],
+        associated_types: Vec::new(),
+    };
+
+    trait_def.expand(cx, mitem, item, push)
+}
+
+fn json_path(cx: &ExtCtxt<'_>, components: &[&str]) -> Vec<Ident> {
+    ["rustc_serialize", "json"].iter().chain(components).map(|s| cx.ident_of(s)).collect()
+}
+
+/// Builds `::rustc_serialize::json::ToJson::to_json(&value)`.
+fn to_json(cx: &ExtCtxt<'_>, span: Span, value: &P<Expr>) -> P<Expr> {
+    let value = cx.expr_addr_of(span, value.clone());
+    cx.expr_call_global(span, json_path(cx, &["ToJson", "to_json"]), vec![value])
+}
+
+fn to_json_substructure(cx: &mut ExtCtxt<'_>,
+                        trait_span: Span,
+                        substr: &Substructure<'_>)
+                        -> P<Expr> {
+    match *substr.fields {
+        Struct(_, ref fields) => {
+            let entries = fields.iter().enumerate().map(|(i, field)| {
+                let name = match field.name {
+                    Some(id) => id.name,
+                    None => Symbol::intern(&format!("_field{}", i)),
+                };
+                (field.span, name, to_json(cx, field.span, &field.self_))
+            }).collect();
+            json_object(cx, trait_span, entries)
+        }
+
+        EnumMatching(_, _, variant, ref fields) => {
+            let name = cx.expr_str(trait_span, variant.node.ident.name);
+            let name = cx.expr_call_global(trait_span,
+                                           cx.std_path(&["string", "ToString", "to_string"]),
+                                           vec![name]);
+            if fields.is_empty() {
+                return cx.expr_call_global(trait_span, json_path(cx, &["Json", "String"]),
+                                           vec![name]);
+            }
+            let values = fields.iter().map(|&FieldInfo { ref self_, span, .. }| {
+                to_json(cx, span, self_)
+            }).collect();
+            let name = cx.expr_call_global(trait_span, json_path(cx, &["Json", "String"]),
+                                           vec![name]);
+            let values = json_array(cx, trait_span, values);
+            json_object(cx, trait_span, vec![
+                (trait_span, Symbol::intern("variant"), name),
+                (trait_span, Symbol::intern("fields"), values),
+            ])
+        }
+
+        _ => cx.bug("expected Struct or EnumMatching in derive(ToJson)"),
+    }
+}
+
+/// Builds a block that inserts the entries into a new `Object` and wraps it in `Json::Object`.
+fn json_object(cx: &ExtCtxt<'_>,
+               span: Span,
+               entries: Vec<(Span, Symbol, P<Expr>)>)
+               -> P<Expr> {
+    let object = cx.ident_of("__object");
+    let new = cx.expr_call_global(span, json_path(cx, &["Object", "new"]), vec![]);
+    let mut stmts = vec![cx.stmt_let(span, true, object, new)];
+    for (span, name, value) in entries {
+        let key = cx.expr_call_global(span,
+                                      cx.std_path(&["string", "ToString", "to_string"]),
+                                      vec![cx.expr_str(span, name)]);
+        let insert = cx.expr_method_call(span,
+                                         cx.expr_ident(span, object),
+                                         cx.ident_of("insert"),
+                                         vec![key, value]);
+        stmts.push(cx.stmt_semi(insert));
+    }
+    let result = cx.expr_call_global(span, json_path(cx, &["Json", "Object"]),
+                                     vec![cx.expr_ident(span, object)]);
+    stmts.push(cx.stmt_expr(result));
+    cx.expr_block(cx.block(span, stmts))
+}
+
+/// Builds a block that pushes the values onto a new `Vec` and wraps it in `Json::Array`.
+fn json_array(cx: &ExtCtxt<'_>, span: Span, values: Vec<P<Expr>>) -> P<Expr> {
+    let array = cx.ident_of("__array");
+    let new = cx.expr_call_global(span, cx.std_path(&["vec", "Vec", "with_capacity"]),
+                                  vec![cx.expr_usize(span, values.len())]);
+    let mut stmts = vec![cx.stmt_let(span, true, array, new)];
+    for value in values {
+        let push = cx.expr_method_call(span,
+                                       cx.expr_ident(span, array),
+                                       cx.ident_of("push"),
+                                       vec![value]);
+        stmts.push(cx.stmt_semi(push));
+    }
+    let result = cx.expr_call_global(span, json_path(cx, &["Json", "Array"]),
+                                     vec![cx.expr_ident(span, array)]);
+    stmts.push(cx.stmt_expr(result));
+    cx.expr_block(cx.block(span, stmts))
+}
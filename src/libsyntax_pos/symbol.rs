@@ -166,6 +166,11 @@ impl Symbol {
         with_interner(|interner| interner.intern(string))
     }
 
+    /// Returns the number of distinct strings interned so far, not counting gensyms.
+    pub fn interned_count() -> usize {
+        with_interner(|interner| interner.strings.len())
+    }
+
     pub fn interned(self) -> Self {
         with_interner(|interner| interner.interned(self))
     }
@@ -0,0 +1,57 @@
+// Check that CopyPropagation propagates copies of fields of locals, also to other basic blocks,
+// but not when the local is changed after the copy.
+
+struct Pair(u32, u32);
+
+fn field(p: Pair, c: bool) -> u32 {
+    let x = p.1;
+    if c { x } else { 0 }
+}
+
+fn mutated(mut p: Pair) -> u32 {
+    let x = p.0;
+    p.0 = 1;
+    x
+}
+
+fn main() {
+    // Make sure the functions actually get instantiated.
+    field(Pair(0, 1), true);
+    mutated(Pair(0, 1));
+}
+
+// END RUST SOURCE
+// START rustc.field.CopyPropagation.before.mir
+// ...
+// _3 = (_1.1: u32);
+// ...
+// _0 = _3;
+// ...
+// END rustc.field.CopyPropagation.before.mir
+// START rustc.field.CopyPropagation.after.mir
+// ...
+// _0 = (_1.1: u32);
+// ...
+// END rustc.field.CopyPropagation.after.mir
+// START rustc.mutated.CopyPropagation.before.mir
+// bb0: {
+//     ...
+//     _2 = (_1.0: u32);
+//     ...
+//     (_1.0: u32) = const 1u32;
+//     ...
+//     _0 = _2;
+//     ...
+// }
+// END rustc.mutated.CopyPropagation.before.mir
+// START rustc.mutated.CopyPropagation.after.mir
+// bb0: {
+//     ...
+//     _2 = (_1.0: u32);
+//     ...
+//     (_1.0: u32) = const 1u32;
+//     ...
+//     _0 = _2;
+//     ...
+// }
+// END rustc.mutated.CopyPropagation.after.mir
@@ -0,0 +1,21 @@
+// Test that `-Z dump-mir=ElaborateDrops` describes the drop flags and how each
+// drop was elaborated.
+
+fn maybe_drop(c: bool, x: String) {
+    if c {
+        std::mem::drop(x);
+    }
+}
+
+fn main() {
+    maybe_drop(true, String::new());
+}
+
+// END RUST SOURCE
+// START rustc.maybe_drop.ElaborateDrops.decisions.mir
+// ...
+// // drop flag _6 tracks _2
+// ...
+//         // conditional: maybe initialized here, drop guarded by its drop flag
+// ...
+// END rustc.maybe_drop.ElaborateDrops.decisions.mir
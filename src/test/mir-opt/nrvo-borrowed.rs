@@ -0,0 +1,27 @@
+// Check that RenameReturnPlace leaves a returned local alone if it is borrowed,
+// as the reference would alias the return place after the renaming.
+
+fn nrvo(init: fn(&mut [u8; 1024])) -> [u8; 1024] {
+    let mut buf = [0; 1024];
+    init(&mut buf);
+    buf
+}
+
+fn main() {
+    let _ = nrvo(|buf| { buf[4] = 4; });
+}
+
+// END RUST SOURCE
+// START rustc.nrvo.RenameReturnPlace.after.mir
+// bb0: {
+//     ...
+//     _2 = [const 0u8; 1024];
+//     ...
+// }
+// bb1: {
+//     ...
+//     _0 = _2;
+//     ...
+//     return;
+// }
+// END rustc.nrvo.RenameReturnPlace.after.mir
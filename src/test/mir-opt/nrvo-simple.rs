@@ -0,0 +1,27 @@
+fn nrvo(x: u8) -> [u8; 1024] {
+    let mut buf = [0; 1024];
+    buf[4] = x;
+    buf
+}
+
+fn main() {
+    let _ = nrvo(4);
+}
+
+// END RUST SOURCE
+// START rustc.nrvo.RenameReturnPlace.before.mir
+// ...
+// _2 = [const 0u8; 1024];
+// ...
+// _0 = _2;
+// ...
+// return;
+// END rustc.nrvo.RenameReturnPlace.before.mir
+// START rustc.nrvo.RenameReturnPlace.after.mir
+// ...
+// _0 = [const 0u8; 1024];
+// ...
+// nop;
+// ...
+// return;
+// END rustc.nrvo.RenameReturnPlace.after.mir
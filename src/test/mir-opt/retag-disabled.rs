@@ -0,0 +1,33 @@
+// compile-flags: -Z mir-emit-retag -Z mir-opt-level=0 -Z span_free_formats
+
+// Check that `#[rustc_mir(no_retag)]` disables retagging for one function only.
+
+#![feature(rustc_attrs)]
+
+fn retagged(x: &i32) -> &i32 { x }
+
+#[rustc_mir(no_retag)]
+fn not_retagged(x: &i32) -> &i32 { x }
+
+fn main() {
+    retagged(&0);
+    not_retagged(&0);
+}
+
+// END RUST SOURCE
+// START rustc.retagged.EraseRegions.after.mir
+//     bb0: {
+//         Retag([fn entry] _1);
+//         ...
+//         _0 = _1;
+//         Retag(_0);
+//         ...
+//         return;
+//     }
+// END rustc.retagged.EraseRegions.after.mir
+// START rustc.not_retagged.EraseRegions.after.mir
+//     bb0: {
+//         _0 = _1;
+//         return;
+//     }
+// END rustc.not_retagged.EraseRegions.after.mir
@@ -0,0 +1,7 @@
+macro_rules! double {
+    ($e:expr) => { $e * 2 }
+}
+
+pub fn four() -> u32 {
+    double!(2)
+}
@@ -0,0 +1,57 @@
+#![feature(rustc_private)]
+
+extern crate getopts;
+extern crate rustc;
+extern crate rustc_driver;
+extern crate rustc_errors as errors;
+extern crate syntax;
+
+use rustc::session::Session;
+use rustc::session::config::{self, ErrorOutputType};
+use rustc_driver::{driver, CompilerCalls, Compilation, RustcDefaultCalls};
+use syntax::ast;
+
+use std::env;
+
+const MYTOOL001: &str = r##"
+A frobnicator was used outside of a frobnication context.
+
+Erroneous code example:
+
+```ignore
+# hidden setup
+frob!();
+```
+"##;
+
+struct Driver(RustcDefaultCalls);
+
+impl<'a> CompilerCalls<'a> for Driver {
+    fn register_diagnostics(&mut self, registry: &mut errors::registry::Registry) {
+        registry.register("MYTOOL001", MYTOOL001);
+    }
+
+    fn early_callback(&mut self,
+                      matches: &getopts::Matches,
+                      sopts: &config::Options,
+                      cfg: &ast::CrateConfig,
+                      descriptions: &errors::registry::Registry,
+                      output: ErrorOutputType)
+                      -> Compilation {
+        self.0.early_callback(matches, sopts, cfg, descriptions, output)
+    }
+
+    fn build_controller(self: Box<Self>,
+                        sess: &Session,
+                        matches: &getopts::Matches)
+                        -> driver::CompileController<'a> {
+        Box::new(self.0).build_controller(sess, matches)
+    }
+}
+
+fn main() {
+    let args = env::args().collect::<Vec<_>>();
+    syntax::with_globals(|| {
+        rustc_driver::run_compiler(&args, Box::new(Driver(RustcDefaultCalls)), None, None);
+    });
+}
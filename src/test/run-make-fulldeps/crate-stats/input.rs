@@ -0,0 +1,12 @@
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn sum(&self) -> i32 {
+        self.x + self.y
+    }
+}
+
+pub const ORIGIN: Point = Point { x: 0, y: 0 };
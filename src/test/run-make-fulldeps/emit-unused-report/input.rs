@@ -0,0 +1,7 @@
+#![allow(unused_extern_crates, unused_imports)]
+
+extern crate core;
+
+use std::io::Write;
+
+pub fn f() {}
@@ -0,0 +1,8 @@
+#[cfg(all(unix, not(feature = "std")))]
+pub fn no_std_unix() {}
+
+#[cfg(feature = "std")]
+pub fn with_std() {}
+
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Maybe;
@@ -0,0 +1,3 @@
+#![crate_type="rlib"]
+
+pub static C: u32 = 0-1;
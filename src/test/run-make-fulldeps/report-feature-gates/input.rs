@@ -0,0 +1,12 @@
+#![feature(box_syntax)]
+#![feature(duration_float)]
+
+pub fn boxed() -> Box<u32> {
+    // The gate of `box_syntax` is checked here.
+    box 1
+}
+
+pub fn seconds() -> f64 {
+    // The stability of `as_secs_f64` is checked here.
+    std::time::Duration::from_secs(1).as_secs_f64()
+}
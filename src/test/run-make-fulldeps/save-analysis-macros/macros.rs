@@ -0,0 +1,11 @@
+pub fn helper() -> u32 {
+    1
+}
+
+macro_rules! call_helper {
+    () => { helper() }
+}
+
+fn main() {
+    call_helper!();
+}
@@ -0,0 +1,24 @@
+#!/usr/bin/env python
+
+import sys
+import json
+
+analysis = json.loads(sys.stdin.readline().strip())
+
+macro_def = [d for d in analysis["defs"] if d["kind"] == "Macro"]
+assert len(macro_def) == 1
+assert macro_def[0]["name"] == "call_helper"
+assert macro_def[0]["span"]["line_start"] == 5
+
+macro_refs = analysis["macro_refs"]
+assert len(macro_refs) == 1
+assert macro_refs[0]["qualname"] == "call_helper"
+assert macro_refs[0]["span"]["line_start"] == 10
+assert macro_refs[0]["callee_span"]["line_start"] == 5
+
+# The call to `helper` is written in the macro body, but recorded at the call
+# site of `call_helper!`.
+helper = [d for d in analysis["defs"] if d["name"] == "helper"][0]
+helper_refs = [r for r in analysis["refs"] if r["ref_id"] == helper["id"]]
+assert len(helper_refs) == 1
+assert helper_refs[0]["span"]["line_start"] == 10
@@ -0,0 +1,29 @@
+// force-host
+
+#![feature(plugin_registrar)]
+#![feature(rustc_private)]
+
+extern crate syntax;
+extern crate rustc;
+extern crate rustc_plugin;
+
+use syntax::parse::token::{BinOpToken, DelimToken, Lit, Token};
+use syntax::symbol::Symbol;
+use syntax::tokenstream::{DelimSpan, TokenStream, TokenTree};
+use rustc_plugin::Registry;
+
+#[plugin_registrar]
+pub fn plugin_registrar(reg: &mut Registry) {
+    // `double!(e)` expands to `(e) * 2`.
+    reg.register_bang_macro("double", |input| {
+        vec![
+            TokenTree::Delimited(DelimSpan::dummy(), DelimToken::Paren, input).into(),
+            TokenStream::from(Token::BinOp(BinOpToken::Star)),
+            TokenStream::from(Token::Literal(Lit::Integer(Symbol::intern("2")), None)),
+        ].into_iter().collect()
+    });
+    // `#[identity]` expands to the annotated item.
+    reg.register_attr_macro("identity", |_attr, item| item);
+    // `#[derive(Nothing)]` adds no items.
+    reg.register_derive_macro("Nothing", &["nothing"], |_item| TokenStream::empty());
+}
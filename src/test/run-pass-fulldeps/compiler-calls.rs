@@ -27,13 +27,22 @@ struct TestCalls<'a> {
 }
 
 impl<'a> CompilerCalls<'a> for TestCalls<'a> {
+    fn register_diagnostics(&mut self, registry: &mut errors::registry::Registry) {
+        *self.count *= 7;
+        assert!(registry.register("TEST0001", "A test error."));
+        // Built-in codes can't be overridden.
+        assert!(!registry.register("E0001", "Not the real explanation."));
+    }
+
     fn early_callback(&mut self,
                       _: &getopts::Matches,
                       _: &config::Options,
                       _: &ast::CrateConfig,
-                      _: &errors::registry::Registry,
+                      descriptions: &errors::registry::Registry,
                       _: config::ErrorOutputType)
                       -> Compilation {
+        assert_eq!(descriptions.find_description("TEST0001"), Some("A test error."));
+        assert_ne!(descriptions.find_description("E0001"), Some("Not the real explanation."));
         *self.count *= 2;
         Compilation::Continue
     }
@@ -87,5 +96,5 @@ fn main() {
             rustc_driver::run_compiler(&args, Box::new(tc), None, None);
         });
     }
-    assert_eq!(count, 30);
+    assert_eq!(count, 210);
 }
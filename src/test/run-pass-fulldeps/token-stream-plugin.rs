@@ -0,0 +1,22 @@
+// aux-build:token_stream_plugin.rs
+// ignore-stage1
+
+#![feature(plugin)]
+#![plugin(token_stream_plugin)]
+
+#[identity]
+fn three() -> u32 {
+    3
+}
+
+#[derive(Nothing)]
+struct S {
+    #[nothing]
+    x: u32,
+}
+
+fn main() {
+    assert_eq!(double!(three() + 1), 8);
+    let s = S { x: 1 };
+    assert_eq!(s.x, 1);
+}
@@ -1,5 +1,6 @@
 // run-pass
 #![allow(dead_code)]
+#![allow(unaligned_references)]
 #[repr(packed)]
 pub struct Good {
     data: &'static u32,
@@ -1,5 +1,6 @@
 // run-pass
 #![allow(dead_code)]
+#![allow(unaligned_references)]
 // ignore-emscripten weird assertion?
 
 #[repr(packed)]
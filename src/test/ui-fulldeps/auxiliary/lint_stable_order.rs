@@ -0,0 +1,61 @@
+// force-host
+
+#![feature(plugin_registrar)]
+#![feature(box_syntax, rustc_private)]
+
+// Load rustc as a plugin to get macros.
+#[macro_use]
+extern crate rustc;
+extern crate rustc_plugin;
+extern crate syntax_pos;
+
+use rustc::hir;
+use rustc::hir::intravisit::FnKind;
+use rustc::lint::{LateContext, LintContext, LintPass, LateLintPass, LintArray};
+use rustc_plugin::Registry;
+use syntax_pos::Span;
+
+declare_lint!(STABLE_ORDER, Warn, "Checks the stable order of the typeck tables of `checkme`");
+
+struct Pass;
+
+impl LintPass for Pass {
+    fn name(&self) -> &'static str {
+        "Pass"
+    }
+
+    fn get_lints(&self) -> LintArray {
+        lint_array!(STABLE_ORDER)
+    }
+}
+
+impl<'a, 'tcx> LateLintPass<'a, 'tcx> for Pass {
+    fn check_fn(&mut self,
+                cx: &LateContext<'a, 'tcx>,
+                fk: FnKind<'tcx>,
+                _: &'tcx hir::FnDecl,
+                _: &'tcx hir::Body,
+                span: Span,
+                _: hir::HirId) {
+        match fk {
+            FnKind::ItemFn(ident, ..) if ident.as_str() == "checkme" => {}
+            _ => return,
+        }
+
+        let node_types = cx.tables.node_types_in_stable_order();
+        assert_eq!(node_types.len(), cx.tables.node_types().iter().count());
+        assert!(node_types.windows(2).all(|w| w[0].0.local_id < w[1].0.local_id));
+
+        let adjustments = cx.tables.adjustments_in_stable_order();
+        assert!(!adjustments.is_empty());
+        assert_eq!(adjustments.len(), cx.tables.adjustments().iter().count());
+        assert!(adjustments.windows(2).all(|w| w[0].0.local_id < w[1].0.local_id));
+
+        cx.span_lint(STABLE_ORDER, span, "node types and adjustments are in stable order");
+    }
+}
+
+#[plugin_registrar]
+pub fn plugin_registrar(reg: &mut Registry) {
+    reg.register_late_lint_pass(box Pass);
+}
@@ -0,0 +1,16 @@
+// compile-pass
+// aux-build:lint_stable_order.rs
+// ignore-stage1
+
+// Test that `TypeckTables::node_types_in_stable_order` and
+// `adjustments_in_stable_order` return every entry, sorted by `ItemLocalId`.
+
+#![feature(plugin)]
+#![plugin(lint_stable_order)]
+#![allow(dead_code)]
+
+fn checkme(v: Vec<u8>, i: usize) -> u8 { //~ WARNING in stable order
+    v.len() as u8 + v[i]
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+// compile-flags: -Z borrowck=mir -Z two-phase-borrows
+
+// Check that conflicts with a two-phase borrow that is only reserved explain
+// what the reservation allows.
+
+struct S(i32);
+
+impl S {
+    fn set(&mut self, v: i32) {
+        self.0 = v;
+    }
+}
+
+fn main() {
+    let mut v = vec![0];
+    v.push({ v.push(1); 2 });
+    //~^ ERROR cannot borrow `v` as mutable more than once at a time
+
+    let mut s = S(0);
+    s.set({ s = S(1); 2 });
+    //~^ ERROR cannot assign to `s` because it is borrowed
+}
@@ -0,0 +1,13 @@
+// compile-pass
+// compile-flags: -Z explain-const-promotion
+
+use std::cell::Cell;
+
+fn f() -> i32 { 1 }
+
+fn main() {
+    let _: &i32 = &f();
+    let _: &Cell<i32> = &Cell::new(1);
+    let _: &Vec<i32> = &Vec::new();
+    let _: &i32 = &1;
+}
@@ -1,4 +1,5 @@
 #![deny(safe_packed_borrows)]
+#![allow(unaligned_references)]
 
 // check that derive on a packed struct with non-Copy fields
 // correctly. This can't be made to work perfectly because
@@ -0,0 +1,4 @@
+#[derive(ToJson)] //~ ERROR `#[derive(ToJson)]` is experimental
+struct S;
+
+fn main() {}
@@ -0,0 +1,4 @@
+#![register_tool(my_tool)] //~ ERROR registering tools for tool attributes is experimental
+
+#[my_tool::marker]
+fn main() {}
@@ -0,0 +1,8 @@
+#![feature(stmt_expr_attributes)]
+
+fn main() {
+    let x = 0;
+    let p = &x as *const i32;
+    #[unsafe_reasons(raw_pointer_deref)] //~ ERROR restricting the unsafe operations
+    unsafe { *p };
+}
@@ -0,0 +1,19 @@
+// compile-flags: -Z print-generator-sizes
+// compile-pass
+
+#![feature(generators, generator_trait)]
+
+use std::ops::Generator;
+use std::pin::Pin;
+
+fn main() {
+    let x = 7u64;
+    let mut gen = move || {
+        let a = [0u8; 16];
+        yield;
+        let b = 1u32;
+        yield;
+        drop((a, b, x));
+    };
+    Pin::new(&mut gen).resume();
+}
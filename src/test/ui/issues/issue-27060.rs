@@ -1,3 +1,4 @@
+#![allow(unaligned_references)]
 #[repr(packed)]
 pub struct Good {
     data: &'static u32,
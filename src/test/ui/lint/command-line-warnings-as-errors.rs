@@ -0,0 +1,16 @@
+// Warnings from the lint groups passed to `-Z warnings-as-errors` are turned
+// into errors, while other warnings and allowed lints are left alone.
+
+// compile-flags: -Z warnings-as-errors=bad-style
+
+#[allow(non_snake_case)]
+fn allowed() {
+    let _AllowedCamelCasing = true;
+}
+
+fn main() {
+    let _InappropriateCamelCasing = true; //~ ERROR should have a snake
+    while true { //~ WARN denote infinite loops
+        allowed();
+    }
+}
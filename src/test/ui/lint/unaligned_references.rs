@@ -0,0 +1,20 @@
+#![deny(unaligned_references)]
+
+#[repr(packed)]
+pub struct Good {
+    data: u64,
+    ptr: &'static u64,
+    aligned: [u8; 32],
+}
+
+fn main() {
+    unsafe {
+        let good = Good { data: 0, ptr: &0, aligned: [0; 32] };
+
+        let _ = &good.data; //~ ERROR reference to packed field is unaligned
+        let _ = &good.ptr; //~ ERROR reference to packed field is unaligned
+        let _ = &*good.ptr; // ok, behind a pointer
+        let _ = &good.aligned; // ok, has align 1
+        let _ = &good.aligned[2]; // ok, has align 1
+    }
+}
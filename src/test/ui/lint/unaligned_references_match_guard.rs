@@ -0,0 +1,24 @@
+// compile-pass
+
+// Matching on a packed field with a guard only creates fake borrows of it,
+// which must not trigger `unaligned_references`.
+
+#![deny(unaligned_references)]
+#![allow(dead_code)]
+
+#[repr(packed)]
+pub struct Packed {
+    start: u8,
+    data: u32,
+}
+
+fn check(packed: Packed, c: bool) -> u32 {
+    match packed.data {
+        0 if c => 1,
+        n => n,
+    }
+}
+
+fn main() {
+    check(Packed { start: 0, data: 1 }, true);
+}
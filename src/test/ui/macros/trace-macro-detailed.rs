@@ -0,0 +1,13 @@
+// compile-flags: -Z trace-macros=detailed
+// compile-pass
+
+macro_rules! pick {
+    (one $x:expr) => { $x };
+    (many $($y:ident),*) => { 0 $(+ $y)* };
+}
+
+fn main() {
+    let a = 1;
+    let b = 2;
+    let _ = pick!(many a, b);
+}
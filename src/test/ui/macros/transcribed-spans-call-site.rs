@@ -0,0 +1,14 @@
+// compile-flags: -Z macro-call-site-spans
+
+// With `-Z macro-call-site-spans`, tokens transcribed from a `macro_rules!`
+// body are attributed to the macro invocation.
+
+macro_rules! bad_let {
+    () => {
+        let _x: u32 = "one";
+    }
+}
+
+fn main() {
+    bad_let!(); //~ ERROR mismatched types
+}
@@ -0,0 +1,12 @@
+// Tokens transcribed from a `macro_rules!` body keep their position in the
+// definition by default.
+
+macro_rules! bad_let {
+    () => {
+        let _x: u32 = "one"; //~ ERROR mismatched types
+    }
+}
+
+fn main() {
+    bad_let!();
+}
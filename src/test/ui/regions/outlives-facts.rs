@@ -0,0 +1,9 @@
+// Test the outlives facts that are known to hold in the body of a function.
+
+#![feature(rustc_attrs)]
+
+#[rustc_outlives_facts]
+fn foo<'a, 'b: 'a, T: 'b>(x: &'a &'b T) {}
+//~^ ERROR rustc_outlives_facts
+
+fn main() {}
@@ -0,0 +1,9 @@
+// compile-pass
+// compile-flags: --cfg use_my_tool
+
+#![feature(register_tool)]
+#![cfg_attr(use_my_tool, register_tool(my_tool))]
+#![cfg_attr(not_set, register_tool(unused_tool))]
+
+#[my_tool::marker]
+fn main() {}
@@ -0,0 +1,11 @@
+// compile-pass
+
+#![feature(register_tool)]
+#![register_tool(my_tool, other_tool)]
+
+#[my_tool::marker]
+#[other_tool::config(level = 3)]
+fn main() {
+    #[my_tool::skip]
+    let _x = 1;
+}
@@ -0,0 +1,38 @@
+#![feature(stmt_expr_attributes, unsafe_reasons, core_intrinsics)]
+
+static mut COUNTER: u32 = 0;
+
+unsafe fn f() {}
+
+fn main() {
+    let x = 0;
+    let p = &x as *const i32;
+
+    #[unsafe_reasons(raw_pointer_deref)]
+    unsafe {
+        let _y = *p;
+        f(); //~ ERROR call to unsafe function is not allowed in this `unsafe` block
+    }
+
+    #[unsafe_reasons(raw_pointer_deref, mutable_static, unsafe_fn_call)]
+    unsafe {
+        COUNTER += *p as u32;
+        f();
+    }
+
+    #[unsafe_reasons(bogus)] //~ ERROR unknown unsafe reason `bogus`
+    unsafe {
+        f(); //~ ERROR call to unsafe function is not allowed in this `unsafe` block
+    }
+
+    let _z = COUNTER; //~ ERROR use of mutable static is unsafe
+
+    extern "C" { fn abs(x: i32) -> i32; }
+
+    #[unsafe_reasons(ffi_call)]
+    unsafe {
+        abs(-1);
+        std::intrinsics::transmute::<u32, i32>(1);
+        //~^ ERROR call to unsafe function is not allowed in this `unsafe` block
+    }
+}